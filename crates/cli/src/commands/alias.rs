@@ -0,0 +1,65 @@
+use alloy::primitives::Address;
+use contender_core::{db::DbOps, error::ContenderError, generator::util::resolve_alias, Result};
+
+/// Returns true if `name` can be referenced as `@name` in scenario files.
+fn is_valid_alias(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Save an address book entry, replacing any existing entry with the same name. `address` may be
+/// another entry's `@name`.
+pub fn add_alias(db: &impl DbOps, name: &str, address: &str) -> Result<()> {
+    if !is_valid_alias(name) {
+        return Err(ContenderError::GenericError(
+            "alias names may only contain letters, numbers, and underscores:",
+            name.to_owned(),
+        ));
+    }
+    let address = match resolve_alias(address, |name| db.get_alias(name))? {
+        Some(address) => address,
+        None => address
+            .parse::<Address>()
+            .map_err(|e| ContenderError::with_err(e, "invalid address"))?,
+    };
+    db.insert_alias(name, address)?;
+    println!("@{} -> {}", name, address);
+    Ok(())
+}
+
+/// Remove an address book entry.
+pub fn remove_alias(db: &impl DbOps, name: &str) -> Result<()> {
+    if db.remove_alias(name)? {
+        println!("removed @{}", name);
+    } else {
+        println!("alias @{} does not exist", name);
+    }
+    Ok(())
+}
+
+/// Print all address book entries.
+pub fn list_aliases(db: &impl DbOps) -> Result<()> {
+    let aliases = db.list_aliases()?;
+    if aliases.is_empty() {
+        println!(
+            "address book is empty. Add an entry with `contender alias add <name> <address>`."
+        );
+    }
+    for (name, address) in aliases {
+        println!("@{}\t{}", name, address);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_alias;
+
+    #[test]
+    fn validates_alias_names() {
+        assert!(is_valid_alias("weth"));
+        assert!(is_valid_alias("uni_v2_router"));
+        assert!(!is_valid_alias(""));
+        assert!(!is_valid_alias("@weth"));
+        assert!(!is_valid_alias("uni-v2"));
+    }
+}
@@ -0,0 +1,407 @@
+use contender_core::{
+    db::{DbOps, RunScore, RunTx},
+    generator::RandSeed,
+};
+use contender_report::grade;
+use serde::Serialize;
+
+use crate::{default_scenarios::BuiltinScenario, util::NumFormat};
+
+use super::{
+    notify::{notify, RunNotification},
+    run::{run, RunCommandArgs},
+};
+
+/// Version of the bench suite. Bump it whenever `BENCH_SUITE` changes, since score cards are only
+/// comparable between runs of the same suite.
+pub const BENCH_VERSION: u32 = 1;
+
+/// Seed shared by every bench run, so each node gets the same accounts & fuzzed values.
+const BENCH_SEED: [u8; 32] = [0x42; 32];
+
+/// A builtin scenario run at a fixed rate & duration, scored under `category`.
+struct BenchCase {
+    category: &'static str,
+    scenario: BuiltinScenario,
+    txs_per_period: usize,
+    periods: usize,
+    interval_secs: usize,
+}
+
+const BENCH_SUITE: [BenchCase; 3] = [
+    BenchCase {
+        category: "compute",
+        scenario: BuiltinScenario::FillBlock,
+        txs_per_period: 50,
+        periods: 10,
+        interval_secs: 2,
+    },
+    BenchCase {
+        category: "storage",
+        scenario: BuiltinScenario::Oracle,
+        txs_per_period: 100,
+        periods: 10,
+        interval_secs: 2,
+    },
+    BenchCase {
+        category: "erc20",
+        scenario: BuiltinScenario::Permit2,
+        txs_per_period: 50,
+        periods: 10,
+        interval_secs: 2,
+    },
+];
+
+impl BenchCase {
+    /// Seconds the case is meant to spam for.
+    fn planned_secs(&self) -> usize {
+        self.periods * self.interval_secs
+    }
+}
+
+/// Results of one bench case, normalized to per-second rates so nodes can be compared.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchScore {
+    pub category: String,
+    pub run_id: u64,
+    pub txs_sent: usize,
+    pub txs_landed: usize,
+    pub gas_per_second: u128,
+    pub txs_per_second: f64,
+    /// 95th percentile of milliseconds from sending a tx to seeing it land.
+    pub p95_latency_ms: u64,
+    pub score: RunScore,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScoreCard {
+    pub version: u32,
+    pub rpc_url: String,
+    pub client_version: Option<String>,
+    /// Mean score of the categories that ran, out of 100.
+    pub score: Option<f64>,
+    pub grade: Option<String>,
+    pub weights: ScoreWeights,
+    pub scores: Vec<BenchScore>,
+}
+
+/// Weights of each component of a run's composite score, and the latency that scores full marks.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreWeights {
+    pub throughput: f64,
+    pub latency: f64,
+    pub reliability: f64,
+    pub target_latency_secs: u64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            throughput: 0.5,
+            latency: 0.3,
+            reliability: 0.2,
+            target_latency_secs: 2,
+        }
+    }
+}
+
+pub struct BenchCommandArgs {
+    pub rpc_url: String,
+    pub private_key: Option<String>,
+    /// Path to write the score card to, as JSON.
+    pub out_file: Option<String>,
+    pub yes_i_know: bool,
+    pub weights: ScoreWeights,
+    /// Baseline series to append each run to.
+    pub baseline: Option<String>,
+    /// Webhook to notify when each case completes or fails.
+    pub notify_url: Option<String>,
+    pub num_format: NumFormat,
+}
+
+/// Combines a run's throughput, latency & reliability into a score out of 100.
+///
+/// Throughput compares the gas/sec that landed to the gas/sec that was sent over `planned_secs`,
+/// assuming unlanded txs would have used as much gas as the average landed tx.
+fn rate_run(
+    txs_sent: usize,
+    txs_landed: usize,
+    gas_per_second: u128,
+    p95_latency_ms: u64,
+    planned_secs: usize,
+    avg_gas_per_tx: f64,
+    weights: &ScoreWeights,
+) -> RunScore {
+    let sent_gas_per_second = avg_gas_per_tx * txs_sent as f64 / planned_secs.max(1) as f64;
+    let throughput = if sent_gas_per_second > 0.0 {
+        (gas_per_second as f64 / sent_gas_per_second).min(1.0)
+    } else {
+        0.0
+    };
+    let latency = if txs_landed > 0 {
+        (weights.target_latency_secs.max(1) as f64 * 1000.0 / p95_latency_ms.max(1) as f64).min(1.0)
+    } else {
+        0.0
+    };
+    let reliability = if txs_sent > 0 {
+        (txs_landed as f64 / txs_sent as f64).min(1.0)
+    } else {
+        0.0
+    };
+
+    let total_weight = weights.throughput + weights.latency + weights.reliability;
+    let score = if total_weight > 0.0 {
+        100.0
+            * (throughput * weights.throughput
+                + latency * weights.latency
+                + reliability * weights.reliability)
+            / total_weight
+    } else {
+        0.0
+    };
+    RunScore {
+        score,
+        grade: grade(score).to_owned(),
+        throughput,
+        latency,
+        reliability,
+    }
+}
+
+/// Scores a bench run from the txs that landed; latency & rates are measured over the span from the
+/// first tx sent to the last block a tx landed in.
+fn score_run(
+    case: &BenchCase,
+    run_id: u64,
+    run_txs: &[RunTx],
+    weights: &ScoreWeights,
+) -> BenchScore {
+    let txs_sent = case.periods * case.txs_per_period;
+    let start = run_txs.iter().map(|tx| tx.start_timestamp).min();
+    let end = run_txs.iter().map(|tx| tx.end_timestamp).max();
+    let elapsed_ms = match (start, end) {
+        (Some(start), Some(end)) => end.saturating_sub(start).max(1),
+        _ => 1000,
+    };
+    let total_gas = run_txs.iter().map(|tx| tx.gas_used).sum::<u128>();
+
+    let mut latencies = run_txs
+        .iter()
+        .map(RunTx::time_to_inclusion_ms)
+        .collect::<Vec<_>>();
+    latencies.sort();
+    let p95_latency_ms = latencies
+        .get(latencies.len().saturating_sub(1) * 95 / 100)
+        .copied()
+        .unwrap_or_default();
+
+    let gas_per_second = total_gas * 1000 / elapsed_ms as u128;
+    let avg_gas_per_tx = total_gas as f64 / run_txs.len().max(1) as f64;
+
+    BenchScore {
+        category: case.category.to_owned(),
+        run_id,
+        txs_sent,
+        txs_landed: run_txs.len(),
+        gas_per_second,
+        txs_per_second: run_txs.len() as f64 * 1000.0 / elapsed_ms as f64,
+        p95_latency_ms,
+        score: rate_run(
+            txs_sent,
+            run_txs.len(),
+            gas_per_second,
+            p95_latency_ms,
+            case.planned_secs(),
+            avg_gas_per_tx,
+            weights,
+        ),
+    }
+}
+
+fn print_score_card(card: &ScoreCard, fmt: NumFormat) {
+    println!(
+        "\ncontender bench v{} — {} ({})",
+        card.version,
+        card.rpc_url,
+        card.client_version.as_deref().unwrap_or("unknown client")
+    );
+    if let (Some(score), Some(grade)) = (card.score, &card.grade) {
+        println!("grade: {} ({:.1}/100)", grade, score);
+    }
+    println!(
+        "{:<10} {:>8} {:>14} {:>16} {:>10} {:>12} {:>8}",
+        "category", "run", "landed/sent", "gas/sec", "tx/sec", "p95 latency", "score"
+    );
+    for score in &card.scores {
+        println!(
+            "{:<10} {:>8} {:>14} {:>16} {:>10} {:>12} {:>5.1} {}",
+            score.category,
+            score.run_id,
+            format!("{}/{}", score.txs_landed, score.txs_sent),
+            fmt.si(score.gas_per_second as f64, "gas/s"),
+            fmt.si(score.txs_per_second, "tx/s"),
+            fmt.duration_ms(score.p95_latency_ms as f64),
+            score.score.score,
+            score.score.grade
+        );
+    }
+}
+
+/// Runs each case of the bench suite and prints a score card. Cases that can't run on the target
+/// (e.g. Permit2 isn't deployed) are skipped.
+pub async fn bench(
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    args: BenchCommandArgs,
+) -> Result<ScoreCard, Box<dyn std::error::Error>> {
+    let mut scores = vec![];
+    let mut client_version = None;
+    for case in BENCH_SUITE {
+        println!("bench: running {} ({:?})", case.category, case.scenario);
+        let res = run(
+            db,
+            RunCommandArgs {
+                scenario: case.scenario,
+                rpc_url: args.rpc_url.to_owned(),
+                private_key: args.private_key.to_owned(),
+                interval: case.interval_secs,
+                duration: case.periods,
+                txs_per_duration: case.txs_per_period,
+                yes_i_know: args.yes_i_know,
+                seed: RandSeed::seed_from_bytes(&BENCH_SEED),
+                redeploy: Some(true),
+            },
+        )
+        .await;
+        let run_id = match res {
+            Ok(run_id) => run_id,
+            Err(e) => {
+                println!("bench: skipping {}: {}", case.category, e);
+                if let Some(url) = &args.notify_url {
+                    let notification =
+                        RunNotification::failed(case.category, &args.rpc_url, &e.to_string());
+                    notify(url, &notification).await;
+                }
+                continue;
+            }
+        };
+        if let Some(metadata) = db.get_run_metadata(run_id)? {
+            client_version = Some(metadata.client_version);
+        }
+        let score = score_run(&case, run_id, &db.get_run_txs(run_id)?, &args.weights);
+        db.insert_run_score(run_id, &score.score)?;
+        if let Some(series) = &args.baseline {
+            db.insert_baseline_run(series, run_id)?;
+        }
+        if let Some(url) = &args.notify_url {
+            match RunNotification::completed(db, run_id, case.category, &args.rpc_url, None) {
+                Ok(notification) => notify(url, &notification).await,
+                Err(e) => println!("failed to build notification for run {}: {}", run_id, e),
+            }
+        }
+        scores.push(score);
+    }
+
+    let score = (!scores.is_empty())
+        .then(|| scores.iter().map(|s| s.score.score).sum::<f64>() / scores.len() as f64);
+    let card = ScoreCard {
+        version: BENCH_VERSION,
+        rpc_url: args.rpc_url,
+        client_version,
+        score,
+        grade: score.map(|score| grade(score).to_owned()),
+        weights: args.weights,
+        scores,
+    };
+    print_score_card(&card, args.num_format);
+    if let Some(out_file) = &args.out_file {
+        std::fs::write(out_file, serde_json::to_string_pretty(&card)?)?;
+        println!("saved score card to {}", out_file);
+    }
+    Ok(card)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_tx(start_timestamp: usize, end_timestamp: usize, gas_used: u128) -> RunTx {
+        RunTx {
+            gas_used,
+            ..RunTx::mock(0).with_times(start_timestamp, end_timestamp)
+        }
+    }
+
+    fn case(txs_per_period: usize, periods: usize, interval_secs: usize) -> BenchCase {
+        BenchCase {
+            category: "compute",
+            scenario: BuiltinScenario::FillBlock,
+            txs_per_period,
+            periods,
+            interval_secs,
+        }
+    }
+
+    #[test]
+    fn scores_landed_txs() {
+        let txs = (0..20)
+            .map(|i| {
+                run_tx(
+                    100_000 + i / 2 * 1000,
+                    101_000 + (i / 2 + i / 19) * 1000,
+                    50_000,
+                )
+            })
+            .collect::<Vec<_>>();
+        let score = score_run(&case(5, 5, 2), 7, &txs, &ScoreWeights::default());
+        // txs span 100s..=111s
+        assert_eq!(score.txs_landed, 20);
+        assert_eq!(score.txs_sent, 25);
+        assert_eq!(score.gas_per_second, 20 * 50_000 / 11);
+        assert_eq!(score.txs_per_second, 20.0 / 11.0);
+        assert_eq!(score.p95_latency_ms, 1000);
+
+        let empty = score_run(&case(5, 2, 2), 8, &[], &ScoreWeights::default());
+        assert_eq!(empty.txs_landed, 0);
+        assert_eq!(empty.gas_per_second, 0);
+        assert_eq!(empty.p95_latency_ms, 0);
+        assert_eq!(empty.score.score, 0.0);
+        assert_eq!(empty.score.grade, "F");
+    }
+
+    #[test]
+    fn weights_score_components() {
+        let weights = ScoreWeights::default();
+        // everything landed at the rate it was sent, within the target latency
+        let perfect = rate_run(100, 100, 50_000, 1000, 10, 5_000.0, &weights);
+        assert_eq!(perfect.score, 100.0);
+        assert_eq!(perfect.grade, "A");
+
+        // half the txs landed, at half the rate, with 4x the target latency
+        let slow = rate_run(100, 50, 25_000, 8000, 10, 5_000.0, &weights);
+        assert_eq!(slow.throughput, 0.5);
+        assert_eq!(slow.latency, 0.25);
+        assert_eq!(slow.reliability, 0.5);
+        assert_eq!(slow.score, 100.0 * (0.5 * 0.5 + 0.25 * 0.3 + 0.5 * 0.2));
+        assert_eq!(slow.grade, "F");
+
+        let latency_only = ScoreWeights {
+            throughput: 0.0,
+            latency: 1.0,
+            reliability: 0.0,
+            target_latency_secs: 8,
+        };
+        assert_eq!(
+            rate_run(100, 50, 25_000, 8000, 10, 5_000.0, &latency_only).grade,
+            "A"
+        );
+    }
+
+    #[test]
+    fn grades_scores() {
+        assert_eq!(grade(95.0), "A");
+        assert_eq!(grade(80.0), "B");
+        assert_eq!(grade(79.9), "C");
+        assert_eq!(grade(60.0), "D");
+        assert_eq!(grade(12.0), "F");
+    }
+}
@@ -1,7 +1,12 @@
 use clap::Subcommand;
-use std::path::PathBuf;
+use contender_core::{provider::Routing, spammer::ErrorRateLimit};
+use std::{path::PathBuf, time::Duration};
 
-use crate::default_scenarios::BuiltinScenario;
+use crate::{
+    commands::{ChartFormat, ReportFormat},
+    default_scenarios::BuiltinScenario,
+    util::{parse_duration, parse_error_rate_limit, parse_gas},
+};
 
 #[derive(Debug, Subcommand)]
 pub enum ContenderSubcommand {
@@ -11,15 +16,55 @@ pub enum ContenderSubcommand {
         command: DbCommand,
     },
 
+    #[command(
+        name = "alias",
+        about = "Manage the address book",
+        long_about = "Manage the address book. Saved addresses may be referenced as `@name` in the `to`, `args`, and `env` values of scenario files, and in place of an address in `alias add` & `contracts show`."
+    )]
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommand,
+    },
+
+    #[command(
+        name = "contracts",
+        about = "Inspect contracts deployed by contender",
+        long_about = "Inspect the contract registry: every contract contender has deployed, where, and by which scenario, across all runs."
+    )]
+    Contracts {
+        #[command(subcommand)]
+        command: ContractsCommand,
+    },
+
+    #[command(
+        name = "scenarios",
+        about = "Inspect scenario files",
+        long_about = "Inspect scenario files, e.g. to check whether a change to a scenario keeps benchmark results comparable."
+    )]
+    Scenarios {
+        #[command(subcommand)]
+        command: ScenariosCommand,
+    },
+
     #[command(
         name = "spam",
         long_about = "Spam the RPC with tx requests as designated in the given testfile."
     )]
     Spam {
         /// The path to the test file to use for spamming.
+        /// Pass `-` to read the scenario from stdin (or `--inline-toml`).
         testfile: String,
 
-        /// The HTTP JSON-RPC URL to spam with requests.
+        /// Scenario TOML to use when the testfile is `-`, instead of reading stdin.
+        #[arg(
+            long,
+            value_name = "TOML",
+            long_help = "Scenario TOML to use when the testfile is `-`, instead of reading it from stdin. Lets wrapper scripts pass generated scenarios without writing temp files."
+        )]
+        inline_toml: Option<String>,
+
+        /// The JSON-RPC URL to spam with requests: HTTP, or a local node's IPC socket
+        /// (`ipc:///path/to/node.ipc`).
         rpc_url: String,
 
         /// HTTP JSON-RPC URL to use for bundle spamming (must support `eth_sendBundle`).
@@ -30,6 +75,30 @@ pub enum ContenderSubcommand {
         )]
         builder_url: Option<String>,
 
+        /// WebSocket JSON-RPC URL to send txs over, instead of the HTTP `rpc_url`.
+        #[arg(
+            long = "ws",
+            value_name = "WS_URL",
+            long_help = "WebSocket JSON-RPC URL of the same node as `rpc_url`. Spam txs are sent over one persistent connection to it instead of an HTTP request per tx; receipts are still collected over HTTP."
+        )]
+        ws_url: Option<String>,
+
+        /// Another JSON-RPC URL of the same chain to spread spam txs across. May be repeated.
+        #[arg(
+            long = "rpc-url",
+            value_name = "URL",
+            long_help = "Another JSON-RPC URL (HTTP, WebSocket or IPC) of the same chain, e.g. another node of a load-balanced cluster. Spam txs are spread across `rpc_url` and every --rpc-url by --routing; setup, nonces & receipts still use `rpc_url`. May be repeated."
+        )]
+        spam_rpc_urls: Vec<String>,
+
+        /// How spam txs are spread across RPC URLs: `round-robin` or `least-latency`.
+        #[arg(
+            long,
+            default_value = "round-robin",
+            long_help = "How spam txs are spread across `rpc_url` and each --rpc-url: `round-robin` cycles through them in order; `least-latency` sends each tx to the endpoint with the lowest average response time so far, with failed sends counted as slow responses."
+        )]
+        routing: Routing,
+
         /// The number of txs to send per second using the timed spammer. This is the default spammer.
         /// May not be set if `txs_per_block` is set.
         #[arg(long, long_help = "Number of txs to send per second. Must not be set if --txs-per-block is set.", visible_aliases = &["tps"])]
@@ -45,6 +114,32 @@ Requires --priv-key to be set for each 'from' address in the given testfile.",
         visible_aliases = &["tpb"])]
         txs_per_block: Option<usize>,
 
+        /// Txs per second to send at the start of a ramp. Requires --tps-end & --ramp.
+        #[arg(
+            long,
+            requires_all = ["tps_end", "ramp"],
+            conflicts_with_all = ["txs_per_second", "txs_per_block", "total_txs"],
+            long_help = "Txs per second to send at the start of a ramp. The rate is stepped linearly from --tps-start to --tps-end every --ramp interval over the run's --duration, to find a node's breaking point in a single run."
+        )]
+        tps_start: Option<usize>,
+
+        /// Txs per second to send at the end of a ramp.
+        #[arg(
+            long,
+            requires = "tps_start",
+            long_help = "Txs per second to send during the last step of a ramp started with --tps-start."
+        )]
+        tps_end: Option<usize>,
+
+        /// Time between rate steps of a ramp, e.g. `30s`.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            requires = "tps_start",
+            long_help = "Time between rate steps of a ramp started with --tps-start, e.g. `30s`. Rounded down to whole seconds (at least 1)."
+        )]
+        ramp: Option<Duration>,
+
         /// The duration of the spamming run in seconds or blocks, depending on whether `txs_per_second` or `txs_per_block` is set.
         #[arg(
             short,
@@ -80,6 +175,13 @@ May be specified multiple times."
         )]
         disable_reports: bool,
 
+        /// Only count accepted & rejected sends; don't track receipts or write to the DB.
+        #[arg(
+            long,
+            long_help = "Fire-and-forget mode: only count which sends the RPC accepts or rejects. Receipts aren't polled and nothing is written to the DB, so contender's own bookkeeping doesn't limit send throughput. For tests where only node-side metrics matter. Implies --disable-reports."
+        )]
+        no_track: bool,
+
         /// The minimum balance to check for each private key.
         #[arg(
             long,
@@ -97,6 +199,216 @@ May be specified multiple times."
             long_help = "Filename of the saved report. May be a fully-qualified path. If not provided, the report can be generated with the `report` subcommand. '.csv' extension is added automatically."
         )]
         gen_report: bool,
+
+        /// Take an `evm_snapshot` before spamming and `evm_revert` once results are collected.
+        #[arg(
+            long,
+            long_help = "Take an `evm_snapshot` before spamming and `evm_revert` to it once the results have been collected, so repeated runs measure identical chain state. Only supported by Anvil/Hardhat-style nodes."
+        )]
+        snapshot_revert: bool,
+
+        /// Send txs even if the RPC's chain ID doesn't match the scenario or belongs to a known mainnet.
+        #[arg(
+            long,
+            long_help = "Send txs even if the RPC reports a chain ID that differs from the scenario's `chain_id`, or that belongs to a known mainnet."
+        )]
+        yes_i_know: bool,
+
+        /// Max number of sent txs queued for tracking before spam tasks wait.
+        #[arg(
+            long,
+            default_value = "12",
+            long_help = "Max number of sent txs queued for tracking. Once the queue is full, spam tasks wait for it to drain instead of growing memory."
+        )]
+        tx_queue_capacity: usize,
+
+        /// Max number of unconfirmed txs to track. Unbounded if not set.
+        #[arg(
+            long,
+            long_help = "Max number of unconfirmed txs to track. Once reached, the oldest unconfirmed tx is dropped and won't appear in reports. Unbounded if not set. This only limits contender's own tracking; to throttle on the node's mempool, use --pause-above-pending."
+        )]
+        max_pending_txs: Option<usize>,
+
+        /// Pause spamming while more than this many txs are pending in the node's mempool.
+        #[arg(
+            long,
+            value_name = "NUM_TXS",
+            long_help = "Pause spamming while the node's pending pool (from `txpool_status`) holds more than NUM_TXS txs, and resume once it has drained to 3/4 of that, so the spammer doesn't overrun mempool limits. Nodes without `txpool_status` are throttled on the run's own pending txs. Off if not set."
+        )]
+        pause_above_pending: Option<u64>,
+
+        /// Send unsigned txs via `eth_sendTransaction`, signed by accounts unlocked on the node.
+        #[arg(
+            long,
+            long_help = "Send unsigned txs via `eth_sendTransaction`, signed by accounts unlocked on the node (e.g. dev chains). Every spam tx must set `from` to an unlocked account; `from_pool` and bundles aren't supported. Accounts aren't funded, and nonces & gas limits are left to the node."
+        )]
+        unlocked: bool,
+
+        /// Send each account's txs in nonce order, with at most one in flight.
+        #[arg(
+            long,
+            long_help = "Send each account's txs strictly in nonce order, waiting for each tx to land before sending the account's next one. For scenarios (escrows, sequential state machines) that break when concurrent submissions are reordered. Accounts are still spammed concurrently. Bundles aren't supported."
+        )]
+        strict_ordering: bool,
+
+        /// Re-broadcast txs pending longer than this many seconds with bumped fees.
+        #[arg(
+            long = "pending-tx-timeout",
+            long_help = "Re-broadcast txs that have been pending longer than this many seconds with bumped fees at the same nonce, keeping sender lanes moving during fee spikes. Fee bumping is disabled if not set."
+        )]
+        pending_tx_timeout_secs: Option<u64>,
+
+        /// Max number of times a stuck tx is re-broadcast before it's declared failed.
+        #[arg(
+            long,
+            default_value = "3",
+            long_help = "Max number of times a stuck tx is re-broadcast before it's declared failed. Only used with --pending-tx-timeout."
+        )]
+        max_fee_bumps: u32,
+
+        /// Percentage added to a stuck tx's fees each time it's re-broadcast.
+        #[arg(
+            long,
+            default_value = "10",
+            long_help = "Percentage added to a stuck tx's max fee & priority fee each time it's re-broadcast. Most clients reject replacements that bump fees by less than 10%. Only used with --pending-tx-timeout."
+        )]
+        fee_bump_percent: u64,
+
+        /// Give up on a tx send that hasn't been answered after this long, e.g. `2s`.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            long_help = "Give up on a tx send (`eth_sendRawTransaction` / `eth_sendTransaction`) that the RPC hasn't answered after this long, e.g. `2s` or `500ms`, and count it as a timeout, which is retried. Sends may take as long as the transport allows if not set."
+        )]
+        send_timeout: Option<Duration>,
+
+        /// Number of times a failed tx send is retried before the tx is dropped.
+        #[arg(
+            long,
+            default_value = "3",
+            long_help = "Number of times a tx send that failed with a transport error, a timeout, or an error matching --retry-on is retried before the tx is dropped and counted as an error."
+        )]
+        send_retries: usize,
+
+        /// Backoff before the first retry of a failed tx send, e.g. `250ms`; doubled after each retry.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            default_value = "250ms",
+            long_help = "Time to wait before the first retry of a failed tx send, e.g. `250ms`; doubled after each retry."
+        )]
+        retry_backoff: Duration,
+
+        /// Also retry tx sends that fail with an error containing this text, e.g. `nonce too low`.
+        #[arg(
+            long,
+            value_name = "ERROR",
+            long_help = "Also retry tx sends that fail with an error containing this text (case-insensitive), e.g. `nonce too low` or an error code like `-32000`. Transport errors & timeouts are always retried. May be specified multiple times."
+        )]
+        retry_on: Vec<String>,
+
+        /// Number of accounts in each `relayer_pool`.
+        #[arg(
+            long = "relayers",
+            default_value = "4",
+            long_help = "Number of accounts in each `relayer_pool`. Relayers send the txs of spam steps with a `relayer_pool`, so many `from_pool` accounts sign while only a few send."
+        )]
+        num_relayers: usize,
+
+        /// Log send rate, inclusion rate, pending txs & errors at this interval while spamming.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            long_help = "Log a progress line with the rolling send rate, inclusion rate, pending tx count, and error count at this interval while spamming, e.g. `5s` or `500ms`. Pending & inclusion counts are only tracked when reports are enabled. Off if not set."
+        )]
+        stats_interval: Option<Duration>,
+
+        /// Spam until txs landed in the run have used this much gas, e.g. `10Ggas`.
+        #[arg(
+            long,
+            value_parser = parse_gas,
+            long_help = "Spam until txs landed in the run have used this much gas (e.g. `10Ggas`, `500Mgas`), instead of stopping after --duration. The --duration periods of txs are generated up front and sent repeatedly until the budget is used. Requires reports to be enabled."
+        )]
+        total_gas: Option<u128>,
+
+        /// Send exactly this many txs, then end the run once they've all landed or timed out.
+        #[arg(
+            long,
+            conflicts_with = "total_gas",
+            long_help = "Send exactly this many txs at the given rate (bundles count as one), ignoring --duration; the last period may send fewer. The run ends once every tx has landed, or after result collection times out waiting for the rest."
+        )]
+        total_txs: Option<usize>,
+
+        /// Stop spamming when the error rate over a rolling window exceeds a limit, e.g. `20%/30s`.
+        #[arg(
+            long,
+            value_parser = parse_error_rate_limit,
+            long_help = "Stop spamming when the share of failed sends over a rolling window exceeds a limit, e.g. `20%/30s`, so a run doesn't keep burning funds once the node has fallen over. Txs already sent are still collected and saved. The rate isn't checked until a full window has passed."
+        )]
+        abort_on_error_rate: Option<ErrorRateLimit>,
+
+        /// Spam for this long before the measured run, without recording the txs, e.g. `30s`.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            long_help = "Spam for this long (e.g. `30s`) before the measured --duration starts, without tracking or saving the txs, so JIT caches, peering, and connection warmup don't skew the results."
+        )]
+        warmup: Option<Duration>,
+
+        /// Keep tracking pending txs & new blocks for up to this long after the last tx is sent, e.g. `60s`.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            long_help = "Keep tracking pending txs & new blocks for up to this long (e.g. `60s`) after the last tx is sent, so late inclusions are captured. The run closes once every tx has landed, or when the cooldown is over; txs still pending then are counted as timed out."
+        )]
+        cooldown: Option<Duration>,
+
+        /// Wait for the node's mempool to drain before finishing the run.
+        #[arg(
+            long,
+            value_name = "MAX_TXS",
+            num_args = 0..=1,
+            default_missing_value = "0",
+            long_help = "Poll `txpool_status` until the node's mempool holds at most MAX_TXS pending & queued txs (default 0) before taking end balances & generating the report, so chained runs start from a clean boundary."
+        )]
+        wait_for_empty_mempool: Option<u64>,
+
+        /// Append the run to a named baseline series.
+        #[arg(
+            long,
+            long_help = "Append the run to the named baseline series (e.g. \"nightly\"), to track performance over time with `contender report baseline`."
+        )]
+        baseline: Option<String>,
+
+        /// Unix socket to accept pause, resume & status commands on while spamming.
+        #[arg(
+            long,
+            value_name = "PATH",
+            long_help = "Listen on a unix socket at PATH for `pause`, `resume`, `toggle` & `status` commands (one per line) while spamming, e.g. `echo pause | nc -U PATH`. Sending SIGUSR1 to contender also toggles between paused and running. While paused, no txs are sent but sent txs keep being tracked."
+        )]
+        control_socket: Option<String>,
+
+        /// Address to serve the tx actor's queue metrics on, for Prometheus to scrape.
+        #[arg(
+            long,
+            value_name = "ADDR",
+            long_help = "Serve the tx actor's queue depth, cache size, evicted txs & flush latency at http://ADDR/metrics while spamming, in the Prometheus text format, e.g. `--metrics-addr 0.0.0.0:9464`."
+        )]
+        metrics_addr: Option<std::net::SocketAddr>,
+
+        /// Webhook URL to POST a JSON summary to when the run completes or fails.
+        #[arg(
+            long,
+            long_help = "Webhook URL to POST a JSON summary (run_id, scenario, key metrics, report path) to when the run completes or fails. The payload includes a `text` field, so Slack incoming webhooks can be used directly."
+        )]
+        notify_url: Option<String>,
+
+        /// Exit with an error if the run failed a gas or latency assertion.
+        #[arg(
+            long,
+            long_help = "Exit with an error once the run is saved if any of its txs used more gas than their step's `max_gas_used`, or the scenario's `[[slo]]` latency targets were missed, so regressions fail CI jobs."
+        )]
+        fail_on_violations: bool,
     },
 
     #[command(
@@ -105,11 +417,29 @@ May be specified multiple times."
     )]
     Setup {
         /// The path to the test file to use for setup.
+        /// Pass `-` to read the scenario from stdin (or `--inline-toml`).
         testfile: String,
 
-        /// The HTTP JSON-RPC URL to use for setup.
+        /// Scenario TOML to use when the testfile is `-`, instead of reading stdin.
+        #[arg(
+            long,
+            value_name = "TOML",
+            long_help = "Scenario TOML to use when the testfile is `-`, instead of reading it from stdin. Lets wrapper scripts pass generated scenarios without writing temp files."
+        )]
+        inline_toml: Option<String>,
+
+        /// The JSON-RPC URL to use for setup: HTTP, or a local node's IPC socket
+        /// (`ipc:///path/to/node.ipc`).
         rpc_url: String,
 
+        /// WebSocket JSON-RPC URL to send setup txs over, instead of the HTTP `rpc_url`.
+        #[arg(
+            long = "ws",
+            value_name = "WS_URL",
+            long_help = "WebSocket JSON-RPC URL of the same node as `rpc_url`. Deployments & setup txs are sent over one persistent connection to it instead of an HTTP request per call."
+        )]
+        ws_url: Option<String>,
+
         /// The private keys to use for setup.
         #[arg(
             short,
@@ -130,15 +460,28 @@ May be specified multiple times."
         /// The seed used to generate pool accounts.
         #[arg(short, long, long_help = "The seed used to generate pool accounts.")]
         seed: Option<String>,
+
+        /// Send txs even if the RPC's chain ID doesn't match the scenario or belongs to a known mainnet.
+        #[arg(
+            long,
+            long_help = "Send txs even if the RPC reports a chain ID that differs from the scenario's `chain_id`, or that belongs to a known mainnet."
+        )]
+        yes_i_know: bool,
     },
 
     #[command(
         name = "report",
-        long_about = "Export chain performance report for a spam run."
+        long_about = "Export chain performance report for a spam run.",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
     )]
     Report {
+        #[command(subcommand)]
+        command: Option<ReportCommand>,
+
         /// The HTTP JSON-RPC URL to use for setup.
-        rpc_url: String,
+        #[arg(required = true)]
+        rpc_url: Option<String>,
 
         /// The run ID to include in the report.
         #[arg(
@@ -157,6 +500,203 @@ May be specified multiple times."
             default_value = "0"
         )]
         preceding_runs: u64,
+
+        /// Only include txs of the given kind(s) in the report.
+        #[arg(
+            short,
+            long = "kind",
+            long_help = "Only include txs with the given `kind` in the charts. May be specified multiple times."
+        )]
+        kinds: Option<Vec<String>>,
+
+        /// Break charts down by tx kind.
+        #[arg(
+            short,
+            long,
+            long_help = "Draw a separate series for each tx kind in the time-to-inclusion and gas-used charts."
+        )]
+        group_by_kind: bool,
+
+        /// Draw a separate storage heatmap for each of the given contracts.
+        #[arg(
+            short,
+            long = "contract",
+            long_help = "Draw a separate storage slot heatmap for the named contract (the `name` of a [[create]] step deployed to the target RPC). May be specified multiple times."
+        )]
+        contracts: Option<Vec<String>>,
+
+        /// Chart gas used per opcode family.
+        #[arg(
+            long,
+            long_help = "Trace the blocks including the run's txs with the default struct logger (debug_traceBlockByNumber) and chart the gas the run's txs spent on each opcode family (SSTORE, SLOAD, CALL, KECCAK, precompiles, etc). This can be slow for large runs."
+        )]
+        opcode_profile: bool,
+
+        /// Save a self-contained report.
+        #[arg(
+            long,
+            long_help = "Save a self-contained HTML report with all charts and CSVs embedded, suitable for attaching to tickets."
+        )]
+        archive: bool,
+
+        /// Output format of the report.
+        #[arg(
+            long,
+            default_value = "html",
+            long_help = "Output format of the report: `html` (charts & CSVs), or `openmetrics` to save the runs' tx counters, time-to-inclusion histogram & RPC latencies as an OpenMetrics text file, for loading into Prometheus-compatible tooling (e.g. `promtool tsdb create-blocks-from openmetrics`)."
+        )]
+        format: ReportFormat,
+
+        /// Image format to render the report's charts in.
+        #[arg(
+            long,
+            default_value = "png",
+            long_help = "Image format to render the report's charts in: `png`, or `svg` for vector charts which stay crisp when resized and can be embedded in other docs."
+        )]
+        chart_format: ChartFormat,
+
+        /// Wait for the node's mempool to drain before generating the report.
+        #[arg(
+            long,
+            value_name = "MAX_TXS",
+            num_args = 0..=1,
+            default_missing_value = "0",
+            long_help = "Poll `txpool_status` until the node's mempool holds at most MAX_TXS pending & queued txs (default 0) before generating the report."
+        )]
+        wait_for_empty_mempool: Option<u64>,
+    },
+
+    #[command(
+        name = "diff",
+        long_about = "Compare runs of the same scenario sent to different RPC endpoints (e.g. different clients). Renders inclusion latency, gas/sec, and error rate per endpoint."
+    )]
+    Diff {
+        /// The runs to compare; at least 2.
+        #[arg(required = true, num_args = 2..)]
+        run_ids: Vec<u64>,
+
+        /// Save a self-contained report.
+        #[arg(
+            long,
+            long_help = "Save a self-contained HTML report with all charts and CSVs embedded, suitable for attaching to tickets."
+        )]
+        archive: bool,
+    },
+
+    #[command(
+        name = "read",
+        long_about = "Send the read RPCs defined by the `[[read]]` steps of the given testfile (eth_call, eth_getLogs, eth_getBalance) at a fixed rate, and record the latency of each method. No txs are sent."
+    )]
+    Read {
+        /// The path to the test file defining the reads.
+        /// Pass `-` to read the scenario from stdin (or `--inline-toml`).
+        testfile: String,
+
+        /// Scenario TOML to use when the testfile is `-`, instead of reading stdin.
+        #[arg(
+            long,
+            value_name = "TOML",
+            long_help = "Scenario TOML to use when the testfile is `-`, instead of reading it from stdin. Lets wrapper scripts pass generated scenarios without writing temp files."
+        )]
+        inline_toml: Option<String>,
+
+        /// The JSON-RPC URL to send reads to: HTTP, or a local node's IPC socket
+        /// (`ipc:///path/to/node.ipc`).
+        rpc_url: String,
+
+        /// The number of reads to send per second.
+        #[arg(
+            short,
+            long,
+            long_help = "Number of reads to send per second, cycling through the testfile's read steps.",
+            default_value = "100",
+            visible_aliases = &["rps"]
+        )]
+        reads_per_second: usize,
+
+        /// The number of seconds to send reads for.
+        #[arg(
+            short,
+            long,
+            long_help = "The number of seconds to send reads for.",
+            default_value = "10"
+        )]
+        duration: usize,
+
+        /// The seed used to pick log query ranges.
+        #[arg(short, long, long_help = "The seed used to pick log query ranges.")]
+        seed: Option<String>,
+    },
+
+    #[command(
+        name = "genesis",
+        long_about = "Emit a genesis `alloc` section that prefunds all accounts used by the given testfile."
+    )]
+    Genesis {
+        /// The path to the test file to derive accounts from.
+        /// Pass `-` to read the scenario from stdin (or `--inline-toml`).
+        testfile: String,
+
+        /// Scenario TOML to use when the testfile is `-`, instead of reading stdin.
+        #[arg(
+            long,
+            value_name = "TOML",
+            long_help = "Scenario TOML to use when the testfile is `-`, instead of reading it from stdin. Lets wrapper scripts pass generated scenarios without writing temp files."
+        )]
+        inline_toml: Option<String>,
+
+        /// The seed used to generate pool accounts.
+        #[arg(short, long, long_help = "The seed used to generate pool accounts.")]
+        seed: Option<String>,
+
+        /// The number of txs per second/block the scenario will be spammed with.
+        #[arg(
+            long,
+            long_help = "Number of txs per second or per block that will be used with `spam`. Determines how many accounts are generated for each spam pool.",
+            visible_aliases = &["tps", "tpb"]
+        )]
+        txs_per_period: Option<usize>,
+
+        /// Number of accounts in each `relayer_pool`.
+        #[arg(
+            long = "relayers",
+            default_value = "4",
+            long_help = "Number of accounts in each `relayer_pool` that will be used with `spam --relayers`."
+        )]
+        num_relayers: usize,
+
+        /// The balance to assign to each account.
+        #[arg(
+            long,
+            long_help = "The balance to assign to each account in decimal-ETH format (`--balance 1.5` means 1.5 * 1e18 wei).",
+            default_value = "100.0"
+        )]
+        balance: String,
+
+        /// Additional private keys to prefund.
+        #[arg(
+            short,
+            long = "priv-key",
+            long_help = "Add private keys to prefund. The default admin accounts are always included.
+May be specified multiple times."
+        )]
+        private_keys: Option<Vec<String>>,
+
+        /// Include the code of contracts previously deployed to this RPC URL.
+        #[arg(
+            long = "contracts-from",
+            long_help = "Include the runtime code of the testfile's [[create]] contracts as deployed to the given RPC URL (looked up in the contender DB). Storage is not copied."
+        )]
+        contracts_rpc_url: Option<String>,
+
+        /// Path to write the genesis alloc JSON to.
+        #[arg(
+            short,
+            long = "out",
+            long_help = "Path to write the genesis alloc JSON to.",
+            default_value = "alloc.json"
+        )]
+        out_file: String,
     },
 
     #[command(name = "run", long_about = "Run a builtin scenario.")]
@@ -164,7 +704,8 @@ May be specified multiple times."
         /// The scenario to run.
         scenario: BuiltinScenario,
 
-        /// The HTTP JSON-RPC URL to target with the scenario.
+        /// The JSON-RPC URL to target with the scenario: HTTP, or a local node's IPC socket
+        /// (`ipc:///path/to/node.ipc`).
         rpc_url: String,
 
         #[arg(
@@ -197,8 +738,204 @@ May be specified multiple times."
             default_value = "100"
         )]
         txs_per_duration: usize,
+
+        /// Send txs even if the RPC's chain ID doesn't match the scenario or belongs to a known mainnet.
+        #[arg(
+            long,
+            long_help = "Send txs even if the RPC reports a chain ID that differs from the scenario's `chain_id`, or that belongs to a known mainnet."
+        )]
+        yes_i_know: bool,
         // TODO: DRY duplicate args
     },
+
+    #[command(
+        name = "bench",
+        long_about = "Run the standard benchmark suite of builtin scenarios at fixed seeds & rates, and print a score card per category."
+    )]
+    Bench {
+        /// The HTTP JSON-RPC URL to benchmark.
+        rpc_url: String,
+
+        #[arg(
+            short,
+            long = "priv-key",
+            long_help = "Private key used to send all transactions."
+        )]
+        private_key: Option<String>,
+
+        #[arg(
+            short,
+            long = "out",
+            long_help = "Path to write the score card to, as JSON."
+        )]
+        out_file: Option<String>,
+
+        #[arg(
+            long,
+            long_help = "Weight of throughput (gas/sec landed vs. sent) in each run's score.",
+            default_value = "0.5"
+        )]
+        throughput_weight: f64,
+
+        #[arg(
+            long,
+            long_help = "Weight of p95 inclusion latency in each run's score.",
+            default_value = "0.3"
+        )]
+        latency_weight: f64,
+
+        #[arg(
+            long,
+            long_help = "Weight of reliability (share of sent txs that landed) in each run's score.",
+            default_value = "0.2"
+        )]
+        reliability_weight: f64,
+
+        #[arg(
+            long,
+            long_help = "p95 inclusion latency, in seconds, that scores full marks for latency.",
+            default_value = "2"
+        )]
+        target_latency: u64,
+
+        /// Append each run to a named baseline series.
+        #[arg(
+            long,
+            long_help = "Append each run to the named baseline series (e.g. \"nightly\"), to track performance over time with `contender report baseline`."
+        )]
+        baseline: Option<String>,
+
+        /// Webhook URL to POST a JSON summary to when each bench case completes or fails.
+        #[arg(
+            long,
+            long_help = "Webhook URL to POST a JSON summary (run_id, scenario, key metrics) to when each bench case completes or fails. The payload includes a `text` field, so Slack incoming webhooks can be used directly."
+        )]
+        notify_url: Option<String>,
+
+        /// Send txs even if the RPC's chain ID belongs to a known mainnet.
+        #[arg(
+            long,
+            long_help = "Send txs even if the RPC reports a chain ID that belongs to a known mainnet."
+        )]
+        yes_i_know: bool,
+    },
+
+    #[command(
+        name = "reproduce",
+        long_about = "Re-run a past spam run with the same scenario, seed and settings."
+    )]
+    Reproduce {
+        /// ID of the run to reproduce.
+        run_id: u64,
+
+        /// Send the txs to this RPC URL instead of the original run's.
+        #[arg(
+            short,
+            long,
+            long_help = "HTTP JSON-RPC URL to send the txs to. Defaults to the original run's RPC URL."
+        )]
+        rpc_url: Option<String>,
+
+        /// The private keys used by the original run, if any were given.
+        #[arg(
+            short,
+            long = "priv-key",
+            long_help = "Add private keys used by the original run. Private keys aren't saved with runs, so they must be passed again.
+May be specified multiple times."
+        )]
+        private_keys: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AliasCommand {
+    #[command(name = "add", about = "Save an address under the given name")]
+    Add {
+        /// Name used to reference the address as `@name`
+        #[arg(help = "Name used to reference the address as `@name`")]
+        name: String,
+
+        /// Address to save, or `@name` of a saved address
+        #[arg(help = "Address to save, or `@name` of a saved address")]
+        address: String,
+    },
+
+    #[command(name = "remove", about = "Remove an address from the address book")]
+    Remove {
+        /// Name of the alias to remove
+        #[arg(help = "Name of the alias to remove")]
+        name: String,
+    },
+
+    #[command(name = "list", about = "List all saved addresses")]
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContractsCommand {
+    #[command(
+        name = "list",
+        about = "List the latest deployment of each contract on each RPC URL"
+    )]
+    List,
+
+    #[command(name = "show", about = "Show the details of a deployed contract")]
+    Show {
+        /// Name, address, or `@alias` of the contract
+        #[arg(help = "Name, address, or `@alias` of the contract")]
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ScenariosCommand {
+    #[command(
+        name = "diff",
+        about = "Compare two scenario files setting by setting",
+        long_about = "Compare two scenario files setting by setting: steps added or removed, changed args, gas limits & fees, and env differences. Steps are matched by name, kind, or signature & target rather than by line."
+    )]
+    Diff {
+        /// Path to the original scenario file
+        #[arg(help = "Path to the original scenario file")]
+        a: String,
+
+        /// Path to the changed scenario file
+        #[arg(help = "Path to the changed scenario file")]
+        b: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    #[command(
+        name = "cost",
+        about = "Print the ETH spent on gas per run and agent pool"
+    )]
+    Cost {
+        /// The HTTP JSON-RPC URL to fetch receipts from.
+        rpc_url: String,
+
+        /// The last run to include.
+        #[arg(
+            short = 'i',
+            long,
+            long_help = "The last run to include. If not provided, the latest run is used."
+        )]
+        last_run_id: Option<u64>,
+
+        /// The number of runs preceding `last_run_id` to include.
+        #[arg(short, long, default_value = "0")]
+        preceding_runs: u64,
+    },
+
+    #[command(
+        name = "baseline",
+        about = "Chart gas/sec over time for each scenario in a baseline series"
+    )]
+    Baseline {
+        /// Name of the baseline series, as passed to `--baseline`.
+        series: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -216,10 +953,58 @@ pub enum DbCommand {
         out_path: PathBuf,
     },
 
+    #[command(
+        name = "sync",
+        about = "Push local runs to a remote contender DB",
+        long_about = "Push runs that haven't been pushed to URL yet to a remote contender DB, so results collected on isolated machines can be centralized. Each run is POSTed as JSON with its txs, metadata, scenario snapshot, and balances, tagged with --source. Only HTTP(S) endpoints are supported."
+    )]
+    Sync {
+        /// HTTP endpoint to POST runs to
+        #[arg(help = "HTTP endpoint to POST runs to")]
+        url: String,
+
+        /// Name of this machine, sent with each run. Defaults to $HOSTNAME.
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Push every run from this one on, even if it was pushed before.
+        #[arg(long)]
+        from_run: Option<u64>,
+    },
+
     #[command(name = "import", about = "Import database from a file")]
     Import {
         /// Path to the database file to import
         #[arg(help = "Path to the database file to import")]
         src_path: PathBuf,
     },
+
+    #[command(
+        name = "stats",
+        about = "Print the database size, row counts, largest runs, and schema version"
+    )]
+    Stats {
+        /// Number of runs to list, largest first
+        #[arg(short, long, default_value_t = 5)]
+        num_runs: usize,
+    },
+
+    #[command(
+        name = "vacuum",
+        about = "Rebuild the database file to reclaim unused space"
+    )]
+    Vacuum,
+
+    #[command(
+        name = "alias-rpc",
+        about = "Treat an RPC URL as another name for an endpoint",
+        long_about = "Treat an RPC URL as another name for an endpoint. Contracts deployed via either URL are found when running scenarios against the other."
+    )]
+    AliasRpc {
+        /// The RPC URL to add as an alias
+        rpc_url: String,
+
+        /// The RPC URL that contracts are saved under
+        canonical_url: String,
+    },
 }
@@ -0,0 +1,110 @@
+use alloy::primitives::Address;
+use contender_core::{
+    db::{DbOps, DeployedContract},
+    error::ContenderError,
+    generator::util::resolve_alias,
+    Result,
+};
+
+/// Returns true if `query` is the contract's name or address.
+fn matches(contract: &DeployedContract, query: &str) -> bool {
+    contract.name == query
+        || query
+            .parse::<Address>()
+            .is_ok_and(|address| address == contract.address)
+}
+
+/// Print the latest deployment of each contract contender has deployed.
+pub fn list_contracts(db: &impl DbOps) -> Result<()> {
+    let contracts = db.get_contracts()?;
+    if contracts.is_empty() {
+        println!("no contracts deployed yet. Deploy some with `contender setup`.");
+    }
+    for contract in contracts {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            contract.name,
+            contract.namespace.unwrap_or_default(),
+            contract.address,
+            contract.rpc_url,
+            contract.info.scenario_name.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Print the details of the contracts with the given name or address.
+pub fn show_contract(db: &impl DbOps, name_or_address: &str) -> Result<()> {
+    let name_or_address = &match resolve_alias(name_or_address, |name| db.get_alias(name))? {
+        Some(address) => address.to_string(),
+        None => name_or_address.to_owned(),
+    };
+    let contracts = db
+        .get_contracts()?
+        .into_iter()
+        .filter(|contract| matches(contract, name_or_address))
+        .collect::<Vec<_>>();
+    if contracts.is_empty() {
+        return Err(ContenderError::GenericError(
+            "no deployed contract found with name or address:",
+            name_or_address.to_owned(),
+        ));
+    }
+    for contract in contracts {
+        let info = contract.info;
+        println!("name:        {}", contract.name);
+        if let Some(namespace) = contract.namespace {
+            println!("namespace:   {}", namespace);
+        }
+        println!("address:     {}", contract.address);
+        println!("rpc url:     {}", contract.rpc_url);
+        println!("deploy tx:   {}", contract.tx_hash);
+        if let Some(deployed_at) = contract.deployed_at {
+            println!("deployed at: {} ms", deployed_at);
+        }
+        if let Some(scenario_name) = info.scenario_name {
+            println!("scenario:    {}", scenario_name);
+        }
+        if let Some(code_hash) = info.code_hash {
+            println!("code hash:   {}", code_hash);
+        }
+        if let Some(deploy_data) = info.deploy_data {
+            println!("deploy data: 0x{}", deploy_data);
+        }
+        if let Some(abi) = info.abi {
+            println!("abi:         {}", abi);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::TxHash;
+    use contender_core::db::ContractInfo;
+
+    #[test]
+    fn matches_contracts_by_name_or_address() {
+        let contract = DeployedContract {
+            name: "weth".to_owned(),
+            namespace: None,
+            address: Address::from_slice(&[4u8; 20]),
+            tx_hash: TxHash::default(),
+            rpc_url: "http://localhost:8545".to_owned(),
+            info: ContractInfo::default(),
+            deployed_at: None,
+        };
+        assert!(matches(&contract, "weth"));
+        assert!(matches(
+            &contract,
+            "0x0404040404040404040404040404040404040404"
+        ));
+        assert!(!matches(&contract, "usdc"));
+        assert!(!matches(
+            &contract,
+            "0x0505050505050505050505050505050505050505"
+        ));
+    }
+}
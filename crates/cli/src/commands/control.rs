@@ -0,0 +1,168 @@
+use std::{os::unix::fs::FileTypeExt, sync::Arc};
+
+use contender_core::spammer::SpamControl;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    signal::unix::{signal, SignalKind},
+    task::JoinHandle,
+};
+
+/// Lets a spam run be paused & resumed while it's in progress: SIGUSR1 toggles between paused and
+/// running, and if a socket path is given, commands are also accepted on a local unix socket.
+/// The handlers are stopped (and the socket removed) when this is dropped.
+pub struct ControlHandlers {
+    tasks: Vec<JoinHandle<()>>,
+    socket_path: Option<String>,
+}
+
+impl ControlHandlers {
+    pub fn spawn(
+        control: Arc<SpamControl>,
+        socket_path: Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut tasks = vec![];
+
+        let mut sigusr1 = signal(SignalKind::user_defined1())?;
+        let signal_control = control.clone();
+        tasks.push(tokio::task::spawn(async move {
+            while sigusr1.recv().await.is_some() {
+                let paused = signal_control.toggle();
+                println!(
+                    "SIGUSR1 received, {} spam",
+                    if paused { "pausing" } else { "resuming" }
+                );
+            }
+        }));
+
+        if let Some(path) = &socket_path {
+            // a socket left behind by a run that crashed would fail the bind
+            remove_socket(path);
+            let listener = UnixListener::bind(path)?;
+            println!("listening for pause/resume/status commands on {}", path);
+            tasks.push(tokio::task::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::task::spawn(handle_connection(stream, control.clone()));
+                }
+            }));
+        }
+
+        Ok(Self { tasks, socket_path })
+    }
+}
+
+impl Drop for ControlHandlers {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        if let Some(path) = &self.socket_path {
+            remove_socket(path);
+        }
+    }
+}
+
+/// Removes `path` if it's a unix socket. Anything else there is left alone, so a mistyped path
+/// fails the bind instead of deleting the file.
+fn remove_socket(path: &str) {
+    let is_socket =
+        std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket());
+    if is_socket {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Runs one command per line (`pause`, `resume`, `toggle` or `status`), replying with the
+/// spammer's state after each: `paused` or `running`.
+async fn handle_connection(stream: UnixStream, control: Arc<SpamControl>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let reply = match line.trim() {
+            "pause" => {
+                if control.pause() {
+                    println!("pause requested over the control socket");
+                }
+                state(&control)
+            }
+            "resume" => {
+                if control.resume() {
+                    println!("resume requested over the control socket");
+                }
+                state(&control)
+            }
+            "toggle" => {
+                control.toggle();
+                state(&control)
+            }
+            "status" => state(&control),
+            other => format!("unknown command: {}", other),
+        };
+        if writer
+            .write_all(format!("{}\n", reply).as_bytes())
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn state(control: &SpamControl) -> String {
+    if control.is_paused() {
+        "paused"
+    } else {
+        "running"
+    }
+    .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn pauses_and_resumes_over_socket() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("control.sock").to_str().unwrap().to_owned();
+        let control = Arc::new(SpamControl::new());
+        let handlers = ControlHandlers::spawn(control.clone(), Some(path.to_owned())).unwrap();
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"pause\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "paused");
+        assert!(control.is_paused());
+        writer.write_all(b"status\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "paused");
+        writer.write_all(b"resume\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "running");
+        assert!(!control.is_paused());
+        writer.write_all(b"stop\n").await.unwrap();
+        assert_eq!(
+            lines.next_line().await.unwrap().unwrap(),
+            "unknown command: stop"
+        );
+
+        drop(handlers);
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn leaves_files_that_arent_sockets() {
+        let dir = TempDir::new().unwrap();
+        let path = dir
+            .path()
+            .join("scenario.toml")
+            .to_str()
+            .unwrap()
+            .to_owned();
+        std::fs::write(&path, "[[spam]]").unwrap();
+
+        let control = Arc::new(SpamControl::new());
+        assert!(ControlHandlers::spawn(control, Some(path.to_owned())).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[[spam]]");
+    }
+}
@@ -1,6 +1,15 @@
-use contender_core::{db::DbOps, error::ContenderError, Result};
+use alloy::transports::http::reqwest;
+use contender_core::{
+    db::{
+        normalize_rpc_url, AccountBalance, BundleSubmission, DbOps, FundingTx, LatencySnapshot,
+        ReplacedTx, RunArtifacts, RunError, RunMetadata, RunProvenance, RunScore, RunTx, SpamRun,
+    },
+    error::ContenderError,
+    Result,
+};
 use contender_sqlite::SqliteDb;
-use std::{fs, path::PathBuf};
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 /// Delete the database file
 pub async fn drop_db(db_path: &str) -> Result<()> {
@@ -75,9 +84,192 @@ pub async fn import_db(src_path: PathBuf, target_path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Print the size, row counts, largest runs, and schema version of the database
+pub fn db_stats(db: &SqliteDb, num_runs: usize) -> Result<()> {
+    let stats = db.stats(num_runs)?;
+    println!("size:           {}", format_bytes(stats.size_bytes));
+    println!(
+        "reclaimable:    {} (run `contender db vacuum` to free it)",
+        format_bytes(stats.free_bytes)
+    );
+    println!("schema version: {}", stats.schema_version);
+    println!("\nrows per table:");
+    for (table, rows) in &stats.table_rows {
+        println!("  {:<16}{}", table, rows);
+    }
+    if !stats.largest_runs.is_empty() {
+        println!("\nlargest runs:");
+        for (run_id, num_txs) in &stats.largest_runs {
+            println!("  run {:<12}{} txs", run_id, num_txs);
+        }
+    }
+    Ok(())
+}
+
+/// Reclaim space left behind by deleted rows
+pub fn vacuum_db(db: &SqliteDb) -> Result<()> {
+    let before = db.stats(0)?.size_bytes;
+    db.vacuum()?;
+    let after = db.stats(0)?.size_bytes;
+    println!(
+        "Database vacuumed: {} -> {}",
+        format_bytes(before),
+        format_bytes(after)
+    );
+    Ok(())
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.2} {}", size, UNITS[unit])
+    }
+}
+
+/// Make `rpc_url` an alias of `canonical_url`, so named txs saved under either are shared
+pub fn alias_rpc_url(db: &impl DbOps, rpc_url: &str, canonical_url: &str) -> Result<()> {
+    db.insert_rpc_url_alias(rpc_url, canonical_url)?;
+    println!(
+        "{} -> {}",
+        normalize_rpc_url(rpc_url),
+        normalize_rpc_url(canonical_url)
+    );
+    Ok(())
+}
+
+/// A run and everything recorded with it, as pushed to a remote by `contender db sync`.
+#[derive(Debug, Serialize)]
+pub struct RunExport {
+    /// Name of the machine the run was collected on, so a central DB can tell runs from different
+    /// machines apart (their run IDs overlap).
+    pub source: String,
+    pub run: SpamRun,
+    pub metadata: Option<RunMetadata>,
+    pub provenance: Option<RunProvenance>,
+    pub artifacts: Option<RunArtifacts>,
+    pub score: Option<RunScore>,
+    pub txs: Vec<RunTx>,
+    pub latency_snapshots: Vec<LatencySnapshot>,
+    pub bundles: Vec<BundleSubmission>,
+    pub funding_txs: Vec<FundingTx>,
+    pub replaced_txs: Vec<ReplacedTx>,
+    pub errors: Vec<RunError>,
+    pub start_balances: Vec<AccountBalance>,
+    pub end_balances: Vec<AccountBalance>,
+}
+
+impl RunExport {
+    /// Loads the run with everything recorded with it, or `None` if there's no such run.
+    pub fn load(db: &impl DbOps, run_id: u64, source: &str) -> Result<Option<Self>> {
+        let Some(run) = db.get_run(run_id)? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            source: source.to_owned(),
+            run,
+            metadata: db.get_run_metadata(run_id)?,
+            provenance: db.get_run_provenance(run_id)?,
+            artifacts: db.get_run_artifacts(run_id)?,
+            score: db.get_run_score(run_id)?,
+            txs: db.get_run_txs(run_id)?,
+            latency_snapshots: db.get_latency_snapshots(run_id)?,
+            bundles: db.get_bundles(run_id)?,
+            funding_txs: db.get_funding_txs(run_id)?,
+            replaced_txs: db.get_replaced_txs(run_id)?,
+            errors: db.get_run_errors(run_id)?,
+            start_balances: db.get_balances(run_id, true)?,
+            end_balances: db.get_balances(run_id, false)?,
+        }))
+    }
+
+    /// Whether the run has finished. Spam runs record their accounts' balances at the start & end
+    /// of the run, so a run with start balances but no end balances is still in progress (or was
+    /// interrupted).
+    pub fn is_complete(&self) -> bool {
+        self.start_balances.is_empty() || !self.end_balances.is_empty()
+    }
+}
+
+/// Last run pushed to each remote, by URL, so `contender db sync` only pushes new runs.
+fn read_sync_state(state_path: &str) -> BTreeMap<String, u64> {
+    fs::read_to_string(state_path)
+        .ok()
+        .and_then(|state| serde_json::from_str(&state).ok())
+        .unwrap_or_default()
+}
+
+/// Push runs that haven't been pushed to `url` yet (or every run from `from_run` on) to a remote
+/// contender DB, as JSON `RunExport`s POSTed one run at a time. Stops at the first run that hasn't
+/// finished, so it's pushed with all of its data by a later sync, or at the first run the remote
+/// rejects; runs pushed before it aren't pushed again.
+pub async fn sync_db(
+    db: &impl DbOps,
+    url: &str,
+    source: &str,
+    from_run: Option<u64>,
+    state_path: &str,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(ContenderError::DbError(
+            "only HTTP(S) remotes are supported",
+            Some(url.to_owned()),
+        )
+        .into());
+    }
+    let mut state = read_sync_state(state_path);
+    let first_run = from_run.unwrap_or_else(|| state.get(url).map_or(1, |last| last + 1));
+    let num_runs = db.num_runs()?;
+    if first_run > num_runs {
+        println!("no new runs to push to {}", url);
+        return Ok(());
+    }
+    let client = reqwest::Client::new();
+    let mut last_run = None;
+    for run_id in first_run..=num_runs {
+        let Some(export) = RunExport::load(db, run_id, source)? else {
+            continue;
+        };
+        if !export.is_complete() {
+            println!(
+                "run {} hasn't finished; it and later runs will be pushed by the next sync (pass --from-run {} to skip it)",
+                run_id,
+                run_id + 1
+            );
+            break;
+        }
+        client
+            .post(url)
+            .header("content-type", "application/json")
+            .body(serde_json::to_string(&export)?)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| {
+                ContenderError::DbError("remote rejected run", Some(format!("{}: {}", run_id, e)))
+            })?;
+        println!("pushed run {} ({} txs)", run_id, export.txs.len());
+        state.insert(url.to_owned(), run_id);
+        fs::write(state_path, serde_json::to_string_pretty(&state)?)?;
+        last_run = Some(run_id);
+    }
+    if let Some(last_run) = last_run {
+        println!("synced runs {}..={} to {}", first_run, last_run, url);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy::primitives::{Address, U256};
     use tempfile::TempDir;
 
     /// Creates a temp directory containing a database file with the given name.
@@ -108,6 +300,66 @@ mod tests {
         assert!(fs::metadata(&db_path).is_err());
     }
 
+    #[test]
+    fn test_run_export() {
+        let (_temp_dir, db_path) = setup_test_env("run_export");
+        let db = SqliteDb::from_file(&db_path).unwrap();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(1000, 20, "stress.toml", "http://localhost:8545")
+            .unwrap();
+
+        let export = RunExport::load(&db, run_id, "lab-1").unwrap().unwrap();
+        assert_eq!(export.source, "lab-1");
+        assert_eq!(export.run.scenario_name, "stress.toml");
+        assert!(export.txs.is_empty());
+        assert!(export.is_complete());
+        assert!(RunExport::load(&db, run_id + 1, "lab-1").unwrap().is_none());
+
+        let balance = |at_start| {
+            db.insert_balances(
+                run_id,
+                at_start,
+                &[AccountBalance {
+                    pool: "admin".to_owned(),
+                    address: Address::repeat_byte(1),
+                    balance: U256::from(1),
+                }],
+            )
+            .unwrap()
+        };
+        balance(true);
+        let export = RunExport::load(&db, run_id, "lab-1").unwrap().unwrap();
+        assert!(!export.is_complete());
+        balance(false);
+        let export = RunExport::load(&db, run_id, "lab-1").unwrap().unwrap();
+        assert!(export.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_sync_db_rejects_non_http_remotes() {
+        let (temp_dir, db_path) = setup_test_env("sync");
+        let db = SqliteDb::from_file(&db_path).unwrap();
+        let state_path = temp_dir.path().join("sync.json");
+        let res = sync_db(
+            &db,
+            "postgres://localhost/contender",
+            "lab-1",
+            None,
+            state_path.to_str().unwrap(),
+        )
+        .await;
+        assert!(res.is_err());
+        assert!(read_sync_state(state_path.to_str().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.00 GiB");
+    }
+
     #[tokio::test]
     async fn test_reset_db() {
         let (_temp_dir, db_path) = setup_test_env("reset");
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use alloy::{
+    primitives::{utils::parse_ether, Address, U256},
+    providers::Provider,
+    transports::http::reqwest::Url,
+};
+use contender_core::{
+    agent_controller::{AgentStore, SignerStore},
+    db::DbOps,
+    generator::RandSeed,
+    provider::any_provider,
+};
+use contender_testfile::TestConfig;
+use serde::Serialize;
+
+use crate::util::{
+    get_create_pools, get_setup_pools, get_signers_with_defaults, get_spam_pools,
+    get_spam_relayer_pools,
+};
+
+#[derive(Debug)]
+pub struct GenesisCommandArgs {
+    pub testfile: String,
+    pub seed: String,
+    pub txs_per_period: Option<usize>,
+    pub num_relayers: usize,
+    pub balance: String,
+    pub private_keys: Option<Vec<String>>,
+    pub contracts_rpc_url: Option<String>,
+    pub out_file: String,
+}
+
+/// A single account entry in a genesis `alloc` section.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GenesisAccount {
+    pub balance: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenesisAlloc {
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+}
+
+impl GenesisAlloc {
+    /// Prefunds each address with `balance`, ignoring duplicates.
+    pub fn prefunded(addresses: &[Address], balance: U256) -> Self {
+        let alloc = addresses
+            .iter()
+            .map(|addr| {
+                (
+                    *addr,
+                    GenesisAccount {
+                        balance: format!("{:#x}", balance),
+                        code: None,
+                    },
+                )
+            })
+            .collect();
+        Self { alloc }
+    }
+
+    /// Adds a contract account. Existing entries at `address` keep their balance.
+    pub fn add_contract(&mut self, address: Address, code: String) {
+        self.alloc
+            .entry(address)
+            .or_insert(GenesisAccount {
+                balance: "0x0".to_owned(),
+                code: None,
+            })
+            .code = Some(code);
+    }
+}
+
+/// Emits a genesis `alloc` section prefunding every account contender would use for the given scenario.
+pub async fn genesis(
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    args: GenesisCommandArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let testconfig = TestConfig::from_file(&args.testfile)?;
+    let rand_seed = RandSeed::seed_from_str(&args.seed);
+    let balance = parse_ether(&args.balance)?;
+
+    // derive agents the same way `setup` and `spam` do, so the funded addresses match
+    let mut agents = AgentStore::new();
+    let setup_pools = [get_setup_pools(&testconfig), get_create_pools(&testconfig)].concat();
+    for from_pool in &setup_pools {
+        if !agents.has_agent(from_pool) {
+            agents.add_agent(from_pool, SignerStore::new_random(1, &rand_seed, from_pool));
+        }
+    }
+
+    let mut spam_agents = AgentStore::new();
+    if let Some(spam) = &testconfig.spam {
+        for relayer_pool in &get_spam_relayer_pools(&testconfig) {
+            let agent = SignerStore::new_random(args.num_relayers.max(1), &rand_seed, relayer_pool);
+            spam_agents.add_agent(relayer_pool, agent);
+        }
+        let spam_pools = get_spam_pools(&testconfig);
+        let signers_per_period = args.txs_per_period.unwrap_or(spam.len());
+        for from_pool in &spam_pools {
+            if spam_agents.has_agent(from_pool) {
+                continue;
+            }
+            let agent = SignerStore::new_random(
+                signers_per_period / spam_pools.len(),
+                &rand_seed,
+                from_pool,
+            );
+            spam_agents.add_agent(from_pool, agent);
+        }
+    }
+
+    let mut addresses = get_signers_with_defaults(args.private_keys)
+        .iter()
+        .map(|s| s.address())
+        .collect::<Vec<_>>();
+    for (_, agent) in agents.all_agents().chain(spam_agents.all_agents()) {
+        addresses.extend(agent.signers.iter().map(|s| s.address()));
+    }
+
+    let mut genesis = GenesisAlloc::prefunded(&addresses, balance);
+
+    if let Some(rpc_url) = args.contracts_rpc_url {
+        let url = Url::parse(&rpc_url).expect("Invalid RPC URL");
+        let rpc_client = any_provider(&url).await?;
+        let namespace = testconfig.namespace_for(&args.testfile);
+        for create in testconfig.create.unwrap_or_default() {
+            let named_tx = db.get_named_tx(&create.name, &rpc_url, Some(&namespace))?;
+            let address = match named_tx.and_then(|tx| tx.address) {
+                Some(address) => address,
+                None => {
+                    eprintln!(
+                        "contract '{}' has not been deployed to {}; skipping",
+                        create.name, rpc_url
+                    );
+                    continue;
+                }
+            };
+            let code = rpc_client.get_code_at(address).await?;
+            // storage is not copied; only stateless contracts are safe to preset this way
+            genesis.add_contract(address, code.to_string());
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&genesis)?;
+    std::fs::write(&args.out_file, json)?;
+    println!(
+        "genesis alloc with {} accounts saved to {}",
+        genesis.alloc.len(),
+        args.out_file
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GenesisAlloc;
+    use alloy::primitives::{Address, U256};
+
+    #[test]
+    fn prefunds_each_address_once() {
+        let addr = Address::repeat_byte(0x11);
+        let genesis = GenesisAlloc::prefunded(&[addr, addr], U256::from(1000));
+        assert_eq!(genesis.alloc.len(), 1);
+        assert_eq!(genesis.alloc[&addr].balance, "0x3e8");
+
+        let json = serde_json::to_value(&genesis).unwrap();
+        assert!(json["alloc"][addr.to_string()]["code"].is_null());
+    }
+
+    #[test]
+    fn contracts_keep_existing_balance() {
+        let addr = Address::repeat_byte(0x22);
+        let mut genesis = GenesisAlloc::prefunded(&[addr], U256::from(1));
+        genesis.add_contract(addr, "0x6000".to_owned());
+        genesis.add_contract(Address::ZERO, "0x6001".to_owned());
+        assert_eq!(genesis.alloc[&addr].balance, "0x1");
+        assert_eq!(genesis.alloc[&addr].code.as_deref(), Some("0x6000"));
+        assert_eq!(genesis.alloc[&Address::ZERO].balance, "0x0");
+    }
+}
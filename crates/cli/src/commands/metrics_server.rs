@@ -0,0 +1,106 @@
+use std::net::SocketAddr;
+
+use contender_core::spammer::tx_actor::TxActorMetrics;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// Serves the tx actor's queue metrics over HTTP at `/metrics` while spamming, in the Prometheus
+/// text format. The server is stopped when this is dropped.
+pub struct MetricsServer {
+    task: JoinHandle<()>,
+    addr: SocketAddr,
+}
+
+impl MetricsServer {
+    pub async fn spawn(
+        addr: SocketAddr,
+        metrics: impl Fn() -> TxActorMetrics + Send + Clone + 'static,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        println!("serving tx actor metrics at http://{}/metrics", addr);
+        let task = tokio::task::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::task::spawn(handle_connection(stream, metrics.clone()));
+            }
+        });
+        Ok(Self { task, addr })
+    }
+
+    /// Address the server is listening on, e.g. to find the port picked for port 0.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answers a single request; `GET /metrics` gets the metrics, anything else a 404.
+async fn handle_connection(stream: TcpStream, metrics: impl Fn() -> TxActorMetrics) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let Ok(Some(request_line)) = lines.next_line().await else {
+        return;
+    };
+    // skip the headers
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", metrics().to_prometheus()),
+        _ => ("404 Not Found", "not found\n".to_owned()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = writer.write_all(response.as_bytes()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serves_tx_actor_metrics() {
+        let server = MetricsServer::spawn("127.0.0.1:0".parse().unwrap(), || TxActorMetrics {
+            queue_depth: 5,
+            cache_size: 120,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let response = get(server.addr(), "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\ncontender_tx_actor_queue_depth 5\n"));
+        assert!(response.contains("\ncontender_tx_actor_cache_size 120\n"));
+
+        let response = get(server.addr(), "/").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -1,16 +1,40 @@
+mod alias;
+mod bench;
 mod contender_subcommand;
+mod contracts;
+mod control;
 mod db;
+mod genesis;
+mod metrics_server;
+mod notify;
+mod read;
 mod report;
+mod reproduce;
 mod run;
+mod run_summary;
+mod scenarios;
 mod setup;
 mod spam;
 
 use clap::Parser;
 
-pub use contender_subcommand::{ContenderSubcommand, DbCommand};
+pub use alias::{add_alias, list_aliases, remove_alias};
+pub use bench::{bench, BenchCommandArgs, ScoreWeights};
+pub use contender_subcommand::{
+    AliasCommand, ContenderSubcommand, ContractsCommand, DbCommand, ReportCommand, ScenariosCommand,
+};
+pub use contracts::{list_contracts, show_contract};
 pub use db::*;
-pub use report::report;
-pub use run::run;
+pub use genesis::{genesis, GenesisCommandArgs};
+pub use notify::{notify, RunNotification};
+pub use read::{read, ReadCommandArgs};
+pub use report::{
+    report, report_baseline, report_cost, report_diff, ChartFormat, ReportCommandArgs, ReportFormat,
+};
+pub use reproduce::reproduce;
+pub use run::{run, RunCommandArgs};
+pub use run_summary::RunSummary;
+pub use scenarios::{diff_scenario_files, diff_scenarios, ScenarioChange};
 pub use setup::setup;
 pub use spam::{spam, SpamCommandArgs};
 
@@ -18,6 +42,10 @@ pub use spam::{spam, SpamCommandArgs};
 pub struct ContenderCli {
     #[command(subcommand)]
     pub command: ContenderSubcommand,
+
+    /// Print plain numbers in base units (gas, txs, ms) rather than abbreviating them (e.g. `1.2 Mgas/s`).
+    #[arg(long, global = true)]
+    pub raw_numbers: bool,
 }
 
 impl ContenderCli {
@@ -0,0 +1,140 @@
+use alloy::transports::http::reqwest;
+use contender_core::db::{redact_rpc_url, redact_rpc_url_in, DbOps};
+use serde::Serialize;
+
+use super::run_summary::RunMetrics;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    Failed,
+}
+
+/// JSON payload posted to `--notify-url` when a run completes or fails.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunNotification {
+    /// One-line summary, so the payload can be posted to Slack incoming webhooks as-is.
+    pub text: String,
+    pub status: RunStatus,
+    /// Not set for failed runs.
+    pub run_id: Option<u64>,
+    pub scenario: String,
+    /// Only the RPC's scheme & host, so API keys in the URL aren't posted to the webhook.
+    pub rpc_url: String,
+    pub metrics: Option<RunMetrics>,
+    pub report_path: Option<String>,
+    pub error: Option<String>,
+}
+
+impl RunNotification {
+    /// Builds a notification for a recorded run, with metrics from its landed txs.
+    pub fn completed(
+        db: &impl DbOps,
+        run_id: u64,
+        scenario: &str,
+        rpc_url: &str,
+        report_path: Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let txs_sent = db
+            .get_run(run_id)?
+            .map(|run| run.tx_count)
+            .unwrap_or_default();
+        let metrics = RunMetrics::build(txs_sent, &db.get_run_txs(run_id)?);
+        Ok(Self {
+            text: format!(
+                "contender run {} ({}) completed: {}/{} txs landed, {} gas/sec, p95 latency {}ms",
+                run_id,
+                scenario,
+                metrics.txs_landed,
+                metrics.txs_sent,
+                metrics.gas_per_second,
+                metrics.p95_latency_ms
+            ),
+            status: RunStatus::Completed,
+            run_id: Some(run_id),
+            scenario: scenario.to_owned(),
+            rpc_url: redact_rpc_url(rpc_url),
+            metrics: Some(metrics),
+            report_path,
+            error: None,
+        })
+    }
+
+    /// Builds a notification for a run that failed before it completed.
+    pub fn failed(scenario: &str, rpc_url: &str, error: &str) -> Self {
+        // transport errors may quote the URL
+        let error = redact_rpc_url_in(error, rpc_url);
+        Self {
+            text: format!("contender run ({}) failed: {}", scenario, error),
+            status: RunStatus::Failed,
+            run_id: None,
+            scenario: scenario.to_owned(),
+            rpc_url: redact_rpc_url(rpc_url),
+            metrics: None,
+            report_path: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Posts `notification` to `url` as JSON. Failing to notify doesn't fail the run, so errors are
+/// only printed.
+pub async fn notify(url: &str, notification: &RunNotification) {
+    let res = async {
+        let body = serde_json::to_string(notification)?;
+        reqwest::Client::new()
+            .post(url)
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok::<_, Box<dyn std::error::Error>>(())
+    }
+    .await;
+    match res {
+        Ok(_) => println!("sent notification to {}", url),
+        Err(e) => println!("failed to send notification to {}: {}", url, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contender_core::db::MockDb;
+
+    #[test]
+    fn serializes_failed_runs() {
+        let notification =
+            RunNotification::failed("stress.toml", "http://localhost:8545", "no funds");
+        let json = serde_json::to_value(&notification).unwrap();
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["error"], "no funds");
+        assert_eq!(json["rpc_url"], "http://localhost");
+        assert!(json["run_id"].is_null());
+        assert!(json["text"].as_str().unwrap().contains("no funds"));
+        assert!(RunNotification::completed(&MockDb, 1, "stress.toml", "", None).is_ok());
+    }
+
+    #[test]
+    fn redacts_rpc_url() {
+        let rpc_url = "https://rpc.example.com/v2/secret-key";
+        let notification = RunNotification::failed(
+            "stress.toml",
+            rpc_url,
+            &format!("error sending request for url ({})", rpc_url),
+        );
+        assert_eq!(notification.rpc_url, "https://rpc.example.com");
+        assert!(!notification.text.contains("secret-key"));
+        assert!(!notification.error.unwrap().contains("secret-key"));
+
+        // reqwest quotes the parsed URL, which may differ from the one given
+        let notification = RunNotification::failed(
+            "stress.toml",
+            "HTTPS://rpc.example.com?apikey=secret-key",
+            "error sending request for url (https://rpc.example.com/?apikey=secret-key)",
+        );
+        assert!(!notification.text.contains("secret-key"));
+    }
+}
@@ -0,0 +1,88 @@
+use alloy::transports::http::reqwest::Url;
+use contender_core::{
+    agent_controller::AgentStore,
+    db::{DbOps, RunArtifacts},
+    generator::RandSeed,
+    provider::any_provider,
+    spammer::{ReadSpammer, ReadSummary},
+    test_scenario::TestScenario,
+};
+use contender_testfile::TestConfig;
+
+use crate::util::{get_run_metadata, NumFormat};
+
+#[derive(Debug)]
+pub struct ReadCommandArgs {
+    pub testfile: String,
+    pub rpc_url: String,
+    pub reads_per_second: usize,
+    pub duration: usize,
+    pub seed: String,
+    pub num_format: NumFormat,
+}
+
+/// Sends the scenario's `[[read]]` RPCs at a fixed rate and records their latency.
+/// Returns the ID of the run the latencies are saved to.
+pub async fn read(
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    args: ReadCommandArgs,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut testconfig = TestConfig::from_file(&args.testfile)?;
+    testconfig.resolve_aliases(|name| db.get_alias(name))?;
+    let namespace = testconfig.namespace_for(&args.testfile);
+    let url = Url::parse(&args.rpc_url).expect("Invalid RPC URL");
+    let rpc_client = any_provider(&url).await?;
+
+    // reads aren't signed, so no accounts are needed
+    let mut scenario = TestScenario::new(
+        testconfig,
+        db.clone().into(),
+        url,
+        None,
+        RandSeed::seed_from_str(&args.seed),
+        &[],
+        AgentStore::default(),
+    )
+    .await?;
+    scenario.namespace = Some(namespace);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis();
+    // no txs are sent; the run only holds the latency snapshots
+    let run_id = db.insert_run(timestamp as u64, 0, &args.testfile, &args.rpc_url)?;
+    db.insert_run_metadata(run_id, &get_run_metadata(&rpc_client).await)?;
+    db.insert_run_artifacts(run_id, &RunArtifacts::new(scenario.config.snapshot()?))?;
+
+    println!(
+        "sending {} reads/s for {}s...",
+        args.reads_per_second, args.duration
+    );
+    let summary = ReadSpammer::new(args.reads_per_second)
+        .spam_reads(&scenario, args.duration, Some(run_id))
+        .await?;
+    print_read_summary(&summary, args.num_format);
+    println!("done. run_id={}", run_id);
+    Ok(run_id)
+}
+
+pub fn print_read_summary(summary: &ReadSummary, fmt: NumFormat) {
+    println!("method\tok\terrors\tp50\tp95");
+    for snapshot in &summary.latency {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            snapshot.method,
+            snapshot.count,
+            summary.errors.get(&snapshot.method).unwrap_or(&0),
+            fmt.duration_ms(snapshot.p50_ms as f64),
+            fmt.duration_ms(snapshot.p95_ms as f64)
+        );
+    }
+    // methods whose calls all failed have no latencies
+    for (method, errors) in &summary.errors {
+        if !summary.latency.iter().any(|s| &s.method == method) {
+            println!("{}\t0\t{}\t-\t-", method, errors);
+        }
+    }
+}
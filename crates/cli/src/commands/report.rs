@@ -0,0 +1,49 @@
+use contender_core::db::DbOps;
+pub use contender_report::{report_cost, ChartFormat, ReportCommandArgs, ReportFormat};
+
+use crate::util::{data_dir, NumFormat};
+
+/// Returns the fully-qualified path to the report directory.
+fn report_dir() -> Result<String, Box<dyn std::error::Error>> {
+    let path = format!("{}/reports", data_dir()?);
+    std::fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// Returns the fully-qualified path to the directory block traces are cached in.
+fn trace_cache_dir() -> Result<String, Box<dyn std::error::Error>> {
+    Ok(format!("{}/trace_cache", data_dir()?))
+}
+
+/// Generates a report in the data dir, caching block traces there too, and opens HTML reports in
+/// the default web browser. Returns the path to the report.
+pub async fn report(
+    mut args: ReportCommandArgs,
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    rpc_url: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if args.trace_cache_dir.is_none() {
+        args.trace_cache_dir = Some(trace_cache_dir()?);
+    }
+    let open_report = args.format == ReportFormat::Html;
+    let report_path = contender_report::report(args, db, rpc_url, &report_dir()?).await?;
+    if let Some(report_path) = report_path.as_ref().filter(|_| open_report) {
+        webbrowser::open(report_path)?;
+    }
+    Ok(report_path)
+}
+
+/// Builds a differential report in the data dir. Returns the path to the report.
+pub fn report_diff(
+    run_ids: &[u64],
+    db: &impl DbOps,
+    archive: bool,
+    num_format: NumFormat,
+) -> Result<String, Box<dyn std::error::Error>> {
+    contender_report::report_diff(run_ids, db, archive, num_format, &report_dir()?)
+}
+
+/// Prints a baseline series' gas/sec per run and saves its trend chart in the data dir.
+pub fn report_baseline(db: &impl DbOps, series: &str) -> Result<(), Box<dyn std::error::Error>> {
+    contender_report::report_baseline(db, series, &report_dir()?)
+}
@@ -1,11 +0,0 @@
-mod chart_id;
-mod gas_per_block;
-mod heatmap;
-mod time_to_inclusion;
-mod tx_gas_used;
-
-pub use chart_id::ReportChartId;
-pub use gas_per_block::GasPerBlockChart;
-pub use heatmap::HeatMapChart;
-pub use time_to_inclusion::TimeToInclusionChart;
-pub use tx_gas_used::TxGasUsedChart;
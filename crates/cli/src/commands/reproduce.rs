@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use contender_core::{db::DbOps, error::ContenderError};
+
+use super::{spam, SpamCommandArgs};
+use crate::util::data_dir;
+
+/// Re-runs a past spam run with the scenario, seed and settings saved with it.
+/// Returns the ID of the new run.
+pub async fn reproduce(
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    run_id: u64,
+    rpc_url: Option<String>,
+    private_keys: Option<Vec<String>>,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let provenance = db
+        .get_run_provenance(run_id)?
+        .ok_or(ContenderError::DbError(
+            "no provenance saved for run; only spam runs can be reproduced",
+            Some(run_id.to_string()),
+        ))?;
+    let mut args: SpamCommandArgs = serde_json::from_str(&provenance.args)?;
+
+    // restore the scenario as it was when the run started; keeping its filename keeps reports titled the same
+    let dir = format!("{}/reproduce/{}", data_dir()?, run_id);
+    std::fs::create_dir_all(&dir)?;
+    let testfile = scenario_path(&dir, &args.testfile);
+    // prefer the resolved snapshot, which doesn't need the contract sources the scenario was built from
+    let scenario = match db.get_run_artifacts(run_id)? {
+        Some(artifacts) => artifacts.scenario,
+        None => provenance.scenario,
+    };
+    std::fs::write(&testfile, scenario)?;
+
+    println!(
+        "reproducing run {} ({}, seed {})",
+        run_id, args.testfile, args.seed
+    );
+    args.testfile = testfile;
+    args.private_keys = private_keys;
+    if let Some(rpc_url) = rpc_url {
+        args.rpc_url = rpc_url;
+    }
+
+    let new_run_id = spam(db, args).await?;
+    println!("reproduced run {} as run {}", run_id, new_run_id);
+    Ok(new_run_id)
+}
+
+/// Path in `dir` to restore the original `testfile` to.
+fn scenario_path(dir: &str, testfile: &str) -> String {
+    let filename = Path::new(testfile)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or("scenario.toml".to_owned());
+    format!("{}/{}", dir, filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scenario_path;
+
+    #[test]
+    fn keeps_original_scenario_filename() {
+        assert_eq!(
+            scenario_path("/tmp/reproduce/3", "./scenarios/stress.toml"),
+            "/tmp/reproduce/3/stress.toml"
+        );
+        assert_eq!(
+            scenario_path("/tmp/reproduce/3", ""),
+            "/tmp/reproduce/3/scenario.toml"
+        );
+    }
+}
@@ -1,42 +1,68 @@
-use std::{env, str::FromStr, sync::Arc};
+use std::{env, str::FromStr, sync::Arc, time::Duration};
 
 use alloy::{
-    eips::BlockId,
-    network::AnyNetwork,
-    providers::{Provider, ProviderBuilder},
-    rpc::types::BlockTransactionsKind,
-    transports::http::reqwest::Url,
+    eips::BlockId, primitives::utils::parse_ether, providers::Provider,
+    rpc::types::BlockTransactionsKind, transports::http::reqwest::Url,
 };
 use contender_core::{
-    agent_controller::AgentStore,
-    db::DbOps,
+    agent_controller::{AgentStore, SignerStore},
+    db::{DbOps, RunArtifacts},
     error::ContenderError,
     generator::RandSeed,
-    spammer::{LogCallback, Spammer, TimedSpammer},
+    provider::{any_provider, eth_provider},
+    spammer::{LogCallback, ReadSpammer, Spammer, TimedSpammer},
     test_scenario::TestScenario,
 };
 use contender_testfile::TestConfig;
 
 use crate::{
-    default_scenarios::{BuiltinScenario, BuiltinScenarioConfig},
-    util::{check_private_keys, get_signers_with_defaults, prompt_cli},
+    default_scenarios::{
+        interop::{self, L2_TO_L2_MESSENGER_ADDRESS},
+        rpc_mix::RpcMix,
+        BuiltinScenario, BuiltinScenarioConfig, PERMIT2_ADDRESS,
+    },
+    util::{
+        check_chain_id, check_private_keys, fund_accounts, get_balances, get_run_accounts,
+        get_run_metadata, get_signers_with_defaults, prompt_cli, NumFormat,
+    },
 };
 
+use super::read::print_read_summary;
+
+pub struct RunCommandArgs {
+    pub scenario: BuiltinScenario,
+    pub rpc_url: String,
+    pub private_key: Option<String>,
+    pub interval: usize,
+    pub duration: usize,
+    pub txs_per_duration: usize,
+    pub yes_i_know: bool,
+    pub seed: RandSeed,
+    /// Whether to re-deploy the scenario's contract if it's already deployed; prompts if unset.
+    pub redeploy: Option<bool>,
+}
+
+/// Runs a builtin scenario and returns the ID of its spam run.
 pub async fn run(
     db: &(impl DbOps + Clone + Send + Sync + 'static),
-    scenario: BuiltinScenario,
-    rpc_url: String,
-    private_key: Option<String>,
-    interval: usize,
-    duration: usize,
-    txs_per_duration: usize,
-) -> Result<(), Box<dyn std::error::Error>> {
+    args: RunCommandArgs,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let RunCommandArgs {
+        scenario,
+        rpc_url,
+        private_key,
+        interval,
+        duration,
+        txs_per_duration,
+        yes_i_know,
+        seed: rand_seed,
+        redeploy,
+    } = args;
     let user_signers = get_signers_with_defaults(private_key.map(|s| vec![s]));
     let admin_signer = &user_signers[0];
-    let rand_seed = RandSeed::default();
-    let provider = ProviderBuilder::new()
-        .network::<AnyNetwork>()
-        .on_http(Url::parse(&rpc_url).expect("Invalid RPC URL"));
+    let provider = any_provider(&Url::parse(&rpc_url).expect("Invalid RPC URL")).await?;
+    let chain_id = provider.get_chain_id().await?;
+    check_chain_id(None, chain_id, yes_i_know)?;
     let block_gas_limit = provider
         .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
         .await?
@@ -50,6 +76,23 @@ pub async fn run(
         .map(|s| u16::from_str(&s).expect("invalid u16: fill_percent"))
         .unwrap_or(100u16);
 
+    // the interop scenario's messages are relayed to a second chain
+    let dest_provider = match scenario {
+        BuiltinScenario::Interop => {
+            let dest_rpc_url = env::var("C_DEST_RPC_URL").map_err(|_| {
+                ContenderError::SetupError(
+                    "the interop scenario requires C_DEST_RPC_URL to be set to the destination chain's RPC URL",
+                    None,
+                )
+            })?;
+            Some(
+                eth_provider(&Url::parse(&dest_rpc_url).expect("Invalid destination RPC URL"))
+                    .await?,
+            )
+        }
+        _ => None,
+    };
+
     let scenario_config = match scenario {
         BuiltinScenario::FillBlock => BuiltinScenarioConfig::fill_block(
             block_gas_limit,
@@ -57,12 +100,90 @@ pub async fn run(
             admin_signer.address(),
             fill_percent,
         ),
+        BuiltinScenario::Permit2 => {
+            // Permit2 can't be deployed by the scenario; it must be at its canonical address
+            if provider
+                .get_code_at(PERMIT2_ADDRESS.parse()?)
+                .await?
+                .is_empty()
+            {
+                return Err(ContenderError::SetupError(
+                    "Permit2 not found on this chain; deploy it to its canonical address first",
+                    Some(PERMIT2_ADDRESS.to_owned()),
+                )
+                .into());
+            }
+            BuiltinScenarioConfig::permit2(chain_id, admin_signer.address())
+        }
+        BuiltinScenario::Oracle => {
+            let reads_per_update = env::var("C_READS_PER_UPDATE")
+                .map(|s| u64::from_str(&s).expect("invalid u64: reads_per_update"))
+                .unwrap_or(4u64);
+            BuiltinScenarioConfig::oracle(
+                txs_per_duration as u64,
+                admin_signer.address(),
+                reads_per_update,
+            )
+        }
+        BuiltinScenario::Interop => {
+            let dest_provider = dest_provider
+                .as_ref()
+                .expect("destination provider is set for interop");
+            let messenger = L2_TO_L2_MESSENGER_ADDRESS.parse()?;
+            if provider.get_code_at(messenger).await?.is_empty()
+                || dest_provider.get_code_at(messenger).await?.is_empty()
+            {
+                return Err(ContenderError::SetupError(
+                    "L2ToL2CrossDomainMessenger not found; both chains must have OP interop predeploys",
+                    Some(L2_TO_L2_MESSENGER_ADDRESS.to_owned()),
+                )
+                .into());
+            }
+            BuiltinScenarioConfig::interop(
+                dest_provider.get_chain_id().await?,
+                admin_signer.address(),
+            )
+        }
+        BuiltinScenario::RpcMix => {
+            let mix = env::var("C_RPC_MIX")
+                .map(|s| RpcMix::from_str(&s))
+                .unwrap_or_else(|_| Ok(RpcMix::default()))?;
+            BuiltinScenarioConfig::rpc_mix(admin_signer.address(), mix)
+        }
+    };
+    // the rpc-mix scenario splits its calls between txs & reads sent alongside them
+    let (txs_per_duration, reads_per_second) = match &scenario_config {
+        BuiltinScenarioConfig::RpcMix { mix, .. } => mix.split(txs_per_duration, interval),
+        _ => (txs_per_duration, 0),
     };
+    let mut agents = AgentStore::default();
+    for (pool, num_signers) in scenario_config.agent_pools() {
+        agents.add_agent(pool, SignerStore::new_random(num_signers, &rand_seed, pool));
+    }
     let scenario_name = scenario_config.to_string();
+    let contract_name = scenario_config.contract_name();
     let testconfig: TestConfig = scenario_config.into();
+    let namespace = testconfig.namespace_for(&scenario_name);
     check_private_keys(&testconfig, &user_signers);
 
     let rpc_url = Url::parse(&rpc_url).expect("Invalid RPC URL");
+    // reads aren't signed, so they get their own scenario without accounts
+    let read_scenario = if reads_per_second > 0 && testconfig.read.is_some() {
+        let mut read_scenario = TestScenario::new(
+            testconfig.to_owned(),
+            db.clone().into(),
+            rpc_url.to_owned(),
+            None,
+            rand_seed.to_owned(),
+            &[],
+            AgentStore::default(),
+        )
+        .await?;
+        read_scenario.namespace = Some(namespace.to_owned());
+        Some(read_scenario)
+    } else {
+        None
+    };
     let mut scenario = TestScenario::new(
         testconfig,
         db.clone().into(),
@@ -70,18 +191,21 @@ pub async fn run(
         None,
         rand_seed,
         &user_signers,
-        AgentStore::default(),
+        agents,
     )
     .await?;
+    scenario.scenario_name = Some(scenario_name.to_owned());
+    scenario.namespace = Some(namespace.to_owned());
 
-    let contract_name = "SpamMe";
-    let contract_result = db.get_named_tx(contract_name, rpc_url.as_str())?;
+    let contract_result = db.get_named_tx(contract_name, rpc_url.as_str(), Some(&namespace))?;
     let do_deploy_contracts = if contract_result.is_some() {
-        let input = prompt_cli(format!(
-            "{} deployment already detected. Re-deploy? [y/N]",
-            contract_name
-        ));
-        input.to_lowercase() == "y"
+        redeploy.unwrap_or_else(|| {
+            let input = prompt_cli(format!(
+                "{} deployment already detected. Re-deploy? [y/N]",
+                contract_name
+            ));
+            input.to_lowercase() == "y"
+        })
     } else {
         true
     };
@@ -91,6 +215,20 @@ pub async fn run(
         scenario.deploy_contracts().await?;
     }
 
+    let agent_addrs = scenario
+        .agent_store
+        .all_agents()
+        .flat_map(|(_, agent)| agent.signers.iter().map(|signer| signer.address()))
+        .collect::<Vec<_>>();
+    let funding_txs = fund_accounts(
+        &agent_addrs,
+        admin_signer,
+        &provider,
+        &eth_provider(&rpc_url).await?,
+        parse_ether("1.0")?,
+    )
+    .await?;
+
     println!("running setup...");
     scenario.run_setup().await?;
 
@@ -104,23 +242,89 @@ pub async fn run(
         timestamp as u64,
         duration * txs_per_duration,
         &format!("{} ({})", contract_name, scenario_name),
+        rpc_url.as_str(),
     )?;
-    let callback = LogCallback::new(Arc::new(
-        ProviderBuilder::new()
-            .network::<AnyNetwork>()
-            .on_http(rpc_url),
-    ));
+    db.insert_run_metadata(run_id, &get_run_metadata(&provider).await)?;
+    db.insert_run_artifacts(run_id, &RunArtifacts::new(scenario.config.snapshot()?))?;
+    db.insert_funding_txs(Some(run_id), &funding_txs)?;
+    let run_accounts = get_run_accounts(&user_signers, &scenario.agent_store);
+    db.insert_run_accounts(run_id, &run_accounts)?;
+    db.insert_balances(run_id, true, &get_balances(&run_accounts, &provider).await?)?;
+    let callback = LogCallback::new(Arc::new(any_provider(&rpc_url).await?));
+
+    // messages are matched to relays by the blocks they're sent in on each chain
+    let interop_start_blocks = match &dest_provider {
+        Some(dest_provider) => Some((
+            provider.get_block_number().await?,
+            dest_provider.get_block_number().await?,
+        )),
+        None => None,
+    };
 
     println!("starting spammer...");
-    spammer
-        .spam_rpc(
-            &mut scenario,
-            txs_per_duration,
-            duration,
-            Some(run_id),
-            callback.into(),
+    let spam = spammer.spam_rpc(
+        &mut scenario,
+        txs_per_duration,
+        duration,
+        Some(run_id),
+        callback.into(),
+    );
+    match &read_scenario {
+        Some(read_scenario) => {
+            println!("sending {} reads/s alongside txs...", reads_per_second);
+            let reads = ReadSpammer::new(reads_per_second).spam_reads(
+                read_scenario,
+                interval * duration,
+                Some(run_id),
+            );
+            let (_, summary) = tokio::try_join!(spam, reads)?;
+            print_read_summary(&summary, NumFormat::default());
+        }
+        None => spam.await?,
+    }
+
+    db.insert_balances(
+        run_id,
+        false,
+        &get_balances(&run_accounts, &provider).await?,
+    )?;
+
+    if let (Some(dest_provider), Some((from_block, dest_from_block))) =
+        (&dest_provider, interop_start_blocks)
+    {
+        let relay_timeout = env::var("C_RELAY_TIMEOUT")
+            .map(|s| u64::from_str(&s).expect("invalid u64: relay_timeout"))
+            .unwrap_or(60u64);
+        let source_provider = eth_provider(&rpc_url).await?;
+        let sent = interop::sent_messages(
+            &source_provider,
+            from_block,
+            source_provider.get_block_number().await?,
+            dest_provider.get_chain_id().await?,
+            &[admin_signer.address()],
         )
         .await?;
+        let stats = interop::wait_for_relays(
+            dest_provider,
+            dest_from_block,
+            chain_id,
+            &sent,
+            Duration::from_secs(relay_timeout),
+        )
+        .await?;
+        println!(
+            "{}/{} messages relayed",
+            stats.latencies.len(),
+            stats.num_sent
+        );
+        if let (Some(p50), Some(p99), Some(max)) = (
+            stats.percentile(50),
+            stats.percentile(99),
+            stats.percentile(100),
+        ) {
+            println!("relay latency: p50={}s, p99={}s, max={}s", p50, p99, max);
+        }
+    }
 
-    Ok(())
+    Ok(run_id)
 }
@@ -0,0 +1,215 @@
+use alloy::primitives::{utils::format_ether, U256};
+use contender_core::db::{AccountBalance, DbOps, RunTx};
+use contender_report::{run_slos, SloSummary};
+use serde::Serialize;
+
+use crate::util::NumFormat;
+
+/// Key metrics of a finished run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunMetrics {
+    pub txs_sent: usize,
+    pub txs_landed: usize,
+    pub gas_used: u128,
+    pub gas_per_second: u128,
+    pub txs_per_second: f64,
+    pub p50_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    /// Txs that used more gas than their step's `max_gas_used`.
+    pub gas_violations: usize,
+}
+
+impl RunMetrics {
+    /// Rates are measured over the span from the first tx sent to the last tx landing.
+    pub fn build(txs_sent: usize, run_txs: &[RunTx]) -> Self {
+        let start = run_txs.iter().map(|tx| tx.start_timestamp).min();
+        let end = run_txs.iter().map(|tx| tx.end_timestamp).max();
+        let elapsed_ms = match (start, end) {
+            (Some(start), Some(end)) => end.saturating_sub(start).max(1),
+            _ => 1000,
+        };
+        let gas_used = run_txs.iter().map(|tx| tx.gas_used).sum::<u128>();
+
+        let mut latencies = run_txs
+            .iter()
+            .map(RunTx::time_to_inclusion_ms)
+            .collect::<Vec<_>>();
+        latencies.sort();
+        let percentile = |p: usize| {
+            latencies
+                .get(latencies.len().saturating_sub(1) * p / 100)
+                .copied()
+                .unwrap_or_default()
+        };
+
+        Self {
+            txs_sent,
+            txs_landed: run_txs.len(),
+            gas_used,
+            gas_per_second: gas_used * 1000 / elapsed_ms as u128,
+            txs_per_second: run_txs.len() as f64 * 1000.0 / elapsed_ms as f64,
+            p50_latency_ms: percentile(50),
+            p95_latency_ms: percentile(95),
+            gas_violations: run_txs
+                .iter()
+                .filter(|tx| tx.exceeds_max_gas_used())
+                .count(),
+        }
+    }
+
+    /// Number of sent txs which didn't land.
+    pub fn txs_failed(&self) -> usize {
+        self.txs_sent.saturating_sub(self.txs_landed)
+    }
+}
+
+/// Summary of a spam run, printed when it completes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub run_id: u64,
+    pub metrics: RunMetrics,
+    /// Net decrease of the run accounts' balances over the run.
+    pub eth_spent: U256,
+    /// Results of the scenario's latency SLOs.
+    pub slo_summaries: Vec<SloSummary>,
+    pub report_path: Option<String>,
+}
+
+impl RunSummary {
+    /// Accounts missing from either balance snapshot are skipped.
+    pub fn build(
+        run_id: u64,
+        metrics: RunMetrics,
+        start_balances: &[AccountBalance],
+        end_balances: &[AccountBalance],
+        slo_summaries: Vec<SloSummary>,
+        report_path: Option<String>,
+    ) -> Self {
+        let (mut start, mut end) = (U256::ZERO, U256::ZERO);
+        for start_balance in start_balances {
+            if let Some(end_balance) = end_balances
+                .iter()
+                .find(|b| b.pool == start_balance.pool && b.address == start_balance.address)
+            {
+                start += start_balance.balance;
+                end += end_balance.balance;
+            }
+        }
+        Self {
+            run_id,
+            metrics,
+            eth_spent: start.saturating_sub(end),
+            slo_summaries,
+            report_path,
+        }
+    }
+
+    pub fn load(
+        db: &impl DbOps,
+        run_id: u64,
+        report_path: Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let txs_sent = db
+            .get_run(run_id)?
+            .map(|run| run.tx_count)
+            .unwrap_or_default();
+        let run_txs = db.get_run_txs(run_id)?;
+        Ok(Self::build(
+            run_id,
+            RunMetrics::build(txs_sent, &run_txs),
+            &db.get_balances(run_id, true)?,
+            &db.get_balances(run_id, false)?,
+            SloSummary::build_run(run_id, &run_slos(db, run_id)?, &run_txs),
+            report_path,
+        ))
+    }
+
+    /// Number of failed assertions: txs over their `max_gas_used`, and missed latency SLOs.
+    pub fn num_violations(&self) -> usize {
+        self.metrics.gas_violations + self.slo_summaries.iter().filter(|s| !s.passed).count()
+    }
+
+    pub fn print(&self, fmt: NumFormat) {
+        let m = &self.metrics;
+        let rows = [
+            ("run_id", self.run_id.to_string()),
+            ("txs sent", fmt.si(m.txs_sent as f64, "tx")),
+            ("txs landed", fmt.si(m.txs_landed as f64, "tx")),
+            ("txs failed", fmt.si(m.txs_failed() as f64, "tx")),
+            ("gas used", fmt.si(m.gas_used as f64, "gas")),
+            ("gas/sec", fmt.si(m.gas_per_second as f64, "gas/s")),
+            ("tx/sec", fmt.si(m.txs_per_second, "tx/s")),
+            ("p50 inclusion", fmt.duration_ms(m.p50_latency_ms as f64)),
+            ("p95 inclusion", fmt.duration_ms(m.p95_latency_ms as f64)),
+            ("gas violations", m.gas_violations.to_string()),
+            ("ETH spent", format_ether(self.eth_spent)),
+            (
+                "report",
+                self.report_path.to_owned().unwrap_or_else(|| {
+                    format!("not generated; run `contender report -i {}`", self.run_id)
+                }),
+            ),
+        ];
+        println!("\nrun summary");
+        for (name, value) in rows {
+            println!("  {:<14} {}", name, value);
+        }
+        for slo in &self.slo_summaries {
+            println!(
+                "  SLO {} {}: {} ({})",
+                slo.kind,
+                slo.target,
+                if slo.passed { "pass" } else { "FAIL" },
+                slo.observed
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    fn run_tx(start_timestamp: usize, end_timestamp: usize) -> RunTx {
+        RunTx::mock(0).with_times(start_timestamp, end_timestamp)
+    }
+
+    fn balance(n: u8, wei: u64) -> AccountBalance {
+        AccountBalance {
+            pool: "pool1".to_owned(),
+            address: Address::repeat_byte(n),
+            balance: U256::from(wei),
+        }
+    }
+
+    #[test]
+    fn measures_run_metrics() {
+        let mut txs = [
+            run_tx(10_000, 10_400),
+            run_tx(10_000, 12_000),
+            run_tx(11_000, 13_000),
+            run_tx(12_000, 14_000),
+        ];
+        txs[3].max_gas_used = Some(20_000);
+        let metrics = RunMetrics::build(5, &txs);
+        assert_eq!(metrics.txs_landed, 4);
+        assert_eq!(metrics.txs_failed(), 1);
+        assert_eq!(metrics.gas_used, 4 * 21_000);
+        assert_eq!(metrics.gas_per_second, 4 * 21_000 / 4);
+        assert_eq!(metrics.txs_per_second, 1.0);
+        assert_eq!(metrics.p50_latency_ms, 2000);
+        assert_eq!(metrics.p95_latency_ms, 2000);
+        assert_eq!(metrics.gas_violations, 1);
+        assert_eq!(RunMetrics::build(5, &[]).gas_per_second, 0);
+    }
+
+    #[test]
+    fn sums_eth_spent_by_run_accounts() {
+        let start = [balance(1, 1000), balance(2, 1000), balance(3, 1000)];
+        // account 3 has no end balance; skipped
+        let end = [balance(1, 700), balance(2, 1100)];
+        let summary = RunSummary::build(1, RunMetrics::build(0, &[]), &start, &end, vec![], None);
+        assert_eq!(summary.eth_spent, U256::from(200));
+    }
+}
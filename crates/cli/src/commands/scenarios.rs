@@ -0,0 +1,325 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use contender_testfile::TestConfig;
+use serde_json::Value;
+
+/// Sections holding lists of steps, which are matched by label rather than position.
+const STEP_SECTIONS: [&str; 3] = ["create", "setup", "spam"];
+
+/// Values longer than this (e.g. bytecode) are truncated when printed.
+const MAX_VALUE_LEN: usize = 80;
+
+/// A semantic difference between two scenarios. `path` names the setting, e.g. `env.amount`
+/// or `spam[tx swap].args[1]`.
+#[derive(Debug, PartialEq)]
+pub enum ScenarioChange {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl Display for ScenarioChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioChange::Added { path, value } => write!(f, "+ {} = {}", path, value),
+            ScenarioChange::Removed { path, value } => write!(f, "- {} = {}", path, value),
+            ScenarioChange::Changed { path, old, new } => {
+                write!(f, "~ {}: {} -> {}", path, old, new)
+            }
+        }
+    }
+}
+
+/// Compares two scenarios setting by setting. Steps are matched by their `name` (create) or
+/// `kind`, falling back to their signature & target, so reordering or inserting steps doesn't
+/// show every following step as changed.
+pub fn diff_scenarios(
+    a: &TestConfig,
+    b: &TestConfig,
+) -> Result<Vec<ScenarioChange>, serde_json::Error> {
+    let a = serde_json::to_value(a)?;
+    let b = serde_json::to_value(b)?;
+    let mut changes = vec![];
+    for key in object_keys(&a, &b) {
+        if STEP_SECTIONS.contains(&key.as_str()) {
+            diff_steps(&key, &a[&key], &b[&key], &mut changes);
+        } else {
+            diff_values(key.to_owned(), &a[&key], &b[&key], &mut changes);
+        }
+    }
+    Ok(changes)
+}
+
+/// Print the semantic differences between the scenario files at `path_a` & `path_b`.
+pub fn diff_scenario_files(path_a: &str, path_b: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let a = TestConfig::from_snapshot(&std::fs::read_to_string(path_a)?)?;
+    let b = TestConfig::from_snapshot(&std::fs::read_to_string(path_b)?)?;
+    let changes = diff_scenarios(&a, &b)?;
+    if changes.is_empty() {
+        println!("scenarios are equivalent");
+        return Ok(());
+    }
+    println!("--- {}", path_a);
+    println!("+++ {}", path_b);
+    for change in &changes {
+        println!("{}", change);
+    }
+    println!("{} change(s)", changes.len());
+    Ok(())
+}
+
+/// Keys of two JSON objects, sorted & deduplicated.
+fn object_keys(a: &Value, b: &Value) -> BTreeSet<String> {
+    a.as_object()
+        .into_iter()
+        .chain(b.as_object())
+        .flat_map(|obj| obj.keys().cloned())
+        .collect()
+}
+
+fn diff_values(path: String, old: &Value, new: &Value, changes: &mut Vec<ScenarioChange>) {
+    match (old, new) {
+        (old, new) if old == new => {}
+        (Value::Null, new) => changes.push(ScenarioChange::Added {
+            path,
+            value: fmt_value(new),
+        }),
+        (old, Value::Null) => changes.push(ScenarioChange::Removed {
+            path,
+            value: fmt_value(old),
+        }),
+        (Value::Object(_), Value::Object(_)) => {
+            for key in object_keys(old, new) {
+                diff_values(format!("{}.{}", path, key), &old[&key], &new[&key], changes);
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (i, (old, new)) in old_items.iter().zip(new_items).enumerate() {
+                diff_values(format!("{}[{}]", path, i), old, new, changes);
+            }
+        }
+        (old, new) => changes.push(ScenarioChange::Changed {
+            path,
+            old: fmt_value(old),
+            new: fmt_value(new),
+        }),
+    }
+}
+
+fn diff_steps(section: &str, old: &Value, new: &Value, changes: &mut Vec<ScenarioChange>) {
+    let old_steps = labeled_steps(section, old);
+    let new_steps = labeled_steps(section, new);
+    for (label, old_step) in &old_steps {
+        let path = format!("{}[{}]", section, label);
+        match new_steps.iter().find(|(new_label, _)| new_label == label) {
+            Some((_, new_step)) => diff_values(path, old_step, new_step, changes),
+            None => changes.push(ScenarioChange::Removed {
+                path,
+                value: fmt_value(old_step),
+            }),
+        }
+    }
+    for (label, new_step) in &new_steps {
+        if !old_steps.iter().any(|(old_label, _)| old_label == label) {
+            changes.push(ScenarioChange::Added {
+                path: format!("{}[{}]", section, label),
+                value: fmt_value(new_step),
+            });
+        }
+    }
+}
+
+/// Labels each step of a section, numbering repeated labels (`#2`, `#3`, ...) in order.
+fn labeled_steps<'a>(section: &str, steps: &'a Value) -> Vec<(String, &'a Value)> {
+    let mut labeled: Vec<(String, &Value)> = vec![];
+    for step in steps.as_array().into_iter().flatten() {
+        let label = step_label(section, step);
+        let num_seen = labeled
+            .iter()
+            .filter(|(seen, _)| seen == &label || seen.starts_with(&format!("{} #", label)))
+            .count();
+        let label = if num_seen == 0 {
+            label
+        } else {
+            format!("{} #{}", label, num_seen + 1)
+        };
+        labeled.push((label, step));
+    }
+    labeled
+}
+
+fn step_label(section: &str, step: &Value) -> String {
+    match section {
+        "create" => step["name"].as_str().unwrap_or_default().to_owned(),
+        "setup" => call_label(step),
+        _ => match step.as_object().and_then(|obj| obj.iter().next()) {
+            Some((variant, def)) if variant == "bundle" => format!(
+                "bundle {}",
+                def["tx"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(call_label)
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            ),
+            Some((variant, def)) if variant == "transfer" => match def["kind"].as_str() {
+                Some(kind) => format!("transfer {}", kind),
+                None => match def["to_pool"].as_str() {
+                    Some(pool) => format!("transfer to pool {}", pool),
+                    None => format!("transfer to {}", target(&def["to"])),
+                },
+            },
+            Some((variant, def)) => format!("{} {}", variant, call_label(def)),
+            None => fmt_value(step),
+        },
+    }
+}
+
+/// A function call's `kind`, or its signature & target if it has none.
+fn call_label(call: &Value) -> String {
+    match call["kind"].as_str() {
+        Some(kind) => kind.to_owned(),
+        None => format!(
+            "{} @ {}",
+            call["signature"].as_str().unwrap_or_default(),
+            target(&call["to"])
+        ),
+    }
+}
+
+fn target(to: &Value) -> String {
+    match to {
+        Value::String(to) => to.to_owned(),
+        Value::Array(targets) => targets
+            .iter()
+            .map(|to| to.as_str().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => String::new(),
+    }
+}
+
+fn fmt_value(value: &Value) -> String {
+    let value = value.to_string();
+    let len = value.chars().count();
+    if len > MAX_VALUE_LEN {
+        format!(
+            "{}… ({} chars)",
+            value.chars().take(MAX_VALUE_LEN).collect::<String>(),
+            len
+        )
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(toml: &str) -> TestConfig {
+        TestConfig::from_snapshot(toml).unwrap()
+    }
+
+    #[test]
+    fn diffs_steps_by_label_and_env_by_key() {
+        let a = scenario(
+            r#"
+[env]
+amount = "100"
+token = "weth"
+
+[[spam]]
+[spam.tx]
+to = "{pool}"
+signature = "swap(uint256)"
+args = ["100"]
+kind = "swap"
+
+[[spam]]
+[spam.tx]
+to = "{pool}"
+signature = "mint()"
+"#,
+        );
+        let b = scenario(
+            r#"
+[env]
+amount = "200"
+
+[[spam]]
+[spam.tx]
+to = "{pool}"
+signature = "burn()"
+
+[[spam]]
+[spam.tx]
+to = "{pool}"
+signature = "swap(uint256)"
+args = ["100"]
+kind = "swap"
+gas_limit = 90000
+"#,
+        );
+        let changes = diff_scenarios(&a, &b).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                ScenarioChange::Changed {
+                    path: "env.amount".to_owned(),
+                    old: "\"100\"".to_owned(),
+                    new: "\"200\"".to_owned(),
+                },
+                ScenarioChange::Removed {
+                    path: "env.token".to_owned(),
+                    value: "\"weth\"".to_owned(),
+                },
+                ScenarioChange::Added {
+                    path: "spam[tx swap].gas_limit".to_owned(),
+                    value: "90000".to_owned(),
+                },
+                ScenarioChange::Removed {
+                    path: "spam[tx mint() @ {pool}]".to_owned(),
+                    value: fmt_value(&spam_step(&a, 1)),
+                },
+                ScenarioChange::Added {
+                    path: "spam[tx burn() @ {pool}]".to_owned(),
+                    value: fmt_value(&spam_step(&b, 0)),
+                },
+            ]
+        );
+        assert!(diff_scenarios(&a, &a).unwrap().is_empty());
+    }
+
+    fn spam_step(config: &TestConfig, idx: usize) -> Value {
+        serde_json::to_value(config).unwrap()["spam"][idx].to_owned()
+    }
+
+    #[test]
+    fn numbers_repeated_step_labels() {
+        let steps = serde_json::json!([
+            { "name": "token" },
+            { "name": "token" },
+            { "name": "pool" },
+            { "name": "token" },
+        ]);
+        let labels = labeled_steps("create", &steps)
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["token", "token #2", "pool", "token #3"]);
+    }
+}
@@ -1,40 +1,48 @@
 use alloy::{
-    network::AnyNetwork,
     primitives::utils::{format_ether, parse_ether},
-    providers::ProviderBuilder,
+    providers::Provider,
     signers::local::PrivateKeySigner,
     transports::http::reqwest::Url,
 };
 use contender_core::{
     agent_controller::{AgentStore, SignerStore},
+    db::DbOps,
     error::ContenderError,
     generator::RandSeed,
+    provider::{any_provider, eth_provider},
     test_scenario::TestScenario,
 };
 use contender_testfile::TestConfig;
 use std::str::FromStr;
 
 use crate::util::{
-    check_private_keys_fns, find_insufficient_balances, fund_accounts, get_create_pools,
-    get_setup_pools, get_signers_with_defaults,
+    check_chain_id, check_private_keys_fns, find_insufficient_balances, fund_accounts,
+    get_create_pools, get_safe_owner_pools, get_setup_pools, get_signers_with_defaults,
 };
 
 pub async fn setup(
-    db: &(impl contender_core::db::DbOps + Clone + Send + Sync + 'static),
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
     testfile: impl AsRef<str>,
     rpc_url: impl AsRef<str>,
+    ws_url: Option<String>,
     private_keys: Option<Vec<String>>,
     min_balance: String,
     seed: RandSeed,
+    yes_i_know: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let url = Url::parse(rpc_url.as_ref()).expect("Invalid RPC URL");
-    let rpc_client = ProviderBuilder::new()
-        .network::<AnyNetwork>()
-        .on_http(url.to_owned());
-    let eth_client = ProviderBuilder::new().on_http(url.to_owned());
-    let testconfig: TestConfig = TestConfig::from_file(testfile.as_ref())?;
+    let rpc_client = any_provider(&url).await?;
+    let eth_client = eth_provider(&url).await?;
+    let mut testconfig: TestConfig = TestConfig::from_file(testfile.as_ref())?;
+    testconfig.resolve_aliases(|name| db.get_alias(name))?;
     let min_balance = parse_ether(&min_balance)?;
 
+    check_chain_id(
+        testconfig.chain_id,
+        rpc_client.get_chain_id().await?,
+        yes_i_know,
+    )?;
+
     let user_signers = private_keys
         .as_ref()
         .unwrap_or(&vec![])
@@ -98,9 +106,17 @@ pub async fn setup(
     ]
     .concat();
 
+    // Safe owners only sign the Safe's txs, so they aren't funded
+    for (owner_pool, num_owners) in &get_safe_owner_pools(&testconfig) {
+        if !agents.has_agent(owner_pool) {
+            let agent = SignerStore::new_random(*num_owners, &seed, owner_pool);
+            agents.add_agent(owner_pool, agent);
+        }
+    }
+
     let admin_signer = &user_signers_with_defaults[0];
 
-    fund_accounts(
+    let funding_txs = fund_accounts(
         &all_signer_addrs,
         admin_signer,
         &rpc_client,
@@ -108,6 +124,7 @@ pub async fn setup(
         min_balance,
     )
     .await?;
+    db.insert_funding_txs(None, &funding_txs)?;
 
     let mut scenario = TestScenario::new(
         testconfig.to_owned(),
@@ -119,6 +136,13 @@ pub async fn setup(
         agents,
     )
     .await?;
+    scenario.scenario_name = Some(testfile.as_ref().to_owned());
+    scenario.namespace = Some(testconfig.namespace_for(testfile.as_ref()));
+    if let Some(ws_url) = ws_url {
+        scenario
+            .connect_ws(Url::parse(&ws_url).expect("Invalid WebSocket URL"))
+            .await?;
+    }
 
     scenario.deploy_contracts().await?;
     println!("Finished deploying contracts. Running setup txs...");
@@ -1,41 +1,126 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use alloy::{
-    network::AnyNetwork,
     primitives::{
         utils::{format_ether, parse_ether},
-        U256,
+        Address, U256,
     },
-    providers::{Provider, ProviderBuilder},
+    providers::Provider,
     transports::http::reqwest::Url,
 };
 use contender_core::{
     agent_controller::{AgentStore, SignerStore},
-    db::DbOps,
-    error::ContenderError,
-    generator::{seeder::Seeder, types::AnyProvider, Generator, PlanType, RandSeed},
-    spammer::{BlockwiseSpammer, ExecutionPayload, Spammer, TimedSpammer},
+    db::{DbOps, RunArtifacts, RunProvenance},
+    error::{ContenderError, RetryPolicy},
+    generator::{
+        multicall::MULTICALL3_ADDRESS, seeder::Seeder, types::AnyProvider, Generator, PlanType,
+        RandSeed,
+    },
+    provider::{any_provider, eth_provider, Routing},
+    spammer::{
+        tx_actor::TxActorConfig, Backpressure, BlockwiseSpammer, ErrorRateLimit, ExecutionPayload,
+        FeeBumpPolicy, Ramp, RampSpammer, Spammer, TimedSpammer,
+    },
     test_scenario::TestScenario,
 };
 use contender_testfile::TestConfig;
+use serde::{Deserialize, Serialize};
 
+use super::{control::ControlHandlers, metrics_server::MetricsServer};
 use crate::util::{
-    check_private_keys, fund_accounts, get_signers_with_defaults, get_spam_pools,
-    spam_callback_default, SpamCallbackType,
+    check_chain_id, check_private_keys, deploy_multicall, fund_accounts, get_balances,
+    get_run_accounts, get_run_metadata, get_safe_owner_pools, get_signers_with_defaults,
+    get_spam_pools, get_spam_relayer_pools, spam_callback_default, wait_for_empty_mempool,
+    SpamCallbackType,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SpamCommandArgs {
     pub testfile: String,
     pub rpc_url: String,
     pub builder_url: Option<String>,
+    /// WebSocket URL of the node at `rpc_url` to send txs over, instead of HTTP.
+    #[serde(default)]
+    pub ws_url: Option<String>,
+    /// More endpoints of the same chain to spread spam txs across, alongside `rpc_url`.
+    #[serde(default)]
+    pub spam_rpc_urls: Vec<String>,
+    /// How spam txs are spread across `rpc_url` & `spam_rpc_urls`.
+    #[serde(default)]
+    pub routing: Routing,
     pub txs_per_block: Option<usize>,
     pub txs_per_second: Option<usize>,
+    /// Step the send rate (in txs per second) over the run instead of sending at `txs_per_second`.
+    #[serde(default)]
+    pub ramp: Option<Ramp>,
     pub duration: Option<usize>,
     pub seed: String,
+    /// Not saved with the run's provenance; they must be passed again when reproducing a run.
+    #[serde(skip)]
     pub private_keys: Option<Vec<String>>,
     pub disable_reports: bool,
+    /// Only count accepted & rejected sends, without tracking receipts or writing to the DB.
+    #[serde(default)]
+    pub no_track: bool,
     pub min_balance: String,
+    pub snapshot_revert: bool,
+    pub yes_i_know: bool,
+    pub tx_queue_capacity: usize,
+    pub max_pending_txs: Option<usize>,
+    /// Pause spamming while too many txs are pending. Off if `None`.
+    #[serde(default)]
+    pub backpressure: Option<Backpressure>,
+    /// Send unsigned txs via `eth_sendTransaction` from accounts unlocked on the node.
+    #[serde(default)]
+    pub unlocked: bool,
+    /// Send each account's txs in nonce order, with at most one in flight.
+    #[serde(default)]
+    pub strict_ordering: bool,
+    /// Re-broadcast stuck txs with bumped fees. Off if `None`.
+    #[serde(default)]
+    pub fee_bump: Option<FeeBumpPolicy>,
+    /// Timeout & retries of tx sends.
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// Number of accounts in each `relayer_pool`.
+    #[serde(default = "default_num_relayers")]
+    pub num_relayers: usize,
+    /// Interval to log progress at while spamming. Off if `None`.
+    #[serde(default)]
+    pub stats_interval: Option<Duration>,
+    /// Gas to spend on landed txs before the run ends. If set, the `duration` periods of txs are
+    /// sent repeatedly until it's used.
+    #[serde(default)]
+    pub total_gas: Option<u128>,
+    /// Exact number of txs to send. If set, `duration` is ignored.
+    #[serde(default)]
+    pub total_txs: Option<usize>,
+    /// Stop spamming when the error rate exceeds this limit. Off if `None`.
+    #[serde(default)]
+    pub abort_on_error_rate: Option<ErrorRateLimit>,
+    /// Time to spam for, unrecorded, before the measured run.
+    #[serde(default)]
+    pub warmup: Option<Duration>,
+    /// Time to keep tracking pending txs after the last tx is sent.
+    #[serde(default)]
+    pub cooldown: Option<Duration>,
+    /// Wait until the node's mempool holds at most this many txs before finishing the run.
+    #[serde(default)]
+    pub wait_for_empty_mempool: Option<u64>,
+    /// Baseline series to append the run to. Not saved with the run's provenance, so reproduced
+    /// runs aren't added to the series.
+    #[serde(skip)]
+    pub baseline: Option<String>,
+    /// Unix socket to accept pause/resume commands on while spamming. Not saved with the run.
+    #[serde(skip)]
+    pub control_socket: Option<String>,
+    /// Address to serve the tx actor's metrics on while spamming. Not saved with the run.
+    #[serde(skip)]
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+fn default_num_relayers() -> usize {
+    4
 }
 
 /// Runs spammer and returns run ID.
@@ -43,18 +128,74 @@ pub async fn spam(
     db: &(impl DbOps + Clone + Send + Sync + 'static),
     args: SpamCommandArgs,
 ) -> Result<u64, Box<dyn std::error::Error>> {
-    let testconfig = TestConfig::from_file(&args.testfile)?;
+    let mut testconfig = TestConfig::from_file(&args.testfile)?;
+    testconfig.resolve_aliases(|name| db.get_alias(name))?;
+    // saved with the run so it can be reproduced exactly
+    let provenance = RunProvenance {
+        scenario: std::fs::read_to_string(&args.testfile)?,
+        args: serde_json::to_string(&args)?,
+    };
+    let artifacts = RunArtifacts::new(testconfig.snapshot()?);
     let rand_seed = RandSeed::seed_from_str(&args.seed);
     let url = Url::parse(&args.rpc_url).expect("Invalid RPC URL");
-    let rpc_client = ProviderBuilder::new()
-        .network::<AnyNetwork>()
-        .on_http(url.to_owned());
-    let eth_client = ProviderBuilder::new().on_http(url.to_owned());
+    let rpc_client = any_provider(&url).await?;
+    let eth_client = eth_provider(&url).await?;
 
-    let duration = args.duration.unwrap_or_default();
+    // phases defined by the scenario, or the steps of a ramp, replace the duration & rate
+    let phases = match &args.ramp {
+        Some(_)
+            if testconfig
+                .phases
+                .as_ref()
+                .is_some_and(|phases| !phases.is_empty()) =>
+        {
+            return Err(ContenderError::SpamError(
+                "a ramp can't be combined with the scenario's [[phases]]",
+                None,
+            )
+            .into());
+        }
+        Some(ramp) => ramp.phases(args.duration.unwrap_or_default()),
+        None => testconfig.phases.to_owned().unwrap_or_default(),
+    };
+    let phase_txs =
+        (!phases.is_empty()).then(|| phases.iter().map(|phase| phase.rate * phase.duration).sum());
+    let duration = match args.total_txs {
+        _ if !phases.is_empty() => phases.iter().map(|phase| phase.duration).sum(),
+        // the last period may be partial
+        Some(total_txs) => {
+            let txs_per_period = args.txs_per_block.or(args.txs_per_second).unwrap_or(1);
+            total_txs.div_ceil(txs_per_period.max(1))
+        }
+        None => args.duration.unwrap_or_default(),
+    };
     let min_balance = parse_ether(&args.min_balance)?;
 
+    check_chain_id(
+        testconfig.chain_id,
+        rpc_client.get_chain_id().await?,
+        args.yes_i_know,
+    )?;
+
     let user_signers = get_signers_with_defaults(args.private_keys);
+
+    // Multicall3 isn't deployed on fresh dev chains; deploy a stand-in there
+    if let Some(multicall) = testconfig.multicall.as_mut() {
+        if let Ok(address) = multicall.address.parse::<Address>() {
+            if rpc_client.get_code_at(address).await?.is_empty() {
+                if address != MULTICALL3_ADDRESS.parse::<Address>()? {
+                    return Err(ContenderError::SpamError(
+                        "multicall contract not found at `multicall.address`",
+                        Some(address.to_string()),
+                    )
+                    .into());
+                }
+                let address = deploy_multicall(&user_signers[0], &rpc_client, &eth_client).await?;
+                multicall.address = address.to_string();
+            }
+        }
+    }
+
     let spam = testconfig
         .spam
         .as_ref()
@@ -63,10 +204,20 @@ pub async fn spam(
     // distill all from_pool arguments from the spam requests
     let from_pool_declarations = get_spam_pools(&testconfig);
 
+    let relayer_pools = get_spam_relayer_pools(&testconfig);
+
     let mut agents = AgentStore::new();
     let signers_per_period = args
         .txs_per_block
-        .unwrap_or(args.txs_per_second.unwrap_or(spam.len()));
+        .or(args.txs_per_second)
+        .or(args.ramp.map(|ramp| ramp.start_rate.max(ramp.end_rate)))
+        .unwrap_or(spam.len());
+
+    // relayers send the txs signed by from_pool accounts, so there are only a few of them
+    for relayer_pool in &relayer_pools {
+        let agent = SignerStore::new_random(args.num_relayers.max(1), &rand_seed, relayer_pool);
+        agents.add_agent(relayer_pool, agent);
+    }
 
     for from_pool in &from_pool_declarations {
         if agents.has_agent(from_pool) {
@@ -93,17 +244,75 @@ pub async fn spam(
     ]
     .concat();
 
-    check_private_keys(&testconfig, &user_signers);
+    // Safe owners only sign the Safe's txs, so they aren't funded
+    for (owner_pool, num_owners) in &get_safe_owner_pools(&testconfig) {
+        if !agents.has_agent(owner_pool) {
+            let agent = SignerStore::new_random(*num_owners, &rand_seed, owner_pool);
+            agents.add_agent(owner_pool, agent);
+        }
+    }
+
+    if args.unlocked {
+        // the node signs unlocked txs, so we can't re-sign them with bumped fees
+        if args.fee_bump.is_some() {
+            return Err(ContenderError::SpamError(
+                "fee bumping (--pending-tx-timeout) is not supported in unlocked mode",
+                None,
+            )
+            .into());
+        }
+        // pool accounts are generated locally, so the node can't sign for them
+        if !from_pool_declarations.is_empty() {
+            return Err(ContenderError::SpamError(
+                "from_pool is not supported in unlocked mode; set `from` to an account unlocked on the node",
+                Some(from_pool_declarations.join(", ")),
+            )
+            .into());
+        }
+        if !relayer_pools.is_empty() {
+            return Err(ContenderError::SpamError(
+                "relayer_pool is not supported in unlocked mode",
+                Some(relayer_pools.join(", ")),
+            )
+            .into());
+        }
+    } else {
+        check_private_keys(&testconfig, &user_signers);
+    }
 
     if args.txs_per_block.is_some() && args.txs_per_second.is_some() {
         panic!("Cannot set both --txs-per-block and --txs-per-second");
     }
-    if args.txs_per_block.is_none() && args.txs_per_second.is_none() {
-        panic!("Must set either --txs-per-block (--tpb) or --txs-per-second (--tps)");
+    if args.txs_per_block.is_none() && args.txs_per_second.is_none() && args.ramp.is_none() {
+        panic!("Must set either --txs-per-block (--tpb), --txs-per-second (--tps), or a ramp (--tps-start)");
+    }
+
+    // landed gas is counted as txs are saved to the DB
+    if args.total_gas.is_some() && (args.disable_reports || args.no_track) {
+        return Err(ContenderError::SpamError(
+            "--total-gas can't be used with --disable-reports or --no-track",
+            None,
+        )
+        .into());
+    }
+    if args.no_track {
+        let conflicting = [
+            (args.fee_bump.is_some(), "--pending-tx-timeout"),
+            (args.cooldown.is_some(), "--cooldown"),
+            (args.baseline.is_some(), "--baseline"),
+        ];
+        if let Some((_, flag)) = conflicting.iter().find(|(set, _)| *set) {
+            return Err(ContenderError::SpamError(
+                "--no-track doesn't track txs after they're sent",
+                Some(format!("{} can't be used with --no-track", flag)),
+            )
+            .into());
+        }
     }
 
     let mut run_id = 0;
 
+    let namespace = testconfig.namespace_for(&args.testfile);
     let mut scenario = TestScenario::new(
         testconfig,
         db.clone().into(),
@@ -115,85 +324,261 @@ pub async fn spam(
         agents,
     )
     .await?;
+    scenario.set_tx_actor_config(TxActorConfig {
+        queue_capacity: args.tx_queue_capacity,
+        max_cache_size: args.max_pending_txs,
+    });
+    if let Some(ws_url) = &args.ws_url {
+        scenario
+            .connect_ws(Url::parse(ws_url).expect("Invalid WebSocket URL"))
+            .await?;
+    }
+    if !args.spam_rpc_urls.is_empty() {
+        let urls = args
+            .spam_rpc_urls
+            .iter()
+            .map(|url| Url::parse(url).expect("Invalid RPC URL"))
+            .collect::<Vec<_>>();
+        scenario.route_spam(&urls, args.routing).await?;
+    }
+    scenario.unlocked = args.unlocked;
+    scenario.strict_ordering = args.strict_ordering;
+    scenario.fee_bump = args.fee_bump;
+    scenario.retry_policy = args.retry_policy.to_owned();
+    scenario.stats_interval = args.stats_interval;
+    scenario.gas_budget = args.total_gas;
+    scenario.total_txs = args.total_txs;
+    scenario.error_rate_limit = args.abort_on_error_rate;
+    scenario.warmup = args.warmup;
+    scenario.cooldown = args.cooldown;
+    scenario.backpressure = args.backpressure;
+    let _control_handlers =
+        ControlHandlers::spawn(scenario.control.clone(), args.control_socket.to_owned())?;
+    let _metrics_server = match args.metrics_addr {
+        Some(addr) => {
+            let msg_handle = scenario.msg_handle.clone();
+            Some(MetricsServer::spawn(addr, move || msg_handle.metrics()).await?)
+        }
+        None => None,
+    };
+    scenario.no_track = args.no_track;
+    scenario.namespace = Some(namespace);
 
-    let total_cost =
-        get_max_spam_cost(scenario.to_owned(), &rpc_client).await? * U256::from(duration);
-    if min_balance < U256::from(total_cost) {
-        return Err(ContenderError::SpamError(
-            "min_balance is not enough to cover the cost of the spam transactions",
-            format!(
-                "min_balance: {}, total_cost: {}",
-                format_ether(min_balance),
-                format_ether(total_cost)
+    // unlocked accounts are managed by the node, so we don't check or top up their balances
+    let funding_txs = if args.unlocked {
+        println!("unlocked mode: txs will be signed by the node; skipping funding");
+        vec![]
+    } else {
+        let total_cost =
+            get_max_spam_cost(scenario.to_owned(), &rpc_client).await? * U256::from(duration);
+        if min_balance < U256::from(total_cost) {
+            return Err(ContenderError::SpamError(
+                "min_balance is not enough to cover the cost of the spam transactions",
+                format!(
+                    "min_balance: {}, total_cost: {}",
+                    format_ether(min_balance),
+                    format_ether(total_cost)
+                )
+                .into(),
             )
-            .into(),
+            .into());
+        }
+        fund_accounts(
+            &all_signer_addrs,
+            &user_signers[0],
+            &rpc_client,
+            &eth_client,
+            min_balance,
         )
-        .into());
-    }
+        .await?
+    };
 
-    fund_accounts(
-        &all_signer_addrs,
-        &user_signers[0],
-        &rpc_client,
-        &eth_client,
-        min_balance,
-    )
-    .await?;
+    // take a snapshot so the chain can be reset once the results are collected
+    let snapshot_id = if args.snapshot_revert {
+        let id = scenario.evm_snapshot().await?;
+        println!("took evm snapshot {}", id);
+        Some(id)
+    } else {
+        None
+    };
 
-    // trigger blockwise spammer
-    if let Some(txs_per_block) = args.txs_per_block {
-        println!("Blockwise spamming with {} txs per block", txs_per_block);
-        let spammer = BlockwiseSpammer {};
-
-        match spam_callback_default(!args.disable_reports, Arc::new(rpc_client).into()).await {
-            SpamCallbackType::Log(cback) => {
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("Time went backwards")
-                    .as_millis();
-                run_id =
-                    db.insert_run(timestamp as u64, txs_per_block * duration, &args.testfile)?;
-                spammer
-                    .spam_rpc(
-                        &mut scenario,
-                        txs_per_block,
-                        duration,
-                        Some(run_id),
-                        cback.into(),
-                    )
-                    .await?;
+    // the run is wrapped so the chain is reverted whether it succeeds or not
+    let res = async {
+        let run_metadata = get_run_metadata(&rpc_client).await;
+        let run_accounts = get_run_accounts(&user_signers, &scenario.agent_store);
+        let start_balances = get_balances(&run_accounts, &rpc_client).await?;
+
+        // trigger blockwise spammer
+        if let Some(txs_per_block) = args.txs_per_block {
+            println!("Blockwise spamming with {} txs per block", txs_per_block);
+            let spammer = BlockwiseSpammer {};
+
+            match spam_callback_default(
+                !(args.disable_reports || args.no_track),
+                Arc::new(rpc_client.clone()).into(),
+            )
+            .await
+            {
+                SpamCallbackType::Log(cback) => {
+                    let timestamp = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .expect("Time went backwards")
+                        .as_millis();
+                    run_id = db.insert_run(
+                        timestamp as u64,
+                        args.total_txs
+                            .or(phase_txs)
+                            .unwrap_or(txs_per_block * duration),
+                        &args.testfile,
+                        &args.rpc_url,
+                    )?;
+                    db.insert_run_metadata(run_id, &run_metadata)?;
+                    db.insert_run_provenance(run_id, &provenance)?;
+                    db.insert_run_artifacts(run_id, &artifacts)?;
+                    db.insert_run_accounts(run_id, &run_accounts)?;
+                    db.insert_funding_txs(Some(run_id), &funding_txs)?;
+                    db.insert_balances(run_id, true, &start_balances)?;
+                    spammer
+                        .spam_rpc(
+                            &mut scenario,
+                            txs_per_block,
+                            duration,
+                            Some(run_id),
+                            cback.into(),
+                        )
+                        .await?;
+                }
+                SpamCallbackType::Nil(cback) => {
+                    spammer
+                        .spam_rpc(&mut scenario, txs_per_block, duration, None, cback.into())
+                        .await?;
+                }
+            };
+        } else if let Some(ramp) = args.ramp {
+            // trigger ramp spammer
+            println!(
+                "Ramp spamming from {} to {} txs per second, stepping every {} seconds",
+                ramp.start_rate, ramp.end_rate, ramp.step_periods
+            );
+            let spammer = RampSpammer::new(Duration::from_secs(1), ramp);
+            match spam_callback_default(
+                !(args.disable_reports || args.no_track),
+                Arc::new(rpc_client.clone()).into(),
+            )
+            .await
+            {
+                SpamCallbackType::Log(cback) => {
+                    let timestamp = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .expect("Time went backwards")
+                        .as_millis();
+                    run_id = db.insert_run(
+                        timestamp as u64,
+                        phase_txs.unwrap_or_default(),
+                        &args.testfile,
+                        &args.rpc_url,
+                    )?;
+                    db.insert_run_metadata(run_id, &run_metadata)?;
+                    db.insert_run_provenance(run_id, &provenance)?;
+                    db.insert_run_artifacts(run_id, &artifacts)?;
+                    db.insert_run_accounts(run_id, &run_accounts)?;
+                    db.insert_funding_txs(Some(run_id), &funding_txs)?;
+                    db.insert_balances(run_id, true, &start_balances)?;
+                    spammer
+                        .spam_rpc(
+                            &mut scenario,
+                            ramp.start_rate,
+                            duration,
+                            Some(run_id),
+                            cback.into(),
+                        )
+                        .await?;
+                }
+                SpamCallbackType::Nil(cback) => {
+                    spammer
+                        .spam_rpc(&mut scenario, ramp.start_rate, duration, None, cback.into())
+                        .await?;
+                }
+            };
+        } else {
+            // trigger timed spammer
+            let tps = args.txs_per_second.unwrap_or(10);
+            println!("Timed spamming with {} txs per second", tps);
+            let interval = std::time::Duration::from_secs(1);
+            let spammer = TimedSpammer::new(interval);
+            match spam_callback_default(
+                !(args.disable_reports || args.no_track),
+                Arc::new(rpc_client.clone()).into(),
+            )
+            .await
+            {
+                SpamCallbackType::Log(cback) => {
+                    let timestamp = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .expect("Time went backwards")
+                        .as_millis();
+                    run_id = db.insert_run(
+                        timestamp as u64,
+                        args.total_txs.or(phase_txs).unwrap_or(tps * duration),
+                        &args.testfile,
+                        &args.rpc_url,
+                    )?;
+                    db.insert_run_metadata(run_id, &run_metadata)?;
+                    db.insert_run_provenance(run_id, &provenance)?;
+                    db.insert_run_artifacts(run_id, &artifacts)?;
+                    db.insert_run_accounts(run_id, &run_accounts)?;
+                    db.insert_funding_txs(Some(run_id), &funding_txs)?;
+                    db.insert_balances(run_id, true, &start_balances)?;
+                    spammer
+                        .spam_rpc(&mut scenario, tps, duration, Some(run_id), cback.into())
+                        .await?;
+                }
+                SpamCallbackType::Nil(cback) => {
+                    spammer
+                        .spam_rpc(&mut scenario, tps, duration, None, cback.into())
+                        .await?;
+                }
+            };
+        }
+
+        if let Some(router) = &scenario.spam_router {
+            println!("spam endpoints:");
+            for endpoint in router.stats() {
+                println!(
+                    "  {}\t{} txs\tavg. {:.1} ms",
+                    endpoint.url, endpoint.num_requests, endpoint.avg_latency_ms
+                );
             }
-            SpamCallbackType::Nil(cback) => {
-                spammer
-                    .spam_rpc(&mut scenario, txs_per_block, duration, None, cback.into())
-                    .await?;
+        }
+
+        if let Some(max_txs) = args.wait_for_empty_mempool {
+            wait_for_empty_mempool(&rpc_client, max_txs).await?;
+        }
+
+        // balances must be taken before reverting the chain
+        if run_id != 0 {
+            let end_balances = get_balances(&run_accounts, &rpc_client).await?;
+            db.insert_balances(run_id, false, &end_balances)?;
+            if let Some(series) = &args.baseline {
+                db.insert_baseline_run(series, run_id)?;
             }
-        };
-        return Ok(run_id);
+        }
+
+        Ok::<_, Box<dyn std::error::Error>>(())
     }
+    .await;
 
-    // trigger timed spammer
-    let tps = args.txs_per_second.unwrap_or(10);
-    println!("Timed spamming with {} txs per second", tps);
-    let interval = std::time::Duration::from_secs(1);
-    let spammer = TimedSpammer::new(interval);
-    match spam_callback_default(!args.disable_reports, Arc::new(rpc_client).into()).await {
-        SpamCallbackType::Log(cback) => {
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .expect("Time went backwards")
-                .as_millis();
-            run_id = db.insert_run(timestamp as u64, tps * duration, &args.testfile)?;
-            spammer
-                .spam_rpc(&mut scenario, tps, duration, Some(run_id), cback.into())
-                .await?;
-        }
-        SpamCallbackType::Nil(cback) => {
-            spammer
-                .spam_rpc(&mut scenario, tps, duration, None, cback.into())
-                .await?;
+    if let Some(snapshot_id) = snapshot_id {
+        match scenario.evm_revert(snapshot_id).await {
+            Ok(_) => println!("reverted to evm snapshot {}", snapshot_id),
+            // don't hide the error that stopped the run
+            Err(e) if res.is_err() => {
+                eprintln!("failed to revert to evm snapshot {}: {}", snapshot_id, e)
+            }
+            Err(e) => return Err(e.into()),
         }
-    };
+    }
+    res?;
 
     Ok(run_id)
 }
@@ -233,6 +618,7 @@ async fn get_max_spam_cost<D: DbOps + Send + Sync + 'static, S: Seeder + Send +
         .map(|ex_payload| match ex_payload {
             ExecutionPayload::SignedTx(_envelope, tx_req) => vec![tx_req.to_owned()],
             ExecutionPayload::SignedTxBundle(_envelopes, tx_reqs) => tx_reqs.to_vec(),
+            ExecutionPayload::UnsignedTx(_tx, tx_req) => vec![tx_req.to_owned()],
         })
         .collect::<Vec<_>>()
         .concat();
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    primitives::{keccak256, Address, B256, U256},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol,
+    sol_types::SolEvent,
+};
+use contender_core::generator::types::EthProvider;
+
+/// Address of the L2ToL2CrossDomainMessenger predeploy on OP interop chains.
+pub const L2_TO_L2_MESSENGER_ADDRESS: &str = "0x4200000000000000000000000000000000000023";
+
+sol! {
+    event SentMessage(
+        uint256 indexed destination,
+        address indexed target,
+        uint256 indexed messageNonce,
+        address sender,
+        bytes message
+    );
+}
+
+/// `RelayedMessage` signatures, before and after `returnDataHash` was added to the event.
+const RELAYED_MESSAGE_SIGNATURES: [&str; 2] = [
+    "RelayedMessage(uint256,uint256,bytes32)",
+    "RelayedMessage(uint256,uint256,bytes32,bytes32)",
+];
+
+/// How often the destination chain is polled for relayed messages.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Relay latencies of the messages sent during a run.
+#[derive(Debug, Default)]
+pub struct RelayStats {
+    pub num_sent: usize,
+    /// Seconds between the source block a message was sent in and the destination block it was
+    /// relayed in, sorted ascending.
+    pub latencies: Vec<u64>,
+}
+
+impl RelayStats {
+    /// Number of sent messages which weren't relayed.
+    pub fn num_unrelayed(&self) -> usize {
+        self.num_sent - self.latencies.len()
+    }
+
+    /// Returns the latency at percentile `p` (0-100) of relayed messages.
+    pub fn percentile(&self, p: usize) -> Option<u64> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let idx = (self.latencies.len() - 1) * p.min(100) / 100;
+        Some(self.latencies[idx])
+    }
+}
+
+/// Returns the timestamp of each log's block, keyed by block number.
+async fn block_timestamps(
+    provider: &EthProvider,
+    logs: &[Log],
+) -> Result<HashMap<u64, u64>, Box<dyn std::error::Error>> {
+    let mut timestamps = HashMap::new();
+    for block_num in logs.iter().filter_map(|log| log.block_number) {
+        if timestamps.contains_key(&block_num) {
+            continue;
+        }
+        let block = provider
+            .get_block_by_number(block_num.into(), false)
+            .await?
+            .ok_or(format!("block {} not found", block_num))?;
+        timestamps.insert(block_num, block.header.timestamp);
+    }
+    Ok(timestamps)
+}
+
+/// Returns the nonce of a message log (its third topic) with its block's timestamp.
+fn nonce_timestamps(logs: &[Log], timestamps: &HashMap<u64, u64>) -> HashMap<U256, u64> {
+    logs.iter()
+        .filter_map(|log| {
+            let nonce = log.topics().get(2)?;
+            let timestamp = timestamps.get(&log.block_number?)?;
+            Some((U256::from_be_bytes(nonce.0), *timestamp))
+        })
+        .collect()
+}
+
+/// Returns the send times of messages to `dest_chain_id` sent by `senders` between `from_block`
+/// and `to_block` on the source chain, keyed by message nonce.
+pub async fn sent_messages(
+    provider: &EthProvider,
+    from_block: u64,
+    to_block: u64,
+    dest_chain_id: u64,
+    senders: &[Address],
+) -> Result<HashMap<U256, u64>, Box<dyn std::error::Error>> {
+    let filter = Filter::new()
+        .address(L2_TO_L2_MESSENGER_ADDRESS.parse::<Address>()?)
+        .event_signature(SentMessage::SIGNATURE_HASH)
+        .topic1(B256::from(U256::from(dest_chain_id)))
+        .from_block(from_block)
+        .to_block(to_block);
+    let logs = provider
+        .get_logs(&filter)
+        .await?
+        .into_iter()
+        .filter(|log| {
+            SentMessage::decode_log_data(log.data(), true)
+                .map(|event| senders.contains(&event.sender))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    let timestamps = block_timestamps(provider, &logs).await?;
+    Ok(nonce_timestamps(&logs, &timestamps))
+}
+
+/// Returns the relay times of messages from `source_chain_id` relayed since `from_block` on the
+/// destination chain, keyed by message nonce.
+pub async fn relayed_messages(
+    provider: &EthProvider,
+    from_block: u64,
+    source_chain_id: u64,
+) -> Result<HashMap<U256, u64>, Box<dyn std::error::Error>> {
+    let filter = Filter::new()
+        .address(L2_TO_L2_MESSENGER_ADDRESS.parse::<Address>()?)
+        .event_signature(
+            RELAYED_MESSAGE_SIGNATURES
+                .iter()
+                .map(|sig| keccak256(sig.as_bytes()))
+                .collect::<Vec<_>>(),
+        )
+        .topic1(B256::from(U256::from(source_chain_id)))
+        .from_block(from_block);
+    let logs = provider.get_logs(&filter).await?;
+    let timestamps = block_timestamps(provider, &logs).await?;
+    Ok(nonce_timestamps(&logs, &timestamps))
+}
+
+/// Compares send times to relay times, by message nonce.
+pub fn relay_stats(sent: &HashMap<U256, u64>, relayed: &HashMap<U256, u64>) -> RelayStats {
+    let mut latencies = sent
+        .iter()
+        .filter_map(|(nonce, sent_at)| {
+            relayed
+                .get(nonce)
+                .map(|relayed_at| relayed_at.saturating_sub(*sent_at))
+        })
+        .collect::<Vec<_>>();
+    latencies.sort();
+    RelayStats {
+        num_sent: sent.len(),
+        latencies,
+    }
+}
+
+/// Polls the destination chain until every message in `sent` is relayed or `timeout` elapses.
+pub async fn wait_for_relays(
+    dest_provider: &EthProvider,
+    dest_from_block: u64,
+    source_chain_id: u64,
+    sent: &HashMap<U256, u64>,
+    timeout: Duration,
+) -> Result<RelayStats, Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    loop {
+        let relayed = relayed_messages(dest_provider, dest_from_block, source_chain_id).await?;
+        let stats = relay_stats(sent, &relayed);
+        if stats.num_unrelayed() == 0 || start.elapsed() >= timeout {
+            return Ok(stats);
+        }
+        println!(
+            "waiting for {}/{} messages to be relayed...",
+            stats.num_unrelayed(),
+            stats.num_sent
+        );
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_relay_latency_by_nonce() {
+        let sent = HashMap::from([
+            (U256::from(1), 100),
+            (U256::from(2), 102),
+            (U256::from(3), 104),
+        ]);
+        let relayed = HashMap::from([
+            (U256::from(1), 104),
+            (U256::from(2), 104),
+            (U256::from(9), 100),
+        ]);
+        let stats = relay_stats(&sent, &relayed);
+        assert_eq!(stats.num_sent, 3);
+        assert_eq!(stats.num_unrelayed(), 1);
+        assert_eq!(stats.latencies, vec![2, 4]);
+        assert_eq!(stats.percentile(0), Some(2));
+        assert_eq!(stats.percentile(100), Some(4));
+        assert_eq!(RelayStats::default().percentile(50), None);
+    }
+}
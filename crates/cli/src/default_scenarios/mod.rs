@@ -1,4 +1,6 @@
 mod bytecode;
+pub mod interop;
+pub mod rpc_mix;
 mod runconfig;
 
-pub use runconfig::{BuiltinScenario, BuiltinScenarioConfig};
+pub use runconfig::{BuiltinScenario, BuiltinScenarioConfig, PERMIT2_ADDRESS};
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use contender_core::{
+    error::ContenderError,
+    generator::types::{ReadBlockDefinition, ReadCallDefinition, ReadLogsDefinition, ReadRequest},
+};
+
+/// Relative weights of the RPC methods sent by the `rpc-mix` scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcMix {
+    pub get_block_by_number: u64,
+    pub get_logs: u64,
+    pub call: u64,
+    pub estimate_gas: u64,
+    pub send_raw_transaction: u64,
+}
+
+impl Default for RpcMix {
+    fn default() -> Self {
+        Self {
+            get_block_by_number: 30,
+            get_logs: 10,
+            call: 30,
+            estimate_gas: 10,
+            send_raw_transaction: 20,
+        }
+    }
+}
+
+impl FromStr for RpcMix {
+    type Err = ContenderError;
+
+    /// Parses comma-separated `method=weight` pairs, e.g. `getLogs=20,call=50`. The `eth_`
+    /// prefix is optional; methods that aren't listed keep their default weight.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mix = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let invalid = || ContenderError::SetupError("invalid rpc mix", Some(pair.to_owned()));
+            let (method, weight) = pair.split_once('=').ok_or_else(invalid)?;
+            let weight = weight.trim().parse::<u64>().map_err(|_| invalid())?;
+            let method = method.trim();
+            let method = method.strip_prefix("eth_").unwrap_or(method);
+            let slot = match method {
+                "getBlockByNumber" => &mut mix.get_block_by_number,
+                "getLogs" => &mut mix.get_logs,
+                "call" => &mut mix.call,
+                "estimateGas" => &mut mix.estimate_gas,
+                "sendRawTransaction" => &mut mix.send_raw_transaction,
+                _ => {
+                    return Err(ContenderError::SetupError(
+                        "unsupported rpc mix method",
+                        Some(method.to_owned()),
+                    ))
+                }
+            };
+            *slot = weight;
+        }
+        if mix.send_raw_transaction == 0 {
+            return Err(ContenderError::SetupError(
+                "sendRawTransaction must have a non-zero weight in the rpc mix",
+                Some(s.to_owned()),
+            ));
+        }
+        Ok(mix)
+    }
+}
+
+impl RpcMix {
+    fn total(&self) -> u64 {
+        self.get_block_by_number
+            + self.get_logs
+            + self.call
+            + self.estimate_gas
+            + self.send_raw_transaction
+    }
+
+    /// Splits `num_calls` RPCs per `interval` seconds into txs sent per interval & reads sent
+    /// per second, following the mix. At least one tx is sent per interval.
+    pub fn split(&self, num_calls: usize, interval: usize) -> (usize, usize) {
+        let total = self.total() as usize;
+        let num_reads = num_calls * (total - self.send_raw_transaction as usize) / total;
+        let num_txs = num_calls.saturating_sub(num_reads).max(1);
+        (num_txs, num_reads / interval.max(1))
+    }
+
+    /// Read steps of the mix, each repeated by its weight (reduced to the smallest equivalent
+    /// weights), so that cycling through them follows the mix. `contract` is the placeholder of
+    /// the contract that calls & gas estimates are sent to.
+    pub fn read_steps(&self, contract: &str) -> Vec<ReadRequest> {
+        let weights = [
+            self.get_block_by_number,
+            self.get_logs,
+            self.call,
+            self.estimate_gas,
+        ];
+        let divisor = weights
+            .iter()
+            .fold(0, |acc, weight| gcd(acc, *weight))
+            .max(1);
+        let consume_gas = ReadCallDefinition {
+            to: contract.to_owned(),
+            signature: "consumeGas(uint256 gas)".to_owned(),
+            args: Some(vec!["50000".to_owned()]),
+        };
+        let steps = [
+            ReadRequest::Block(ReadBlockDefinition {
+                lookback: None,
+                full_txs: None,
+            }),
+            ReadRequest::Logs(ReadLogsDefinition {
+                address: None,
+                event: None,
+                max_range: Some(10),
+                lookback: Some(1_000),
+            }),
+            ReadRequest::Call(consume_gas.to_owned()),
+            ReadRequest::EstimateGas(consume_gas),
+        ];
+        // interleave the methods so that every second gets a mix of them
+        let max_repeats = weights.iter().max().copied().unwrap_or_default() / divisor;
+        (0..max_repeats)
+            .flat_map(|round| {
+                steps
+                    .iter()
+                    .zip(weights)
+                    .filter(move |(_, weight)| round < weight / divisor)
+                    .map(|(step, _)| step.to_owned())
+            })
+            .collect()
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rpc_mix() {
+        let mix = RpcMix::from_str("eth_getLogs=20, call=50,sendRawTransaction=5").unwrap();
+        assert_eq!(
+            mix,
+            RpcMix {
+                get_logs: 20,
+                call: 50,
+                send_raw_transaction: 5,
+                ..Default::default()
+            }
+        );
+        assert!(RpcMix::from_str("eth_chainId=1").is_err());
+        assert!(RpcMix::from_str("call=fast").is_err());
+        assert!(RpcMix::from_str("sendRawTransaction=0").is_err());
+    }
+
+    #[test]
+    fn read_steps_follow_weights() {
+        let mix = RpcMix {
+            get_block_by_number: 30,
+            get_logs: 10,
+            call: 20,
+            estimate_gas: 0,
+            send_raw_transaction: 40,
+        };
+        let methods = mix
+            .read_steps("{SpamMe}")
+            .iter()
+            .map(|step| match step {
+                ReadRequest::Block(_) => "block",
+                ReadRequest::Logs(_) => "logs",
+                ReadRequest::Call(_) => "call",
+                _ => panic!("unexpected read step"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            vec!["block", "logs", "call", "block", "call", "block"]
+        );
+        // 40% of 100 calls per 2s interval are txs; the rest are reads, sent per second
+        assert_eq!(mix.split(100, 2), (40, 30));
+    }
+}
@@ -1,17 +1,27 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
-use alloy::primitives::Address;
-use contender_core::generator::types::{CreateDefinition, FunctionCallDefinition, SpamRequest};
+use alloy::primitives::{Address, U256};
+use contender_core::generator::{
+    eip712::{TypedDataDefinition, TypedDataDomain},
+    types::{CreateDefinition, FunctionCallDefinition, FuzzParam, SpamRequest},
+};
 use contender_testfile::TestConfig;
 use serde::{Deserialize, Serialize};
 
-use super::bytecode;
+use super::{bytecode, interop::L2_TO_L2_MESSENGER_ADDRESS, rpc_mix::RpcMix};
 
 #[derive(Serialize, Deserialize, Debug, Clone, clap::ValueEnum)]
 pub enum BuiltinScenario {
     FillBlock,
+    Permit2,
+    Oracle,
+    Interop,
+    RpcMix,
 }
 
+/// Address Permit2 is deployed to on most chains.
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
 impl Display for BuiltinScenarioConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -21,6 +31,20 @@ impl Display for BuiltinScenarioConfig {
                 sender: _,
                 fill_percent: _,
             } => write!(f, "fill-block"),
+            BuiltinScenarioConfig::Permit2 {
+                chain_id: _,
+                sender: _,
+            } => write!(f, "permit2"),
+            BuiltinScenarioConfig::Oracle {
+                num_txs: _,
+                sender: _,
+                reads_per_update: _,
+            } => write!(f, "oracle"),
+            BuiltinScenarioConfig::Interop {
+                dest_chain_id: _,
+                sender: _,
+            } => write!(f, "interop"),
+            BuiltinScenarioConfig::RpcMix { sender: _, mix: _ } => write!(f, "rpc-mix"),
         }
     }
 }
@@ -32,6 +56,23 @@ pub enum BuiltinScenarioConfig {
         sender: Address,
         fill_percent: u16,
     },
+    Permit2 {
+        chain_id: u64,
+        sender: Address,
+    },
+    Oracle {
+        num_txs: u64,
+        sender: Address,
+        reads_per_update: u64,
+    },
+    Interop {
+        dest_chain_id: u64,
+        sender: Address,
+    },
+    RpcMix {
+        sender: Address,
+        mix: RpcMix,
+    },
 }
 
 impl BuiltinScenarioConfig {
@@ -48,6 +89,62 @@ impl BuiltinScenarioConfig {
             fill_percent,
         }
     }
+
+    pub fn permit2(chain_id: u64, sender: Address) -> Self {
+        Self::Permit2 { chain_id, sender }
+    }
+
+    pub fn oracle(num_txs: u64, sender: Address, reads_per_update: u64) -> Self {
+        Self::Oracle {
+            num_txs,
+            sender,
+            reads_per_update,
+        }
+    }
+
+    pub fn interop(dest_chain_id: u64, sender: Address) -> Self {
+        Self::Interop {
+            dest_chain_id,
+            sender,
+        }
+    }
+
+    pub fn rpc_mix(sender: Address, mix: RpcMix) -> Self {
+        Self::RpcMix { sender, mix }
+    }
+
+    /// Name of the contract the scenario deploys.
+    pub fn contract_name(&self) -> &'static str {
+        match self {
+            BuiltinScenarioConfig::FillBlock { .. } | BuiltinScenarioConfig::RpcMix { .. } => {
+                "SpamMe"
+            }
+            BuiltinScenarioConfig::Permit2 { .. } => "SpamToken",
+            BuiltinScenarioConfig::Oracle { .. } => "PushOracle",
+            // predeployed; nothing is deployed
+            BuiltinScenarioConfig::Interop { .. } => "L2ToL2CrossDomainMessenger",
+        }
+    }
+
+    /// Agent pools the scenario sends from, with the number of accounts in each.
+    pub fn agent_pools(&self) -> Vec<(&'static str, usize)> {
+        match self {
+            BuiltinScenarioConfig::Oracle {
+                num_txs,
+                reads_per_update,
+                ..
+            } => {
+                // one account per tx sent each period
+                let num_reporters = (num_txs / (reads_per_update + 1)).max(1);
+                let num_consumers = num_txs.saturating_sub(num_reporters).max(1);
+                vec![
+                    ("reporters", num_reporters as usize),
+                    ("consumers", num_consumers as usize),
+                ]
+            }
+            _ => vec![],
+        }
+    }
 }
 
 impl From<BuiltinScenarioConfig> for TestConfig {
@@ -68,7 +165,10 @@ impl From<BuiltinScenarioConfig> for TestConfig {
                 let spam_txs = (0..num_txs)
                     .map(|_| {
                         SpamRequest::Tx(FunctionCallDefinition {
-                            to: "{SpamMe}".to_owned(),
+                            to: "{SpamMe}".into(),
+                            to_selection: None,
+                            relayer_pool: None,
+                            safe: None,
                             from: Some(sender.to_string()),
                             signature: "consumeGas(uint256 gas)".to_owned(),
                             from_pool: None,
@@ -76,22 +176,365 @@ impl From<BuiltinScenarioConfig> for TestConfig {
                             value: None,
                             fuzz: None,
                             kind: Some("fill-block".to_owned()),
+                            gas_limit: None,
+                            max_fee_per_gas: None,
+                            max_priority_fee_per_gas: None,
+                            rate: None,
+                            weight: None,
+                            expect_status: None,
+                            max_gas_used: None,
+                            route: None,
                         })
                     })
                     .collect::<Vec<_>>();
 
                 TestConfig {
+                    chain_id: None,
                     env: None,
+                    gas: None,
+                    defaults: None,
+                    multicall: None,
+                    phases: None,
+                    slo: None,
+                    namespace: None,
+                    read: None,
+                    eip712: None,
+                    sender_assignment: None,
+                    concurrent_setup: None,
                     create: Some(vec![CreateDefinition {
                         name: "SpamMe".to_owned(),
                         bytecode: bytecode::SPAM_ME.to_owned(),
                         from: Some(sender.to_string()),
                         from_pool: None,
+                        source: None,
+                        contract: None,
+                        abi: None,
                     }]),
                     setup: None,
                     spam: Some(spam_txs),
                 }
             }
+            BuiltinScenarioConfig::Permit2 { chain_id, sender } => {
+                let fncall = |to: &str, signature: &str, args: &[&str]| FunctionCallDefinition {
+                    to: to.into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
+                    from: Some(sender.to_string()),
+                    signature: signature.to_owned(),
+                    from_pool: None,
+                    args: Some(args.iter().map(|arg| arg.to_string()).collect()),
+                    value: None,
+                    fuzz: None,
+                    kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
+                };
+                let fuzz = |param: &str, min: U256, max: U256| FuzzParam {
+                    param: Some(param.to_owned()),
+                    value: None,
+                    min: Some(min),
+                    max: Some(max),
+                };
+
+                // let Permit2 move the sender's tokens
+                let approve = FunctionCallDefinition {
+                    kind: Some("permit2_approve".to_owned()),
+                    ..fncall(
+                        "{SpamToken}",
+                        "approve(address spender, uint256 amount) returns (bool)",
+                        &[PERMIT2_ADDRESS, &U256::MAX.to_string()],
+                    )
+                };
+
+                // the sender signs a permit for a random amount, nonce & deadline, then redeems it,
+                // sending the tokens to a burn address
+                let transfer = FunctionCallDefinition {
+                    kind: Some("permit2_transfer".to_owned()),
+                    fuzz: Some(vec![
+                        fuzz(
+                            "permit.permitted.amount",
+                            U256::from(1),
+                            U256::from(10).pow(U256::from(18)),
+                        ),
+                        // Permit2 nonces are unordered; random ones are all but certain to be unused
+                        fuzz("permit.nonce", U256::ZERO, U256::MAX),
+                        fuzz(
+                            "permit.deadline",
+                            U256::from(u32::MAX),
+                            U256::from(u64::MAX),
+                        ),
+                    ]),
+                    ..fncall(
+                        PERMIT2_ADDRESS,
+                        "permitTransferFrom(((address token, uint256 amount) permitted, uint256 nonce, uint256 deadline) permit, (address to, uint256 requestedAmount) transferDetails, address owner, bytes signature)",
+                        &[
+                            "(({SpamToken}, 1), 0, 0)",
+                            "(0x000000000000000000000000000000000000dEaD, 1)",
+                            "{_sender}",
+                            "{_sig712:PermitTransferFrom:permit.permitted,spender,permit.nonce,permit.deadline}",
+                        ],
+                    )
+                };
+
+                let permit = TypedDataDefinition {
+                    encode_type: "PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)".to_owned(),
+                    domain: TypedDataDomain {
+                        name: Some("Permit2".to_owned()),
+                        version: None,
+                        chain_id: Some(chain_id),
+                        verifying_contract: Some(PERMIT2_ADDRESS.to_owned()),
+                    },
+                    // Permit2 requires the spender to be the caller
+                    values: Some(HashMap::from([(
+                        "spender".to_owned(),
+                        "{_sender}".to_owned(),
+                    )])),
+                };
+
+                TestConfig {
+                    chain_id: Some(chain_id),
+                    env: Some(HashMap::from([(
+                        "initialSupply".to_owned(),
+                        format!("{:064x}", U256::MAX >> 8),
+                    )])),
+                    gas: None,
+                    defaults: None,
+                    multicall: None,
+                    phases: None,
+                    slo: None,
+                    namespace: None,
+                    read: None,
+                    eip712: Some(HashMap::from([("PermitTransferFrom".to_owned(), permit)])),
+                    sender_assignment: None,
+                    concurrent_setup: None,
+                    create: Some(vec![CreateDefinition {
+                        name: "SpamToken".to_owned(),
+                        bytecode: format!("{}{{initialSupply}}", bytecode::TEST_TOKEN),
+                        from: Some(sender.to_string()),
+                        from_pool: None,
+                        source: None,
+                        contract: None,
+                        abi: None,
+                    }]),
+                    setup: Some(vec![approve]),
+                    spam: Some(vec![SpamRequest::Tx(transfer)]),
+                }
+            }
+            BuiltinScenarioConfig::Oracle {
+                num_txs: _,
+                sender,
+                reads_per_update,
+            } => {
+                let fncall =
+                    |from_pool: &str, signature: &str, kind: &str| FunctionCallDefinition {
+                        to: "{PushOracle}".into(),
+                        to_selection: None,
+                        relayer_pool: None,
+                        safe: None,
+                        from: None,
+                        signature: signature.to_owned(),
+                        from_pool: Some(from_pool.to_owned()),
+                        args: None,
+                        value: None,
+                        fuzz: None,
+                        kind: Some(kind.to_owned()),
+                        gas_limit: None,
+                        max_fee_per_gas: None,
+                        max_priority_fee_per_gas: None,
+                        rate: None,
+                        weight: None,
+                        expect_status: None,
+                        max_gas_used: None,
+                        route: None,
+                    };
+
+                // prices with 8 decimals, between $1 and $10,000
+                let update = FunctionCallDefinition {
+                    args: Some(vec!["0".to_owned()]),
+                    fuzz: Some(vec![FuzzParam {
+                        param: Some("answer".to_owned()),
+                        value: None,
+                        min: Some(U256::from(100_000_000u64)),
+                        max: Some(U256::from(1_000_000_000_000u64)),
+                    }]),
+                    ..fncall("reporters", "update(uint256 answer)", "oracle_update")
+                };
+                // each step gets an equal share of txs, so reads are repeated to set the cadence
+                let reads = (0..reads_per_update).map(|_| {
+                    SpamRequest::Tx(fncall(
+                        "consumers",
+                        "latestAnswer() returns (int256)",
+                        "oracle_read",
+                    ))
+                });
+
+                TestConfig {
+                    chain_id: None,
+                    env: None,
+                    gas: None,
+                    defaults: None,
+                    multicall: None,
+                    phases: None,
+                    slo: None,
+                    namespace: None,
+                    read: None,
+                    eip712: None,
+                    sender_assignment: None,
+                    concurrent_setup: None,
+                    create: Some(vec![CreateDefinition {
+                        name: "PushOracle".to_owned(),
+                        bytecode: bytecode::PUSH_ORACLE.to_owned(),
+                        from: Some(sender.to_string()),
+                        from_pool: None,
+                        source: None,
+                        contract: None,
+                        abi: None,
+                    }]),
+                    setup: None,
+                    spam: Some(
+                        std::iter::once(SpamRequest::Tx(update))
+                            .chain(reads)
+                            .collect(),
+                    ),
+                }
+            }
+            BuiltinScenarioConfig::Interop {
+                dest_chain_id,
+                sender,
+            } => {
+                // the message calls the sender's address on the destination chain, which has no
+                // code, so relaying only costs the messenger's own validation
+                let send = FunctionCallDefinition {
+                    to: L2_TO_L2_MESSENGER_ADDRESS.into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
+                    from: Some(sender.to_string()),
+                    signature:
+                        "sendMessage(uint256 destination, address target, bytes message) returns (bytes32)"
+                            .to_owned(),
+                    from_pool: None,
+                    args: Some(vec![
+                        dest_chain_id.to_string(),
+                        sender.to_string(),
+                        "0x".to_owned(),
+                    ]),
+                    value: None,
+                    fuzz: None,
+                    kind: Some("interop_send".to_owned()),
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
+                };
+
+                TestConfig {
+                    chain_id: None,
+                    env: None,
+                    gas: None,
+                    defaults: None,
+                    multicall: None,
+                    phases: None,
+                    slo: None,
+                    namespace: None,
+                    read: None,
+                    eip712: None,
+                    sender_assignment: None,
+                    concurrent_setup: None,
+                    create: None,
+                    setup: None,
+                    spam: Some(vec![SpamRequest::Tx(send)]),
+                }
+            }
+            BuiltinScenarioConfig::RpcMix { sender, mix } => {
+                // txs are the mix's eth_sendRawTransaction share; reads are sent alongside them
+                let send = FunctionCallDefinition {
+                    to: "{SpamMe}".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
+                    from: Some(sender.to_string()),
+                    signature: "consumeGas(uint256 gas)".to_owned(),
+                    from_pool: None,
+                    args: Some(vec!["50000".to_owned()]),
+                    value: None,
+                    fuzz: None,
+                    kind: Some("rpc_mix".to_owned()),
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
+                };
+
+                TestConfig {
+                    chain_id: None,
+                    env: None,
+                    gas: None,
+                    defaults: None,
+                    multicall: None,
+                    phases: None,
+                    slo: None,
+                    namespace: None,
+                    read: Some(mix.read_steps("{SpamMe}")),
+                    eip712: None,
+                    sender_assignment: None,
+                    concurrent_setup: None,
+                    create: Some(vec![CreateDefinition {
+                        name: "SpamMe".to_owned(),
+                        bytecode: bytecode::SPAM_ME.to_owned(),
+                        from: Some(sender.to_string()),
+                        from_pool: None,
+                        source: None,
+                        contract: None,
+                        abi: None,
+                    }]),
+                    setup: None,
+                    spam: Some(vec![SpamRequest::Tx(send)]),
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oracle_reads_follow_cadence() {
+        let config = BuiltinScenarioConfig::oracle(20, Address::ZERO, 3);
+        assert_eq!(
+            config.agent_pools(),
+            vec![("reporters", 5), ("consumers", 15)]
+        );
+        let testconfig: TestConfig = config.into();
+        let kinds = testconfig
+            .spam
+            .unwrap()
+            .into_iter()
+            .map(|req| match req {
+                SpamRequest::Tx(tx) => tx.kind.unwrap(),
+                _ => panic!("expected txs"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec!["oracle_update", "oracle_read", "oracle_read", "oracle_read"]
+        );
+    }
+}
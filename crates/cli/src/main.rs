@@ -5,11 +5,21 @@ mod util;
 use std::sync::LazyLock;
 
 use alloy::hex;
-use commands::{ContenderCli, ContenderSubcommand, DbCommand, SpamCommandArgs};
-use contender_core::{db::DbOps, generator::RandSeed};
+use commands::{
+    AliasCommand, BenchCommandArgs, ContenderCli, ContenderSubcommand, ContractsCommand, DbCommand,
+    GenesisCommandArgs, ReadCommandArgs, ReportCommand, ReportCommandArgs, RunCommandArgs,
+    RunNotification, RunSummary, ScenariosCommand, ScoreWeights, SpamCommandArgs,
+};
+use contender_core::{
+    db::DbOps,
+    error::RetryPolicy,
+    generator::RandSeed,
+    provider::any_provider,
+    spammer::{Backpressure, FeeBumpPolicy, Ramp},
+};
 use contender_sqlite::SqliteDb;
 use rand::Rng;
-use util::{data_dir, db_file};
+use util::{data_dir, db_file, NumFormat};
 
 static DB: LazyLock<SqliteDb> = std::sync::LazyLock::new(|| {
     let path = db_file().expect("failed to get DB file path");
@@ -20,6 +30,9 @@ static DB: LazyLock<SqliteDb> = std::sync::LazyLock::new(|| {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = ContenderCli::parse_args();
+    let num_format = NumFormat {
+        raw: args.raw_numbers,
+    };
     DB.create_tables()?;
     let db = DB.clone();
     let data_path = data_dir()?;
@@ -45,68 +58,344 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             DbCommand::Reset => commands::reset_db(&db_path).await?,
             DbCommand::Export { out_path } => commands::export_db(&db_path, out_path).await?,
             DbCommand::Import { src_path } => commands::import_db(src_path, &db_path).await?,
+            DbCommand::Sync {
+                url,
+                source,
+                from_run,
+            } => {
+                let source = source
+                    .or_else(|| std::env::var("HOSTNAME").ok())
+                    .unwrap_or_else(|| "unknown".to_owned());
+                let state_path = format!("{}/sync.json", data_path);
+                commands::sync_db(&db, &url, &source, from_run, &state_path).await?
+            }
+            DbCommand::Stats { num_runs } => commands::db_stats(&db, num_runs)?,
+            DbCommand::Vacuum => commands::vacuum_db(&db)?,
+            DbCommand::AliasRpc {
+                rpc_url,
+                canonical_url,
+            } => commands::alias_rpc_url(&db, &rpc_url, &canonical_url)?,
+        },
+
+        ContenderSubcommand::Alias { command } => match command {
+            AliasCommand::Add { name, address } => commands::add_alias(&db, &name, &address)?,
+            AliasCommand::Remove { name } => commands::remove_alias(&db, &name)?,
+            AliasCommand::List => commands::list_aliases(&db)?,
+        },
+
+        ContenderSubcommand::Contracts { command } => match command {
+            ContractsCommand::List => commands::list_contracts(&db)?,
+            ContractsCommand::Show { name } => commands::show_contract(&db, &name)?,
+        },
+
+        ContenderSubcommand::Scenarios { command } => match command {
+            ScenariosCommand::Diff { a, b } => commands::diff_scenario_files(&a, &b)?,
         },
 
         ContenderSubcommand::Setup {
             testfile,
+            inline_toml,
             rpc_url,
+            ws_url,
             private_keys,
             min_balance,
             seed,
+            yes_i_know,
         } => {
+            let testfile = util::resolve_testfile(testfile, inline_toml)?;
             let seed = seed.unwrap_or(stored_seed);
             commands::setup(
                 &db,
                 testfile,
                 rpc_url,
+                ws_url,
                 private_keys,
                 min_balance,
                 RandSeed::seed_from_str(&seed),
+                yes_i_know,
             )
             .await?
         }
 
         ContenderSubcommand::Spam {
             testfile,
+            inline_toml,
             rpc_url,
             builder_url,
+            ws_url,
+            spam_rpc_urls,
+            routing,
             txs_per_block,
             txs_per_second,
+            tps_start,
+            tps_end,
+            ramp,
             duration,
             seed,
             private_keys,
             disable_reports,
+            no_track,
             min_balance,
             gen_report,
+            snapshot_revert,
+            yes_i_know,
+            tx_queue_capacity,
+            max_pending_txs,
+            pause_above_pending,
+            unlocked,
+            strict_ordering,
+            pending_tx_timeout_secs,
+            max_fee_bumps,
+            fee_bump_percent,
+            send_timeout,
+            send_retries,
+            retry_backoff,
+            retry_on,
+            num_relayers,
+            stats_interval,
+            total_gas,
+            total_txs,
+            abort_on_error_rate,
+            warmup,
+            cooldown,
+            wait_for_empty_mempool,
+            baseline,
+            control_socket,
+            metrics_addr,
+            notify_url,
+            fail_on_violations,
         } => {
+            let testfile = util::resolve_testfile(testfile, inline_toml)?;
             let seed = seed.unwrap_or(stored_seed);
-            let run_id = commands::spam(
+            let scenario = testfile.to_owned();
+            let res = async {
+                let run_id = commands::spam(
+                    &db,
+                    SpamCommandArgs {
+                        testfile,
+                        rpc_url: rpc_url.to_owned(),
+                        builder_url,
+                        ws_url,
+                        spam_rpc_urls,
+                        routing,
+                        txs_per_block,
+                        txs_per_second,
+                        ramp: tps_start.zip(tps_end).zip(ramp).map(
+                            |((start_rate, end_rate), step)| Ramp {
+                                start_rate,
+                                end_rate,
+                                step_periods: step.as_secs().max(1) as usize,
+                            },
+                        ),
+                        duration,
+                        seed,
+                        private_keys,
+                        disable_reports,
+                        no_track,
+                        min_balance,
+                        snapshot_revert,
+                        yes_i_know,
+                        tx_queue_capacity,
+                        max_pending_txs,
+                        backpressure: pause_above_pending.map(Backpressure::new),
+                        unlocked,
+                        strict_ordering,
+                        fee_bump: pending_tx_timeout_secs.map(|pending_tx_timeout_secs| {
+                            FeeBumpPolicy {
+                                pending_tx_timeout_secs,
+                                max_bumps: max_fee_bumps,
+                                bump_percent: fee_bump_percent,
+                            }
+                        }),
+                        retry_policy: RetryPolicy {
+                            max_retries: send_retries,
+                            backoff: retry_backoff,
+                            timeout: send_timeout,
+                            retry_on,
+                        },
+                        num_relayers,
+                        stats_interval,
+                        total_gas,
+                        total_txs,
+                        abort_on_error_rate,
+                        warmup,
+                        cooldown,
+                        wait_for_empty_mempool,
+                        baseline,
+                        control_socket,
+                        metrics_addr,
+                    },
+                )
+                .await?;
+                let report_path = if gen_report {
+                    commands::report(
+                        ReportCommandArgs {
+                            last_run_id: Some(run_id),
+                            num_format,
+                            ..Default::default()
+                        },
+                        &db,
+                        &rpc_url,
+                    )
+                    .await?
+                } else {
+                    None
+                };
+                // runs aren't recorded if reports are disabled
+                if run_id != 0 {
+                    let summary = RunSummary::load(&db, run_id, report_path.to_owned())?;
+                    summary.print(num_format);
+                    if fail_on_violations && summary.num_violations() > 0 {
+                        return Err(format!(
+                            "run {} failed {} assertions (gas used or latency SLOs)",
+                            run_id,
+                            summary.num_violations()
+                        )
+                        .into());
+                    }
+                }
+                Ok::<_, Box<dyn std::error::Error>>((run_id, report_path))
+            }
+            .await;
+            if let Some(url) = &notify_url {
+                // failing to notify doesn't fail the run
+                let notification = match &res {
+                    Ok((run_id, report_path)) => match RunNotification::completed(
+                        &db,
+                        *run_id,
+                        &scenario,
+                        &rpc_url,
+                        report_path.to_owned(),
+                    ) {
+                        Ok(notification) => Some(notification),
+                        Err(e) => {
+                            println!("failed to build notification for run {}: {}", run_id, e);
+                            None
+                        }
+                    },
+                    Err(e) => Some(RunNotification::failed(&scenario, &rpc_url, &e.to_string())),
+                };
+                if let Some(notification) = notification {
+                    commands::notify(url, &notification).await;
+                }
+            }
+            res?;
+        }
+
+        ContenderSubcommand::Report {
+            command:
+                Some(ReportCommand::Cost {
+                    rpc_url,
+                    last_run_id,
+                    preceding_runs,
+                }),
+            ..
+        } => {
+            commands::report_cost(&db, &rpc_url, last_run_id, preceding_runs).await?;
+        }
+
+        ContenderSubcommand::Report {
+            command: Some(ReportCommand::Baseline { series }),
+            ..
+        } => {
+            commands::report_baseline(&db, &series)?;
+        }
+
+        ContenderSubcommand::Report {
+            command: None,
+            rpc_url,
+            last_run_id,
+            preceding_runs,
+            kinds,
+            group_by_kind,
+            contracts,
+            opcode_profile,
+            archive,
+            format,
+            chart_format,
+            wait_for_empty_mempool,
+        } => {
+            let rpc_url = rpc_url.expect("rpc_url is required");
+            if let Some(max_txs) = wait_for_empty_mempool {
+                let rpc_client = any_provider(&rpc_url.parse()?).await?;
+                util::wait_for_empty_mempool(&rpc_client, max_txs).await?;
+            }
+            commands::report(
+                ReportCommandArgs {
+                    last_run_id,
+                    preceding_runs,
+                    kinds: kinds.unwrap_or_default(),
+                    group_by_kind,
+                    contracts: contracts.unwrap_or_default(),
+                    opcode_profile,
+                    archive,
+                    format,
+                    chart_format,
+                    num_format,
+                    // cached in the data dir
+                    trace_cache_dir: None,
+                },
+                &db,
+                &rpc_url,
+            )
+            .await?;
+        }
+
+        ContenderSubcommand::Diff { run_ids, archive } => {
+            let report_path = commands::report_diff(&run_ids, &db, archive, num_format)?;
+            webbrowser::open(&report_path)?;
+        }
+
+        ContenderSubcommand::Read {
+            testfile,
+            inline_toml,
+            rpc_url,
+            reads_per_second,
+            duration,
+            seed,
+        } => {
+            let testfile = util::resolve_testfile(testfile, inline_toml)?;
+            commands::read(
                 &db,
-                SpamCommandArgs {
+                ReadCommandArgs {
                     testfile,
-                    rpc_url: rpc_url.to_owned(),
-                    builder_url,
-                    txs_per_block,
-                    txs_per_second,
+                    rpc_url,
+                    reads_per_second,
                     duration,
-                    seed,
-                    private_keys,
-                    disable_reports,
-                    min_balance,
+                    seed: seed.unwrap_or(stored_seed),
+                    num_format,
                 },
             )
             .await?;
-            if gen_report {
-                commands::report(Some(run_id), 0, &db, &rpc_url).await?;
-            }
         }
 
-        ContenderSubcommand::Report {
-            rpc_url,
-            last_run_id,
-            preceding_runs,
+        ContenderSubcommand::Genesis {
+            testfile,
+            inline_toml,
+            seed,
+            txs_per_period,
+            num_relayers,
+            balance,
+            private_keys,
+            contracts_rpc_url,
+            out_file,
         } => {
-            commands::report(last_run_id, preceding_runs, &db, &rpc_url).await?;
+            let testfile = util::resolve_testfile(testfile, inline_toml)?;
+            let seed = seed.unwrap_or(stored_seed);
+            commands::genesis(
+                &db,
+                GenesisCommandArgs {
+                    testfile,
+                    seed,
+                    txs_per_period,
+                    num_relayers,
+                    balance,
+                    private_keys,
+                    contracts_rpc_url,
+                    out_file,
+                },
+            )
+            .await?
         }
 
         ContenderSubcommand::Run {
@@ -116,17 +405,64 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             interval,
             duration,
             txs_per_duration,
+            yes_i_know,
         } => {
             commands::run(
                 &db,
-                scenario,
-                rpc_url,
-                private_key,
-                interval,
-                duration,
-                txs_per_duration,
+                RunCommandArgs {
+                    scenario,
+                    rpc_url,
+                    private_key,
+                    interval,
+                    duration,
+                    txs_per_duration,
+                    yes_i_know,
+                    seed: RandSeed::default(),
+                    redeploy: None,
+                },
             )
-            .await?
+            .await?;
+        }
+
+        ContenderSubcommand::Bench {
+            rpc_url,
+            private_key,
+            out_file,
+            throughput_weight,
+            latency_weight,
+            reliability_weight,
+            target_latency,
+            baseline,
+            notify_url,
+            yes_i_know,
+        } => {
+            commands::bench(
+                &db,
+                BenchCommandArgs {
+                    rpc_url,
+                    private_key,
+                    out_file,
+                    yes_i_know,
+                    weights: ScoreWeights {
+                        throughput: throughput_weight,
+                        latency: latency_weight,
+                        reliability: reliability_weight,
+                        target_latency_secs: target_latency,
+                    },
+                    baseline,
+                    notify_url,
+                    num_format,
+                },
+            )
+            .await?;
+        }
+
+        ContenderSubcommand::Reproduce {
+            run_id,
+            rpc_url,
+            private_keys,
+        } => {
+            commands::reproduce(&db, run_id, rpc_url, private_keys).await?;
         }
     }
     Ok(())
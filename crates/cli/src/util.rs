@@ -1,20 +1,55 @@
 use alloy::{
     network::{EthereumWallet, TransactionBuilder},
-    primitives::{utils::format_ether, Address, U256},
+    primitives::{address, keccak256, utils::format_ether, Address, Bytes, TxKind, B256, U256},
     providers::{PendingTransactionConfig, Provider},
-    rpc::types::TransactionRequest,
+    rpc::types::{BlockId, BlockTransactionsKind, Header, TransactionInput, TransactionRequest},
     signers::local::PrivateKeySigner,
 };
 use contender_core::{
-    db::RunTx,
-    generator::types::{AnyProvider, EthProvider, FunctionCallDefinition, SpamRequest},
-    spammer::{LogCallback, NilCallback},
+    agent_controller::AgentStore,
+    db::{AccountBalance, FundingTx, RunMetadata},
+    error::ContenderError,
+    generator::{
+        multicall::{self, MULTICALL3_ADDRESS},
+        types::{AnyProvider, EthProvider, FunctionCallDefinition, SpamRequest},
+    },
+    spammer::{ErrorRateLimit, LogCallback, NilCallback, TxpoolStatus},
 };
+pub use contender_report::NumFormat;
 use contender_testfile::TestConfig;
-use csv::Writer;
-use std::{io::Write, str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use termcolor::{ColorChoice, ColorSpec, StandardStream, WriteColor};
 
+/// How long to wait for the mempool to drain before giving up.
+const MEMPOOL_DRAIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Deterministic deployment proxy (a CREATE2 factory), predeployed on most chains & dev nodes.
+const CREATE2_FACTORY: Address = address!("4e59b44847b379578588920cA78FbF26c0B4956C");
+
+/// Init code of a minimal disperse contract. Its calldata is a list of 32-byte words, each holding a
+/// recipient address; `msg.value` is split evenly between the recipients. Reverts if any transfer fails.
+const DISPERSE_INITCODE: &str = "0x602b80600b6000396000f360005b3681101560295760203604340460008080808486355af1602157600080fd5b506020016002565b00";
+
+/// Init code of a minimal stand-in for Multicall3, deployed where Multicall3 isn't. It implements
+/// `aggregate3` & `aggregate3Value` without return data, making each call in order and reverting if
+/// any of them fails, so it only supports `allowFailure: false`.
+const MULTICALL_INITCODE: &str = "0x608080600b6000396000f360003560e01c806382ad56cb149063174dea7114908117601e57600080fd5b60043560040180359060200160005b82811015607e578060051b8201358201806040013585028560051b82016040013582018035906020018190600037600060008260008587355af16074573d6000803e3d6000fd5b505050600101602d565b00";
+
+/// Accounts are funded through the disperse contract when at least this many need funds.
+const DISPERSE_MIN_RECIPIENTS: usize = 10;
+
+/// Max number of accounts funded per disperse tx.
+const DISPERSE_BATCH_SIZE: usize = 200;
+
+/// Gas limit per disperse recipient; a transfer to a new account costs ~34k gas.
+const DISPERSE_GAS_PER_RECIPIENT: u128 = 40_000;
+
 pub enum SpamCallbackType {
     Log(LogCallback),
     Nil(NilCallback),
@@ -33,6 +68,182 @@ pub const DEFAULT_PRV_KEYS: [&str; 10] = [
     "0x2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6",
 ];
 
+/// Chain IDs of public production networks which must never be spammed by accident.
+pub const KNOWN_MAINNET_CHAIN_IDS: [(u64, &str); 12] = [
+    (1, "Ethereum"),
+    (10, "OP Mainnet"),
+    (56, "BNB Smart Chain"),
+    (100, "Gnosis"),
+    (137, "Polygon"),
+    (324, "zkSync Era"),
+    (8453, "Base"),
+    (42161, "Arbitrum One"),
+    (59144, "Linea"),
+    (81457, "Blast"),
+    (534352, "Scroll"),
+    (7777777, "Zora"),
+];
+
+/// Returns an error if the connected chain doesn't match the scenario's intended chain ID,
+/// or if it is a known mainnet, unless the user has explicitly confirmed with `--yes-i-know`.
+pub fn check_chain_id(
+    intended_chain_id: Option<u64>,
+    rpc_chain_id: u64,
+    yes_i_know: bool,
+) -> Result<(), ContenderError> {
+    if yes_i_know {
+        return Ok(());
+    }
+    if let Some(intended) = intended_chain_id {
+        if intended != rpc_chain_id {
+            return Err(ContenderError::SetupError(
+                "RPC chain ID does not match the scenario's chain_id. Pass --yes-i-know to send anyway.",
+                Some(format!("expected {}, got {}", intended, rpc_chain_id)),
+            ));
+        }
+    }
+    if let Some((_, name)) = KNOWN_MAINNET_CHAIN_IDS
+        .iter()
+        .find(|(id, _)| *id == rpc_chain_id)
+    {
+        return Err(ContenderError::SetupError(
+            "RPC is connected to a known mainnet. Pass --yes-i-know to send anyway.",
+            Some(format!("{} (chain ID {})", name, rpc_chain_id)),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the name of the latest protocol fork, inferred from the fields present in `header`.
+pub fn fork_name(header: &Header) -> &'static str {
+    if header.requests_root.is_some() {
+        "Prague"
+    } else if header.blob_gas_used.is_some() || header.excess_blob_gas.is_some() {
+        "Cancun"
+    } else if header.withdrawals_root.is_some() {
+        "Shanghai"
+    } else if header.base_fee_per_gas.is_some() {
+        "London"
+    } else {
+        "pre-London"
+    }
+}
+
+/// Collects node & chain metadata to store with a run. Metadata the node fails to return is left
+/// empty (with a warning); it's informational, so it doesn't stop the run.
+pub async fn get_run_metadata(rpc_client: &AnyProvider) -> RunMetadata {
+    let genesis = rpc_client.get_block_by_number(0.into(), false).await;
+    let latest = rpc_client
+        .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
+        .await;
+    let client_version = rpc_client.get_client_version().await.map(Some);
+    let chain_id = rpc_client.get_chain_id().await.map(Some);
+    RunMetadata {
+        client_version: warn_missing("client version", client_version).unwrap_or_default(),
+        chain_id: warn_missing("chain ID", chain_id).unwrap_or_default(),
+        genesis_hash: warn_missing("genesis block", genesis)
+            .map(|block| block.header.hash)
+            .unwrap_or_default(),
+        fork: warn_missing("latest block", latest)
+            .map(|block| fork_name(&block.header).to_owned())
+            .unwrap_or_default(),
+    }
+}
+
+/// Unwraps a piece of run metadata, printing a warning if the node didn't return it.
+fn warn_missing<T>(field: &str, res: Result<Option<T>, impl std::fmt::Display>) -> Option<T> {
+    match res {
+        Ok(Some(value)) => Some(value),
+        Ok(None) => {
+            eprintln!(
+                "warning: {} not found; leaving it out of the run metadata",
+                field
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!(
+                "warning: failed to get {} for the run metadata: {}",
+                field, e
+            );
+            None
+        }
+    }
+}
+
+/// Name of the pool that user-provided signers are recorded under.
+pub const ADMIN_POOL: &str = "admin";
+
+/// Returns (pool name, address) pairs for every account a run may send from.
+pub fn get_run_accounts(
+    user_signers: &[PrivateKeySigner],
+    agents: &AgentStore,
+) -> Vec<(String, Address)> {
+    let mut accounts = user_signers
+        .iter()
+        .map(|signer| (ADMIN_POOL.to_owned(), signer.address()))
+        .collect::<Vec<_>>();
+    for (name, agent) in agents.all_agents() {
+        accounts.extend(
+            agent
+                .signers
+                .iter()
+                .map(|signer| (name.to_owned(), signer.address())),
+        );
+    }
+    accounts
+}
+
+/// Fetches the current balance of each (pool name, address) pair in `accounts`.
+pub async fn get_balances(
+    accounts: &[(String, Address)],
+    rpc_client: &AnyProvider,
+) -> Result<Vec<AccountBalance>, Box<dyn std::error::Error>> {
+    let addresses = accounts
+        .iter()
+        .map(|(_, address)| *address)
+        .collect::<Vec<_>>();
+    let balances = get_eth_balances(&addresses, rpc_client).await?;
+    Ok(accounts
+        .iter()
+        .zip(balances)
+        .map(|((pool, address), balance)| AccountBalance {
+            pool: pool.to_owned(),
+            address: *address,
+            balance,
+        })
+        .collect())
+}
+
+/// Gets the balances of `addresses`, batched into a few `eth_call`s through Multicall3 if it's
+/// deployed. Falls back to one `eth_getBalance` per address otherwise, or if the multicall fails.
+pub async fn get_eth_balances(
+    addresses: &[Address],
+    rpc_client: &AnyProvider,
+) -> Result<Vec<U256>, Box<dyn std::error::Error>> {
+    let multicall_address = MULTICALL3_ADDRESS.parse::<Address>()?;
+    if addresses.len() > 1 && !rpc_client.get_code_at(multicall_address).await?.is_empty() {
+        match multicall::get_eth_balances(rpc_client, multicall_address, addresses).await {
+            Ok(balances) => return Ok(balances),
+            Err(e) => eprintln!(
+                "failed to batch balance checks through Multicall3; checking one at a time: {}",
+                e
+            ),
+        }
+    }
+
+    let mut balances = vec![];
+    for address in addresses {
+        balances.push(
+            rpc_client
+                .get_balance(*address)
+                .await
+                .map_err(|e| format!("Error checking balance for address {}: {}", address, e))?,
+        );
+    }
+    Ok(balances)
+}
+
 pub fn get_create_pools(testconfig: &TestConfig) -> Vec<String> {
     testconfig
         .create
@@ -49,7 +260,8 @@ pub fn get_setup_pools(testconfig: &TestConfig) -> Vec<String> {
         .to_owned()
         .unwrap_or_default()
         .into_iter()
-        .filter_map(|s| s.from_pool)
+        .flat_map(|s| [s.from_pool, s.relayer_pool])
+        .flatten()
         .collect()
 }
 
@@ -74,6 +286,11 @@ pub fn get_spam_pools(testconfig: &TestConfig) -> Vec<String> {
                     }
                 }
             }
+            SpamRequest::Transfer(transfer) => {
+                // recipient pools are created like sender pools, so their accounts exist
+                from_pools.extend(transfer.from_pool.iter().cloned());
+                from_pools.extend(transfer.to_pool.iter().cloned());
+            }
         }
     }
 
@@ -83,6 +300,46 @@ pub fn get_spam_pools(testconfig: &TestConfig) -> Vec<String> {
     from_pools
 }
 
+/// Returns the unique `relayer_pool`s of the spam requests.
+pub fn get_spam_relayer_pools(testconfig: &TestConfig) -> Vec<String> {
+    let mut relayer_pools = testconfig
+        .spam
+        .iter()
+        .flatten()
+        .flat_map(|s| match s {
+            SpamRequest::Tx(fn_call) => vec![fn_call],
+            SpamRequest::Bundle(bundle) => bundle.txs.iter().collect(),
+            SpamRequest::Transfer(_) => vec![],
+        })
+        .filter_map(|fn_call| fn_call.relayer_pool.to_owned())
+        .collect::<Vec<_>>();
+    relayer_pools.sort();
+    relayer_pools.dedup();
+    relayer_pools
+}
+
+/// Returns the unique `owner_pool`s of Safe calls in setup & spam, with the number of owners each
+/// needs to sign for the highest threshold it's used with.
+pub fn get_safe_owner_pools(testconfig: &TestConfig) -> Vec<(String, usize)> {
+    let setup = testconfig.setup.iter().flatten();
+    let spam = testconfig.spam.iter().flatten().flat_map(|s| match s {
+        SpamRequest::Tx(fn_call) => vec![fn_call],
+        SpamRequest::Bundle(bundle) => bundle.txs.iter().collect(),
+        SpamRequest::Transfer(_) => vec![],
+    });
+    let mut owner_pools = HashMap::<String, usize>::new();
+    for safe in setup
+        .chain(spam)
+        .filter_map(|fn_call| fn_call.safe.as_ref())
+    {
+        let threshold = owner_pools.entry(safe.owner_pool.to_owned()).or_default();
+        *threshold = (*threshold).max(safe.threshold);
+    }
+    let mut owner_pools = owner_pools.into_iter().collect::<Vec<_>>();
+    owner_pools.sort();
+    owner_pools
+}
+
 pub fn get_signers_with_defaults(private_keys: Option<Vec<String>>) -> Vec<PrivateKeySigner> {
     if private_keys.is_none() {
         println!("No private keys provided. Using default private keys.");
@@ -125,6 +382,14 @@ pub fn check_private_keys(testconfig: &TestConfig, prv_keys: &[PrivateKeySigner]
             SpamRequest::Bundle(bundle) => {
                 fn_calls.extend(bundle.txs.iter().map(|s| s.to_owned()));
             }
+            SpamRequest::Transfer(transfer) => {
+                if let Some(from) = &transfer.from {
+                    let address = from.parse::<Address>().expect("invalid 'from' address");
+                    if prv_keys.iter().all(|k| k.address() != address) {
+                        panic!("No private key found for address: {}", address);
+                    }
+                }
+            }
         }
     }
 
@@ -158,18 +423,37 @@ pub async fn fund_accounts(
     rpc_client: &AnyProvider,
     eth_client: &EthProvider,
     min_balance: U256,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Vec<FundingTx>, Box<dyn std::error::Error>> {
     let insufficient_balances =
         find_insufficient_balances(recipient_addresses, min_balance, rpc_client).await?;
+    // accounts are derived from the seed, so they're usually still funded from a previous run
+    let num_funded = recipient_addresses.len() - insufficient_balances.len();
+    if num_funded > 0 {
+        println!(
+            "{}/{} accounts already have at least {} ETH; skipping them",
+            num_funded,
+            recipient_addresses.len(),
+            format_ether(min_balance)
+        );
+    }
 
     let mut pending_fund_txs = vec![];
     let admin_nonce = rpc_client
         .get_transaction_count(fund_with.address())
         .await?;
 
+    // with many accounts to fund, batch the transfers through a disperse contract
+    let use_disperse = insufficient_balances.len() >= DISPERSE_MIN_RECIPIENTS
+        && !rpc_client.get_code_at(CREATE2_FACTORY).await?.is_empty();
+    let gas_per_account = if use_disperse {
+        DISPERSE_GAS_PER_RECIPIENT
+    } else {
+        21000
+    };
+
     // pre-check if admin account has sufficient balance
     let gas_price = rpc_client.get_gas_price().await?;
-    let gas_cost_per_tx = U256::from(21000) * U256::from(gas_price + (gas_price / 10));
+    let gas_cost_per_tx = U256::from(gas_per_account) * U256::from(gas_price + (gas_price / 10));
     let chain_id = rpc_client.get_chain_id().await?;
 
     let total_cost = U256::from(insufficient_balances.len()) * (min_balance + gas_cost_per_tx);
@@ -186,6 +470,23 @@ pub async fn fund_accounts(
         .into());
     }
 
+    if use_disperse {
+        let recipients = insufficient_balances
+            .iter()
+            .map(|(address, _)| *address)
+            .collect::<Vec<_>>();
+        let disperse = deploy_disperse(fund_with, rpc_client, eth_client).await?;
+        return disperse_funds(
+            &recipients,
+            disperse,
+            fund_with,
+            rpc_client,
+            eth_client,
+            min_balance,
+        )
+        .await;
+    }
+
     for (idx, (address, _)) in insufficient_balances.iter().enumerate() {
         let (balance_sufficient, balance) =
             is_balance_sufficient(&fund_with.address(), min_balance, rpc_client).await?;
@@ -203,7 +504,9 @@ pub async fn fund_accounts(
         }
 
         let fund_amount = min_balance;
-        pending_fund_txs.push(
+        pending_fund_txs.push((
+            *address,
+            fund_amount,
             fund_account(
                 fund_with,
                 *address,
@@ -212,15 +515,188 @@ pub async fn fund_accounts(
                 Some(admin_nonce + idx as u64),
             )
             .await?,
+        ));
+    }
+
+    let mut funding_txs = vec![];
+    for (recipient, amount, tx) in pending_fund_txs {
+        let pending = rpc_client.watch_pending_transaction(tx).await?;
+        let tx_hash = pending.await?;
+        println!("funding tx confirmed ({})", tx_hash);
+        let receipt = eth_client.get_transaction_receipt(tx_hash).await?.ok_or(
+            ContenderError::SetupError("funding tx receipt not found", Some(tx_hash.to_string())),
+        )?;
+        funding_txs.push(FundingTx {
+            tx_hash,
+            sender: fund_with.address(),
+            recipient,
+            amount,
+            gas_used: receipt.gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+        });
+    }
+
+    Ok(funding_txs)
+}
+
+/// Deploys the disperse contract through the CREATE2 factory, unless it's already deployed.
+/// Returns its address, which is the same on every chain.
+async fn deploy_disperse(
+    sender: &PrivateKeySigner,
+    rpc_client: &AnyProvider,
+    eth_client: &EthProvider,
+) -> Result<Address, Box<dyn std::error::Error>> {
+    deploy_create2(
+        "disperse",
+        DISPERSE_INITCODE,
+        sender,
+        rpc_client,
+        eth_client,
+    )
+    .await
+}
+
+/// Deploys the Multicall3 stand-in (see [`MULTICALL_INITCODE`]) through the CREATE2 factory,
+/// unless it's already deployed. Returns its address, which is the same on every chain.
+pub async fn deploy_multicall(
+    sender: &PrivateKeySigner,
+    rpc_client: &AnyProvider,
+    eth_client: &EthProvider,
+) -> Result<Address, Box<dyn std::error::Error>> {
+    if rpc_client.get_code_at(CREATE2_FACTORY).await?.is_empty() {
+        return Err(ContenderError::SetupError(
+            "Multicall3 not found and it can't be deployed without the CREATE2 factory; deploy it in a [[create]] step and set `multicall.address` to its placeholder (e.g. \"{multicall3}\")",
+            Some(MULTICALL3_ADDRESS.to_owned()),
+        )
+        .into());
+    }
+    deploy_create2(
+        "multicall",
+        MULTICALL_INITCODE,
+        sender,
+        rpc_client,
+        eth_client,
+    )
+    .await
+}
+
+/// Deploys `init_code` through the CREATE2 factory (with a zero salt), unless it's already
+/// deployed. Returns the contract's address.
+async fn deploy_create2(
+    name: &str,
+    init_code: &str,
+    sender: &PrivateKeySigner,
+    rpc_client: &AnyProvider,
+    eth_client: &EthProvider,
+) -> Result<Address, Box<dyn std::error::Error>> {
+    let init_code = Bytes::from_str(init_code)?;
+    let address = CREATE2_FACTORY.create2_from_code(B256::ZERO, &init_code);
+    if !rpc_client.get_code_at(address).await?.is_empty() {
+        return Ok(address);
+    }
+
+    println!("deploying {} contract to {}", name, address);
+    // the factory takes the salt followed by the init code
+    let input = [B256::ZERO.as_slice(), &init_code[..]].concat();
+    let tx_req = TransactionRequest {
+        from: Some(sender.address()),
+        to: Some(TxKind::Call(CREATE2_FACTORY)),
+        input: TransactionInput::new(input.into()),
+        gas: Some(100_000),
+        gas_price: Some(eth_client.get_gas_price().await? + 4_200_000_000),
+        nonce: Some(eth_client.get_transaction_count(sender.address()).await?),
+        chain_id: Some(eth_client.get_chain_id().await?),
+        ..Default::default()
+    };
+    let tx = tx_req
+        .build(&EthereumWallet::from(sender.to_owned()))
+        .await?;
+    let tx_hash = eth_client
+        .send_tx_envelope(tx)
+        .await?
+        .get_receipt()
+        .await?
+        .transaction_hash;
+    if rpc_client.get_code_at(address).await?.is_empty() {
+        return Err(ContenderError::SetupError(
+            "failed to deploy contract through the CREATE2 factory",
+            Some(format!("{} ({})", name, tx_hash)),
+        )
+        .into());
+    }
+    Ok(address)
+}
+
+/// Sends `amount` to each of `recipients` through the disperse contract at `disperse`, funding up
+/// to [`DISPERSE_BATCH_SIZE`] accounts per tx. Each recipient's funding record gets an even share
+/// of its batch's gas.
+async fn disperse_funds(
+    recipients: &[Address],
+    disperse: Address,
+    sender: &PrivateKeySigner,
+    rpc_client: &AnyProvider,
+    eth_client: &EthProvider,
+    amount: U256,
+) -> Result<Vec<FundingTx>, Box<dyn std::error::Error>> {
+    let gas_price = eth_client.get_gas_price().await?;
+    let chain_id = eth_client.get_chain_id().await?;
+    let nonce = eth_client.get_transaction_count(sender.address()).await?;
+    let eth_wallet = EthereumWallet::from(sender.to_owned());
+
+    let mut pending_fund_txs = vec![];
+    for (idx, batch) in recipients.chunks(DISPERSE_BATCH_SIZE).enumerate() {
+        println!(
+            "funding {} accounts with user account {}",
+            batch.len(),
+            sender.address()
         );
+        let input = batch
+            .iter()
+            .flat_map(|recipient| recipient.into_word().0)
+            .collect::<Vec<_>>();
+        let tx_req = TransactionRequest {
+            from: Some(sender.address()),
+            to: Some(TxKind::Call(disperse)),
+            value: Some(amount * U256::from(batch.len())),
+            input: TransactionInput::new(input.into()),
+            gas: Some(DISPERSE_GAS_PER_RECIPIENT * batch.len() as u128 + 30_000),
+            gas_price: Some(gas_price + 4_200_000_000),
+            nonce: Some(nonce + idx as u64),
+            chain_id: Some(chain_id),
+            ..Default::default()
+        };
+        let tx = tx_req.build(&eth_wallet).await?;
+        let res = eth_client.send_tx_envelope(tx).await?;
+        pending_fund_txs.push((batch, res.into_inner()));
     }
 
-    for tx in pending_fund_txs {
+    let mut funding_txs = vec![];
+    for (batch, tx) in pending_fund_txs {
         let pending = rpc_client.watch_pending_transaction(tx).await?;
-        println!("funding tx confirmed ({})", pending.await?);
+        let tx_hash = pending.await?;
+        println!("funding tx confirmed ({})", tx_hash);
+        let receipt = eth_client.get_transaction_receipt(tx_hash).await?.ok_or(
+            ContenderError::SetupError("funding tx receipt not found", Some(tx_hash.to_string())),
+        )?;
+        if !receipt.status() {
+            return Err(ContenderError::SetupError(
+                "disperse funding tx reverted",
+                Some(tx_hash.to_string()),
+            )
+            .into());
+        }
+        let gas_used = receipt.gas_used / batch.len() as u128;
+        funding_txs.extend(batch.iter().map(|recipient| FundingTx {
+            tx_hash,
+            sender: sender.address(),
+            recipient: *recipient,
+            amount,
+            gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+        }));
     }
 
-    Ok(())
+    Ok(funding_txs)
 }
 
 pub async fn fund_account(
@@ -256,22 +732,19 @@ pub async fn fund_account(
     Ok(res.into_inner())
 }
 
-/// Returns an error if any of the private keys do not have sufficient balance.
+/// Returns the addresses with less than `min_balance`, along with their balances.
 pub async fn find_insufficient_balances(
     addresses: &[Address],
     min_balance: U256,
     rpc_client: &AnyProvider,
 ) -> Result<Vec<(Address, U256)>, Box<dyn std::error::Error>> {
-    let mut insufficient_balances = vec![];
-    for address in addresses {
-        let (balance_sufficient, balance) = is_balance_sufficient(address, min_balance, rpc_client)
-            .await
-            .map_err(|e| format!("Error checking balance for address {}: {}", address, e))?;
-        if !balance_sufficient {
-            insufficient_balances.push((*address, balance));
-        }
-    }
-    Ok(insufficient_balances)
+    let balances = get_eth_balances(addresses, rpc_client).await?;
+    Ok(addresses
+        .iter()
+        .zip(balances)
+        .filter(|(_, balance)| *balance < min_balance)
+        .map(|(address, balance)| (*address, balance))
+        .collect())
 }
 
 pub async fn spam_callback_default(
@@ -286,17 +759,6 @@ pub async fn spam_callback_default(
     SpamCallbackType::Nil(NilCallback)
 }
 
-pub fn write_run_txs<T: std::io::Write>(
-    writer: &mut Writer<T>,
-    txs: &[RunTx],
-) -> Result<(), Box<dyn std::error::Error>> {
-    for tx in txs {
-        writer.serialize(tx)?;
-    }
-    writer.flush()?;
-    Ok(())
-}
-
 pub fn prompt_cli(msg: impl AsRef<str>) -> String {
     let mut stdout = StandardStream::stdout(ColorChoice::Always);
     stdout
@@ -312,6 +774,111 @@ pub fn prompt_cli(msg: impl AsRef<str>) -> String {
     input.trim().to_owned()
 }
 
+/// Polls `txpool_status` until the node's mempool holds at most `max_txs` (pending + queued),
+/// so the next run (or report) starts from a clean boundary. Gives up after a few minutes, or right
+/// away if the node doesn't support `txpool_status`.
+pub async fn wait_for_empty_mempool(
+    rpc_client: &AnyProvider,
+    max_txs: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    loop {
+        let status = match rpc_client
+            .raw_request::<_, TxpoolStatus>("txpool_status".into(), ())
+            .await
+        {
+            Ok(status) => status,
+            Err(e) => {
+                println!(
+                    "txpool_status isn't supported by the node; not waiting for the mempool to drain: {}",
+                    e
+                );
+                return Ok(());
+            }
+        };
+        let (pending, queued) = (status.pending.to::<u64>(), status.queued.to::<u64>());
+        if pending + queued <= max_txs {
+            println!("mempool drained ({} pending, {} queued)", pending, queued);
+            return Ok(());
+        }
+        if start.elapsed() >= MEMPOOL_DRAIN_TIMEOUT {
+            println!(
+                "mempool still holds {} pending & {} queued txs after {:?}; continuing",
+                pending, queued, MEMPOOL_DRAIN_TIMEOUT
+            );
+            return Ok(());
+        }
+        println!(
+            "waiting for the mempool to drain: {} pending, {} queued",
+            pending, queued
+        );
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Parses a duration like `500ms`, `5s`, or `2m`. A bare number is read as seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let num = num
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration \"{}\"; expected e.g. 5s", s))?;
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(num)),
+        "" | "s" => Ok(Duration::from_secs(num)),
+        "m" => num
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("duration \"{}\" is too long", s)),
+        _ => Err(format!(
+            "invalid duration unit \"{}\"; expected ms, s, or m",
+            unit
+        )),
+    }
+}
+
+/// Parses a nonzero amount of gas like `10Ggas`, `500Mgas`, `1.5Kgas`, or `21000`.
+pub fn parse_gas(s: &str) -> Result<u128, String> {
+    let invalid = || format!("invalid gas amount \"{}\"; expected e.g. 10Ggas", s);
+    let lower = s.trim().to_lowercase();
+    let num = lower.strip_suffix("gas").unwrap_or(&lower);
+    let (num, multiplier) = match num.chars().last() {
+        Some('k') => (&num[..num.len() - 1], 1e3),
+        Some('m') => (&num[..num.len() - 1], 1e6),
+        Some('g') => (&num[..num.len() - 1], 1e9),
+        _ => (num, 1.0),
+    };
+    let num = num.trim().parse::<f64>().map_err(|_| invalid())?;
+    if !num.is_finite() || num < 0.0 {
+        return Err(invalid());
+    }
+    let gas = (num * multiplier) as u128;
+    if gas == 0 {
+        return Err(format!(
+            "invalid gas amount \"{}\"; must be at least 1 gas",
+            s
+        ));
+    }
+    Ok(gas)
+}
+
+/// Parses an error rate limit like `20%/30s`: the max share of failed sends over a rolling window.
+pub fn parse_error_rate_limit(s: &str) -> Result<ErrorRateLimit, String> {
+    let invalid = || format!("invalid error rate limit \"{}\"; expected e.g. 20%/30s", s);
+    let (rate, window) = s.split_once('/').ok_or_else(invalid)?;
+    let max_rate = rate
+        .trim()
+        .strip_suffix('%')
+        .and_then(|rate| rate.trim().parse::<f64>().ok())
+        .filter(|rate| (0.0..=100.0).contains(rate))
+        .ok_or_else(invalid)?;
+    Ok(ErrorRateLimit {
+        max_rate: max_rate / 100.0,
+        window: parse_duration(window)?,
+    })
+}
+
 /// Returns the path to the data directory.
 /// The directory is created if it does not exist.
 pub fn data_dir() -> Result<String, Box<dyn std::error::Error>> {
@@ -325,6 +892,43 @@ pub fn data_dir() -> Result<String, Box<dyn std::error::Error>> {
     Ok(dir)
 }
 
+/// Testfile argument that takes the scenario from stdin (or `--inline-toml`) instead of a file.
+pub const STDIN_TESTFILE: &str = "-";
+
+/// Resolves a testfile argument to a scenario file path. Given `-`, the scenario TOML is taken
+/// from `inline_toml`, or read from stdin if that isn't set, and saved in the data dir under its
+/// hash, so piping the same scenario again resolves to the same file (and namespace).
+pub fn resolve_testfile(
+    testfile: String,
+    inline_toml: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if testfile != STDIN_TESTFILE {
+        if inline_toml.is_some() {
+            return Err("--inline-toml requires `-` as the testfile".into());
+        }
+        return Ok(testfile);
+    }
+    let contents = match inline_toml {
+        Some(contents) => contents,
+        None => {
+            let mut contents = String::new();
+            std::io::stdin().read_to_string(&mut contents)?;
+            contents
+        }
+    };
+    let dir = format!("{}/stdin", data_dir()?);
+    std::fs::create_dir_all(&dir)?;
+    let path = piped_scenario_path(&dir, &contents);
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Path in `dir` to save a scenario read from stdin to, named by the hash of its contents.
+fn piped_scenario_path(dir: &str, contents: &str) -> String {
+    let hash = keccak256(contents.as_bytes()).to_string();
+    format!("{}/scenario-{}.toml", dir, &hash[2..10])
+}
+
 /// Returns path to default contender DB file.
 pub fn db_file() -> Result<String, Box<dyn std::error::Error>> {
     let data_path = data_dir()?;
@@ -333,18 +937,23 @@ pub fn db_file() -> Result<String, Box<dyn std::error::Error>> {
 
 #[cfg(test)]
 mod test {
-    use std::str::FromStr;
+    use std::{str::FromStr, time::Duration};
 
     use alloy::{
         consensus::constants::ETH_TO_WEI,
-        network::AnyNetwork,
+        network::{AnyNetwork, EthereumWallet, TransactionBuilder},
         node_bindings::{Anvil, AnvilInstance},
-        primitives::{Address, U256},
+        primitives::{Address, TxKind, U256},
         providers::{Provider, ProviderBuilder},
+        rpc::types::{Header, TransactionRequest},
         signers::local::PrivateKeySigner,
     };
+    use contender_core::generator::{multicall::aggregate_txs, NamedTxRequest};
 
-    use super::fund_accounts;
+    use super::{
+        check_chain_id, deploy_multicall, fork_name, fund_accounts, get_run_metadata,
+        parse_duration, parse_error_rate_limit, parse_gas, wait_for_empty_mempool,
+    };
 
     pub fn spawn_anvil() -> AnvilInstance {
         Anvil::new().block_time(1).spawn()
@@ -355,8 +964,9 @@ mod test {
         let anvil = spawn_anvil();
         let rpc_client = ProviderBuilder::new()
             .network::<AnyNetwork>()
-            .on_http(anvil.endpoint_url());
-        let eth_client = ProviderBuilder::new().on_http(anvil.endpoint_url());
+            .on_http(anvil.endpoint_url())
+            .boxed();
+        let eth_client = ProviderBuilder::new().on_http(anvil.endpoint_url()).boxed();
         let min_balance = U256::from(ETH_TO_WEI);
         let default_signer = PrivateKeySigner::from_str(super::DEFAULT_PRV_KEYS[0]).unwrap();
         // address: 0x7E57f00F16dE6A0D6B720E9C0af5C869a1f71c66
@@ -373,7 +983,7 @@ mod test {
         .collect();
 
         // send eth to the new signer
-        fund_accounts(
+        let funding_txs = fund_accounts(
             &recipient_addresses,
             &default_signer,
             &rpc_client,
@@ -382,6 +992,9 @@ mod test {
         )
         .await
         .unwrap();
+        assert_eq!(funding_txs.len(), 2);
+        assert_eq!(funding_txs[0].recipient, recipient_addresses[0]);
+        assert_eq!(funding_txs[0].amount, min_balance);
 
         for addr in &recipient_addresses {
             let balance = rpc_client.get_balance(*addr).await.unwrap();
@@ -402,4 +1015,193 @@ mod test {
         println!("res: {:?}", res);
         assert!(res.is_err());
     }
+
+    #[tokio::test]
+    async fn fund_accounts_batches_through_disperse_contract() {
+        let anvil = spawn_anvil();
+        let rpc_client = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_http(anvil.endpoint_url())
+            .boxed();
+        let eth_client = ProviderBuilder::new().on_http(anvil.endpoint_url()).boxed();
+        let min_balance = U256::from(ETH_TO_WEI);
+        let default_signer = PrivateKeySigner::from_str(super::DEFAULT_PRV_KEYS[0]).unwrap();
+        let recipient_addresses = (1..=super::DISPERSE_MIN_RECIPIENTS as u8 + 2)
+            .map(|n| Address::repeat_byte(n))
+            .collect::<Vec<_>>();
+
+        let funding_txs = fund_accounts(
+            &recipient_addresses,
+            &default_signer,
+            &rpc_client,
+            &eth_client,
+            min_balance,
+        )
+        .await
+        .unwrap();
+        assert_eq!(funding_txs.len(), recipient_addresses.len());
+        // all accounts fit in one batch
+        assert!(funding_txs
+            .iter()
+            .all(|tx| tx.tx_hash == funding_txs[0].tx_hash));
+        for addr in &recipient_addresses {
+            assert_eq!(rpc_client.get_balance(*addr).await.unwrap(), min_balance);
+        }
+    }
+
+    #[tokio::test]
+    async fn deploys_multicall_stand_in() {
+        let anvil = spawn_anvil();
+        let rpc_client = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_http(anvil.endpoint_url())
+            .boxed();
+        let eth_client = ProviderBuilder::new().on_http(anvil.endpoint_url()).boxed();
+        let signer = PrivateKeySigner::from_str(super::DEFAULT_PRV_KEYS[0]).unwrap();
+        let multicall = deploy_multicall(&signer, &rpc_client, &eth_client)
+            .await
+            .unwrap();
+        // deploying again reuses the contract
+        assert_eq!(
+            deploy_multicall(&signer, &rpc_client, &eth_client)
+                .await
+                .unwrap(),
+            multicall
+        );
+
+        let calls = [1u8, 2]
+            .map(|n| {
+                let tx = TransactionRequest {
+                    from: Some(signer.address()),
+                    to: Some(TxKind::Call(Address::repeat_byte(n))),
+                    value: Some(U256::from(n)),
+                    ..Default::default()
+                };
+                NamedTxRequest::new(tx, None, None)
+            })
+            .to_vec();
+        let tx = TransactionRequest {
+            gas: Some(200_000),
+            gas_price: Some(eth_client.get_gas_price().await.unwrap()),
+            nonce: Some(
+                eth_client
+                    .get_transaction_count(signer.address())
+                    .await
+                    .unwrap(),
+            ),
+            chain_id: Some(eth_client.get_chain_id().await.unwrap()),
+            ..aggregate_txs(&calls, multicall).unwrap().tx
+        };
+        let receipt = eth_client
+            .send_tx_envelope(tx.build(&EthereumWallet::from(signer)).await.unwrap())
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert!(receipt.status());
+        for n in [1u8, 2] {
+            assert_eq!(
+                rpc_client
+                    .get_balance(Address::repeat_byte(n))
+                    .await
+                    .unwrap(),
+                U256::from(n)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn leaves_out_run_metadata_the_node_fails_to_return() {
+        let anvil = spawn_anvil();
+        let rpc_client = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_http(anvil.endpoint_url())
+            .boxed();
+        let metadata = get_run_metadata(&rpc_client).await;
+        assert_eq!(metadata.chain_id, 31337);
+        assert!(!metadata.client_version.is_empty());
+
+        drop(anvil);
+        let metadata = get_run_metadata(&rpc_client).await;
+        assert_eq!(metadata.chain_id, 0);
+        assert!(metadata.client_version.is_empty());
+        assert!(metadata.fork.is_empty());
+    }
+
+    #[test]
+    fn names_piped_scenarios_by_content_hash() {
+        let path = piped_scenario_path("/tmp/stdin", "[[spam]]");
+        assert!(path.starts_with("/tmp/stdin/scenario-"));
+        assert!(path.ends_with(".toml"));
+        assert_eq!(path, piped_scenario_path("/tmp/stdin", "[[spam]]"));
+        assert_ne!(path, piped_scenario_path("/tmp/stdin", "[[setup]]"));
+    }
+
+    #[test]
+    fn chain_id_guard_rejects_mismatches_and_mainnets() {
+        assert!(check_chain_id(None, 31337, false).is_ok());
+        assert!(check_chain_id(Some(31337), 31337, false).is_ok());
+        assert!(check_chain_id(Some(31337), 17000, false).is_err());
+        assert!(check_chain_id(None, 1, false).is_err());
+        assert!(check_chain_id(Some(1), 1, false).is_err());
+        assert!(check_chain_id(Some(31337), 8453, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn waits_for_empty_mempool() {
+        let anvil = spawn_anvil();
+        let rpc_client = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_http(anvil.endpoint_url())
+            .boxed();
+        assert!(wait_for_empty_mempool(&rpc_client, 0).await.is_ok());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_duration("5"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("5h").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration(&format!("{}m", u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn parses_gas_amounts() {
+        assert_eq!(parse_gas("10Ggas"), Ok(10_000_000_000));
+        assert_eq!(parse_gas("1.5Mgas"), Ok(1_500_000));
+        assert_eq!(parse_gas("30m"), Ok(30_000_000));
+        assert_eq!(parse_gas("21000"), Ok(21_000));
+        assert!(parse_gas("10Tgas").is_err());
+        assert!(parse_gas("-1Ggas").is_err());
+        assert!(parse_gas("0").is_err());
+        assert!(parse_gas("0.1gas").is_err());
+    }
+
+    #[test]
+    fn parses_error_rate_limits() {
+        let limit = parse_error_rate_limit("20%/30s").unwrap();
+        assert_eq!(limit.max_rate, 0.2);
+        assert_eq!(limit.window, Duration::from_secs(30));
+        assert!(parse_error_rate_limit("20/30s").is_err());
+        assert!(parse_error_rate_limit("120%/30s").is_err());
+        assert!(parse_error_rate_limit("20%").is_err());
+    }
+
+    #[test]
+    fn infers_fork_from_header_fields() {
+        let mut header = Header::default();
+        assert_eq!(fork_name(&header), "pre-London");
+        header.base_fee_per_gas = Some(7);
+        assert_eq!(fork_name(&header), "London");
+        header.withdrawals_root = Some(Default::default());
+        assert_eq!(fork_name(&header), "Shanghai");
+        header.blob_gas_used = Some(0);
+        assert_eq!(fork_name(&header), "Cancun");
+        header.requests_root = Some(Default::default());
+        assert_eq!(fork_name(&header), "Prague");
+    }
 }
@@ -64,6 +64,27 @@ impl AgentStore {
         self.agents.iter()
     }
 
+    /// Returns the signer for `address` from any agent.
+    pub fn find_signer(&self, address: &Address) -> Option<&PrivateKeySigner> {
+        self.agents
+            .values()
+            .flat_map(|store| store.signers.iter())
+            .find(|signer| signer.address() == *address)
+    }
+
+    /// Maps each agent signer's address to the name of its pool.
+    pub fn pools_by_address(&self) -> HashMap<Address, String> {
+        self.agents
+            .iter()
+            .flat_map(|(name, store)| {
+                store
+                    .signers
+                    .iter()
+                    .map(move |signer| (signer.address(), name.to_owned()))
+            })
+            .collect()
+    }
+
     pub fn has_agent(&self, name: impl AsRef<str>) -> bool {
         self.agents.contains_key(name.as_ref())
     }
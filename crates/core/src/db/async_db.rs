@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use super::{BundleSubmission, DbOps, LatencySnapshot, NamedTx, RunTx};
+use crate::{error::ContenderError, Result};
+
+/// Async façade over [`DbOps`]. Each call runs on tokio's blocking thread pool, so slow writes
+/// (e.g. SQLite under heavy write load) don't stall the async tasks sharing the runtime.
+#[derive(Debug)]
+pub struct AsyncDb<D> {
+    inner: Arc<D>,
+}
+
+impl<D> Clone for AsyncDb<D> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<D> AsyncDb<D>
+where
+    D: DbOps + Send + Sync + 'static,
+{
+    pub fn new(db: Arc<D>) -> Self {
+        Self { inner: db }
+    }
+
+    /// Returns the wrapped DB, for calls which are fine to make synchronously.
+    pub fn inner(&self) -> &Arc<D> {
+        &self.inner
+    }
+
+    /// Runs `f` against the wrapped DB on a blocking thread.
+    pub async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&D) -> Result<T> + Send + 'static,
+    {
+        let db = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(|e| ContenderError::with_err(e, "db task failed"))?
+    }
+
+    pub async fn insert_run_txs(&self, run_id: u64, run_txs: Vec<RunTx>) -> Result<()> {
+        self.call(move |db| db.insert_run_txs(run_id, run_txs))
+            .await
+    }
+
+    pub async fn insert_latency_snapshots(
+        &self,
+        run_id: u64,
+        snapshots: Vec<LatencySnapshot>,
+    ) -> Result<()> {
+        self.call(move |db| db.insert_latency_snapshots(run_id, &snapshots))
+            .await
+    }
+
+    pub async fn insert_bundles(&self, run_id: u64, bundles: Vec<BundleSubmission>) -> Result<()> {
+        self.call(move |db| db.insert_bundles(run_id, &bundles))
+            .await
+    }
+
+    pub async fn insert_named_txs(&self, named_txs: Vec<NamedTx>, rpc_url: String) -> Result<()> {
+        self.call(move |db| db.insert_named_txs(named_txs, &rpc_url))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MockDb;
+
+    #[tokio::test]
+    async fn runs_calls_on_blocking_pool() {
+        let db = AsyncDb::new(Arc::new(MockDb));
+        assert_eq!(db.call(|db| db.num_runs()).await.unwrap(), 0);
+        assert!(db.insert_run_txs(1, vec![]).await.is_ok());
+    }
+}
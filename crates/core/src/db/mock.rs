@@ -1,6 +1,9 @@
 use alloy::primitives::{Address, TxHash};
 
-use super::{DbOps, NamedTx, RunTx};
+use super::{
+    AccountBalance, BundleSubmission, DbOps, DeployedContract, FundingTx, LatencySnapshot, NamedTx,
+    ReplacedTx, RunArtifacts, RunError, RunMetadata, RunProvenance, RunScore, RunTx, RunTxStats,
+};
 use crate::Result;
 
 pub struct MockDb;
@@ -10,7 +13,13 @@ impl DbOps for MockDb {
         Ok(())
     }
 
-    fn insert_run(&self, _timestamp: u64, _tx_count: usize, _scenario_name: &str) -> Result<u64> {
+    fn insert_run(
+        &self,
+        _timestamp: u64,
+        _tx_count: usize,
+        _scenario_name: &str,
+        _rpc_url: &str,
+    ) -> Result<u64> {
         Ok(0)
     }
 
@@ -18,15 +27,129 @@ impl DbOps for MockDb {
         Ok(None)
     }
 
+    fn get_runs(&self) -> Result<Vec<super::SpamRun>> {
+        Ok(vec![])
+    }
+
     fn num_runs(&self) -> Result<u64> {
         Ok(0)
     }
 
+    fn insert_run_metadata(&self, _run_id: u64, _metadata: &RunMetadata) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_metadata(&self, _run_id: u64) -> Result<Option<RunMetadata>> {
+        Ok(None)
+    }
+
+    fn insert_run_provenance(&self, _run_id: u64, _provenance: &RunProvenance) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_provenance(&self, _run_id: u64) -> Result<Option<RunProvenance>> {
+        Ok(None)
+    }
+
+    fn insert_run_artifacts(&self, _run_id: u64, _artifacts: &RunArtifacts) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_artifacts(&self, _run_id: u64) -> Result<Option<RunArtifacts>> {
+        Ok(None)
+    }
+
+    fn insert_run_score(&self, _run_id: u64, _score: &RunScore) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_score(&self, _run_id: u64) -> Result<Option<RunScore>> {
+        Ok(None)
+    }
+
+    fn insert_baseline_run(&self, _series: &str, _run_id: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_baseline_runs(&self, _series: &str) -> Result<Vec<u64>> {
+        Ok(vec![])
+    }
+
+    fn insert_latency_snapshots(&self, _run_id: u64, _snapshots: &[LatencySnapshot]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_latency_snapshots(&self, _run_id: u64) -> Result<Vec<LatencySnapshot>> {
+        Ok(vec![])
+    }
+
+    fn insert_bundles(&self, _run_id: u64, _bundles: &[BundleSubmission]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_bundles(&self, _run_id: u64) -> Result<Vec<BundleSubmission>> {
+        Ok(vec![])
+    }
+
+    fn insert_run_accounts(&self, _run_id: u64, _accounts: &[(String, Address)]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_accounts(&self, _run_id: u64) -> Result<Vec<(String, Address)>> {
+        Ok(vec![])
+    }
+
+    fn insert_funding_txs(&self, _run_id: Option<u64>, _txs: &[FundingTx]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_funding_txs(&self, _run_id: u64) -> Result<Vec<FundingTx>> {
+        Ok(vec![])
+    }
+
+    fn insert_replaced_txs(&self, _run_id: u64, _txs: &[ReplacedTx]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_replaced_txs(&self, _run_id: u64) -> Result<Vec<ReplacedTx>> {
+        Ok(vec![])
+    }
+
+    fn insert_run_errors(&self, _run_id: u64, _errors: &[RunError]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_run_errors(&self, _run_id: u64) -> Result<Vec<RunError>> {
+        Ok(vec![])
+    }
+
+    fn insert_balances(
+        &self,
+        _run_id: u64,
+        _at_start: bool,
+        _balances: &[AccountBalance],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_balances(&self, _run_id: u64, _at_start: bool) -> Result<Vec<AccountBalance>> {
+        Ok(vec![])
+    }
+
+    fn insert_rpc_url_alias(&self, _rpc_url: &str, _canonical_url: &str) -> Result<()> {
+        Ok(())
+    }
+
     fn insert_named_txs(&self, _named_txs: Vec<NamedTx>, _rpc_url: &str) -> Result<()> {
         Ok(())
     }
 
-    fn get_named_tx(&self, _name: &str, _rpc_url: &str) -> Result<Option<NamedTx>> {
+    fn get_named_tx(
+        &self,
+        _name: &str,
+        _rpc_url: &str,
+        _namespace: Option<&str>,
+    ) -> Result<Option<NamedTx>> {
         Ok(Some(NamedTx::new(
             String::default(),
             TxHash::default(),
@@ -42,6 +165,10 @@ impl DbOps for MockDb {
         )))
     }
 
+    fn get_contracts(&self) -> Result<Vec<DeployedContract>> {
+        Ok(vec![])
+    }
+
     fn insert_run_txs(&self, _run_id: u64, _run_txs: Vec<RunTx>) -> Result<()> {
         Ok(())
     }
@@ -49,4 +176,24 @@ impl DbOps for MockDb {
     fn get_run_txs(&self, _run_id: u64) -> Result<Vec<RunTx>> {
         Ok(vec![])
     }
+
+    fn get_run_tx_stats(&self, _run_id: u64) -> Result<RunTxStats> {
+        Ok(RunTxStats::default())
+    }
+
+    fn insert_alias(&self, _name: &str, _address: Address) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_alias(&self, _name: &str) -> Result<Option<Address>> {
+        Ok(None)
+    }
+
+    fn list_aliases(&self) -> Result<Vec<(String, Address)>> {
+        Ok(vec![])
+    }
+
+    fn remove_alias(&self, _name: &str) -> Result<bool> {
+        Ok(false)
+    }
 }
@@ -1,22 +1,102 @@
+mod async_db;
 mod mock;
+mod rpc_url;
+mod writer;
 
-use alloy::primitives::{Address, TxHash};
+use alloy::primitives::{keccak256, Address, TxHash, B256, U256};
 use serde::Serialize;
 
-use crate::Result;
+use crate::{generator::types::ExpectStatus, Result};
 
+pub use async_db::AsyncDb;
 pub use mock::MockDb;
+pub use rpc_url::{normalize_rpc_url, redact_rpc_url, redact_rpc_url_in};
+pub use writer::{DbWriter, DEFAULT_FLUSH_INTERVAL, DEFAULT_WRITER_CAPACITY};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct RunTx {
     pub tx_hash: TxHash,
+    /// Time the tx was sent, in milliseconds since the unix epoch.
     #[serde(rename = "start_time")]
     pub start_timestamp: usize,
+    /// Time the block including the tx was seen, in milliseconds since the unix epoch.
     #[serde(rename = "end_time")]
     pub end_timestamp: usize,
     pub block_number: u64,
+    /// Timestamp of the block including the tx, in seconds since the unix epoch.
+    pub block_timestamp: u64,
     pub gas_used: u128,
     pub kind: Option<String>,
+    /// Milliseconds the node took to acknowledge the tx's send call (e.g. `eth_sendRawTransaction`);
+    /// `None` for bundled txs and runs recorded before it was measured.
+    pub ack_latency_ms: Option<u64>,
+    /// Agent pool the tx was sent from; `None` for txs sent by the user's own signers and runs
+    /// recorded before it was tracked.
+    pub from_pool: Option<String>,
+    /// Receipt status declared by the tx's step with `expect_status`, if any.
+    pub expect_status: Option<ExpectStatus>,
+    /// Max gas the tx's step allowed it to use with `max_gas_used`, if any.
+    pub max_gas_used: Option<u64>,
+    /// Latest block seen by the spammer when the tx was sent, if known.
+    pub sent_block: Option<u64>,
+}
+
+impl RunTx {
+    /// Milliseconds from sending the tx to seeing it land.
+    pub fn time_to_inclusion_ms(&self) -> u64 {
+        self.end_timestamp.saturating_sub(self.start_timestamp) as u64
+    }
+
+    /// Whether the tx's step meant for it to revert, so its revert isn't an error.
+    pub fn expects_revert(&self) -> bool {
+        self.expect_status == Some(ExpectStatus::Revert)
+    }
+
+    /// Number of blocks from the one the tx was sent in to the one it landed in, if known.
+    pub fn blocks_to_inclusion(&self) -> Option<u64> {
+        self.sent_block
+            .map(|sent_block| self.block_number.saturating_sub(sent_block))
+    }
+
+    /// Whether the tx used more gas than its step's `max_gas_used`.
+    pub fn exceeds_max_gas_used(&self) -> bool {
+        self.max_gas_used
+            .is_some_and(|max_gas_used| self.gas_used > max_gas_used as u128)
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl RunTx {
+    /// Tx for tests, with `n` repeated as its hash, sent at 0 and seen landing 1s later in block 1
+    /// with 21000 gas used. Other fields can be set with struct update syntax, e.g.
+    /// `RunTx { gas_used: 50_000, ..RunTx::mock(1) }`.
+    pub fn mock(n: u8) -> Self {
+        Self {
+            tx_hash: TxHash::repeat_byte(n),
+            start_timestamp: 0,
+            end_timestamp: 1000,
+            block_number: 1,
+            block_timestamp: 1,
+            gas_used: 21_000,
+            kind: None,
+            ack_latency_ms: None,
+            from_pool: None,
+            expect_status: None,
+            max_gas_used: None,
+            sent_block: None,
+        }
+    }
+
+    /// Sets when the tx was sent & seen landing, in milliseconds, and its block's timestamp to
+    /// match.
+    pub fn with_times(self, start_timestamp: usize, end_timestamp: usize) -> Self {
+        Self {
+            start_timestamp,
+            end_timestamp,
+            block_timestamp: (end_timestamp / 1000) as u64,
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -24,6 +104,11 @@ pub struct NamedTx {
     pub name: String,
     pub tx_hash: TxHash,
     pub address: Option<Address>,
+    /// Details of the contract deployed by the tx, if it was a contract deployment.
+    pub contract: Option<ContractInfo>,
+    /// Scope of the name, so scenarios using the same names don't resolve each other's txs.
+    /// `None` for txs saved before namespaces were tracked.
+    pub namespace: Option<String>,
 }
 
 impl NamedTx {
@@ -32,40 +117,324 @@ impl NamedTx {
             name,
             tx_hash,
             address,
+            contract: None,
+            namespace: None,
+        }
+    }
+
+    pub fn with_namespace(self, namespace: Option<String>) -> Self {
+        Self { namespace, ..self }
+    }
+
+    pub fn with_contract(self, contract: ContractInfo) -> Self {
+        Self {
+            contract: Some(contract),
+            ..self
         }
     }
 }
 
+/// What contender knows about a contract it deployed.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct ContractInfo {
+    /// keccak256 of the deployed (runtime) bytecode.
+    pub code_hash: Option<B256>,
+    /// Hex-encoded deployment data: init code followed by the ABI-encoded constructor args.
+    pub deploy_data: Option<String>,
+    /// JSON ABI of the contract, if one was provided in the scenario.
+    pub abi: Option<String>,
+    /// Name of the scenario which deployed the contract.
+    pub scenario_name: Option<String>,
+}
+
+/// An entry in the contract registry: the latest deployment of a named contract to an RPC URL.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct DeployedContract {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub address: Address,
+    pub tx_hash: TxHash,
+    /// Canonical RPC URL the contract was deployed to.
+    pub rpc_url: String,
+    pub info: ContractInfo,
+    /// Time the deployment was recorded, in milliseconds since the unix epoch; `None` for
+    /// deployments recorded before it was tracked.
+    pub deployed_at: Option<u64>,
+}
+
 impl From<NamedTx> for Vec<NamedTx> {
     fn from(named_tx: NamedTx) -> Self {
         vec![named_tx]
     }
 }
 
+#[derive(Debug, Serialize, Clone)]
 pub struct SpamRun {
     pub id: u64,
     pub timestamp: usize,
     pub tx_count: usize,
     pub scenario_name: String,
+    /// RPC URL the run was sent to; empty for runs recorded before it was tracked.
+    pub rpc_url: String,
+}
+
+/// Aggregates of the txs recorded for a run that have landed, computed by the DB so they can be
+/// polled while the run is in progress without loading every tx.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct RunTxStats {
+    pub landed_txs: usize,
+    pub gas_used: u128,
+    /// Latest block a tx of the run landed in.
+    pub latest_block: Option<u64>,
+    /// Mean time from sending a tx to its inclusion, in milliseconds.
+    pub avg_inclusion_ms: Option<u64>,
+}
+
+/// Describes the node and chain a run was sent to.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RunMetadata {
+    /// Result of `web3_clientVersion`.
+    pub client_version: String,
+    pub chain_id: u64,
+    pub genesis_hash: B256,
+    /// Latest protocol fork active at the start of the run, inferred from the latest block header.
+    pub fork: String,
+}
+
+/// RPC latency percentiles for a single method over one window of a run.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LatencySnapshot {
+    /// Time the snapshot was taken, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+    pub method: String,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// Number of calls made during the window.
+    pub count: u64,
+}
+
+/// A bundle sent to the builder during a run.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct BundleSubmission {
+    /// keccak256 of the concatenated tx hashes in the bundle.
+    pub bundle_hash: B256,
+    /// First block the bundle targeted; it's resubmitted for subsequent blocks.
+    pub target_block: u64,
+    /// Number of times the bundle was successfully submitted.
+    pub num_submissions: u64,
+    pub tx_hashes: Vec<TxHash>,
+}
+
+/// A tx sent by the admin account to fund a spammer account.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FundingTx {
+    pub tx_hash: TxHash,
+    pub sender: Address,
+    pub recipient: Address,
+    /// Amount sent to the recipient, in wei.
+    pub amount: U256,
+    pub gas_used: u128,
+    pub effective_gas_price: u128,
+}
+
+/// A sent tx which never landed because another tx from the same sender used its nonce
+/// (e.g. a fee bump, or external traffic from the same account).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReplacedTx {
+    pub tx_hash: TxHash,
+    pub sender: Address,
+    pub nonce: u64,
+    /// Time the tx was sent, in milliseconds since the unix epoch.
+    pub start_timestamp: usize,
+    /// Block in which the nonce was used by another tx.
+    pub block_number: u64,
+    pub kind: Option<String>,
+}
+
+/// Number of errors of one kind (see [`crate::error::ContenderError::kind`]) hit while sending a
+/// run's txs of one tx kind, e.g. failed sends & txs that never landed.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RunError {
+    /// Kind of the txs; `None` for unlabeled txs and errors that weren't tied to a tx.
+    pub kind: Option<String>,
+    pub error_kind: String,
+    pub count: u64,
+}
+
+/// An account's balance at the start or end of a run.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AccountBalance {
+    /// Name of the pool the account belongs to ("admin" for user-provided signers).
+    pub pool: String,
+    pub address: Address,
+    /// Balance in wei.
+    pub balance: U256,
+}
+
+/// What's needed to re-run a spam run exactly.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RunProvenance {
+    /// Contents of the scenario file, as it was when the run started.
+    pub scenario: String,
+    /// JSON-encoded spam args (seed, rates, duration, etc.), excluding private keys.
+    pub args: String,
+}
+
+/// Snapshot of the exact scenario a run was sent from, so reports & reproductions of the run don't
+/// depend on the scenario file, which may have changed since.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RunArtifacts {
+    /// keccak256 hash of `scenario`.
+    pub scenario_hash: B256,
+    /// Fully-resolved scenario TOML (contract sources compiled to bytecode).
+    pub scenario: String,
+}
+
+impl RunArtifacts {
+    pub fn new(scenario: String) -> Self {
+        Self {
+            scenario_hash: keccak256(scenario.as_bytes()),
+            scenario,
+        }
+    }
+}
+
+/// Composite performance score of a run, e.g. from `contender bench`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RunScore {
+    /// Weighted average of the components, out of 100.
+    pub score: f64,
+    /// Letter grade for `score`, A to F.
+    pub grade: String,
+    /// Gas/sec that landed relative to the gas/sec that was sent, from 0 to 1.
+    pub throughput: f64,
+    /// Target latency relative to the run's p95 inclusion latency, from 0 to 1.
+    pub latency: f64,
+    /// Share of sent txs that landed, from 0 to 1.
+    pub reliability: f64,
 }
 
 pub trait DbOps {
     fn create_tables(&self) -> Result<()>;
 
     /// Insert a new run into the database. Returns run_id.
-    fn insert_run(&self, timestamp: u64, tx_count: usize, scenario_name: &str) -> Result<u64>;
+    fn insert_run(
+        &self,
+        timestamp: u64,
+        tx_count: usize,
+        scenario_name: &str,
+        rpc_url: &str,
+    ) -> Result<u64>;
 
     fn num_runs(&self) -> Result<u64>;
 
     fn get_run(&self, run_id: u64) -> Result<Option<SpamRun>>;
 
+    /// Get every run, newest first.
+    fn get_runs(&self) -> Result<Vec<SpamRun>>;
+
+    /// Save node & chain metadata for a run, replacing any existing metadata for that run.
+    fn insert_run_metadata(&self, run_id: u64, metadata: &RunMetadata) -> Result<()>;
+
+    fn get_run_metadata(&self, run_id: u64) -> Result<Option<RunMetadata>>;
+
+    fn insert_run_provenance(&self, run_id: u64, provenance: &RunProvenance) -> Result<()>;
+
+    fn get_run_provenance(&self, run_id: u64) -> Result<Option<RunProvenance>>;
+
+    /// Save a snapshot of a run's scenario, replacing any existing snapshot for that run.
+    fn insert_run_artifacts(&self, run_id: u64, artifacts: &RunArtifacts) -> Result<()>;
+
+    /// Returns the snapshot of a run's scenario. Fails if the snapshot doesn't match its hash.
+    fn get_run_artifacts(&self, run_id: u64) -> Result<Option<RunArtifacts>>;
+
+    /// Save a run's score, replacing any existing score for that run.
+    fn insert_run_score(&self, run_id: u64, score: &RunScore) -> Result<()>;
+
+    fn get_run_score(&self, run_id: u64) -> Result<Option<RunScore>>;
+
+    /// Append a run to a named baseline series, which tracks performance across runs over time.
+    fn insert_baseline_run(&self, series: &str, run_id: u64) -> Result<()>;
+
+    /// Returns the IDs of the runs in a baseline series, oldest first.
+    fn get_baseline_runs(&self, series: &str) -> Result<Vec<u64>>;
+
+    fn insert_latency_snapshots(&self, run_id: u64, snapshots: &[LatencySnapshot]) -> Result<()>;
+
+    /// Returns the run's latency snapshots, ordered by time.
+    fn get_latency_snapshots(&self, run_id: u64) -> Result<Vec<LatencySnapshot>>;
+
+    fn insert_bundles(&self, run_id: u64, bundles: &[BundleSubmission]) -> Result<()>;
+
+    fn get_bundles(&self, run_id: u64) -> Result<Vec<BundleSubmission>>;
+
+    /// Save the accounts a run sends from, as (pool name, address) pairs. Funding txs saved without
+    /// a run (by the setup command) which funded these accounts are linked to the run.
+    fn insert_run_accounts(&self, run_id: u64, accounts: &[(String, Address)]) -> Result<()>;
+
+    fn get_run_accounts(&self, run_id: u64) -> Result<Vec<(String, Address)>>;
+
+    /// Save funding txs. `run_id` is `None` for txs sent outside of a run (e.g. during setup); see
+    /// [`DbOps::insert_run_accounts`].
+    fn insert_funding_txs(&self, run_id: Option<u64>, txs: &[FundingTx]) -> Result<()>;
+
+    fn get_funding_txs(&self, run_id: u64) -> Result<Vec<FundingTx>>;
+
+    fn insert_replaced_txs(&self, run_id: u64, txs: &[ReplacedTx]) -> Result<()>;
+
+    fn get_replaced_txs(&self, run_id: u64) -> Result<Vec<ReplacedTx>>;
+
+    fn insert_run_errors(&self, run_id: u64, errors: &[RunError]) -> Result<()>;
+
+    fn get_run_errors(&self, run_id: u64) -> Result<Vec<RunError>>;
+
+    /// Save account balances taken at the start (`at_start = true`) or end of a run.
+    fn insert_balances(
+        &self,
+        run_id: u64,
+        at_start: bool,
+        balances: &[AccountBalance],
+    ) -> Result<()>;
+
+    fn get_balances(&self, run_id: u64, at_start: bool) -> Result<Vec<AccountBalance>>;
+
+    /// Make `rpc_url` an alias of `canonical_url`, so named txs saved for either one are found
+    /// when looking up the other. Named txs already saved for `rpc_url` are moved to `canonical_url`.
+    fn insert_rpc_url_alias(&self, rpc_url: &str, canonical_url: &str) -> Result<()>;
+
+    /// Named txs are saved per RPC URL; URLs are normalized with [`normalize_rpc_url`] and aliases
+    /// are resolved before lookups.
     fn insert_named_txs(&self, named_txs: Vec<NamedTx>, rpc_url: &str) -> Result<()>;
 
-    fn get_named_tx(&self, name: &str, rpc_url: &str) -> Result<Option<NamedTx>>;
+    /// If `namespace` is set, only named txs in that namespace (or saved without one) are found.
+    fn get_named_tx(
+        &self,
+        name: &str,
+        rpc_url: &str,
+        namespace: Option<&str>,
+    ) -> Result<Option<NamedTx>>;
 
     fn get_named_tx_by_address(&self, address: &Address) -> Result<Option<NamedTx>>;
 
+    /// Returns the contract registry: the latest deployment of each named contract to each
+    /// RPC URL in each namespace, across all runs, sorted by name.
+    fn get_contracts(&self) -> Result<Vec<DeployedContract>>;
+
     fn insert_run_txs(&self, run_id: u64, run_txs: Vec<RunTx>) -> Result<()>;
 
     fn get_run_txs(&self, run_id: u64) -> Result<Vec<RunTx>>;
+
+    /// Get aggregates of the run's landed txs.
+    fn get_run_tx_stats(&self, run_id: u64) -> Result<RunTxStats>;
+
+    /// Save an address book entry, replacing any existing entry with the same name.
+    fn insert_alias(&self, name: &str, address: Address) -> Result<()>;
+
+    fn get_alias(&self, name: &str) -> Result<Option<Address>>;
+
+    /// Returns all address book entries, sorted by name.
+    fn list_aliases(&self) -> Result<Vec<(String, Address)>>;
+
+    /// Remove an address book entry. Returns false if no entry was found.
+    fn remove_alias(&self, name: &str) -> Result<bool>;
 }
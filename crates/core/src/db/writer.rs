@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use tokio::sync::{mpsc, oneshot};
+
+use super::{AsyncDb, DbOps, LatencySnapshot, ReplacedTx, RunTx};
+use crate::{error::ContenderError, Result};
+
+/// Default number of writes that can be queued before senders wait on the writer.
+pub const DEFAULT_WRITER_CAPACITY: usize = 1024;
+
+/// Default time between batched writes.
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+enum DbWrite {
+    RunTxs(u64, Vec<RunTx>),
+    LatencySnapshots(u64, Vec<LatencySnapshot>),
+    ReplacedTxs(u64, Vec<ReplacedTx>),
+    Flush(oneshot::Sender<Result<()>>),
+}
+
+/// Writes queued by run ID, waiting for the next flush.
+#[derive(Default)]
+struct PendingWrites {
+    run_txs: BTreeMap<u64, Vec<RunTx>>,
+    latency_snapshots: BTreeMap<u64, Vec<LatencySnapshot>>,
+    replaced_txs: BTreeMap<u64, Vec<ReplacedTx>>,
+}
+
+impl PendingWrites {
+    fn is_empty(&self) -> bool {
+        self.run_txs.is_empty() && self.latency_snapshots.is_empty() && self.replaced_txs.is_empty()
+    }
+
+    /// Writes every pending batch to the DB, one batch per run & table. A batch that fails to be
+    /// written is kept, along with those after it, to be retried by the next flush.
+    async fn flush<D: DbOps + Send + Sync + 'static>(&mut self, db: &AsyncDb<D>) -> Result<()> {
+        while let Some((run_id, run_txs)) = self.run_txs.pop_first() {
+            if let Err(e) = db.insert_run_txs(run_id, run_txs.clone()).await {
+                self.run_txs.insert(run_id, run_txs);
+                return Err(e);
+            }
+        }
+        while let Some((run_id, snapshots)) = self.latency_snapshots.pop_first() {
+            if let Err(e) = db.insert_latency_snapshots(run_id, snapshots.clone()).await {
+                self.latency_snapshots.insert(run_id, snapshots);
+                return Err(e);
+            }
+        }
+        while let Some((run_id, replaced_txs)) = self.replaced_txs.pop_first() {
+            let batch = replaced_txs.clone();
+            if let Err(e) = db
+                .call(move |db| db.insert_replaced_txs(run_id, &batch))
+                .await
+            {
+                self.replaced_txs.insert(run_id, replaced_txs);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Handle to a task which owns all run_tx, replaced tx & latency writes for a scenario.
+/// Writes are queued on a bounded channel and inserted in batches every `flush_interval`,
+/// so callbacks don't contend for DB connections at high TPS.
+#[derive(Debug, Clone)]
+pub struct DbWriter {
+    sender: mpsc::Sender<DbWrite>,
+}
+
+impl DbWriter {
+    /// Spawns the writer task. `capacity` bounds the number of queued writes; once it's reached,
+    /// senders wait until the writer catches up.
+    pub fn new<D: DbOps + Send + Sync + 'static>(
+        db: Arc<D>,
+        capacity: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<DbWrite>(capacity);
+        let db = AsyncDb::new(db);
+        tokio::task::spawn(async move {
+            let mut pending = PendingWrites::default();
+            let mut interval = tokio::time::interval(flush_interval);
+            loop {
+                tokio::select! {
+                    msg = receiver.recv() => match msg {
+                        Some(DbWrite::RunTxs(run_id, run_txs)) => {
+                            pending.run_txs.entry(run_id).or_default().extend(run_txs);
+                        }
+                        Some(DbWrite::LatencySnapshots(run_id, snapshots)) => {
+                            pending
+                                .latency_snapshots
+                                .entry(run_id)
+                                .or_default()
+                                .extend(snapshots);
+                        }
+                        Some(DbWrite::ReplacedTxs(run_id, replaced_txs)) => {
+                            pending.replaced_txs.entry(run_id).or_default().extend(replaced_txs);
+                        }
+                        Some(DbWrite::Flush(on_flush)) => {
+                            let _ = on_flush.send(pending.flush(&db).await);
+                        }
+                        None => {
+                            // all handles were dropped; write what's left and stop
+                            if let Err(e) = pending.flush(&db).await {
+                                eprintln!("failed to write to DB: {:?}", e);
+                            }
+                            break;
+                        }
+                    },
+                    _ = interval.tick() => {
+                        if pending.is_empty() {
+                            continue;
+                        }
+                        // the writes are kept; an explicit flush retries them & returns the error
+                        if let Err(e) = pending.flush(&db).await {
+                            eprintln!("failed to write to DB, retrying on the next flush: {:?}", e);
+                        }
+                    }
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    async fn send(&self, msg: DbWrite) -> Result<()> {
+        self.sender
+            .send(msg)
+            .await
+            .map_err(|_| ContenderError::DbError("DB writer has stopped", None))
+    }
+
+    pub async fn insert_run_txs(&self, run_id: u64, run_txs: Vec<RunTx>) -> Result<()> {
+        self.send(DbWrite::RunTxs(run_id, run_txs)).await
+    }
+
+    pub async fn insert_latency_snapshots(
+        &self,
+        run_id: u64,
+        snapshots: Vec<LatencySnapshot>,
+    ) -> Result<()> {
+        self.send(DbWrite::LatencySnapshots(run_id, snapshots))
+            .await
+    }
+
+    pub async fn insert_replaced_txs(&self, run_id: u64, txs: Vec<ReplacedTx>) -> Result<()> {
+        self.send(DbWrite::ReplacedTxs(run_id, txs)).await
+    }
+
+    /// Writes all queued inserts to the DB. Returns once they've been written.
+    pub async fn flush(&self) -> Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.send(DbWrite::Flush(sender)).await?;
+        receiver
+            .await
+            .map_err(|_| ContenderError::DbError("DB writer has stopped", None))?
+    }
+}
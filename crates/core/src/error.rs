@@ -1,16 +1,201 @@
-use std::error::Error;
+use std::{error::Error, future::Future, time::Duration};
+
+use alloy::transports::{RpcError, TransportError};
+use serde::{Deserialize, Serialize};
+
+/// Number of times an RPC call is retried after a retryable error.
+pub const RPC_RETRIES: usize = 3;
+/// Backoff before the first retry; doubled after each attempt.
+const RPC_RETRY_BACKOFF: Duration = Duration::from_millis(250);
 
 pub enum ContenderError {
     DbError(&'static str, Option<String>),
     SpamError(&'static str, Option<String>),
     SetupError(&'static str, Option<String>),
     GenericError(&'static str, String),
+    /// The RPC couldn't be reached, or returned a malformed response.
+    Transport(String),
+    /// The tx's nonce was already used, or a tx with the same nonce is pending.
+    NonceConflict(String),
+    /// The sender can't pay for the tx.
+    InsufficientFunds(String),
+    /// The tx reverted, or would revert.
+    Reverted(String),
+    /// The RPC didn't respond in time, or a tx didn't land in time.
+    Timeout(String),
+    /// The node rejected the request for another reason, e.g. `intrinsic gas too low`.
+    Rejected(String),
 }
 
 impl ContenderError {
     pub fn with_err(err: impl Error, msg: &'static str) -> Self {
         ContenderError::GenericError(msg, format!("{:?}", err))
     }
+
+    /// Classifies an error returned by an RPC call. Errors the node responded with are classified
+    /// by their message; failures to reach the node (or to read its response) are transport errors.
+    pub fn from_rpc_error(err: TransportError) -> Self {
+        match err {
+            RpcError::ErrorResp(payload) => ContenderError::from_error_message(payload),
+            RpcError::Transport(e) => {
+                let msg = e.to_string();
+                if is_timeout(&msg.to_lowercase()) {
+                    ContenderError::Timeout(msg)
+                } else {
+                    ContenderError::Transport(msg)
+                }
+            }
+            err @ (RpcError::NullResp | RpcError::DeserError { .. }) => {
+                ContenderError::Transport(err.to_string())
+            }
+            err => ContenderError::GenericError("failed to send RPC request", err.to_string()),
+        }
+    }
+
+    /// Classifies an error message returned by the node, e.g. by a builder's `eth_sendBundle`.
+    /// Messages that aren't recognized are counted as rejections, which aren't retried.
+    pub fn from_error_message(err: impl std::fmt::Display) -> Self {
+        let msg = err.to_string();
+        let lower = msg.to_lowercase();
+        if lower.contains("nonce too low")
+            || lower.contains("nonce too high")
+            || lower.contains("already known")
+            || lower.contains("replacement transaction underpriced")
+        {
+            ContenderError::NonceConflict(msg)
+        } else if lower.contains("insufficient funds") {
+            ContenderError::InsufficientFunds(msg)
+        } else if lower.contains("revert") {
+            ContenderError::Reverted(msg)
+        } else if is_timeout(&lower) {
+            ContenderError::Timeout(msg)
+        } else {
+            ContenderError::Rejected(msg)
+        }
+    }
+
+    /// Whether the same request may succeed if it's sent again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ContenderError::Transport(_) | ContenderError::Timeout(_)
+        )
+    }
+
+    /// Short name of the error's variant, used to break down errors in logs & reports.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ContenderError::DbError(_, _) => "db",
+            ContenderError::SpamError(_, _) => "spam",
+            ContenderError::SetupError(_, _) => "setup",
+            ContenderError::GenericError(_, _) => "generic",
+            ContenderError::Transport(_) => "transport",
+            ContenderError::NonceConflict(_) => "nonce_conflict",
+            ContenderError::InsufficientFunds(_) => "insufficient_funds",
+            ContenderError::Reverted(_) => "reverted",
+            ContenderError::Timeout(_) => "timeout",
+            ContenderError::Rejected(_) => "rejected",
+        }
+    }
+}
+
+fn is_timeout(lower_msg: &str) -> bool {
+    lower_msg.contains("timed out") || lower_msg.contains("timeout")
+}
+
+/// How failed RPC calls (e.g. spam tx sends) are retried.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Number of times a call is retried after a retryable error.
+    pub max_retries: usize,
+    /// Backoff before the first retry; doubled after each attempt.
+    pub backoff: Duration,
+    /// Give up on an attempt that hasn't returned after this long, and count it as a timeout.
+    /// Attempts may take as long as the transport allows if `None`.
+    pub timeout: Option<Duration>,
+    /// Also retry errors whose message contains any of these (case-insensitive), e.g. `-32000`
+    /// or `nonce too low`. Transport errors & timeouts are always retried.
+    #[serde(default)]
+    pub retry_on: Vec<String>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: RPC_RETRIES,
+            backoff: RPC_RETRY_BACKOFF,
+            timeout: None,
+            retry_on: vec![],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The same policy without retries, for sends that aren't idempotent: the node assigns the
+    /// nonce of an `eth_sendTransaction`, so sending it again after a timeout may send a duplicate.
+    pub fn without_retries(&self) -> Self {
+        Self {
+            max_retries: 0,
+            ..self.clone()
+        }
+    }
+
+    /// Whether a call that failed with `err` should be sent again.
+    pub fn should_retry(&self, err: &ContenderError) -> bool {
+        if err.is_retryable() {
+            return true;
+        }
+        let msg = err.to_string().to_lowercase();
+        self.retry_on
+            .iter()
+            .any(|pattern| msg.contains(&pattern.to_lowercase()))
+    }
+}
+
+/// Calls `f` until it succeeds, returns an error that isn't retryable, or has been retried
+/// [`RPC_RETRIES`] times.
+pub async fn with_retries<T, F, Fut>(f: F) -> Result<T, ContenderError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ContenderError>>,
+{
+    with_retry_policy(&RetryPolicy::default(), f).await
+}
+
+/// Calls `f` until it succeeds, returns an error `policy` doesn't retry, or has been retried
+/// `policy.max_retries` times.
+pub async fn with_retry_policy<T, F, Fut>(policy: &RetryPolicy, f: F) -> Result<T, ContenderError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ContenderError>>,
+{
+    let mut backoff = policy.backoff;
+    let mut retries = 0;
+    loop {
+        let res = match policy.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, f())
+                .await
+                .unwrap_or_else(|_| {
+                    Err(ContenderError::Timeout(format!(
+                        "no response within {:?}",
+                        timeout
+                    )))
+                }),
+            None => f().await,
+        };
+        match res {
+            Err(e) if retries < policy.max_retries && policy.should_retry(&e) => {
+                retries += 1;
+                eprintln!(
+                    "{}; retrying in {:?} ({}/{})",
+                    e, backoff, retries, policy.max_retries
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            res => return res,
+        }
+    }
 }
 
 impl std::fmt::Display for ContenderError {
@@ -22,6 +207,12 @@ impl std::fmt::Display for ContenderError {
                 write!(f, "{} {}", msg, e.to_owned())
             }
             ContenderError::SetupError(msg, _) => write!(f, "SetupError: {}", msg),
+            ContenderError::Transport(e) => write!(f, "TransportError: {}", e),
+            ContenderError::NonceConflict(e) => write!(f, "NonceConflict: {}", e),
+            ContenderError::InsufficientFunds(e) => write!(f, "InsufficientFunds: {}", e),
+            ContenderError::Reverted(e) => write!(f, "Reverted: {}", e),
+            ContenderError::Timeout(e) => write!(f, "Timeout: {}", e),
+            ContenderError::Rejected(e) => write!(f, "Rejected: {}", e),
         }
     }
 }
@@ -42,8 +233,111 @@ impl std::fmt::Debug for ContenderError {
             ContenderError::GenericError(msg, e) => {
                 write!(f, "{} {}", msg, e.to_owned())
             }
+            _ => write!(f, "{}", self),
         }
     }
 }
 
 impl Error for ContenderError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use alloy::transports::TransportErrorKind;
+
+    /// An error the node responded with.
+    fn rpc_error(msg: &str) -> ContenderError {
+        let payload =
+            serde_json::from_value(serde_json::json!({ "code": -32000, "message": msg })).unwrap();
+        ContenderError::from_rpc_error(RpcError::ErrorResp(payload))
+    }
+
+    /// A failure to reach the node.
+    fn transport_error(msg: &str) -> ContenderError {
+        ContenderError::from_rpc_error(TransportErrorKind::custom_str(msg))
+    }
+
+    #[test]
+    fn classifies_rpc_errors() {
+        assert_eq!(
+            rpc_error("nonce too low: next nonce 5").kind(),
+            "nonce_conflict"
+        );
+        assert_eq!(rpc_error("already known").kind(), "nonce_conflict");
+        assert_eq!(
+            rpc_error("insufficient funds for gas * price + value").kind(),
+            "insufficient_funds"
+        );
+        assert_eq!(rpc_error("execution reverted: STF").kind(), "reverted");
+        assert_eq!(rpc_error("request timed out").kind(), "timeout");
+        assert_eq!(transport_error("operation timed out").kind(), "timeout");
+        assert_eq!(transport_error("connection refused").kind(), "transport");
+        assert_eq!(rpc_error("intrinsic gas too low").kind(), "rejected");
+
+        assert!(transport_error("connection refused").is_retryable());
+        assert!(rpc_error("request timed out").is_retryable());
+        assert!(!rpc_error("already known").is_retryable());
+        assert!(!rpc_error("execution reverted").is_retryable());
+        // the node responded, so sending the same tx again would fail the same way
+        assert!(!rpc_error("exceeds block gas limit").is_retryable());
+        assert!(!rpc_error("fee cap less than base fee").is_retryable());
+    }
+
+    #[tokio::test]
+    async fn retries_retryable_errors() {
+        let calls = &AtomicUsize::new(0);
+        let res = with_retries(|| async move {
+            match calls.fetch_add(1, Ordering::Relaxed) {
+                0 => Err(transport_error("connection reset")),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(res.unwrap(), 1);
+
+        calls.store(0, Ordering::Relaxed);
+        let res = with_retries(|| async move {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>(rpc_error("insufficient funds"))
+        })
+        .await;
+        assert_eq!(res.unwrap_err().kind(), "insufficient_funds");
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_by_policy() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(1),
+            timeout: Some(Duration::from_millis(20)),
+            retry_on: vec!["Nonce Too Low".to_owned()],
+        };
+        assert!(policy.should_retry(&rpc_error("error code -32000: nonce too low")));
+        assert!(!policy.should_retry(&rpc_error("insufficient funds")));
+
+        // attempts that hang are timed out & retried
+        let calls = &AtomicUsize::new(0);
+        let res = with_retry_policy(&policy, || async move {
+            if calls.fetch_add(1, Ordering::Relaxed) == 0 {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+            Ok(())
+        })
+        .await;
+        assert!(res.is_ok());
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        // gives up after max_retries
+        calls.store(0, Ordering::Relaxed);
+        let res = with_retry_policy(&policy, || async move {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>(rpc_error("nonce too low"))
+        })
+        .await;
+        assert_eq!(res.unwrap_err().kind(), "nonce_conflict");
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use alloy::{
+    dyn_abi::TypedData,
+    hex::ToHexExt,
+    primitives::{Address, B256},
+    signers::{local::PrivateKeySigner, SignerSync},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{error::ContenderError, generator::util::split_top_level, Result};
+
+/// Prefix of placeholders which are replaced with an EIP-712 signature by the tx's sender,
+/// e.g. `{_sig712:Permit}` or `{_sig712:Permit:owner,spender,amount,deadline}`.
+pub const SIG712_PREFIX: &str = "_sig712";
+
+/// EIP-712 typed data, signed by each tx's sender where a call's args contain `{_sig712:<name>}`.
+///
+/// Values of the primary type's fields are taken from the call's args with the same names, or from
+/// the args listed in the placeholder (`{_sig712:<name>:<arg>,<arg>,...}`, in field order). Listed
+/// args may be paths to fields of tuple args, e.g. `permit.nonce`.
+/// `values` fills fields which aren't args. Struct values are written as tuples, e.g. `(0x..,100)`,
+/// and arrays as `[a,b]`; values can't contain commas otherwise.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TypedDataDefinition {
+    /// EIP-712 encodeType of the primary type followed by the struct types it references, e.g.
+    /// `"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"`.
+    #[serde(rename = "type")]
+    pub encode_type: String,
+    pub domain: TypedDataDomain,
+    /// Values for fields which aren't taken from the call's args. May contain placeholders.
+    pub values: Option<HashMap<String, String>>,
+}
+
+/// EIP-712 domain. Only the fields which are set are included in the domain separator.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct TypedDataDomain {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<u64>,
+    /// Address (or placeholder, e.g. `{testToken}`) of the contract verifying the signature.
+    pub verifying_contract: Option<String>,
+}
+
+/// A struct type's fields, as (type, name) pairs.
+type StructFields = Vec<(String, String)>;
+
+/// Parses `{_sig712:<name>[:<arg>,<arg>,...]}` into the definition's name & the args to take
+/// field values from, if listed. Returns `None` if `arg` isn't a signature placeholder.
+pub fn parse_sig712_placeholder(arg: &str) -> Option<(String, Option<Vec<String>>)> {
+    let inner = arg
+        .trim()
+        .strip_prefix('{')?
+        .strip_suffix('}')?
+        .strip_prefix(SIG712_PREFIX)?
+        .strip_prefix(':')?;
+    let mut parts = inner.splitn(2, ':');
+    let name = parts.next()?.trim().to_owned();
+    let fields = parts.next().map(|fields| {
+        fields
+            .split(',')
+            .map(|f| f.trim().to_owned())
+            .collect::<Vec<_>>()
+    });
+    Some((name, fields))
+}
+
+/// Parses an encodeType string into its struct types, in order. The first is the primary type.
+fn parse_encode_type(encode_type: &str) -> Result<Vec<(String, StructFields)>> {
+    let invalid = || {
+        ContenderError::SpamError(
+            "invalid EIP-712 type; expected e.g. \"Mail(address to,string contents)\"",
+            Some(encode_type.to_owned()),
+        )
+    };
+    let mut structs = vec![];
+    let mut rest = encode_type.trim();
+    while !rest.is_empty() {
+        let open = rest.find('(').ok_or_else(invalid)?;
+        let close = rest.find(')').ok_or_else(invalid)?;
+        if close < open {
+            return Err(invalid());
+        }
+        let name = rest[..open].trim().to_owned();
+        let fields = rest[open + 1..close]
+            .split(',')
+            .filter(|field| !field.trim().is_empty())
+            .map(|field| {
+                let mut parts = field.split_whitespace();
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(ty), Some(name), None) => Ok((ty.to_owned(), name.to_owned())),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        structs.push((name, fields));
+        rest = rest[close + 1..].trim();
+    }
+    if structs.is_empty() {
+        return Err(invalid());
+    }
+    Ok(structs)
+}
+
+/// Converts a value written as a string into JSON for a field of type `ty`.
+fn json_value(ty: &str, value: &str, structs: &HashMap<String, StructFields>) -> Result<Value> {
+    let value = value.trim();
+    if let Some(elem_ty) = ty.strip_suffix(']').and_then(|ty| ty.rsplit_once('[')) {
+        let items = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .ok_or(ContenderError::SpamError(
+                "EIP-712 array values must be written as [a,b,...]",
+                Some(value.to_owned()),
+            ))?;
+        return split_top_level(items)
+            .into_iter()
+            .map(|item| json_value(elem_ty.0, item, structs))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array);
+    }
+    if let Some(fields) = structs.get(ty) {
+        let items = value
+            .strip_prefix('(')
+            .and_then(|v| v.strip_suffix(')'))
+            .map(split_top_level)
+            .filter(|items| items.len() == fields.len())
+            .ok_or(ContenderError::SpamError(
+                "EIP-712 struct values must be written as a tuple of all the struct's fields",
+                Some(format!("{}: {}", ty, value)),
+            ))?;
+        let mut object = Map::new();
+        for ((field_ty, field_name), item) in fields.iter().zip(items) {
+            object.insert(field_name.to_owned(), json_value(field_ty, item, structs)?);
+        }
+        return Ok(Value::Object(object));
+    }
+    Ok(Value::String(value.to_owned()))
+}
+
+/// Returns the EIP-712 signing hash of `def`, with the primary type's fields set to `field_values`
+/// (in field order) and the domain verified by `verifying_contract`.
+pub fn typed_data_hash(
+    def: &TypedDataDefinition,
+    field_values: &[String],
+    verifying_contract: Option<Address>,
+) -> Result<B256> {
+    let structs = parse_encode_type(&def.encode_type)?;
+    let (primary_type, primary_fields) = structs[0].to_owned();
+    if primary_fields.len() != field_values.len() {
+        return Err(ContenderError::SpamError(
+            "wrong number of EIP-712 field values",
+            Some(format!(
+                "{} has {} fields; got {}",
+                primary_type,
+                primary_fields.len(),
+                field_values.len()
+            )),
+        ));
+    }
+    let struct_map = structs.iter().cloned().collect::<HashMap<_, _>>();
+
+    let mut message = Map::new();
+    for ((ty, name), value) in primary_fields.iter().zip(field_values) {
+        message.insert(name.to_owned(), json_value(ty, value, &struct_map)?);
+    }
+
+    let mut domain = Map::new();
+    let mut domain_type = vec![];
+    if let Some(name) = &def.domain.name {
+        domain.insert("name".to_owned(), json!(name));
+        domain_type.push(json!({"name": "name", "type": "string"}));
+    }
+    if let Some(version) = &def.domain.version {
+        domain.insert("version".to_owned(), json!(version));
+        domain_type.push(json!({"name": "version", "type": "string"}));
+    }
+    if let Some(chain_id) = def.domain.chain_id {
+        domain.insert("chainId".to_owned(), json!(chain_id));
+        domain_type.push(json!({"name": "chainId", "type": "uint256"}));
+    }
+    if let Some(verifying_contract) = verifying_contract {
+        domain.insert(
+            "verifyingContract".to_owned(),
+            json!(verifying_contract.to_string()),
+        );
+        domain_type.push(json!({"name": "verifyingContract", "type": "address"}));
+    }
+
+    let mut types = Map::new();
+    types.insert("EIP712Domain".to_owned(), Value::Array(domain_type));
+    for (name, fields) in structs.iter() {
+        let fields = fields
+            .iter()
+            .map(|(ty, name)| json!({"name": name, "type": ty}))
+            .collect();
+        types.insert(name.to_owned(), Value::Array(fields));
+    }
+
+    let typed_data: TypedData = serde_json::from_value(json!({
+        "types": types,
+        "primaryType": primary_type,
+        "domain": domain,
+        "message": message,
+    }))
+    .map_err(|e| ContenderError::with_err(e, "invalid EIP-712 typed data"))?;
+    typed_data
+        .eip712_signing_hash()
+        .map_err(|e| ContenderError::with_err(e, "failed to hash EIP-712 typed data"))
+}
+
+/// Signs `def` with `signer` and returns the 65-byte signature (r, s, v) as 0x-prefixed hex.
+pub fn sign_typed_data(
+    def: &TypedDataDefinition,
+    field_values: &[String],
+    verifying_contract: Option<Address>,
+    signer: &PrivateKeySigner,
+) -> Result<String> {
+    let hash = typed_data_hash(def, field_values, verifying_contract)?;
+    let signature = signer
+        .sign_hash_sync(&hash)
+        .map_err(|e| ContenderError::with_err(e, "failed to sign EIP-712 typed data"))?;
+    Ok(format!("0x{}", signature.as_bytes().encode_hex()))
+}
+
+/// Returns the primary type's field names in `def`, in order.
+pub fn primary_field_names(def: &TypedDataDefinition) -> Result<Vec<String>> {
+    Ok(parse_encode_type(&def.encode_type)?
+        .swap_remove(0)
+        .1
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example from EIP-712.
+    fn mail() -> TypedDataDefinition {
+        TypedDataDefinition {
+            encode_type:
+                "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+                    .to_owned(),
+            domain: TypedDataDomain {
+                name: Some("Ether Mail".to_owned()),
+                version: Some("1".to_owned()),
+                chain_id: Some(1),
+                verifying_contract: Some("{mail}".to_owned()),
+            },
+            values: None,
+        }
+    }
+
+    fn mail_values() -> Vec<String> {
+        vec![
+            "(Cow,0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826)".to_owned(),
+            "(Bob,0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB)".to_owned(),
+            "Hello, Bob!".to_owned(),
+        ]
+    }
+
+    #[test]
+    fn parses_sig712_placeholders() {
+        assert_eq!(
+            parse_sig712_placeholder("{_sig712:Permit}"),
+            Some(("Permit".to_owned(), None))
+        );
+        assert_eq!(
+            parse_sig712_placeholder("{_sig712:Permit:owner, spender}"),
+            Some((
+                "Permit".to_owned(),
+                Some(vec!["owner".to_owned(), "spender".to_owned()])
+            ))
+        );
+        assert_eq!(parse_sig712_placeholder("{_sender}"), None);
+        assert_eq!(parse_sig712_placeholder("0x{_sig712:Permit}"), None);
+    }
+
+    #[test]
+    fn hashes_typed_data() {
+        let verifying_contract = "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".parse().ok();
+        let hash = typed_data_hash(&mail(), &mail_values(), verifying_contract).unwrap();
+        assert_eq!(
+            hash,
+            "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+                .parse::<B256>()
+                .unwrap()
+        );
+        assert_eq!(
+            primary_field_names(&mail()).unwrap(),
+            vec!["from", "to", "contents"]
+        );
+        assert!(typed_data_hash(&mail(), &mail_values()[..2], verifying_contract).is_err());
+    }
+
+    #[test]
+    fn signs_typed_data() {
+        let signer = PrivateKeySigner::random();
+        let verifying_contract = Some(Address::repeat_byte(0xcc));
+        let sig = sign_typed_data(&mail(), &mail_values(), verifying_contract, &signer).unwrap();
+        let sig = alloy::hex::decode(sig).unwrap();
+        let sig = alloy::primitives::Signature::try_from(sig.as_slice()).unwrap();
+        let hash = typed_data_hash(&mail(), &mail_values(), verifying_contract).unwrap();
+        assert_eq!(
+            sig.recover_address_from_prehash(&hash).unwrap(),
+            signer.address()
+        );
+    }
+}
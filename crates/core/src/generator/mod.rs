@@ -3,22 +3,36 @@ use crate::{
     db::DbOps,
     error::ContenderError,
     generator::{
-        seeder::{SeedValue, Seeder},
+        seeder::{OffsetSeeder, SeedValue, Seeder},
         templater::Templater,
-        types::{CreateDefinition, FunctionCallDefinition, FuzzParam},
+        types::{
+            CreateDefinition, FunctionCallDefinition, FuzzParam, GasLimit, TransferDefinition,
+        },
+        util::{encode_calldata, tuple_field_range},
     },
     Result,
 };
 use alloy::{
-    hex::ToHexExt,
-    primitives::{Address, U256},
+    hex::{self, ToHexExt},
+    json_abi::Function,
+    primitives::{Address, TxKind, U256},
+    providers::Provider,
+    rpc::types::{Filter, TransactionInput, TransactionRequest},
+    signers::local::PrivateKeySigner,
+    sol_types::SolCall,
 };
 use async_trait::async_trait;
+use eip712::{parse_sig712_placeholder, primary_field_names, sign_typed_data, TypedDataDefinition};
+use multicall::{aggregate_txs, MulticallConfig};
 use named_txs::ExecutionRequest;
 pub use named_txs::NamedTxRequestBuilder;
+use safe::{exec_transaction_calldata, nonceCall, safe_tx, sign_safe_tx, SafeNonces};
 pub use seeder::rand_seed::RandSeed;
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
-use types::{CreateDefinitionStrict, FunctionCallDefinitionStrict, SpamRequest};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Mutex};
+use types::{
+    CallTarget, CreateDefinitionStrict, FunctionCallDefinitionStrict, GasEstimateConfig, Phase,
+    ReadRequest, ReadRequestStrict, SenderAssignment, SpamRequest, SpamRoute, TargetSelection,
+};
 
 pub use types::{CallbackResult, NamedTxRequest, PlanType};
 
@@ -26,6 +40,15 @@ pub use types::{CallbackResult, NamedTxRequest, PlanType};
 /// Used for tracking transactions in a test scenario.
 pub mod named_txs;
 
+/// Aggregates spam calls into Multicall3 txs.
+pub mod multicall;
+
+/// Signs EIP-712 typed data for `{_sig712:...}` placeholders.
+pub mod eip712;
+
+/// Wraps calls in Safe `execTransaction` calls signed by the Safe's owners.
+pub mod safe;
+
 /// Generates values for fuzzed parameters.
 /// Contains the Seeder trait and an implementation.
 pub mod seeder;
@@ -42,6 +65,10 @@ pub mod util;
 
 const VALUE_KEY: &str = "__tx_value_contender__";
 
+/// Prefix of placeholders which are replaced with the addresses of an agent pool, as an array,
+/// e.g. `{_pool:safeOwners}`.
+pub const POOL_PREFIX: &str = "_pool:";
+
 pub trait PlanConfig<K>
 where
     K: Eq + Hash + Debug + Send + Sync,
@@ -57,6 +84,80 @@ where
 
     /// Get spam step templates from the plan configuration.
     fn get_spam_steps(&self) -> Result<Vec<SpamRequest>>;
+
+    /// Get settings for estimating gas limits of txs which don't set one.
+    fn get_gas_estimate_config(&self) -> GasEstimateConfig {
+        GasEstimateConfig::default()
+    }
+
+    /// Get settings for aggregating spam calls into Multicall3 txs. Calls aren't aggregated if `None`.
+    fn get_multicall_config(&self) -> Option<MulticallConfig> {
+        None
+    }
+
+    /// Get how spam txs are assigned senders from their `from_pool`.
+    fn get_sender_assignment(&self) -> SenderAssignment {
+        SenderAssignment::default()
+    }
+
+    /// Whether setup txs which don't depend on each other are sent concurrently; setup txs are
+    /// sent one at a time if `false`.
+    fn get_concurrent_setup(&self) -> bool {
+        false
+    }
+
+    /// Get EIP-712 typed data definitions, by the name used in `{_sig712:<name>}` placeholders.
+    fn get_typed_data(&self) -> HashMap<String, TypedDataDefinition> {
+        HashMap::new()
+    }
+
+    /// Get the phases of the spam run's traffic profile. Spam is sent at the spammer's rate if empty.
+    fn get_phases(&self) -> Vec<Phase> {
+        vec![]
+    }
+
+    /// Get the read RPCs sent by a read-only load test.
+    fn get_read_steps(&self) -> Vec<ReadRequest> {
+        vec![]
+    }
+}
+
+/// Returns `amount` seeded indices in `0..len`, from the seed's values starting at `offset`.
+fn seeded_indices(seeder: &impl Seeder, offset: usize, amount: usize, len: usize) -> Vec<usize> {
+    seeder
+        .seed_values_from(offset, amount, None, None)
+        .map(|v| (v.as_u256() % U256::from(len)).to::<usize>())
+        .collect()
+}
+
+/// Returns the index of the pool account to send each of a spam step's `num_txs` txs from.
+/// Round-robin assignment continues from the step's `first_tx`th tx.
+///
+/// Random picks are taken from the seed's values after the first `num_fuzz_values`, which are
+/// used for fuzzing, so senders aren't correlated with fuzzed args.
+fn sender_indices(
+    assignment: SenderAssignment,
+    seeder: &impl Seeder,
+    step_idx: usize,
+    first_tx: usize,
+    num_txs: usize,
+    num_accts: usize,
+    num_fuzz_values: usize,
+) -> Vec<usize> {
+    match assignment {
+        SenderAssignment::RoundRobin => (first_tx..first_tx + num_txs)
+            .map(|i| i % num_accts)
+            .collect(),
+        SenderAssignment::Random => seeded_indices(
+            seeder,
+            num_fuzz_values + step_idx * num_txs,
+            num_txs,
+            num_accts,
+        ),
+        SenderAssignment::Sticky => {
+            vec![seeded_indices(seeder, num_fuzz_values + step_idx, 1, num_accts)[0]; num_txs]
+        }
+    }
 }
 
 fn parse_map_key(fuzz: FuzzParam) -> Result<String> {
@@ -90,6 +191,160 @@ fn parse_map_key(fuzz: FuzzParam) -> Result<String> {
     Ok(key)
 }
 
+/// Returns seeded values for picking the random target of each of a spam step's `num_txs` txs.
+///
+/// Values are taken from after those used for fuzzing & sender assignment
+/// (`2 * num_fuzz_values`), so targets aren't correlated with either.
+/// Replaces each `{_pool:<name>}` in `arg` with the addresses of the named agent pool, e.g. `[0x..,0x..]`.
+fn replace_pool_placeholders(arg: &str, agents: &AgentStore) -> Result<String> {
+    let mut arg = arg.to_owned();
+    let pattern = format!("{{{}", POOL_PREFIX);
+    while let Some(start) = arg.find(&pattern) {
+        let end =
+            arg[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or(ContenderError::SpamError(
+                    "unterminated pool placeholder",
+                    Some(arg.to_owned()),
+                ))?;
+        let name = &arg[start + pattern.len()..end];
+        let pool = agents.get_agent(name).ok_or(ContenderError::SpamError(
+            "pool not found in agent store",
+            Some(name.to_owned()),
+        ))?;
+        let addresses = pool
+            .signers
+            .iter()
+            .map(|signer| signer.address().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        arg.replace_range(start..=end, &format!("[{}]", addresses));
+    }
+    Ok(arg)
+}
+
+fn target_picks(
+    seeder: &impl Seeder,
+    step_idx: usize,
+    num_txs: usize,
+    num_fuzz_values: usize,
+) -> Vec<U256> {
+    seeder
+        .seed_values_from(
+            2 * num_fuzz_values + step_idx * num_txs,
+            num_txs,
+            None,
+            None,
+        )
+        .map(|v| v.as_u256())
+        .collect()
+}
+
+/// Splits `num_txs` between spam steps in proportion to their `weights`, giving the txs left over
+/// from rounding down to the steps with the largest remainders. `credit` holds each step's
+/// rounding remainder from previous batches, and is updated with this batch's, so steps whose
+/// share of a small batch rounds to 0 still get their share over many batches.
+fn weighted_step_txs(weights: &[f64], num_txs: usize, credit: &mut [f64]) -> Result<Vec<usize>> {
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(ContenderError::SpamError(
+            "spam step weights must be non-negative numbers",
+            Some(format!("{:?}", weights)),
+        ));
+    }
+    let total_weight = weights.iter().sum::<f64>();
+    if total_weight == 0.0 {
+        return Err(ContenderError::SpamError(
+            "at least one spam step must have a positive weight",
+            None,
+        ));
+    }
+    let exact = weights
+        .iter()
+        .zip(credit.iter())
+        .map(|(w, credit)| num_txs as f64 * w / total_weight + credit)
+        .collect::<Vec<_>>();
+    let mut counts = exact
+        .iter()
+        .map(|n| n.floor().max(0.0) as usize)
+        .collect::<Vec<_>>();
+    let mut by_remainder = (0..weights.len()).collect::<Vec<_>>();
+    by_remainder.sort_by(|a, b| {
+        (exact[*b] - counts[*b] as f64).total_cmp(&(exact[*a] - counts[*a] as f64))
+    });
+    let leftover = num_txs.saturating_sub(counts.iter().sum());
+    for idx in by_remainder.into_iter().take(leftover) {
+        counts[idx] += 1;
+    }
+    for (credit, (exact, count)) in credit.iter_mut().zip(exact.iter().zip(&counts)) {
+        *credit = exact - *count as f64;
+    }
+    Ok(counts)
+}
+
+/// Returns the index of the `i`th tx's target, given `pick` from [`target_picks`].
+fn target_index(selection: TargetSelection, i: usize, pick: U256, num_targets: usize) -> usize {
+    match selection {
+        TargetSelection::RoundRobin => i % num_targets,
+        TargetSelection::Random => (pick % U256::from(num_targets)).to::<usize>(),
+    }
+}
+
+/// Returns the `{name}` placeholders in `input` (e.g. `{Pool}`), which refer to contracts & named
+/// txs. Built-in placeholders like `{_sender}` are skipped.
+fn placeholder_refs(input: &str) -> Vec<&str> {
+    let mut refs = vec![];
+    let mut rest = input;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start..=start + len];
+        if !placeholder.starts_with("{_") {
+            refs.push(placeholder);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    refs
+}
+
+/// Groups setup steps into waves that can be sent concurrently, given the resources each step
+/// touches (its sender, its targets & the contracts its args refer to) in scenario order. A step
+/// joins the earliest wave after every earlier step sharing a resource with it, so each sender's
+/// nonces & each contract's calls stay in order. `barriers` (e.g. Safe txs, which read state set by
+/// any earlier step) run alone, after all earlier steps & before all later ones.
+fn setup_waves(resources: &[Vec<String>], barriers: &[bool]) -> Vec<Vec<usize>> {
+    let mut waves: Vec<Vec<usize>> = vec![];
+    // index of the last wave that touched each resource
+    let mut last_wave: HashMap<&str, usize> = HashMap::new();
+    // steps can't be sent before the latest barrier
+    let mut first_wave = 0;
+    for (i, step_resources) in resources.iter().enumerate() {
+        let wave = if barriers[i] {
+            waves.len()
+        } else {
+            step_resources
+                .iter()
+                .filter_map(|resource| last_wave.get(resource.as_str()))
+                .map(|wave| wave + 1)
+                .max()
+                .unwrap_or_default()
+                .max(first_wave)
+        };
+        if wave == waves.len() {
+            waves.push(vec![]);
+        }
+        waves[wave].push(i);
+        for resource in step_resources {
+            last_wave.insert(resource, wave);
+        }
+        if barriers[i] {
+            first_wave = wave + 1;
+        }
+    }
+    waves
+}
+
 #[async_trait]
 pub trait Generator<K, D, T>
 where
@@ -104,13 +359,39 @@ where
     fn get_agent_store(&self) -> &AgentStore;
     fn get_rpc_url(&self) -> String;
 
-    /// Generates a map of N=`num_values` fuzzed values for each parameter in `fuzz_args`.
+    /// Namespace in which named txs are looked up for placeholders; unscoped if `None`.
+    fn get_namespace(&self) -> Option<String> {
+        None
+    }
+
+    /// Index of the batch of spam txs being generated, when a run's spam is generated in batches
+    /// of the same size (e.g. one per period). Later batches continue from the fuzzed values &
+    /// picks of earlier ones instead of repeating them.
+    fn get_spam_batch(&self) -> usize {
+        0
+    }
+
+    /// Rounding remainders of weighted spam steps, carried from one batch to the next so the
+    /// weights are honored over a run of small batches. Each batch is split on its own if `None`.
+    fn get_spam_step_credit(&self) -> Option<&Mutex<Vec<f64>>> {
+        None
+    }
+
+    /// Returns the key of `address`, used to sign EIP-712 typed data. Only agents' keys are
+    /// available by default.
+    fn get_signer(&self, address: &Address) -> Option<PrivateKeySigner> {
+        self.get_agent_store().find_signer(address).cloned()
+    }
+
+    /// Generates a map of N=`num_values` fuzzed values for each parameter in `fuzz_args`, from the
+    /// seed's values starting at `offset`.
     fn create_fuzz_map(
         &self,
+        offset: usize,
         num_values: usize,
         fuzz_args: &[FuzzParam],
     ) -> Result<HashMap<String, Vec<U256>>> {
-        let seed = self.get_fuzz_seeder();
+        let seed = OffsetSeeder::new(self.get_fuzz_seeder(), offset);
         let mut map = HashMap::<String, Vec<U256>>::new();
 
         for fuzz in fuzz_args.iter() {
@@ -126,6 +407,86 @@ where
         Ok(map)
     }
 
+    /// Resolves the placeholders in the plan's read steps.
+    fn load_reads(&self) -> Result<Vec<ReadRequestStrict>> {
+        let templater = self.get_templater();
+        let db = self.get_db();
+        let rpc_url = self.get_rpc_url();
+        let namespace = self.get_namespace();
+        let mut placeholder_map = HashMap::<K, String>::new();
+        for (key, value) in self.get_plan_conf().get_env()? {
+            placeholder_map.insert(key, value);
+        }
+        let mut resolve = |input: &str| -> Result<String> {
+            templater.find_placeholder_values(
+                input,
+                &mut placeholder_map,
+                db,
+                &rpc_url,
+                namespace.as_deref(),
+            )?;
+            Ok(templater.replace_placeholders(input, &placeholder_map))
+        };
+        let parse_address = |input: String| {
+            input.parse::<Address>().map_err(|e| {
+                ContenderError::SpamError(
+                    "failed to parse address",
+                    Some(format!("{}: {}", input, e)),
+                )
+            })
+        };
+
+        self.get_plan_conf()
+            .get_read_steps()
+            .into_iter()
+            .map(|step| match &step {
+                ReadRequest::Call(call) | ReadRequest::EstimateGas(call) => {
+                    let args = call
+                        .args
+                        .to_owned()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|arg| resolve(arg))
+                        .collect::<Result<Vec<_>>>()?;
+                    let to = parse_address(resolve(&call.to)?)?;
+                    let tx = TransactionRequest {
+                        to: Some(TxKind::Call(to)),
+                        input: TransactionInput::both(
+                            encode_calldata(&args, &call.signature)?.into(),
+                        ),
+                        ..Default::default()
+                    };
+                    Ok(match step {
+                        ReadRequest::EstimateGas(_) => ReadRequestStrict::EstimateGas(tx),
+                        _ => ReadRequestStrict::Call(tx),
+                    })
+                }
+                ReadRequest::Logs(logs) => {
+                    let mut filter = Filter::new();
+                    if let Some(address) = &logs.address {
+                        filter = filter.address(parse_address(resolve(address)?)?);
+                    }
+                    if let Some(event) = &logs.event {
+                        filter = filter.event(event);
+                    }
+                    Ok(ReadRequestStrict::Logs {
+                        filter,
+                        max_range: logs.max_range.unwrap_or(100).max(1),
+                        lookback: logs.lookback.unwrap_or(10_000).max(1),
+                    })
+                }
+                ReadRequest::Balance(balance) => Ok(ReadRequestStrict::Balance(parse_address(
+                    resolve(&balance.address)?,
+                )?)),
+                ReadRequest::Block(block) => Ok(ReadRequestStrict::Block {
+                    number: 0,
+                    lookback: block.lookback.unwrap_or(100).max(1),
+                    full_txs: block.full_txs.unwrap_or_default(),
+                }),
+            })
+            .collect()
+    }
+
     fn make_strict_create(
         &self,
         create_def: &CreateDefinition,
@@ -159,7 +520,6 @@ where
 
         let bytecode = create_def
             .bytecode
-            .to_owned()
             .replace("{_sender}", &from_address.encode_hex()); // inject address WITHOUT 0x prefix
 
         Ok(CreateDefinitionStrict {
@@ -205,29 +565,420 @@ where
         let args = args
             .iter()
             .map(|arg| {
+                let arg = replace_pool_placeholders(arg, agents)?;
                 if arg.contains("{_sender}") {
                     // return `from` address WITH 0x prefix
-                    arg.replace("{_sender}", &from_address.to_string())
+                    Ok(arg.replace("{_sender}", &from_address.to_string()))
                 } else {
-                    arg.to_owned()
+                    Ok(arg)
                 }
             })
-            .collect::<Vec<String>>();
+            .collect::<Result<Vec<String>>>()?;
 
-        let to_address = if funcdef.to == "{_sender}" {
+        let to = match &funcdef.to {
+            CallTarget::One(to) => to,
+            CallTarget::Many(_) => {
+                return Err(ContenderError::SpamError(
+                    "multiple `to` targets are only supported in spam steps",
+                    Some(funcdef.signature.to_owned()),
+                ));
+            }
+        };
+        let to_address = if to == "{_sender}" {
             from_address.to_string()
         } else {
-            funcdef.to.to_owned()
+            to.to_owned()
         };
 
+        let relayer = funcdef
+            .relayer_pool
+            .as_ref()
+            .map(|relayer_pool| {
+                let relayers = agents
+                    .get_agent(relayer_pool)
+                    .filter(|agent| !agent.signers.is_empty())
+                    .ok_or(ContenderError::SpamError(
+                        "relayer_pool not found in agent store",
+                        Some(relayer_pool.to_owned()),
+                    ))?;
+                Ok::<_, ContenderError>(relayers.signers[idx % relayers.signers.len()].address())
+            })
+            .transpose()?;
+
         Ok(FunctionCallDefinitionStrict {
             to: to_address,
             from: from_address,
+            relayer,
+            safe: funcdef.safe.to_owned(),
             signature: funcdef.signature.to_owned(),
             args,
             value: funcdef.value.to_owned(),
             fuzz: funcdef.fuzz.to_owned().unwrap_or_default(),
             kind: funcdef.kind.to_owned(),
+            gas_limit: funcdef.gas_limit.unwrap_or_default(),
+            max_fee_per_gas: funcdef.max_fee_per_gas,
+            max_priority_fee_per_gas: funcdef.max_priority_fee_per_gas,
+        })
+    }
+
+    /// Looks up placeholders in the domain & values of typed data signed by `fncall`'s args.
+    fn find_typed_data_placeholders(
+        &self,
+        fncall: &FunctionCallDefinition,
+        placeholder_map: &mut HashMap<K, String>,
+    ) -> Result<()> {
+        let definitions = self.get_plan_conf().get_typed_data();
+        let templater = self.get_templater();
+        let rpc_url = self.get_rpc_url();
+        let namespace = self.get_namespace();
+        for arg in fncall.args.iter().flatten() {
+            let Some((name, _)) = parse_sig712_placeholder(arg) else {
+                continue;
+            };
+            let def = definitions.get(&name).ok_or(ContenderError::SpamError(
+                "EIP-712 typed data definition not found",
+                Some(name.to_owned()),
+            ))?;
+            let values = def.values.iter().flat_map(|values| values.values());
+            for value in values.chain(def.domain.verifying_contract.iter()) {
+                templater.find_placeholder_values(
+                    value,
+                    placeholder_map,
+                    self.get_db(),
+                    &rpc_url,
+                    namespace.as_deref(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces `{_sig712:...}` args in `funcdef` with EIP-712 signatures by its sender.
+    /// Other args must already have their fuzzed values and `{_sender}` filled in.
+    fn sign_typed_data_args(
+        &self,
+        mut funcdef: FunctionCallDefinitionStrict,
+        placeholder_map: &HashMap<K, String>,
+    ) -> Result<FunctionCallDefinitionStrict> {
+        let placeholders = funcdef
+            .args
+            .iter()
+            .enumerate()
+            .filter_map(|(i, arg)| parse_sig712_placeholder(arg).map(|p| (i, p)))
+            .collect::<Vec<_>>();
+        if placeholders.is_empty() {
+            return Ok(funcdef);
+        }
+
+        let templater = self.get_templater();
+        let definitions = self.get_plan_conf().get_typed_data();
+        let signer = self
+            .get_signer(&funcdef.from)
+            .ok_or(ContenderError::SpamError(
+                "no key found to sign EIP-712 typed data; sender must be an agent or a given private key",
+                Some(funcdef.from.to_string()),
+            ))?;
+        let params = Function::parse(&funcdef.signature)
+            .map_err(|e| ContenderError::with_err(e, "failed to parse function signature"))?
+            .inputs;
+        let fill = |input: &str| {
+            templater
+                .replace_placeholders(input, placeholder_map)
+                .replace("{_sender}", &funcdef.from.to_string())
+        };
+        let args = funcdef.args.iter().map(|arg| fill(arg)).collect::<Vec<_>>();
+        // finds the value of an arg by name, or of a tuple arg's field by path, e.g. "permit.nonce"
+        let arg_value = |source: &str| {
+            let mut path = source.split('.');
+            let name = path.next()?;
+            let idx = params.iter().position(|param| param.name == name)?;
+            let path = path.collect::<Vec<_>>();
+            if path.is_empty() {
+                return Some(args[idx].to_owned());
+            }
+            tuple_field_range(&args[idx], &params[idx].components, &path)
+                .map(|range| args[idx][range].to_owned())
+        };
+
+        let mut signatures = vec![];
+        for (arg_idx, (name, sources)) in placeholders {
+            let def = definitions.get(&name).ok_or(ContenderError::SpamError(
+                "EIP-712 typed data definition not found",
+                Some(name.to_owned()),
+            ))?;
+            let field_names = primary_field_names(def)?;
+            let sources = sources.unwrap_or(field_names.to_owned());
+            if sources.len() != field_names.len() {
+                return Err(ContenderError::SpamError(
+                    "EIP-712 placeholder must list an arg for each field of the type",
+                    Some(name),
+                ));
+            }
+            // each field is taken from the definition's `values`, else the arg (or arg field) of the same name
+            let field_values = sources
+                .iter()
+                .map(|source| {
+                    def.values
+                        .as_ref()
+                        .and_then(|values| values.get(source))
+                        .map(|value| fill(value))
+                        .or_else(|| arg_value(source))
+                        .ok_or(ContenderError::SpamError(
+                            "no value found for EIP-712 field; add it to `values` or name an arg",
+                            Some(format!("{}.{}", name, source)),
+                        ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let verifying_contract = def
+                .domain
+                .verifying_contract
+                .as_ref()
+                .map(|contract| {
+                    fill(contract).parse::<Address>().map_err(|e| {
+                        ContenderError::with_err(e, "failed to parse EIP-712 verifying contract")
+                    })
+                })
+                .transpose()?;
+            signatures.push((
+                arg_idx,
+                sign_typed_data(def, &field_values, verifying_contract, &signer)?,
+            ));
+        }
+        for (arg_idx, signature) in signatures {
+            funcdef.args[arg_idx] = signature;
+        }
+        Ok(funcdef)
+    }
+
+    /// Fetches the chain ID & current nonce of each Safe that `fncalls` are executed through.
+    async fn fetch_safe_nonces(
+        &self,
+        fncalls: &[FunctionCallDefinition],
+        placeholder_map: &mut HashMap<K, String>,
+    ) -> Result<SafeNonces> {
+        let mut safe_nonces = SafeNonces::default();
+        let safes = fncalls
+            .iter()
+            .filter_map(|fncall| fncall.safe.to_owned())
+            .collect::<Vec<_>>();
+        if safes.is_empty() {
+            return Ok(safe_nonces);
+        }
+        let rpc_url = self.get_rpc_url();
+        let namespace = self.get_namespace();
+        let provider = crate::provider::eth_provider(
+            &rpc_url
+                .parse()
+                .map_err(|e| ContenderError::with_err(e, "invalid RPC URL"))?,
+        )
+        .await?;
+        safe_nonces.chain_id = provider
+            .get_chain_id()
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to get chain id"))?;
+
+        let templater = self.get_templater();
+        for safe in safes {
+            templater.find_placeholder_values(
+                &safe.address,
+                placeholder_map,
+                self.get_db(),
+                &rpc_url,
+                namespace.as_deref(),
+            )?;
+            let address = templater
+                .replace_placeholders(&safe.address, placeholder_map)
+                .parse::<Address>()
+                .map_err(|e| ContenderError::with_err(e, "failed to parse Safe address"))?;
+            if safe_nonces.contains(&address) {
+                continue;
+            }
+            let call = TransactionRequest {
+                to: Some(TxKind::Call(address)),
+                input: TransactionInput::both(nonceCall {}.abi_encode().into()),
+                ..Default::default()
+            };
+            let res = provider
+                .call(&call)
+                .await
+                .map_err(|e| ContenderError::with_err(e, "failed to get Safe nonce"))?;
+            let nonce = nonceCall::abi_decode_returns(&res, true)
+                .map_err(|e| ContenderError::with_err(e, "failed to decode Safe nonce"))?
+                ._0;
+            safe_nonces.insert(address, nonce);
+        }
+        Ok(safe_nonces)
+    }
+
+    /// Templates `funcdef` into a tx request. If the call is executed through a Safe, the call is
+    /// signed by the Safe's owners and the tx calls the Safe's `execTransaction` instead.
+    fn template_call(
+        &self,
+        funcdef: &FunctionCallDefinitionStrict,
+        placeholder_map: &HashMap<K, String>,
+        safe_nonces: &mut SafeNonces,
+    ) -> Result<TransactionRequest> {
+        let templater = self.get_templater();
+        let tx = templater.template_function_call(funcdef, placeholder_map)?;
+        let safe = match &funcdef.safe {
+            Some(safe) => safe,
+            None => return Ok(tx),
+        };
+
+        let address = templater
+            .replace_placeholders(&safe.address, placeholder_map)
+            .parse::<Address>()
+            .map_err(|e| ContenderError::with_err(e, "failed to parse Safe address"))?;
+        let owners = self
+            .get_agent_store()
+            .get_agent(&safe.owner_pool)
+            .filter(|pool| pool.signers.len() >= safe.threshold)
+            .ok_or(ContenderError::SpamError(
+                "owner_pool not found in agent store or smaller than threshold",
+                Some(safe.owner_pool.to_owned()),
+            ))?;
+        let to = match tx.to {
+            Some(TxKind::Call(to)) => to,
+            _ => {
+                return Err(ContenderError::SpamError(
+                    "Safe txs must call a contract",
+                    Some(funcdef.signature.to_owned()),
+                ))
+            }
+        };
+        let call = safe_tx(
+            to,
+            tx.value.unwrap_or_default(),
+            tx.input.input().cloned().unwrap_or_default(),
+            safe.operation(),
+            safe_nonces.next(&address)?,
+        );
+        let signatures = sign_safe_tx(
+            &call,
+            safe_nonces.chain_id,
+            address,
+            &owners.signers[..safe.threshold],
+        )?;
+
+        Ok(TransactionRequest {
+            to: Some(TxKind::Call(address)),
+            input: TransactionInput::both(exec_transaction_calldata(call, signatures)),
+            value: None,
+            ..tx
+        })
+    }
+
+    /// Returns a transfer from the `sender_idx`th account of the step's pool for the `tx_idx`th
+    /// spam tx. `data` is sent as-is, so nothing is ABI-encoded.
+    fn template_transfer(
+        &self,
+        transfer: &TransferDefinition,
+        sender_idx: usize,
+        tx_idx: usize,
+        target_pick: U256,
+        placeholder_map: &HashMap<K, String>,
+        fuzz_value: Option<String>,
+    ) -> Result<TransactionRequest> {
+        let agents = self.get_agent_store();
+        let templater = self.get_templater();
+        let from = if let Some(from_pool) = &transfer.from_pool {
+            agents
+                .get_agent(from_pool)
+                .and_then(|agent| agent.get_address(sender_idx))
+                .ok_or(ContenderError::SpamError(
+                    "signer not found in agent store",
+                    Some(format!("from_pool={}, idx={}", from_pool, sender_idx)),
+                ))?
+        } else if let Some(from) = &transfer.from {
+            from.parse().map_err(|e| {
+                ContenderError::SpamError(
+                    "failed to parse 'from' address",
+                    Some(format!("from={}, error={}", from, e)),
+                )
+            })?
+        } else {
+            return Err(ContenderError::SpamError(
+                "invalid runtime config: must specify 'from' or 'from_pool'",
+                None,
+            ));
+        };
+
+        let to = match (&transfer.to_pool, &transfer.to) {
+            (Some(to_pool), _) => {
+                let recipients = agents
+                    .get_agent(to_pool)
+                    .filter(|agent| !agent.signers.is_empty())
+                    .ok_or(ContenderError::SpamError(
+                        "to_pool not found in agent store",
+                        Some(to_pool.to_owned()),
+                    ))?;
+                recipients.signers[tx_idx % recipients.signers.len()].address()
+            }
+            (None, Some(to)) => {
+                let targets = to.targets();
+                if targets.is_empty() {
+                    return Err(ContenderError::SpamError(
+                        "`to` must list at least one target",
+                        None,
+                    ));
+                }
+                let to = &targets[target_index(
+                    transfer.to_selection.unwrap_or_default(),
+                    tx_idx,
+                    target_pick,
+                    targets.len(),
+                )];
+                let to = if to == "{_sender}" {
+                    from.to_string()
+                } else {
+                    to.to_owned()
+                };
+                templater
+                    .replace_placeholders(&to, placeholder_map)
+                    .parse::<Address>()
+                    .map_err(|e| ContenderError::with_err(e, "failed to parse address"))?
+            }
+            (None, None) => {
+                return Err(ContenderError::SpamError(
+                    "invalid runtime config: transfers must specify 'to' or 'to_pool'",
+                    None,
+                ));
+            }
+        };
+
+        let value = fuzz_value
+            .or(transfer.value.to_owned())
+            .map(|value| {
+                templater
+                    .replace_placeholders(&value, placeholder_map)
+                    .parse::<U256>()
+                    .map_err(|e| ContenderError::with_err(e, "failed to parse transfer value"))
+            })
+            .transpose()?;
+        let input = transfer
+            .data
+            .as_ref()
+            .map(|data| {
+                let data = templater.replace_placeholders(data, placeholder_map);
+                hex::decode(data)
+                    .map_err(|e| ContenderError::with_err(e, "failed to decode transfer data"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let gas = match transfer.gas_limit.unwrap_or_default() {
+            GasLimit::Auto => None,
+            GasLimit::Fixed(gas) => Some(gas as u128),
+        };
+
+        Ok(TransactionRequest {
+            to: Some(TxKind::Call(to)),
+            input: TransactionInput::both(input.into()),
+            from: Some(from),
+            value,
+            gas,
+            max_fee_per_gas: transfer.max_fee_per_gas.map(u128::from),
+            max_priority_fee_per_gas: transfer.max_priority_fee_per_gas.map(u128::from),
+            ..Default::default()
         })
     }
 
@@ -262,6 +1013,7 @@ where
                         &mut placeholder_map,
                         db,
                         &self.get_rpc_url(),
+                        self.get_namespace().as_deref(),
                     )?;
 
                     // create tx with template values
@@ -275,7 +1027,7 @@ where
                     if let Some(handle) = handle {
                         handle.await.map_err(|e| {
                             ContenderError::with_err(e, "join error; callback crashed")
-                        })?;
+                        })??;
                     }
                     txs.push(tx.into());
                 }
@@ -283,108 +1035,278 @@ where
             PlanType::Setup(on_setup_step) => {
                 let setup_steps = conf.get_setup_steps()?;
 
-                // txs will be grouped by account [from=1, from=1, from=1, from=2, from=2, from=2, ...]
                 let rpc_url = self.get_rpc_url();
+                let namespace = self.get_namespace();
 
+                // if enabled, steps from different senders to different contracts are sent
+                // concurrently; a step passing a contract in its args waits for the contract's calls
+                let concurrent = conf.get_concurrent_setup();
+                let mut resources = vec![];
                 for step in setup_steps.iter() {
-                    // lookup placeholders in DB & update map before templating
-                    templater.find_fncall_placeholders(step, db, &mut placeholder_map, &rpc_url)?;
+                    let from = self.make_strict_call(step, 0)?.from;
+                    let args = step.args.iter().flatten().chain(&step.value);
+                    resources.push(
+                        std::iter::once(format!("from:{from}"))
+                            .chain(step.to.targets().iter().map(|to| format!("to:{to}")))
+                            .chain(
+                                args.flat_map(|arg| placeholder_refs(arg))
+                                    .map(|name| format!("to:{name}")),
+                            )
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                let barriers = setup_steps
+                    .iter()
+                    .map(|step| !concurrent || step.safe.is_some())
+                    .collect::<Vec<_>>();
+
+                let mut setup_txs = vec![None; setup_steps.len()];
+                for wave in setup_waves(&resources, &barriers) {
+                    let mut handles = vec![];
+                    for idx in wave {
+                        let step = &setup_steps[idx];
+                        // lookup placeholders in DB & update map before templating
+                        templater.find_fncall_placeholders(
+                            step,
+                            db,
+                            &mut placeholder_map,
+                            &rpc_url,
+                            namespace.as_deref(),
+                        )?;
+                        self.find_typed_data_placeholders(step, &mut placeholder_map)?;
+                        // the Safe may be deployed by a previous step, so its nonce is fetched per step
+                        let mut safe_nonces = self
+                            .fetch_safe_nonces(std::slice::from_ref(step), &mut placeholder_map)
+                            .await?;
 
-                    // setup tx with template values
-                    let tx = NamedTxRequest::new(
-                        templater.template_function_call(
-                            &self.make_strict_call(step, 0)?, // 'from' address injected here
+                        // setup tx with template values
+                        let step_strict = self.sign_typed_data_args(
+                            self.make_strict_call(step, 0)?, // 'from' address injected here
                             &placeholder_map,
-                        )?,
-                        None,
-                        step.kind.to_owned(),
-                    );
+                        )?;
+                        let tx = NamedTxRequest::new(
+                            self.template_call(&step_strict, &placeholder_map, &mut safe_nonces)?,
+                            None,
+                            step.kind.to_owned(),
+                        )
+                        .with_expect_status(step.expect_status)
+                        .with_max_gas_used(step.max_gas_used);
 
-                    let handle = on_setup_step(tx.to_owned())?;
-                    if let Some(handle) = handle {
-                        handle.await.map_err(|e| {
+                        handles.extend(on_setup_step(tx.to_owned())?);
+                        setup_txs[idx] = Some(tx);
+                    }
+                    // wait for the whole wave to land before sending steps that depend on it
+                    for res in futures::future::join_all(handles).await {
+                        res.map_err(|e| {
                             ContenderError::with_err(e, "join error; callback crashed")
-                        })?;
+                        })??;
                     }
-                    txs.push(tx.into());
                 }
+                txs.extend(setup_txs.into_iter().flatten().map(|tx| tx.into()));
             }
             PlanType::Spam(num_txs, on_spam_setup) => {
                 let spam_steps = conf.get_spam_steps()?;
                 let num_steps = spam_steps.len();
-                // round num_txs up to the nearest multiple of num_steps to prevent missed steps
-                let num_txs = num_txs + (num_txs % num_steps);
+                // generate enough calls to fill `num_txs` multicall txs
+                let multicall = conf.get_multicall_config();
+                let calls_per_tx = multicall
+                    .as_ref()
+                    .map(|multicall| multicall.calls_per_tx.max(1))
+                    .unwrap_or(1);
+                let num_txs = num_txs * calls_per_tx;
+                let weights = spam_steps
+                    .iter()
+                    .map(SpamRequest::weight)
+                    .collect::<Vec<_>>();
+                let (num_txs, step_txs) = if weights.iter().all(|w| *w == weights[0]) {
+                    // round num_txs up to the nearest multiple of num_steps to prevent missed steps
+                    let num_txs = num_txs.div_ceil(num_steps) * num_steps;
+                    (num_txs, vec![num_txs / num_steps; num_steps])
+                } else {
+                    let step_txs = match self.get_spam_step_credit() {
+                        Some(credit) => {
+                            let mut credit = credit.lock().expect("spam step credit lock poisoned");
+                            // a run's first batch starts over
+                            if self.get_spam_batch() == 0 || credit.len() != num_steps {
+                                *credit = vec![0.0; num_steps];
+                            }
+                            weighted_step_txs(&weights, num_txs, &mut credit)?
+                        }
+                        None => weighted_step_txs(&weights, num_txs, &mut vec![0.0; num_steps])?,
+                    };
+                    (num_txs, step_txs)
+                };
+                let max_step_txs = step_txs.iter().max().copied().unwrap_or_default();
+                // a batch takes at most (num_steps + 2) * num_txs seed values (for fuzzing, senders
+                // & targets); later batches take theirs after it, & continue round-robin picks
+                let batch = self.get_spam_batch();
+                let batch_seeder =
+                    OffsetSeeder::new(self.get_fuzz_seeder(), batch * (num_steps + 2) * num_txs);
+                let first_tx = batch * max_step_txs;
                 let mut placeholder_map = HashMap::<K, String>::new();
                 let mut canonical_fuzz_map = HashMap::<String, Vec<U256>>::new();
 
                 // finds fuzzed values for a function call definition and populates `canonical_fuzz_map` with fuzzy values.
-                let mut find_fuzz = |req: &FunctionCallDefinition| {
-                    let fuzz_args = req.fuzz.to_owned().unwrap_or_default();
-                    let fuzz_map = self.create_fuzz_map(num_txs, &fuzz_args)?; // this may create more values than needed, but it's fine
+                let mut find_fuzz = |fuzz_args: Option<&[FuzzParam]>| {
+                    let fuzz_args = fuzz_args.unwrap_or_default();
+                    let fuzz_map = self.create_fuzz_map(
+                        batch * (num_steps + 2) * num_txs,
+                        num_txs,
+                        fuzz_args,
+                    )?; // this may create more values than needed, but it's fine
                     canonical_fuzz_map.extend(fuzz_map);
                     Ok(())
                 };
 
-                // finds placeholders in a function call definition and populates `placeholder_map` and `canonical_fuzz_map` with injectable values.
+                // finds placeholders in a spam step and populates `placeholder_map` and `canonical_fuzz_map` with injectable values.
                 let rpc_url = self.get_rpc_url();
-                let mut lookup_tx_placeholders = |tx: &FunctionCallDefinition| {
-                    let res =
-                        templater.find_fncall_placeholders(tx, db, &mut placeholder_map, &rpc_url);
-                    if let Err(e) = res {
-                        eprintln!("error finding placeholders: {}", e);
-                        return Err(ContenderError::SpamError(
-                            "failed to find placeholder value",
-                            Some(e.to_string()),
-                        ));
+                let namespace = self.get_namespace();
+                let mut lookup_step_placeholders = |step: &SpamRequest| -> Result<()> {
+                    let fncalls = match step {
+                        SpamRequest::Tx(tx) => vec![tx],
+                        SpamRequest::Bundle(req) => req.txs.iter().collect(),
+                        SpamRequest::Transfer(transfer) => {
+                            let inputs = transfer.to.iter().flat_map(|to| to.targets());
+                            for input in inputs.chain(&transfer.value).chain(&transfer.data) {
+                                templater.find_placeholder_values(
+                                    input,
+                                    &mut placeholder_map,
+                                    db,
+                                    &rpc_url,
+                                    namespace.as_deref(),
+                                )?;
+                            }
+                            find_fuzz(transfer.fuzz.as_deref())?;
+                            vec![]
+                        }
+                    };
+                    for tx in fncalls {
+                        let res = templater.find_fncall_placeholders(
+                            tx,
+                            db,
+                            &mut placeholder_map,
+                            &rpc_url,
+                            namespace.as_deref(),
+                        );
+                        if let Err(e) = res {
+                            eprintln!("error finding placeholders: {}", e);
+                            return Err(ContenderError::SpamError(
+                                "failed to find placeholder value",
+                                Some(e.to_string()),
+                            ));
+                        }
+                        self.find_typed_data_placeholders(tx, &mut placeholder_map)?;
+                        find_fuzz(tx.fuzz.as_deref())?;
                     }
-                    find_fuzz(tx)?;
                     Ok(())
                 };
 
                 for step in spam_steps.iter() {
                     // populate placeholder map for each step
-                    match step {
-                        SpamRequest::Tx(tx) => {
-                            lookup_tx_placeholders(tx)?;
-                        }
-                        SpamRequest::Bundle(req) => {
-                            for tx in req.txs.iter() {
-                                lookup_tx_placeholders(tx)?;
-                            }
-                        }
-                    };
+                    lookup_step_placeholders(step)?;
                 }
 
+                let spam_fncalls = spam_steps
+                    .iter()
+                    .flat_map(|step| match step {
+                        SpamRequest::Tx(tx) => vec![tx.to_owned()],
+                        SpamRequest::Bundle(req) => req.txs.to_owned(),
+                        SpamRequest::Transfer(_) => vec![],
+                    })
+                    .collect::<Vec<_>>();
+                let mut safe_nonces = self
+                    .fetch_safe_nonces(&spam_fncalls, &mut placeholder_map)
+                    .await?;
+
                 let agentstore = self.get_agent_store();
-                let num_accts = agentstore
-                    .all_agents()
-                    .next()
-                    .map(|(_, store)| store.signers.len())
-                    .unwrap_or(1);
+                // senders are picked from each step's pool; pools may differ in size
+                let pool_size = |from_pool: &Option<String>| {
+                    from_pool
+                        .as_ref()
+                        .and_then(|pool| agentstore.get_agent(pool))
+                        .map(|store| store.signers.len())
+                };
+                let sender_assignment = conf.get_sender_assignment();
 
                 // txs will be grouped by step [from=1, from=2, from=3, from=1, from=2, from=3, ...]
-                for step in spam_steps.iter() {
-                    for i in 0..(num_txs / num_steps) {
+                for (step_idx, step) in spam_steps.iter().enumerate() {
+                    let num_accts = match step {
+                        SpamRequest::Tx(req) => pool_size(&req.from_pool),
+                        SpamRequest::Bundle(req) => req
+                            .txs
+                            .iter()
+                            .filter_map(|tx| pool_size(&tx.from_pool))
+                            .min(),
+                        SpamRequest::Transfer(req) => pool_size(&req.from_pool),
+                    }
+                    .unwrap_or(1)
+                    .max(1);
+                    let senders = match sender_assignment {
+                        // a step's sticky sender is kept across batches
+                        SenderAssignment::Sticky => sender_indices(
+                            sender_assignment,
+                            self.get_fuzz_seeder(),
+                            step_idx,
+                            first_tx,
+                            max_step_txs,
+                            num_accts,
+                            num_txs,
+                        ),
+                        _ => sender_indices(
+                            sender_assignment,
+                            &batch_seeder,
+                            step_idx,
+                            first_tx,
+                            max_step_txs,
+                            num_accts,
+                            num_txs,
+                        ),
+                    };
+                    let target_seeds = target_picks(&batch_seeder, step_idx, max_step_txs, num_txs);
+                    for i in 0..step_txs[step_idx] {
                         // converts a FunctionCallDefinition to a NamedTxRequest (filling in fuzzable args),
                         // returns a callback handle and the processed tx request
-                        let prepare_tx = |req| {
+                        let mut prepare_tx = |req: &FunctionCallDefinition| {
                             let args = get_fuzzed_args(req, &canonical_fuzz_map, i);
-                            let fuzz_tx_value = get_fuzzed_tx_value(req, &canonical_fuzz_map, i);
+                            let fuzz_tx_value =
+                                get_fuzzed_tx_value(req.fuzz.as_deref(), &canonical_fuzz_map, i);
                             let mut req = req.to_owned();
                             req.args = Some(args);
+                            if let CallTarget::Many(targets) = &req.to {
+                                if targets.is_empty() {
+                                    return Err(ContenderError::SpamError(
+                                        "`to` must list at least one target",
+                                        Some(req.signature.to_owned()),
+                                    ));
+                                }
+                                let target_idx = target_index(
+                                    req.to_selection.unwrap_or_default(),
+                                    first_tx + i,
+                                    target_seeds[i],
+                                    targets.len(),
+                                );
+                                req.to = CallTarget::One(targets[target_idx].to_owned());
+                            }
 
                             if fuzz_tx_value.is_some() {
                                 req.value = fuzz_tx_value;
                             }
 
+                            let req_strict = self.sign_typed_data_args(
+                                self.make_strict_call(&req, senders[i])?, // 'from' address injected here
+                                &placeholder_map,
+                            )?;
                             let tx = NamedTxRequest::new(
-                                templater.template_function_call(
-                                    &self.make_strict_call(&req, i % num_accts)?, // 'from' address injected here
+                                self.template_call(
+                                    &req_strict,
                                     &placeholder_map,
+                                    &mut safe_nonces,
                                 )?,
                                 None,
                                 req.kind.to_owned(),
-                            );
+                            )
+                            .with_expect_status(req.expect_status)
+                            .with_max_gas_used(req.max_gas_used);
                             Ok((on_spam_setup(tx.to_owned())?, tx))
                         };
 
@@ -394,9 +1316,12 @@ where
                                 if let Some(handle) = handle {
                                     handle.await.map_err(|e| {
                                         ContenderError::with_err(e, "error from callback")
-                                    })?;
+                                    })??;
+                                }
+                                match req.route.unwrap_or_default() {
+                                    SpamRoute::Mempool => txs.push(tx.into()),
+                                    SpamRoute::Builder => txs.push(vec![tx].into()),
                                 }
-                                txs.push(tx.into());
                             }
                             SpamRequest::Bundle(req) => {
                                 let mut bundle_txs = vec![];
@@ -405,14 +1330,78 @@ where
                                     if let Some(handle) = handle {
                                         handle.await.map_err(|e| {
                                             ContenderError::with_err(e, "error from callback")
-                                        })?;
+                                        })??;
                                     }
                                     bundle_txs.push(txr);
                                 }
                                 txs.push(bundle_txs.into());
                             }
+                            SpamRequest::Transfer(req) => {
+                                let tx = NamedTxRequest::new(
+                                    self.template_transfer(
+                                        req,
+                                        senders[i],
+                                        first_tx + i,
+                                        target_seeds[i],
+                                        &placeholder_map,
+                                        get_fuzzed_tx_value(
+                                            req.fuzz.as_deref(),
+                                            &canonical_fuzz_map,
+                                            i,
+                                        ),
+                                    )?,
+                                    None,
+                                    req.kind.to_owned(),
+                                );
+                                if let Some(handle) = on_spam_setup(tx.to_owned())? {
+                                    handle.await.map_err(|e| {
+                                        ContenderError::with_err(e, "error from callback")
+                                    })??;
+                                }
+                                txs.push(tx.into());
+                            }
+                        }
+                    }
+                }
+
+                if let Some(multicall) = multicall {
+                    templater.find_placeholder_values(
+                        &multicall.address,
+                        &mut placeholder_map,
+                        db,
+                        &rpc_url,
+                        namespace.as_deref(),
+                    )?;
+                    let address = templater
+                        .replace_placeholders(&multicall.address, &placeholder_map)
+                        .parse::<Address>()
+                        .map_err(|e| {
+                            ContenderError::with_err(e, "failed to parse multicall address")
+                        })?;
+                    let calls = txs
+                        .into_iter()
+                        .map(|req| match req {
+                            ExecutionRequest::Tx(tx) => Ok(tx),
+                            ExecutionRequest::Bundle(_) => Err(ContenderError::SpamError(
+                                "bundles can't be aggregated into multicalls",
+                                None,
+                            )),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    // each multicall is sent by one of its calls' senders, so calls are grouped
+                    // by sender first; senders whose calls don't fill a tx get a smaller one
+                    let mut senders: Vec<(Option<Address>, Vec<NamedTxRequest>)> = vec![];
+                    for call in calls {
+                        match senders.iter_mut().find(|(from, _)| *from == call.tx.from) {
+                            Some((_, sender_calls)) => sender_calls.push(call),
+                            None => senders.push((call.tx.from, vec![call])),
                         }
                     }
+                    txs = senders
+                        .iter()
+                        .flat_map(|(_, sender_calls)| sender_calls.chunks(calls_per_tx))
+                        .map(|chunk| aggregate_txs(chunk, address).map(ExecutionRequest::from))
+                        .collect::<Result<Vec<_>>>()?;
                 }
             }
         }
@@ -454,17 +1443,30 @@ fn get_fuzzed_args(
             };
 
             // !!! args with template values will be overwritten by the fuzzer if it's enabled for this arg
-            maybe_fuzz().unwrap_or(arg.to_owned())
+            let mut arg = maybe_fuzz().unwrap_or(arg.to_owned());
+
+            // fuzz fields of tuple args by path, e.g. "permit.deadline"
+            let param = &func.inputs[idx];
+            for (key, values) in fuzz_map.iter() {
+                let Some(path) = key.strip_prefix(&format!("{}.", param.name)) else {
+                    continue;
+                };
+                let path = path.split('.').collect::<Vec<_>>();
+                if let Some(range) = tuple_field_range(&arg, &param.components, &path) {
+                    arg.replace_range(range, &values[fuzz_idx].to_string());
+                }
+            }
+            arg
         })
         .collect()
 }
 
 fn get_fuzzed_tx_value(
-    tx: &FunctionCallDefinition,
+    fuzz: Option<&[FuzzParam]>,
     fuzz_map: &HashMap<String, Vec<U256>>,
     fuzz_idx: usize,
 ) -> Option<String> {
-    if let Some(fuzz) = &tx.fuzz {
+    if let Some(fuzz) = fuzz {
         for fuzz_param in fuzz {
             if let Some(value) = fuzz_param.value {
                 if value {
@@ -480,3 +1482,157 @@ fn get_fuzzed_tx_value(
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzes_tuple_fields() {
+        let tx = FunctionCallDefinition {
+            to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD".into(),
+            to_selection: None,
+            relayer_pool: None,
+            safe: None,
+            from: None,
+            from_pool: None,
+            signature:
+                "f(((address token, uint256 amount) permitted, uint256 nonce) permit, uint256 x)"
+                    .to_owned(),
+            args: Some(vec![
+                "((0x1111111111111111111111111111111111111111, 1), 2)".to_owned(),
+                "3".to_owned(),
+            ]),
+            fuzz: None,
+            value: None,
+            kind: None,
+            gas_limit: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            rate: None,
+            weight: None,
+            expect_status: None,
+            max_gas_used: None,
+            route: None,
+        };
+        let fuzz_map = HashMap::from([
+            (
+                "permit.permitted.amount".to_owned(),
+                vec![U256::from(10), U256::from(11)],
+            ),
+            ("x".to_owned(), vec![U256::from(30), U256::from(31)]),
+        ]);
+        assert_eq!(
+            get_fuzzed_args(&tx, &fuzz_map, 1),
+            vec![
+                "((0x1111111111111111111111111111111111111111, 11), 2)",
+                "31"
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_setup_steps_into_waves() {
+        let resources = [
+            vec!["from:admin", "to:{Token}"],
+            vec!["from:admin", "to:{Pool}"],
+            vec!["from:alice", "to:{Router}"],
+            // passes {Pool} in its args
+            vec!["from:erin", "to:{Router2}", "to:{Pool}"],
+            vec!["from:bob", "to:{Token}"],
+            vec!["from:carol", "to:{Safe}"],
+            vec!["from:dave", "to:{Router}"],
+        ]
+        .map(|step| step.into_iter().map(String::from).collect::<Vec<_>>());
+        let barriers = [false, false, false, false, false, true, false];
+        assert_eq!(
+            setup_waves(&resources, &barriers),
+            vec![vec![0, 2], vec![1, 4], vec![3], vec![5], vec![6]]
+        );
+    }
+
+    #[test]
+    fn finds_placeholder_refs() {
+        assert_eq!(placeholder_refs("{Pool}"), vec!["{Pool}"]);
+        assert_eq!(
+            placeholder_refs("[{Token},{_sender},{Router}]"),
+            vec!["{Token}", "{Router}"]
+        );
+        assert!(placeholder_refs("0xdead").is_empty());
+    }
+
+    #[test]
+    fn splits_txs_by_step_weight() {
+        let split = |weights: &[f64], num_txs| {
+            weighted_step_txs(weights, num_txs, &mut vec![0.0; weights.len()])
+        };
+        assert_eq!(split(&[9.0, 1.0], 100).unwrap(), vec![90, 10]);
+        assert_eq!(split(&[1.0, 1.0, 1.0], 10).unwrap(), vec![4, 3, 3]);
+        assert_eq!(split(&[2.0, 0.0, 1.0], 7).unwrap(), vec![5, 0, 2]);
+        assert!(split(&[0.0, 0.0], 10).is_err());
+        assert!(split(&[1.0, -1.0], 10).is_err());
+    }
+
+    #[test]
+    fn carries_step_weight_remainders_across_batches() {
+        let weights = [99.0, 1.0];
+        let mut credit = vec![0.0; 2];
+        let mut totals = vec![0; 2];
+        for _ in 0..40 {
+            let counts = weighted_step_txs(&weights, 5, &mut credit).unwrap();
+            assert_eq!(counts.iter().sum::<usize>(), 5);
+            totals[0] += counts[0];
+            totals[1] += counts[1];
+        }
+        // 200 txs at 99:1
+        assert_eq!(totals, vec![198, 2]);
+    }
+
+    #[test]
+    fn selects_targets() {
+        let seed = RandSeed::seed_from_bytes(&[0x01; 32]);
+        let picks = target_picks(&seed, 0, 20, 100);
+        assert_eq!(
+            (0..6)
+                .map(|i| target_index(TargetSelection::RoundRobin, i, picks[i], 3))
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 0, 1, 2]
+        );
+        let random = (0..20)
+            .map(|i| target_index(TargetSelection::Random, i, picks[i], 3))
+            .collect::<Vec<_>>();
+        assert!(random.iter().all(|&i| i < 3));
+        // picks come after the values used for senders
+        assert_ne!(
+            random,
+            sender_indices(SenderAssignment::Random, &seed, 0, 0, 20, 3, 100)
+        );
+    }
+
+    #[test]
+    fn assigns_senders() {
+        let seed = RandSeed::seed_from_bytes(&[0x01; 32]);
+        let assign =
+            |assignment, step_idx| sender_indices(assignment, &seed, step_idx, 0, 20, 5, 100);
+
+        assert_eq!(
+            assign(SenderAssignment::RoundRobin, 0),
+            (0..20).map(|i| i % 5).collect::<Vec<_>>()
+        );
+        // a later batch of txs continues the rotation
+        assert_eq!(
+            sender_indices(SenderAssignment::RoundRobin, &seed, 0, 3, 4, 5, 100),
+            vec![3, 4, 0, 1]
+        );
+
+        let random = assign(SenderAssignment::Random, 0);
+        assert!(random.iter().all(|&i| i < 5));
+        assert_ne!(random, assign(SenderAssignment::RoundRobin, 0));
+        // seeded, so the same seed gives the same senders
+        assert_eq!(random, assign(SenderAssignment::Random, 0));
+        assert_ne!(random, assign(SenderAssignment::Random, 1));
+
+        let sticky = assign(SenderAssignment::Sticky, 0);
+        assert!(sticky.iter().all(|&i| i == sticky[0]));
+    }
+}
@@ -0,0 +1,255 @@
+use alloy::{
+    primitives::{Address, Bytes, TxKind, U256},
+    providers::Provider,
+    rpc::types::{TransactionInput, TransactionRequest, WithOtherFields},
+    sol,
+    sol_types::SolCall,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ContenderError,
+    generator::{types::AnyProvider, NamedTxRequest},
+    Result,
+};
+
+/// Address Multicall3 is deployed to on most chains.
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+sol! {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct Call3Value {
+        address target;
+        bool allowFailure;
+        uint256 value;
+        bytes callData;
+    }
+
+    struct Call3Result {
+        bool success;
+        bytes returnData;
+    }
+
+    function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    function aggregate3Value(Call3Value[] calldata calls) external payable;
+    function getEthBalance(address addr) external view returns (uint256 balance);
+}
+
+/// Max number of balances fetched per `eth_call` by [`get_eth_balances`].
+const BALANCES_PER_CALL: usize = 500;
+
+/// Wraps spam calls into Multicall3 txs, trading tx count for larger txs.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct MulticallConfig {
+    /// Number of calls to aggregate into each tx.
+    pub calls_per_tx: usize,
+    /// Address (or placeholder, e.g. `{multicall3}`) of the Multicall3 contract.
+    #[serde(default = "default_multicall_address")]
+    pub address: String,
+}
+
+fn default_multicall_address() -> String {
+    MULTICALL3_ADDRESS.to_owned()
+}
+
+/// Returns a tx calling `aggregate3` (or `aggregate3Value` if any call sends ETH) on `multicall`
+/// with every call in `txs`. The tx reverts if any call fails, so failed calls aren't counted as
+/// landed. The calls must share a sender, which sends the tx; it's labeled with the calls' kind if
+/// they share one.
+pub fn aggregate_txs(txs: &[NamedTxRequest], multicall: Address) -> Result<NamedTxRequest> {
+    let first = txs
+        .first()
+        .ok_or(ContenderError::SpamError("no calls to aggregate", None))?;
+    if let Some(req) = txs.iter().find(|req| req.tx.from != first.tx.from) {
+        return Err(ContenderError::SpamError(
+            "calls from different senders can't be aggregated into one multicall",
+            req.name.to_owned(),
+        ));
+    }
+    let calls = txs
+        .iter()
+        .map(|req| {
+            let target = match req.tx.to {
+                Some(TxKind::Call(to)) => to,
+                _ => {
+                    return Err(ContenderError::SpamError(
+                        "contract deployments can't be aggregated into a multicall",
+                        req.name.to_owned(),
+                    ))
+                }
+            };
+            Ok(Call3Value {
+                target,
+                allowFailure: false,
+                value: req.tx.value.unwrap_or_default(),
+                callData: req.tx.input.input().cloned().unwrap_or_default(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let value = calls.iter().fold(U256::ZERO, |sum, call| sum + call.value);
+    let input = if value.is_zero() {
+        aggregate3Call {
+            calls: calls
+                .into_iter()
+                .map(|call| Call3 {
+                    target: call.target,
+                    allowFailure: call.allowFailure,
+                    callData: call.callData,
+                })
+                .collect(),
+        }
+        .abi_encode()
+    } else {
+        aggregate3ValueCall { calls }.abi_encode()
+    };
+
+    let kind = first
+        .kind
+        .as_ref()
+        .filter(|kind| txs.iter().all(|req| req.kind.as_ref() == Some(*kind)))
+        .cloned()
+        .or(Some("multicall".to_owned()));
+    let tx = TransactionRequest {
+        from: first.tx.from,
+        to: Some(TxKind::Call(multicall)),
+        input: TransactionInput::both(Bytes::from(input)),
+        value: (!value.is_zero()).then_some(value),
+        max_fee_per_gas: first.tx.max_fee_per_gas,
+        max_priority_fee_per_gas: first.tx.max_priority_fee_per_gas,
+        ..Default::default()
+    };
+    Ok(NamedTxRequest::new(tx, None, kind))
+}
+
+/// Gets the ETH balances of `addresses` through `multicall`'s `getEthBalance`, batching up to
+/// [`BALANCES_PER_CALL`] addresses into each `eth_call` instead of sending one `eth_getBalance`
+/// per address. Balances are returned in the order of `addresses`.
+pub async fn get_eth_balances(
+    provider: &AnyProvider,
+    multicall: Address,
+    addresses: &[Address],
+) -> Result<Vec<U256>> {
+    let mut balances = Vec::with_capacity(addresses.len());
+    for batch in addresses.chunks(BALANCES_PER_CALL) {
+        let tx = TransactionRequest {
+            to: Some(TxKind::Call(multicall)),
+            input: TransactionInput::both(encode_balance_calls(batch, multicall)),
+            ..Default::default()
+        };
+        let res = provider
+            .call(&WithOtherFields::new(tx))
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to get balances from multicall"))?;
+        balances.extend(decode_balances(&res)?);
+    }
+    Ok(balances)
+}
+
+/// `aggregate3` calldata calling `getEthBalance` on `multicall` itself for each of `addresses`.
+fn encode_balance_calls(addresses: &[Address], multicall: Address) -> Bytes {
+    aggregate3Call {
+        calls: addresses
+            .iter()
+            .map(|addr| Call3 {
+                target: multicall,
+                allowFailure: false,
+                callData: getEthBalanceCall { addr: *addr }.abi_encode().into(),
+            })
+            .collect(),
+    }
+    .abi_encode()
+    .into()
+}
+
+fn decode_balances(res: &[u8]) -> Result<Vec<U256>> {
+    let decode_err = |e| ContenderError::with_err(e, "failed to decode multicall balances");
+    aggregate3Call::abi_decode_returns(res, true)
+        .map_err(decode_err)?
+        .returnData
+        .iter()
+        .map(|res| {
+            getEthBalanceCall::abi_decode_returns(&res.returnData, true)
+                .map(|ret| ret.balance)
+                .map_err(decode_err)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(to: u8, value: u64, kind: &str) -> NamedTxRequest {
+        let tx = TransactionRequest {
+            from: Some(Address::repeat_byte(0xaa)),
+            to: Some(TxKind::Call(Address::repeat_byte(to))),
+            input: TransactionInput::both(Bytes::from(vec![to; 4])),
+            value: Some(U256::from(value)),
+            ..Default::default()
+        };
+        NamedTxRequest::new(tx, None, Some(kind.to_owned()))
+    }
+
+    #[test]
+    fn aggregates_calls_into_one_tx() {
+        let multicall: Address = MULTICALL3_ADDRESS.parse().unwrap();
+        let tx = aggregate_txs(&[call(1, 0, "swap"), call(2, 0, "swap")], multicall).unwrap();
+        assert_eq!(tx.tx.to, Some(TxKind::Call(multicall)));
+        assert_eq!(tx.tx.from, Some(Address::repeat_byte(0xaa)));
+        assert_eq!(tx.tx.value, None);
+        assert_eq!(tx.kind, Some("swap".to_owned()));
+        let input = tx.tx.input.input().unwrap();
+        assert_eq!(input[..4], aggregate3Call::SELECTOR);
+        let decoded = aggregate3Call::abi_decode(input, true).unwrap();
+        assert_eq!(decoded.calls.len(), 2);
+        assert_eq!(decoded.calls[1].target, Address::repeat_byte(2));
+        assert!(!decoded.calls[0].allowFailure);
+
+        // calls sending ETH use aggregate3Value
+        let tx = aggregate_txs(&[call(1, 5, "swap"), call(2, 7, "transfer")], multicall).unwrap();
+        assert_eq!(tx.tx.value, Some(U256::from(12)));
+        assert_eq!(tx.kind, Some("multicall".to_owned()));
+        assert_eq!(
+            tx.tx.input.input().unwrap()[..4],
+            aggregate3ValueCall::SELECTOR
+        );
+    }
+
+    #[test]
+    fn rejects_calls_from_different_senders() {
+        let multicall: Address = MULTICALL3_ADDRESS.parse().unwrap();
+        let mut other = call(2, 0, "swap");
+        other.tx.from = Some(Address::repeat_byte(0xbb));
+        assert!(aggregate_txs(&[call(1, 0, "swap"), other], multicall).is_err());
+    }
+
+    #[test]
+    fn decodes_batched_balances() {
+        let multicall: Address = MULTICALL3_ADDRESS.parse().unwrap();
+        let addresses = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let input = encode_balance_calls(&addresses, multicall);
+        let decoded = aggregate3Call::abi_decode(&input, true).unwrap();
+        assert_eq!(decoded.calls.len(), 2);
+        assert_eq!(decoded.calls[0].target, multicall);
+        assert_eq!(decoded.calls[1].callData[..4], getEthBalanceCall::SELECTOR);
+
+        let results = [7u64, 0]
+            .into_iter()
+            .map(|balance| Call3Result {
+                success: true,
+                returnData: getEthBalanceCall::abi_encode_returns(&(U256::from(balance),)).into(),
+            })
+            .collect::<Vec<_>>();
+        let res = aggregate3Call::abi_encode_returns(&(results,));
+        assert_eq!(
+            decode_balances(&res).unwrap(),
+            vec![U256::from(7), U256::ZERO]
+        );
+    }
+}
@@ -1,10 +1,16 @@
 use alloy::rpc::types::TransactionRequest;
 
+use super::types::ExpectStatus;
+
 /// Wrapper for [`TransactionRequest`](alloy::rpc::types::TransactionRequest) that includes optional name and kind fields.
 #[derive(Clone, Debug)]
 pub struct NamedTxRequest {
     pub name: Option<String>,
     pub kind: Option<String>,
+    /// Receipt status the tx is expected to have, if its step declares one.
+    pub expect_status: Option<ExpectStatus>,
+    /// Max gas the tx should use, if its step declares one.
+    pub max_gas_used: Option<u64>,
     pub tx: TransactionRequest,
 }
 
@@ -80,7 +86,27 @@ impl NamedTxRequestBuilder {
 
 impl NamedTxRequest {
     pub fn new(tx: TransactionRequest, name: Option<String>, kind: Option<String>) -> Self {
-        Self { name, kind, tx }
+        Self {
+            name,
+            kind,
+            expect_status: None,
+            max_gas_used: None,
+            tx,
+        }
+    }
+
+    pub fn with_expect_status(self, expect_status: Option<ExpectStatus>) -> Self {
+        Self {
+            expect_status,
+            ..self
+        }
+    }
+
+    pub fn with_max_gas_used(self, max_gas_used: Option<u64>) -> Self {
+        Self {
+            max_gas_used,
+            ..self
+        }
     }
 }
 
@@ -89,6 +115,8 @@ impl From<TransactionRequest> for NamedTxRequest {
         Self {
             name: None,
             kind: None,
+            expect_status: None,
+            max_gas_used: None,
             tx,
         }
     }
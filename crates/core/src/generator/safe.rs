@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use alloy::{
+    primitives::{Address, Bytes, B256, U256},
+    signers::{local::PrivateKeySigner, SignerSync},
+    sol,
+    sol_types::{Eip712Domain, SolCall, SolStruct},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContenderError, Result};
+
+sol! {
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+
+    function execTransaction(
+        address to,
+        uint256 value,
+        bytes data,
+        uint8 operation,
+        uint256 safeTxGas,
+        uint256 baseGas,
+        uint256 gasPrice,
+        address gasToken,
+        address refundReceiver,
+        bytes signatures
+    ) external payable returns (bool success);
+
+    function nonce() external view returns (uint256);
+}
+
+/// Wraps a call in a Safe `execTransaction`, signed by the Safe's owners.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SafeExecConfig {
+    /// Address (or placeholder, e.g. `{safe}`) of the Safe.
+    pub address: String,
+    /// Agent pool holding the Safe's owners. Its first `threshold` accounts sign each tx.
+    pub owner_pool: String,
+    /// Number of owner signatures to collect; the Safe's threshold.
+    pub threshold: usize,
+    /// Execute the call with `delegatecall` instead of `call`.
+    #[serde(default)]
+    pub delegatecall: bool,
+}
+
+impl SafeExecConfig {
+    pub fn operation(&self) -> u8 {
+        self.delegatecall as u8
+    }
+}
+
+/// Returns a Safe tx calling `to` with no gas refund, at the Safe's `nonce`.
+pub fn safe_tx(to: Address, value: U256, data: Bytes, operation: u8, nonce: U256) -> SafeTx {
+    SafeTx {
+        to,
+        value,
+        data,
+        operation,
+        safeTxGas: U256::ZERO,
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        nonce,
+    }
+}
+
+/// Returns the hash Safe owners sign for `tx` (Safe >= 1.3.0).
+pub fn safe_tx_hash(tx: &SafeTx, chain_id: u64, safe: Address) -> B256 {
+    let domain = Eip712Domain {
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(safe),
+        ..Default::default()
+    };
+    tx.eip712_signing_hash(&domain)
+}
+
+/// Signs `tx` with each owner and returns the signatures packed the way `execTransaction` expects:
+/// 65 bytes (r, s, v) per owner, sorted by owner address.
+pub fn sign_safe_tx(
+    tx: &SafeTx,
+    chain_id: u64,
+    safe: Address,
+    owners: &[PrivateKeySigner],
+) -> Result<Bytes> {
+    let hash = safe_tx_hash(tx, chain_id, safe);
+    let mut owners = owners.iter().collect::<Vec<_>>();
+    owners.sort_by_key(|owner| owner.address());
+    let mut signatures = Vec::with_capacity(owners.len() * 65);
+    for owner in owners {
+        let signature = owner
+            .sign_hash_sync(&hash)
+            .map_err(|e| ContenderError::with_err(e, "failed to sign Safe tx"))?;
+        signatures.extend_from_slice(&signature.as_bytes());
+    }
+    Ok(signatures.into())
+}
+
+/// Returns calldata for the Safe to execute `tx` with `signatures`.
+pub fn exec_transaction_calldata(tx: SafeTx, signatures: Bytes) -> Bytes {
+    execTransactionCall {
+        to: tx.to,
+        value: tx.value,
+        data: tx.data,
+        operation: tx.operation,
+        safeTxGas: tx.safeTxGas,
+        baseGas: tx.baseGas,
+        gasPrice: tx.gasPrice,
+        gasToken: tx.gasToken,
+        refundReceiver: tx.refundReceiver,
+        signatures,
+    }
+    .abi_encode()
+    .into()
+}
+
+/// Chain ID & next nonce of each Safe a plan executes through.
+///
+/// Safe nonces are only consumed when a tx executes, so nonces are counted up as txs are generated.
+#[derive(Debug, Default)]
+pub struct SafeNonces {
+    pub chain_id: u64,
+    nonces: HashMap<Address, U256>,
+}
+
+impl SafeNonces {
+    pub fn contains(&self, safe: &Address) -> bool {
+        self.nonces.contains_key(safe)
+    }
+
+    pub fn insert(&mut self, safe: Address, nonce: U256) {
+        self.nonces.insert(safe, nonce);
+    }
+
+    /// Returns the next nonce of `safe` and counts it as used.
+    pub fn next(&mut self, safe: &Address) -> Result<U256> {
+        let nonce = self.nonces.get_mut(safe).ok_or(ContenderError::SpamError(
+            "nonce of Safe was not fetched",
+            Some(safe.to_string()),
+        ))?;
+        let next = *nonce;
+        *nonce += U256::from(1);
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_safe_txs_sorted_by_owner() {
+        let owners = [PrivateKeySigner::random(), PrivateKeySigner::random()];
+        let safe = Address::repeat_byte(0x5a);
+        let tx = safe_tx(
+            Address::repeat_byte(1),
+            U256::ZERO,
+            Bytes::from(vec![1, 2, 3, 4]),
+            0,
+            U256::from(7),
+        );
+        let signatures = sign_safe_tx(&tx, 1, safe, &owners).unwrap();
+        assert_eq!(signatures.len(), 130);
+
+        let hash = safe_tx_hash(&tx, 1, safe);
+        let signers = signatures
+            .chunks(65)
+            .map(|sig| {
+                alloy::primitives::Signature::try_from(sig)
+                    .unwrap()
+                    .recover_address_from_prehash(&hash)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut expected = owners.iter().map(|o| o.address()).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(signers, expected);
+
+        let calldata = exec_transaction_calldata(tx, signatures);
+        assert_eq!(calldata[..4], execTransactionCall::SELECTOR);
+    }
+
+    #[test]
+    fn counts_safe_nonces() {
+        let safe = Address::repeat_byte(0x5a);
+        let mut nonces = SafeNonces::default();
+        assert!(nonces.next(&safe).is_err());
+        nonces.insert(safe, U256::from(3));
+        assert_eq!(nonces.next(&safe).unwrap(), U256::from(3));
+        assert_eq!(nonces.next(&safe).unwrap(), U256::from(4));
+    }
+}
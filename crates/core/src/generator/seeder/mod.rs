@@ -8,6 +8,51 @@ pub trait Seeder {
         min: Option<U256>,
         max: Option<U256>,
     ) -> Box<impl Iterator<Item = impl SeedValue>>;
+
+    /// Like [`Seeder::seed_values`], but starting at the `start`th value.
+    fn seed_values_from(
+        &self,
+        start: usize,
+        amount: usize,
+        min: Option<U256>,
+        max: Option<U256>,
+    ) -> Box<impl Iterator<Item = impl SeedValue>> {
+        Box::new(self.seed_values(start + amount, min, max).skip(start))
+    }
+}
+
+/// Seeds values from another seeder's, starting at its `offset`th value.
+pub struct OffsetSeeder<'a, S> {
+    seeder: &'a S,
+    offset: usize,
+}
+
+impl<'a, S: Seeder> OffsetSeeder<'a, S> {
+    pub fn new(seeder: &'a S, offset: usize) -> Self {
+        Self { seeder, offset }
+    }
+}
+
+impl<S: Seeder> Seeder for OffsetSeeder<'_, S> {
+    fn seed_values(
+        &self,
+        amount: usize,
+        min: Option<U256>,
+        max: Option<U256>,
+    ) -> Box<impl Iterator<Item = impl SeedValue>> {
+        self.seeder.seed_values_from(self.offset, amount, min, max)
+    }
+
+    fn seed_values_from(
+        &self,
+        start: usize,
+        amount: usize,
+        min: Option<U256>,
+        max: Option<U256>,
+    ) -> Box<impl Iterator<Item = impl SeedValue>> {
+        self.seeder
+            .seed_values_from(self.offset + start, amount, min, max)
+    }
 }
 
 pub trait SeedValue {
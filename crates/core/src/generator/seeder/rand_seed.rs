@@ -83,11 +83,22 @@ impl Seeder for RandSeed {
         amount: usize,
         min: Option<U256>,
         max: Option<U256>,
+    ) -> Box<impl Iterator<Item = impl SeedValue>> {
+        self.seed_values_from(0, amount, min, max)
+    }
+
+    fn seed_values_from(
+        &self,
+        start: usize,
+        amount: usize,
+        min: Option<U256>,
+        max: Option<U256>,
     ) -> Box<impl Iterator<Item = impl SeedValue>> {
         let min = min.unwrap_or(U256::ZERO);
         let max = max.unwrap_or(U256::MAX);
         assert!(min < max, "min must be less than max");
-        let vals = (0..amount).map(move |i| {
+        // each value only depends on its index, so there's nothing to skip
+        let vals = (start..start + amount).map(move |i| {
             // generate random-looking value between min and max from seed
             let seed_num = self.as_u256() + U256::from(i);
             let val = keccak256(seed_num.as_le_slice());
@@ -139,4 +150,26 @@ mod tests {
         let seed = super::RandSeed::seed_from_u256(n);
         assert_eq!(seed.as_u256(), n);
     }
+
+    #[test]
+    fn seeds_values_from_an_offset() {
+        use crate::generator::seeder::{OffsetSeeder, Seeder};
+
+        let seed = super::RandSeed::seed_from_str("0x1337");
+        let skipped = seed
+            .seed_values(10, None, None)
+            .skip(4)
+            .map(|v| v.as_u256())
+            .collect::<Vec<_>>();
+        let from = seed
+            .seed_values_from(4, 6, None, None)
+            .map(|v| v.as_u256())
+            .collect::<Vec<_>>();
+        assert_eq!(from, skipped);
+        let offset = OffsetSeeder::new(&seed, 2)
+            .seed_values_from(2, 6, None, None)
+            .map(|v| v.as_u256())
+            .collect::<Vec<_>>();
+        assert_eq!(offset, skipped);
+    }
 }
@@ -1,7 +1,9 @@
 use crate::{
     db::DbOps,
     error::ContenderError,
-    generator::{types::FunctionCallDefinition, util::encode_calldata},
+    generator::{
+        eip712::SIG712_PREFIX, types::FunctionCallDefinition, util::encode_calldata, POOL_PREFIX,
+    },
     Result,
 };
 use alloy::{
@@ -11,7 +13,7 @@ use alloy::{
 };
 use std::collections::HashMap;
 
-use super::types::{CreateDefinitionStrict, FunctionCallDefinitionStrict};
+use super::types::{CreateDefinitionStrict, FunctionCallDefinitionStrict, GasLimit};
 
 pub trait Templater<K>
 where
@@ -26,12 +28,14 @@ where
     fn encode_contract_address(&self, input: &Address) -> String;
 
     /// Looks for {placeholders} in `arg` and updates `env` with the values found by querying the DB.
+    /// Named txs are looked up in `namespace`, if set.
     fn find_placeholder_values(
         &self,
         arg: &str,
         placeholder_map: &mut HashMap<K, String>,
         db: &impl DbOps,
         rpc_url: &str,
+        namespace: Option<&str>,
     ) -> Result<()> {
         // count number of placeholders (by left brace) in arg
         let num_template_vals = self.num_placeholders(arg);
@@ -48,8 +52,9 @@ where
                     ))?;
             last_end = template_end + 1;
 
-            // ignore {_sender} placeholder; it's handled outside the templater
-            if template_key.to_string() == "_sender" {
+            // ignore {_sender}, {_sig712:...} & {_pool:...} placeholders; they're handled outside the templater
+            let key = template_key.to_string();
+            if key == "_sender" || key.starts_with(SIG712_PREFIX) || key.starts_with(POOL_PREFIX) {
                 continue;
             }
 
@@ -59,7 +64,7 @@ where
             }
 
             let template_value = db
-                .get_named_tx(&template_key.to_string(), rpc_url)
+                .get_named_tx(&template_key.to_string(), rpc_url, namespace)
                 .map_err(|e| {
                     ContenderError::SpamError(
                         "Failed to get named tx from DB. There may be an issue with your database.",
@@ -93,13 +98,16 @@ where
         db: &impl DbOps,
         placeholder_map: &mut HashMap<K, String>,
         rpc_url: &str,
+        namespace: Option<&str>,
     ) -> Result<()> {
         // find templates in fn args & `to`
         let fn_args = fncall.args.to_owned().unwrap_or_default();
         for arg in fn_args.iter() {
-            self.find_placeholder_values(arg, placeholder_map, db, rpc_url)?;
+            self.find_placeholder_values(arg, placeholder_map, db, rpc_url, namespace)?;
+        }
+        for to in fncall.to.targets() {
+            self.find_placeholder_values(to, placeholder_map, db, rpc_url, namespace)?;
         }
-        self.find_placeholder_values(&fncall.to, placeholder_map, db, rpc_url)?;
         Ok(())
     }
 
@@ -127,11 +135,20 @@ where
             .map(|s| self.replace_placeholders(s, placeholder_map))
             .and_then(|s| s.parse::<U256>().ok());
 
+        // `gas` is left unset for "auto"; it's estimated when the tx is sent
+        let gas = match funcdef.gas_limit {
+            GasLimit::Auto => None,
+            GasLimit::Fixed(gas) => Some(gas as u128),
+        };
+
         Ok(TransactionRequest {
             to: Some(TxKind::Call(to)),
             input: alloy::rpc::types::TransactionInput::both(input.into()),
-            from: Some(funcdef.from),
+            from: Some(funcdef.relayer.unwrap_or(funcdef.from)),
             value,
+            gas,
+            max_fee_per_gas: funcdef.max_fee_per_gas.map(u128::from),
+            max_priority_fee_per_gas: funcdef.max_priority_fee_per_gas.map(u128::from),
             ..Default::default()
         })
     }
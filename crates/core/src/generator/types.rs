@@ -1,9 +1,10 @@
-use super::named_txs::ExecutionRequest;
+use super::{named_txs::ExecutionRequest, safe::SafeExecConfig};
 use alloy::{
     network::AnyNetwork,
     primitives::{Address, U256},
     providers::RootProvider,
-    transports::http::{Client, Http},
+    rpc::types::{Filter, TransactionRequest},
+    transports::BoxTransport,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -13,20 +14,27 @@ use tokio::task::JoinHandle;
 pub use crate::generator::named_txs::NamedTxRequest;
 
 // -- convenience
-pub type EthProvider = RootProvider<Http<Client>>;
-pub type AnyProvider = RootProvider<Http<Client>, AnyNetwork>;
+pub type EthProvider = RootProvider<BoxTransport>;
+pub type AnyProvider = RootProvider<BoxTransport, AnyNetwork>;
 
 // -- core types for test scenarios
 
 /// User-facing definition of a function call to be executed.
 #[derive(Clone, Deserialize, Debug, Serialize)]
 pub struct FunctionCallDefinition {
-    /// Address of the contract to call.
-    pub to: String,
+    /// Address of the contract to call, or a list of addresses to spread spam txs across.
+    pub to: CallTarget,
+    /// How spam txs pick an address when `to` lists several. Defaults to round-robin.
+    pub to_selection: Option<TargetSelection>,
     /// Address of the tx sender.
     pub from: Option<String>,
     /// Get a `from` address from the pool of signers specified here.
     pub from_pool: Option<String>,
+    /// Send the tx from an account in this pool instead of `from`, which then only signs messages
+    /// embedded in the tx (e.g. ERC-2771 meta-txs). Relayers are picked by the sender's pool index.
+    pub relayer_pool: Option<String>,
+    /// Execute the call through a Safe, signed by its owners.
+    pub safe: Option<SafeExecConfig>,
     /// Name of the function to call.
     pub signature: String,
     /// Parameters to pass to the function.
@@ -37,16 +45,276 @@ pub struct FunctionCallDefinition {
     pub fuzz: Option<Vec<FuzzParam>>,
     /// Optional type of the spam transaction for categorization.
     pub kind: Option<String>,
+    /// Gas limit for the tx; a number, or `"auto"` (default) to estimate it.
+    pub gas_limit: Option<GasLimit>,
+    /// Max fee per gas (in wei). Derived from the node's gas price if not set.
+    pub max_fee_per_gas: Option<u64>,
+    /// Max priority fee per gas (in wei). Derived from the node's gas price if not set.
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Send rate of the spam step, instead of an even split of the global rate. Ignored in setup.
+    pub rate: Option<StepRate>,
+    /// Share of the run's spam txs generated from the step, relative to other steps' weights
+    /// (1 if not set). Ignored in setup.
+    pub weight: Option<f64>,
+    /// Receipt status the step's txs should have. Reverts of steps expected to revert aren't
+    /// counted as errors; txs with any other status are flagged.
+    pub expect_status: Option<ExpectStatus>,
+    /// Max gas each of the step's txs should use. Txs using more are flagged in the report, and
+    /// can fail the run.
+    pub max_gas_used: Option<u64>,
+    /// Where spam txs of the step are sent: the node's public mempool (default), or the builder
+    /// as single-tx bundles. Ignored in setup & bundles.
+    pub route: Option<SpamRoute>,
 }
 
 pub struct FunctionCallDefinitionStrict {
     pub to: String, // may be a placeholder, so we can't use Address
     pub from: Address,
+    /// Sends the tx instead of `from`, if set.
+    pub relayer: Option<Address>,
+    pub safe: Option<SafeExecConfig>,
     pub signature: String,
     pub args: Vec<String>,
     pub value: Option<String>,
     pub fuzz: Vec<FuzzParam>,
     pub kind: Option<String>,
+    pub gas_limit: GasLimit,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+/// Target of a function call: an address (or placeholder), or a list of them.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CallTarget {
+    One(String),
+    /// Spam txs are spread across these, e.g. many instances of the same contract.
+    Many(Vec<String>),
+}
+
+impl CallTarget {
+    pub fn targets(&self) -> &[String] {
+        match self {
+            CallTarget::One(to) => std::slice::from_ref(to),
+            CallTarget::Many(targets) => targets,
+        }
+    }
+
+    pub fn targets_mut(&mut self) -> &mut [String] {
+        match self {
+            CallTarget::One(to) => std::slice::from_mut(to),
+            CallTarget::Many(targets) => targets,
+        }
+    }
+}
+
+impl From<&str> for CallTarget {
+    fn from(to: &str) -> Self {
+        CallTarget::One(to.to_owned())
+    }
+}
+
+impl From<String> for CallTarget {
+    fn from(to: String) -> Self {
+        CallTarget::One(to)
+    }
+}
+
+/// How a spam step's txs pick a target when its `to` lists several.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetSelection {
+    /// Txs cycle through the targets in order.
+    #[default]
+    RoundRobin,
+    /// Each tx gets a target picked at random (from the scenario's seed).
+    Random,
+}
+
+/// Where a spam step's txs are submitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamRoute {
+    /// `eth_sendRawTransaction` to the RPC URL.
+    #[default]
+    Mempool,
+    /// `eth_sendBundle` to the builder URL, one tx per bundle.
+    Builder,
+}
+
+/// Receipt status a step's txs are expected to have.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectStatus {
+    #[default]
+    Success,
+    /// The tx is meant to revert, e.g. to spam reverting calls.
+    Revert,
+}
+
+impl ExpectStatus {
+    /// Whether a receipt with the given status is what's expected.
+    pub fn matches(&self, success: bool) -> bool {
+        match self {
+            ExpectStatus::Success => success,
+            ExpectStatus::Revert => !success,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpectStatus::Success => "success",
+            ExpectStatus::Revert => "revert",
+        }
+    }
+}
+
+impl std::str::FromStr for ExpectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(ExpectStatus::Success),
+            "revert" => Ok(ExpectStatus::Revert),
+            _ => Err(format!(
+                "invalid expect_status '{}'; expected \"success\" or \"revert\"",
+                s
+            )),
+        }
+    }
+}
+
+/// Values applied to every setup & spam tx which doesn't set its own.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct StepDefaults {
+    pub gas_limit: Option<GasLimit>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+    pub value: Option<String>,
+}
+
+impl StepDefaults {
+    /// Fills fields of `funcdef` which aren't set with the defaults.
+    pub fn apply(&self, funcdef: &mut FunctionCallDefinition) {
+        funcdef.gas_limit = funcdef.gas_limit.or(self.gas_limit);
+        funcdef.max_fee_per_gas = funcdef.max_fee_per_gas.or(self.max_fee_per_gas);
+        funcdef.max_priority_fee_per_gas = funcdef
+            .max_priority_fee_per_gas
+            .or(self.max_priority_fee_per_gas);
+        if funcdef.value.is_none() {
+            funcdef.value = self.value.to_owned();
+        }
+    }
+
+    /// Applies the defaults to every tx in a spam step, including each tx of a bundle.
+    pub fn apply_spam(&self, req: &mut SpamRequest) {
+        match req {
+            SpamRequest::Tx(funcdef) => self.apply(funcdef),
+            SpamRequest::Bundle(bundle) => bundle.txs.iter_mut().for_each(|tx| self.apply(tx)),
+            SpamRequest::Transfer(transfer) => {
+                transfer.gas_limit = transfer.gas_limit.or(self.gas_limit);
+                transfer.max_fee_per_gas = transfer.max_fee_per_gas.or(self.max_fee_per_gas);
+                transfer.max_priority_fee_per_gas = transfer
+                    .max_priority_fee_per_gas
+                    .or(self.max_priority_fee_per_gas);
+                if transfer.value.is_none() {
+                    transfer.value = self.value.to_owned();
+                }
+            }
+        }
+    }
+}
+
+/// Gas limit of a tx, given as a number or `"auto"` in scenario files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "GasLimitRepr", into = "GasLimitRepr")]
+pub enum GasLimit {
+    /// Estimate with `eth_estimateGas`; see [`GasEstimateConfig`].
+    #[default]
+    Auto,
+    Fixed(u64),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum GasLimitRepr {
+    Number(u64),
+    String(String),
+}
+
+impl TryFrom<GasLimitRepr> for GasLimit {
+    type Error = String;
+
+    fn try_from(repr: GasLimitRepr) -> std::result::Result<Self, Self::Error> {
+        match repr {
+            GasLimitRepr::Number(n) => Ok(GasLimit::Fixed(n)),
+            GasLimitRepr::String(s) if s.eq_ignore_ascii_case("auto") => Ok(GasLimit::Auto),
+            GasLimitRepr::String(s) => s
+                .parse::<u64>()
+                .map(GasLimit::Fixed)
+                .map_err(|_| format!("invalid gas_limit '{}'; expected a number or \"auto\"", s)),
+        }
+    }
+}
+
+impl From<GasLimit> for GasLimitRepr {
+    fn from(gas_limit: GasLimit) -> Self {
+        match gas_limit {
+            GasLimit::Auto => GasLimitRepr::String("auto".to_owned()),
+            GasLimit::Fixed(n) => GasLimitRepr::Number(n),
+        }
+    }
+}
+
+/// How gas limits are set for txs with `gas_limit = "auto"`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GasEstimateConfig {
+    /// Estimates are multiplied by this, to leave headroom for calls whose gas usage varies
+    /// (e.g. with fuzzed args).
+    pub multiplier: f64,
+    /// Gas limit to use when estimation fails. If unset, failed estimates are errors.
+    pub fallback_gas_limit: Option<u64>,
+    /// Estimate each call shape (function & contract) once instead of each unique calldata, so
+    /// fuzzed calls don't each need an `eth_estimateGas` call. `multiplier` must leave enough
+    /// headroom for the costliest args.
+    pub per_call_shape: bool,
+}
+
+impl Default for GasEstimateConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: 1.0,
+            fallback_gas_limit: None,
+            per_call_shape: false,
+        }
+    }
+}
+
+impl GasEstimateConfig {
+    /// Returns the gas limit to use given the result of `eth_estimateGas`.
+    pub fn gas_limit<E>(
+        &self,
+        estimate: std::result::Result<u128, E>,
+    ) -> std::result::Result<u128, E> {
+        match estimate {
+            Ok(gas) => Ok((gas as f64 * self.multiplier).ceil() as u128),
+            Err(e) => self.fallback_gas_limit.map(|gas| gas as u128).ok_or(e),
+        }
+    }
+}
+
+/// How spam txs with `from_pool` are assigned senders from the pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SenderAssignment {
+    /// Each step's txs cycle through the pool's accounts in order.
+    #[default]
+    RoundRobin,
+    /// Each tx gets an account picked at random (from the scenario's seed).
+    Random,
+    /// Each step sends all of its txs from one account, picked at random (from the scenario's seed).
+    Sticky,
 }
 
 /// User-facing definition of a function call to be executed.
@@ -54,6 +322,10 @@ pub struct FunctionCallDefinitionStrict {
 pub struct BundleCallDefinition {
     #[serde(rename = "tx")]
     pub txs: Vec<FunctionCallDefinition>,
+    /// Send rate of the spam step, instead of an even split of the global rate.
+    pub rate: Option<StepRate>,
+    /// Share of the run's spam txs generated from the step, relative to other steps' weights.
+    pub weight: Option<f64>,
 }
 
 /// Definition of a spam request template.
@@ -64,12 +336,210 @@ pub enum SpamRequest {
     Tx(FunctionCallDefinition),
     #[serde(rename = "bundle")]
     Bundle(BundleCallDefinition),
+    #[serde(rename = "transfer")]
+    Transfer(TransferDefinition),
+}
+
+impl SpamRequest {
+    /// The step's own send rate, if it sets one.
+    pub fn rate(&self) -> Option<StepRate> {
+        match self {
+            SpamRequest::Tx(tx) => tx.rate,
+            SpamRequest::Bundle(bundle) => bundle.rate,
+            SpamRequest::Transfer(transfer) => transfer.rate,
+        }
+    }
+
+    /// The step's weight in the run's mix of spam txs; 1 if it doesn't set one.
+    pub fn weight(&self) -> f64 {
+        match self {
+            SpamRequest::Tx(tx) => tx.weight,
+            SpamRequest::Bundle(bundle) => bundle.weight,
+            SpamRequest::Transfer(transfer) => transfer.weight,
+        }
+        .unwrap_or(1.0)
+    }
+}
+
+/// Send rate of a spam step. A period is a block or a second, depending on the spammer.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepRate {
+    /// Txs per period, regardless of the global rate. May be fractional, e.g. `0.1` sends a tx
+    /// every 10 periods.
+    PerPeriod(f64),
+    /// Share of the global rate left over by `per_period` steps, relative to other steps' shares.
+    /// Steps without a rate have a share of 1.
+    Share(f64),
+}
+
+/// A stage of a scenario's traffic profile, e.g. a warmup, peak, or cooldown.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Phase {
+    /// Name of the phase, logged when it starts.
+    pub name: Option<String>,
+    /// Number of periods (seconds or blocks, depending on the spammer) the phase lasts.
+    pub duration: usize,
+    /// Txs per period, split between the phase's steps by their `rate`s.
+    pub rate: usize,
+    /// Indices of the spam steps sent during the phase, in the order they're defined, from 0.
+    /// All steps are sent if not set.
+    pub steps: Option<Vec<usize>>,
+}
+
+/// Inclusion-latency target for txs of one kind, checked against a run's txs once it's done.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LatencySlo {
+    /// Kind of the txs the target applies to.
+    pub kind: String,
+    /// Max blocks from the block a tx was sent in to the one it landed in.
+    pub max_blocks: Option<u64>,
+    /// Max milliseconds from sending a tx to seeing it land.
+    pub max_ms: Option<u64>,
+    /// Percent of the kind's txs that must meet the target. Defaults to 100.
+    pub percentile: Option<f64>,
+}
+
+/// User-facing definition of a plain transfer; no calldata is ABI-encoded.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct TransferDefinition {
+    /// Recipient address, or a list of addresses to spread transfers across.
+    pub to: Option<CallTarget>,
+    /// Send to accounts from this pool instead of `to`, cycling through the pool.
+    pub to_pool: Option<String>,
+    /// How transfers pick an address when `to` lists several. Defaults to round-robin.
+    pub to_selection: Option<TargetSelection>,
+    /// Address of the tx sender.
+    pub from: Option<String>,
+    /// Get a `from` address from the pool of signers specified here.
+    pub from_pool: Option<String>,
+    /// Value in wei to send.
+    pub value: Option<String>,
+    /// Set `value = true` on a fuzz param to fuzz the value sent.
+    pub fuzz: Option<Vec<FuzzParam>>,
+    /// Raw hex calldata to attach to the tx, sent as-is.
+    pub data: Option<String>,
+    /// Optional type of the spam transaction for categorization.
+    pub kind: Option<String>,
+    /// Gas limit for the tx; a number, or `"auto"` (default) to estimate it.
+    pub gas_limit: Option<GasLimit>,
+    /// Max fee per gas (in wei). Derived from the node's gas price if not set.
+    pub max_fee_per_gas: Option<u64>,
+    /// Max priority fee per gas (in wei). Derived from the node's gas price if not set.
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Send rate of the spam step, instead of an even split of the global rate.
+    pub rate: Option<StepRate>,
+    /// Share of the run's spam txs generated from the step, relative to other steps' weights.
+    pub weight: Option<f64>,
+}
+
+/// Definition of a read RPC sent repeatedly by a read-only load test.
+/// TestConfig uses this for TOML parsing.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub enum ReadRequest {
+    /// `eth_call` to a view function.
+    #[serde(rename = "call")]
+    Call(ReadCallDefinition),
+    /// `eth_getLogs` over a random range of recent blocks.
+    #[serde(rename = "logs")]
+    Logs(ReadLogsDefinition),
+    /// `eth_getBalance` of an address.
+    #[serde(rename = "balance")]
+    Balance(ReadBalanceDefinition),
+    /// `eth_getBlockByNumber` of a random recent block.
+    #[serde(rename = "block")]
+    Block(ReadBlockDefinition),
+    /// `eth_estimateGas` of a contract call.
+    #[serde(rename = "estimate_gas")]
+    EstimateGas(ReadCallDefinition),
+}
+
+/// User-facing definition of a view function call.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ReadCallDefinition {
+    /// Address of the contract to call.
+    pub to: String,
+    /// Name of the function to call.
+    pub signature: String,
+    /// Parameters to pass to the function.
+    pub args: Option<Vec<String>>,
+}
+
+/// User-facing definition of a log query. Each query covers a random range of up to `max_range`
+/// blocks within the latest `lookback` blocks.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ReadLogsDefinition {
+    /// Only return logs emitted by this address.
+    pub address: Option<String>,
+    /// Only return logs of this event, e.g. `"Transfer(address,address,uint256)"`.
+    pub event: Option<String>,
+    /// Max number of blocks covered by a query. Defaults to 100.
+    pub max_range: Option<u64>,
+    /// Number of recent blocks to query. Defaults to 10,000.
+    pub lookback: Option<u64>,
+}
+
+/// User-facing definition of a balance query.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ReadBalanceDefinition {
+    /// Address to get the balance of.
+    pub address: String,
+}
+
+/// User-facing definition of a block query. Each query fetches a random block within the latest
+/// `lookback` blocks.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ReadBlockDefinition {
+    /// Number of recent blocks to query. Defaults to 100.
+    pub lookback: Option<u64>,
+    /// Return full transactions instead of their hashes. Defaults to false.
+    pub full_txs: Option<bool>,
+}
+
+/// A read RPC with its placeholders & aliases resolved.
+#[derive(Clone, Debug)]
+pub enum ReadRequestStrict {
+    Call(TransactionRequest),
+    Logs {
+        filter: Filter,
+        max_range: u64,
+        lookback: u64,
+    },
+    Balance(Address),
+    Block {
+        /// Block to fetch; set when the request is sampled.
+        number: u64,
+        lookback: u64,
+        full_txs: bool,
+    },
+    EstimateGas(TransactionRequest),
+}
+
+impl ReadRequestStrict {
+    /// Name of the RPC method used to send the request.
+    pub fn method(&self) -> &'static str {
+        match self {
+            ReadRequestStrict::Call(_) => "eth_call",
+            ReadRequestStrict::Logs { .. } => "eth_getLogs",
+            ReadRequestStrict::Balance(_) => "eth_getBalance",
+            ReadRequestStrict::Block { .. } => "eth_getBlockByNumber",
+            ReadRequestStrict::EstimateGas(_) => "eth_estimateGas",
+        }
+    }
 }
 
 #[derive(Clone, Deserialize, Debug, Serialize)]
 pub struct CreateDefinition {
-    /// Bytecode of the contract to deploy.
+    /// Bytecode of the contract to deploy. Filled in by compiling `source` if it's set.
+    #[serde(default)]
     pub bytecode: String,
+    /// Path to a Solidity file to compile the contract from, relative to the scenario file.
+    pub source: Option<String>,
+    /// Name of the contract in `source` to deploy.
+    pub contract: Option<String>,
+    /// JSON ABI of the contract, saved in the contract registry. Filled in from the compiled
+    /// artifact if `source` is set.
+    pub abi: Option<String>,
     /// Name to identify the contract later.
     pub name: String,
     /// Address of the tx sender.
@@ -104,10 +574,79 @@ pub struct Plan {
     pub spam_steps: Vec<ExecutionRequest>,
 }
 
-pub type CallbackResult = crate::Result<Option<JoinHandle<()>>>;
+/// A plan callback may spawn a task for each tx, which the generator waits on; an error from the
+/// task fails the plan.
+pub type CallbackResult = crate::Result<Option<JoinHandle<crate::Result<()>>>>;
 
 pub enum PlanType<F: Fn(NamedTxRequest) -> CallbackResult> {
     Create(F),
     Setup(F),
     Spam(usize, F),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Step {
+        gas_limit: Option<GasLimit>,
+    }
+
+    #[test]
+    fn parses_gas_limits() {
+        let parse = |json: &str| serde_json::from_str::<Step>(json).unwrap().gas_limit;
+        assert_eq!(parse(r#"{"gas_limit": "auto"}"#), Some(GasLimit::Auto));
+        assert_eq!(
+            parse(r#"{"gas_limit": 100000}"#),
+            Some(GasLimit::Fixed(100_000))
+        );
+        assert_eq!(
+            parse(r#"{"gas_limit": "21000"}"#),
+            Some(GasLimit::Fixed(21_000))
+        );
+        assert_eq!(parse("{}"), None);
+        assert!(serde_json::from_str::<Step>(r#"{"gas_limit": "lots"}"#).is_err());
+    }
+
+    #[test]
+    fn matches_expected_status() {
+        assert!(ExpectStatus::Success.matches(true));
+        assert!(!ExpectStatus::Success.matches(false));
+        assert!(ExpectStatus::Revert.matches(false));
+        assert!(!ExpectStatus::Revert.matches(true));
+        for status in [ExpectStatus::Success, ExpectStatus::Revert] {
+            assert_eq!(status.as_str().parse::<ExpectStatus>(), Ok(status));
+        }
+        assert!("reverted".parse::<ExpectStatus>().is_err());
+    }
+
+    #[test]
+    fn parses_call_targets() {
+        #[derive(Deserialize)]
+        struct Call {
+            to: CallTarget,
+        }
+        let parse = |json: &str| serde_json::from_str::<Call>(json).unwrap().to;
+        assert_eq!(parse(r#"{"to": "{pool1}"}"#), CallTarget::from("{pool1}"));
+        let to = parse(r#"{"to": ["{pool1}", "{pool2}"]}"#);
+        assert_eq!(to.targets(), ["{pool1}", "{pool2}"]);
+    }
+
+    #[test]
+    fn applies_gas_multiplier_and_fallback() {
+        let config = GasEstimateConfig {
+            multiplier: 1.5,
+            ..Default::default()
+        };
+        assert_eq!(config.gas_limit::<()>(Ok(21_000)), Ok(31_500));
+        assert_eq!(config.gas_limit::<()>(Err(())), Err(()));
+
+        let config = GasEstimateConfig {
+            fallback_gas_limit: Some(500_000),
+            ..Default::default()
+        };
+        assert_eq!(config.gas_limit::<()>(Ok(21_000)), Ok(21_000));
+        assert_eq!(config.gas_limit::<()>(Err(())), Ok(500_000));
+    }
+}
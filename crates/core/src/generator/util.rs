@@ -1,8 +1,10 @@
 use crate::{error::ContenderError, Result};
 use alloy::{
     dyn_abi::{DynSolType, DynSolValue, JsonAbiExt},
-    json_abi,
+    json_abi::{self, Param},
+    primitives::Address,
 };
+use std::ops::Range;
 
 /// Encode the calldata for a function signature given an array of string arguments.
 ///
@@ -41,6 +43,123 @@ pub fn encode_calldata(args: &[impl AsRef<str>], sig: &str) -> Result<Vec<u8>> {
     Ok(input)
 }
 
+/// Returns the address from the address book if `input` is an `@alias` reference: an `@`
+/// followed by alphanumeric/underscore characters, and nothing else. Returns `None` for any other
+/// value, including ones which merely contain an `@`.
+///
+/// ## Example
+/// ```
+/// use contender_core::generator::util::resolve_alias;
+/// use alloy::primitives::Address;
+///
+/// let weth = Address::repeat_byte(0x11);
+/// let lookup = |name: &str| Ok((name == "weth").then_some(weth));
+/// assert_eq!(resolve_alias("@weth", lookup).unwrap(), Some(weth));
+/// assert!(resolve_alias("@dai", lookup).is_err());
+/// // only whole values are aliases
+/// assert_eq!(resolve_alias("0x00@weth", lookup).unwrap(), None);
+/// assert_eq!(resolve_alias("@weth.eth", lookup).unwrap(), None);
+/// assert_eq!(resolve_alias("@", lookup).unwrap(), None);
+/// ```
+pub fn resolve_alias(
+    input: &str,
+    lookup: impl Fn(&str) -> Result<Option<Address>>,
+) -> Result<Option<Address>> {
+    let Some(name) = input.strip_prefix('@') else {
+        return Ok(None);
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Ok(None);
+    }
+    lookup(name)?
+        .ok_or(ContenderError::SpamError(
+            "alias not found in address book. Add it with `contender alias add`.",
+            Some(name.to_owned()),
+        ))
+        .map(Some)
+}
+
+/// Returns the ranges of `input`'s comma-separated items, ignoring commas nested in parentheses
+/// or brackets. Ranges exclude surrounding whitespace.
+fn top_level_items(input: &str) -> Vec<Range<usize>> {
+    if input.trim().is_empty() {
+        return vec![];
+    }
+    let trimmed = |range: Range<usize>| {
+        let item = &input[range.to_owned()];
+        let start = range.start + item.len() - item.trim_start().len();
+        let end = range.end - (item.len() - item.trim_end().len());
+        start..end.max(start)
+    };
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(trimmed(start..i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(trimmed(start..input.len()));
+    items
+}
+
+/// Splits `input` on commas which aren't nested in parentheses or brackets.
+///
+/// ## Example
+/// ```
+/// use contender_core::generator::util::split_top_level;
+///
+/// assert_eq!(split_top_level("(0x11, 5), [1,2], 7"), vec!["(0x11, 5)", "[1,2]", "7"]);
+/// ```
+pub fn split_top_level(input: &str) -> Vec<&str> {
+    top_level_items(input)
+        .into_iter()
+        .map(|range| &input[range])
+        .collect()
+}
+
+/// Returns the range of the field at `path` (component names, outermost first) in `value`,
+/// a tuple arg with the given `components`. Returns `None` if the path or value doesn't fit.
+///
+/// ## Example
+/// ```
+/// use alloy::json_abi::Function;
+/// use contender_core::generator::util::tuple_field_range;
+///
+/// let func = Function::parse("f(((address token, uint256 amount) permitted, uint256 nonce) permit)").unwrap();
+/// let arg = "((0x1111111111111111111111111111111111111111, 100), 5)";
+/// let components = &func.inputs[0].components;
+/// let range = tuple_field_range(arg, components, &["permitted", "amount"]).unwrap();
+/// assert_eq!(&arg[range], "100");
+/// let range = tuple_field_range(arg, components, &["nonce"]).unwrap();
+/// assert_eq!(&arg[range], "5");
+/// ```
+pub fn tuple_field_range(value: &str, components: &[Param], path: &[&str]) -> Option<Range<usize>> {
+    let (name, rest) = path.split_first()?;
+    let idx = components.iter().position(|c| c.name == *name)?;
+    let open = value.find('(')?;
+    let close = value.rfind(')')?;
+    if close < open || !value[..open].trim().is_empty() || !value[close + 1..].trim().is_empty() {
+        return None;
+    }
+    let items = top_level_items(&value[open + 1..close]);
+    if items.len() != components.len() {
+        return None;
+    }
+    let item = items[idx].start + open + 1..items[idx].end + open + 1;
+    if rest.is_empty() {
+        return Some(item);
+    }
+    let inner = tuple_field_range(&value[item.to_owned()], &components[idx].components, rest)?;
+    Some(item.start + inner.start..item.start + inner.end)
+}
+
 #[cfg(test)]
 pub mod test {
     use alloy::node_bindings::{Anvil, AnvilInstance};
@@ -0,0 +1,290 @@
+use crate::{
+    error::ContenderError,
+    generator::types::{AnyProvider, EthProvider},
+    Result,
+};
+use alloy::{
+    providers::RootProvider,
+    rpc::client::{ClientBuilder, RpcClient, WsConnect},
+    transports::{http::reqwest::Url, ipc::IpcConnect, BoxTransport},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Connects to the JSON-RPC endpoint at `url`, picking the transport by its scheme: `ws://` &
+/// `wss://` for WebSocket, `ipc://` for a node's IPC socket on this machine (e.g.
+/// `ipc:///tmp/geth.ipc`), and HTTP otherwise. WebSocket & IPC clients hold one persistent
+/// connection, shared by every provider built from the client.
+pub async fn connect(url: &Url) -> Result<RpcClient<BoxTransport>> {
+    let builder = ClientBuilder::default();
+    let client = match url.scheme() {
+        "ws" | "wss" => builder
+            .ws(WsConnect::new(url.as_str()))
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to connect to WebSocket RPC"))?
+            .boxed(),
+        "ipc" => builder
+            .ipc(IpcConnect::new(ipc_path(url)))
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to connect to IPC socket"))?
+            .boxed(),
+        _ => builder.http(url.to_owned()).boxed(),
+    };
+    Ok(client)
+}
+
+/// Connects a provider for any network to `url`; see [`connect`].
+pub async fn any_provider(url: &Url) -> Result<AnyProvider> {
+    Ok(RootProvider::new(connect(url).await?))
+}
+
+/// Connects an Ethereum provider to `url`; see [`connect`].
+pub async fn eth_provider(url: &Url) -> Result<EthProvider> {
+    Ok(RootProvider::new(connect(url).await?))
+}
+
+/// How requests are spread across the endpoints of an [`EndpointRouter`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Routing {
+    /// Cycle through the endpoints in order.
+    #[default]
+    RoundRobin,
+    /// Send to the endpoint with the lowest average response time so far. Endpoints which
+    /// haven't responded yet are tried first, spread evenly until they have.
+    LeastLatency,
+}
+
+impl std::str::FromStr for Routing {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "round-robin" | "round_robin" => Ok(Routing::RoundRobin),
+            "least-latency" | "least_latency" => Ok(Routing::LeastLatency),
+            _ => Err(format!(
+                "invalid routing '{}'; expected \"round-robin\" or \"least-latency\"",
+                s
+            )),
+        }
+    }
+}
+
+/// An RPC endpoint requests can be routed to, with its response times so far.
+#[derive(Debug)]
+struct Endpoint {
+    url: Url,
+    client: Arc<AnyProvider>,
+    num_requests: AtomicU64,
+    /// Moving average of the endpoint's response time in microseconds; 0 until it's measured.
+    avg_latency_us: AtomicU64,
+}
+
+/// Response time recorded for a failed request, so endpoints which error are avoided like slow
+/// ones.
+const ERROR_PENALTY: Duration = Duration::from_secs(10);
+
+/// Per-endpoint request counts & response times of an [`EndpointRouter`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndpointStats {
+    pub url: Url,
+    pub num_requests: u64,
+    pub avg_latency_ms: f64,
+}
+
+/// Spreads requests across several RPC endpoints serving the same chain, e.g. the nodes of a
+/// load-balanced sequencer cluster.
+#[derive(Debug)]
+pub struct EndpointRouter {
+    endpoints: Vec<Endpoint>,
+    routing: Routing,
+    next: AtomicUsize,
+}
+
+impl EndpointRouter {
+    /// Routes between `endpoints`, which must not be empty.
+    pub fn new(endpoints: Vec<(Url, Arc<AnyProvider>)>, routing: Routing) -> Self {
+        assert!(!endpoints.is_empty(), "router needs at least one endpoint");
+        Self {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(url, client)| Endpoint {
+                    url,
+                    client,
+                    num_requests: AtomicU64::new(0),
+                    avg_latency_us: AtomicU64::new(0),
+                })
+                .collect(),
+            routing,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Connects to each of `urls`; see [`connect`].
+    pub async fn connect(urls: &[Url], routing: Routing) -> Result<Self> {
+        let mut endpoints = vec![];
+        for url in urls {
+            endpoints.push((url.to_owned(), Arc::new(any_provider(url).await?)));
+        }
+        Ok(Self::new(endpoints, routing))
+    }
+
+    /// Picks the endpoint for the next request, returning its index (for
+    /// [`EndpointRouter::record_latency`] & [`EndpointRouter::record_error`]) & client.
+    pub fn pick(&self) -> (usize, Arc<AnyProvider>) {
+        let idx = match self.routing {
+            Routing::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % self.endpoints.len(),
+            Routing::LeastLatency => self
+                .endpoints
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, endpoint)| {
+                    let latency = endpoint.avg_latency_us.load(Ordering::Relaxed);
+                    // unmeasured endpoints go first, so each one gets measured; requests are
+                    // picked before earlier ones respond, so spread them across those endpoints
+                    match latency {
+                        0 => (false, endpoint.num_requests.load(Ordering::Relaxed)),
+                        latency => (true, latency),
+                    }
+                })
+                .map(|(idx, _)| idx)
+                .unwrap_or_default(),
+        };
+        let endpoint = &self.endpoints[idx];
+        endpoint.num_requests.fetch_add(1, Ordering::Relaxed);
+        (idx, endpoint.client.clone())
+    }
+
+    /// Records how long the endpoint at `idx` took to respond to a request.
+    pub fn record_latency(&self, idx: usize, elapsed: Duration) {
+        let sample = (elapsed.as_micros() as u64).max(1);
+        let _ = self.endpoints[idx].avg_latency_us.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |avg| match avg {
+                0 => Some(sample),
+                // weight recent responses, so the router follows endpoints that slow down
+                avg => Some((avg * 7 + sample) / 8),
+            },
+        );
+    }
+
+    /// Records a failed request to the endpoint at `idx`, counted as a very slow response.
+    pub fn record_error(&self, idx: usize) {
+        self.record_latency(idx, ERROR_PENALTY);
+    }
+
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| EndpointStats {
+                url: endpoint.url.to_owned(),
+                num_requests: endpoint.num_requests.load(Ordering::Relaxed),
+                avg_latency_ms: endpoint.avg_latency_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            })
+            .collect()
+    }
+}
+
+/// Path of the socket an `ipc://` URL points to. Relative paths (`ipc://geth.ipc`) are resolved
+/// against the working directory.
+fn ipc_path(url: &Url) -> PathBuf {
+    PathBuf::from(url.as_str().trim_start_matches("ipc://"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{node_bindings::Anvil, providers::Provider};
+
+    #[test]
+    fn parses_ipc_paths() {
+        let url = Url::parse("ipc:///tmp/geth.ipc").unwrap();
+        assert_eq!(ipc_path(&url), PathBuf::from("/tmp/geth.ipc"));
+        let url = Url::parse("ipc://data/reth.ipc").unwrap();
+        assert_eq!(ipc_path(&url), PathBuf::from("data/reth.ipc"));
+    }
+
+    fn router(num_endpoints: usize, routing: Routing) -> EndpointRouter {
+        let endpoints = (0..num_endpoints)
+            .map(|i| {
+                let url = Url::parse(&format!("http://localhost:{}", 8545 + i)).unwrap();
+                let client = Arc::new(RootProvider::new(
+                    ClientBuilder::default().http(url.clone()).boxed(),
+                ));
+                (url, client)
+            })
+            .collect();
+        EndpointRouter::new(endpoints, routing)
+    }
+
+    #[test]
+    fn routes_round_robin() {
+        let router = router(3, Routing::RoundRobin);
+        let picks = (0..5).map(|_| router.pick().0).collect::<Vec<_>>();
+        assert_eq!(picks, vec![0, 1, 2, 0, 1]);
+        assert_eq!(router.stats()[0].num_requests, 2);
+        assert_eq!(router.stats()[2].num_requests, 1);
+    }
+
+    #[test]
+    fn routes_to_least_latency() {
+        let router = router(3, Routing::LeastLatency);
+        router.record_latency(0, Duration::from_millis(30));
+        router.record_latency(1, Duration::from_millis(10));
+        // endpoint 2 hasn't been measured yet
+        assert_eq!(router.pick().0, 2);
+        router.record_latency(2, Duration::from_millis(20));
+        assert_eq!(router.pick().0, 1);
+        // endpoint 1 slows down
+        for _ in 0..10 {
+            router.record_latency(1, Duration::from_millis(100));
+        }
+        assert_eq!(router.pick().0, 2);
+        assert_eq!(router.stats()[2].avg_latency_ms, 20.0);
+    }
+
+    #[test]
+    fn spreads_requests_across_unmeasured_endpoints() {
+        let router = router(3, Routing::LeastLatency);
+        // nothing has responded yet, e.g. the first batch of a spam run
+        let picks = (0..6).map(|_| router.pick().0).collect::<Vec<_>>();
+        assert_eq!(picks, vec![0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn routes_away_from_erroring_endpoints() {
+        let router = router(2, Routing::LeastLatency);
+        router.record_latency(0, Duration::from_millis(10));
+        router.record_latency(1, Duration::from_millis(50));
+        assert_eq!(router.pick().0, 0);
+        // endpoint 0 starts rejecting requests
+        router.record_error(0);
+        assert_eq!(router.pick().0, 1);
+        assert!(router.stats()[0].avg_latency_ms > router.stats()[1].avg_latency_ms);
+    }
+
+    #[tokio::test]
+    async fn connects_over_http_and_ipc() {
+        let socket = std::env::temp_dir().join(format!("contender-{}.ipc", std::process::id()));
+        let anvil = Anvil::new()
+            .arg("--ipc")
+            .arg(socket.to_string_lossy())
+            .try_spawn()
+            .unwrap();
+        let http = any_provider(&anvil.endpoint_url()).await.unwrap();
+        let ipc_url = Url::parse(&format!("ipc://{}", socket.display())).unwrap();
+        let ipc = eth_provider(&ipc_url).await.unwrap();
+        assert_eq!(
+            http.get_chain_id().await.unwrap(),
+            ipc.get_chain_id().await.unwrap()
+        );
+    }
+}
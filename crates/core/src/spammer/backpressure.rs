@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use alloy::{primitives::U64, providers::Provider};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::types::AnyProvider;
+
+/// How often the pending pool is polled while spamming is paused.
+pub const BACKPRESSURE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Result of `txpool_status`.
+#[derive(Debug, Deserialize)]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+}
+
+/// Pauses spamming while too many txs are pending, so the spammer doesn't overrun the node's
+/// mempool limits (and have txs dropped) faster than blocks can include them.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Backpressure {
+    /// Pause once more than this many txs are pending.
+    pub max_pending: u64,
+    /// Resume once the pending txs have drained to this many.
+    pub resume_pending: u64,
+}
+
+impl Backpressure {
+    /// Pauses above `max_pending` txs and resumes at 3/4 of it, so spamming doesn't flap on and
+    /// off right at the threshold.
+    pub fn new(max_pending: u64) -> Self {
+        Self {
+            max_pending,
+            resume_pending: max_pending * 3 / 4,
+        }
+    }
+
+    /// Whether spamming should pause (or stay paused, if it's `paused` already) with `pending`
+    /// txs in the pool.
+    pub fn should_pause(&self, pending: u64, paused: bool) -> bool {
+        if paused {
+            pending > self.resume_pending
+        } else {
+            pending > self.max_pending
+        }
+    }
+}
+
+/// Number of txs in the node's pending pool, or `None` if it doesn't support `txpool_status`.
+pub async fn txpool_pending(rpc_client: &AnyProvider) -> Option<u64> {
+    rpc_client
+        .raw_request::<_, TxpoolStatus>("txpool_status".into(), ())
+        .await
+        .ok()
+        .map(|status| status.pending.to::<u64>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_above_max_and_resumes_below_threshold() {
+        let backpressure = Backpressure::new(1000);
+        assert_eq!(backpressure.resume_pending, 750);
+        assert!(!backpressure.should_pause(1000, false));
+        assert!(backpressure.should_pause(1001, false));
+        // stays paused until the pool drains well below the max
+        assert!(backpressure.should_pause(900, true));
+        assert!(!backpressure.should_pause(750, true));
+    }
+}
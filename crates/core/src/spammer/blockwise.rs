@@ -67,7 +67,9 @@ mod tests {
     #[tokio::test]
     async fn watches_blocks_and_spams_them() {
         let anvil = spawn_anvil();
-        let provider = ProviderBuilder::new().on_http(anvil.endpoint_url().to_owned());
+        let provider = ProviderBuilder::new()
+            .on_http(anvil.endpoint_url().to_owned())
+            .boxed();
         println!("anvil url: {}", anvil.endpoint_url());
         let seed = crate::generator::RandSeed::seed_from_str("444444444444");
         let mut agents = AgentStore::new();
@@ -0,0 +1,59 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// How often a paused spammer checks whether it's been resumed.
+pub const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Pauses & resumes a running spammer from outside the spam loop, e.g. from a signal handler.
+/// While paused, no new txs are sent, but txs already sent keep being tracked.
+#[derive(Debug, Default)]
+pub struct SpamControl {
+    paused: AtomicBool,
+}
+
+impl SpamControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns false if the spammer was already paused.
+    pub fn pause(&self) -> bool {
+        !self.paused.swap(true, Ordering::Relaxed)
+    }
+
+    /// Returns false if the spammer wasn't paused.
+    pub fn resume(&self) -> bool {
+        self.paused.swap(false, Ordering::Relaxed)
+    }
+
+    /// Pauses a running spammer, or resumes a paused one. Returns whether it's now paused.
+    pub fn toggle(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_and_resumes() {
+        let control = SpamControl::new();
+        assert!(!control.is_paused());
+        assert!(control.pause());
+        assert!(!control.pause());
+        assert!(control.is_paused());
+        assert!(control.resume());
+        assert!(!control.resume());
+        assert!(control.toggle());
+        assert!(control.is_paused());
+        assert!(!control.toggle());
+        assert!(!control.is_paused());
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    primitives::{Address, TxHash},
+    rpc::types::TransactionRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::generator::NamedTxRequest;
+
+/// Re-broadcasts spam txs which have been pending for too long, with bumped fees at the same nonce.
+/// Off unless set on the scenario; see [`TestScenario::fee_bump`](crate::test_scenario::TestScenario::fee_bump).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct FeeBumpPolicy {
+    /// Time a tx may stay pending before it's re-broadcast.
+    pub pending_tx_timeout_secs: u64,
+    /// Number of times a tx is re-broadcast before it's declared failed.
+    pub max_bumps: u32,
+    /// Percentage added to both fees on each bump. Most clients reject replacements below 10%.
+    pub bump_percent: u64,
+}
+
+impl Default for FeeBumpPolicy {
+    fn default() -> Self {
+        Self {
+            pending_tx_timeout_secs: 12,
+            max_bumps: 3,
+            bump_percent: 10,
+        }
+    }
+}
+
+impl FeeBumpPolicy {
+    /// Returns (max fee, max priority fee) raised by `bump_percent`, rounded up so each bump
+    /// meets the client's replacement threshold.
+    pub fn bump_fees(&self, max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> (u128, u128) {
+        let bump = |fee: u128| (fee * (100 + self.bump_percent as u128)).div_ceil(100);
+        (bump(max_fee_per_gas), bump(max_priority_fee_per_gas))
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.pending_tx_timeout_secs)
+    }
+}
+
+/// A signed spam tx which may need to be re-broadcast.
+#[derive(Debug, Clone)]
+pub struct PendingSpamTx {
+    pub tx_hash: TxHash,
+    /// The signed request, including nonce, fees & gas limit.
+    pub tx_req: TransactionRequest,
+    pub req: NamedTxRequest,
+    /// When the first version of this tx was sent, in milliseconds since the epoch.
+    pub start_timestamp: u128,
+    /// When this version of the tx was sent.
+    pub sent_at: Instant,
+    pub num_bumps: u32,
+}
+
+impl PendingSpamTx {
+    /// Returns the sender & nonce of the tx, if set.
+    pub fn sender_nonce(&self) -> Option<(Address, u64)> {
+        Some((self.tx_req.from?, self.tx_req.nonce?))
+    }
+}
+
+/// Spam txs sent with a [`FeeBumpPolicy`], tracked until they land or are declared failed.
+#[derive(Debug, Default)]
+pub struct PendingSpamTxs {
+    txs: HashMap<TxHash, PendingSpamTx>,
+    /// When the senders' nonces were last checked for landed txs.
+    nonces_checked_at: Option<Instant>,
+}
+
+impl PendingSpamTxs {
+    pub fn insert(&mut self, tx: PendingSpamTx) {
+        self.txs.insert(tx.tx_hash, tx);
+    }
+
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    /// Senders of all tracked txs.
+    pub fn senders(&self) -> Vec<Address> {
+        let mut senders = self
+            .txs
+            .values()
+            .filter_map(|tx| tx.tx_req.from)
+            .collect::<Vec<_>>();
+        senders.sort();
+        senders.dedup();
+        senders
+    }
+
+    /// Whether the senders' nonces are due to be checked for landed txs, which is at most once per
+    /// the policy's timeout (txs aren't bumped sooner), so each sender isn't polled on every tick.
+    /// Records the check if it's due.
+    pub fn take_nonce_check(&mut self, policy: &FeeBumpPolicy, now: Instant) -> bool {
+        let due = self.nonces_checked_at.map_or(true, |checked_at| {
+            now.duration_since(checked_at) >= policy.timeout()
+        });
+        if due {
+            self.nonces_checked_at = Some(now);
+        }
+        due
+    }
+
+    /// Stops tracking txs whose nonce is below their sender's confirmed tx count; they (or a
+    /// replacement) have landed.
+    pub fn remove_confirmed(&mut self, confirmed_nonces: &HashMap<Address, u64>) {
+        self.txs.retain(|_, tx| {
+            let Some((sender, nonce)) = tx.sender_nonce() else {
+                return false;
+            };
+            !confirmed_nonces
+                .get(&sender)
+                .is_some_and(|confirmed| nonce < *confirmed)
+        });
+    }
+
+    /// Removes & returns txs which have been pending longer than the policy's timeout.
+    pub fn take_stuck(&mut self, policy: &FeeBumpPolicy, now: Instant) -> Vec<PendingSpamTx> {
+        let stuck_hashes = self
+            .txs
+            .values()
+            .filter(|tx| now.duration_since(tx.sent_at) >= policy.timeout())
+            .map(|tx| tx.tx_hash)
+            .collect::<Vec<_>>();
+        let mut stuck = stuck_hashes
+            .iter()
+            .filter_map(|hash| self.txs.remove(hash))
+            .collect::<Vec<_>>();
+        stuck.sort_by_key(|tx| tx.sender_nonce());
+        stuck
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_tx(n: u8, sender: Address, nonce: u64, sent_at: Instant) -> PendingSpamTx {
+        let tx_req = TransactionRequest::default().from(sender).nonce(nonce);
+        PendingSpamTx {
+            tx_hash: TxHash::repeat_byte(n),
+            tx_req: tx_req.to_owned(),
+            req: NamedTxRequest::new(tx_req, None, None),
+            start_timestamp: 0,
+            sent_at,
+            num_bumps: 0,
+        }
+    }
+
+    #[test]
+    fn bumps_fees_by_percent_rounded_up() {
+        let policy = FeeBumpPolicy::default();
+        assert_eq!(policy.bump_fees(100, 10), (110, 11));
+        assert_eq!(policy.bump_fees(101, 1), (112, 2));
+    }
+
+    #[test]
+    fn takes_txs_pending_past_timeout() {
+        let policy = FeeBumpPolicy {
+            pending_tx_timeout_secs: 10,
+            ..Default::default()
+        };
+        let sender = Address::repeat_byte(1);
+        let now = Instant::now();
+        let mut pending = PendingSpamTxs::default();
+        pending.insert(pending_tx(1, sender, 1, now - Duration::from_secs(11)));
+        pending.insert(pending_tx(2, sender, 0, now - Duration::from_secs(10)));
+        pending.insert(pending_tx(3, sender, 2, now - Duration::from_secs(2)));
+
+        let stuck = pending.take_stuck(&policy, now);
+        assert_eq!(
+            stuck.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>(),
+            vec![TxHash::repeat_byte(2), TxHash::repeat_byte(1)]
+        );
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn checks_nonces_once_per_timeout() {
+        let policy = FeeBumpPolicy {
+            pending_tx_timeout_secs: 10,
+            ..Default::default()
+        };
+        let now = Instant::now();
+        let mut pending = PendingSpamTxs::default();
+        assert!(pending.take_nonce_check(&policy, now));
+        assert!(!pending.take_nonce_check(&policy, now + Duration::from_secs(9)));
+        assert!(pending.take_nonce_check(&policy, now + Duration::from_secs(10)));
+        assert!(!pending.take_nonce_check(&policy, now + Duration::from_secs(11)));
+    }
+
+    #[test]
+    fn removes_confirmed_txs() {
+        let sender = Address::repeat_byte(1);
+        let other_sender = Address::repeat_byte(2);
+        let now = Instant::now();
+        let mut pending = PendingSpamTxs::default();
+        pending.insert(pending_tx(1, sender, 0, now));
+        pending.insert(pending_tx(2, sender, 1, now));
+        pending.insert(pending_tx(3, other_sender, 0, now));
+
+        pending.remove_confirmed(&HashMap::from([(sender, 1)]));
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending.senders(), vec![sender, other_sender]);
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::db::LatencySnapshot;
+
+/// Collects RPC call latencies during a spam run.
+///
+/// Samples accumulate until [`LatencyRecorder::snapshot`] is called, which summarizes them into
+/// per-method percentiles and starts a new window. Taking a snapshot after every spam period
+/// produces a latency time series for the run.
+#[derive(Debug, Default)]
+pub struct LatencyRecorder {
+    /// Maps RPC method name to the latencies (in ms) recorded since the last snapshot.
+    samples: Mutex<BTreeMap<String, Vec<u64>>>,
+}
+
+impl LatencyRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, method: &str, elapsed: Duration) {
+        let mut samples = self.samples.lock().expect("latency samples lock poisoned");
+        samples
+            .entry(method.to_owned())
+            .or_default()
+            .push(elapsed.as_millis() as u64);
+    }
+
+    /// Awaits `fut`, recording how long it took under `method`.
+    pub async fn time<T>(&self, method: &str, fut: impl std::future::Future<Output = T>) -> T {
+        let start = Instant::now();
+        let res = fut.await;
+        self.record(method, start.elapsed());
+        res
+    }
+
+    /// Summarizes the samples recorded since the last snapshot, then clears them.
+    pub fn snapshot(&self) -> Vec<LatencySnapshot> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_millis() as u64;
+        let samples =
+            std::mem::take(&mut *self.samples.lock().expect("latency samples lock poisoned"));
+        samples
+            .into_iter()
+            .filter(|(_, latencies)| !latencies.is_empty())
+            .map(|(method, mut latencies)| {
+                latencies.sort_unstable();
+                LatencySnapshot {
+                    timestamp,
+                    method,
+                    p50_ms: percentile(&latencies, 50),
+                    p95_ms: percentile(&latencies, 95),
+                    count: latencies.len() as u64,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Nearest-rank percentile of `sorted`, which must be non-empty and sorted ascending.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_percentiles_per_method() {
+        let recorder = LatencyRecorder::new();
+        for ms in 1..=100 {
+            recorder.record("eth_sendRawTransaction", Duration::from_millis(ms));
+        }
+        recorder.record("eth_blockNumber", Duration::from_millis(7));
+
+        let snapshot = recorder.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].method, "eth_blockNumber");
+        assert_eq!(snapshot[0].p95_ms, 7);
+        assert_eq!(snapshot[1].method, "eth_sendRawTransaction");
+        assert_eq!(snapshot[1].p50_ms, 50);
+        assert_eq!(snapshot[1].p95_ms, 95);
+        assert_eq!(snapshot[1].count, 100);
+
+        // samples are cleared after each snapshot
+        assert!(recorder.snapshot().is_empty());
+    }
+}
@@ -1,14 +1,29 @@
+mod backpressure;
 pub mod blockwise;
+mod control;
+mod fee_bump;
+mod latency;
+mod ramp;
+mod read;
+mod schedule;
 mod spammer_trait;
+mod stats;
 pub mod timed;
 pub mod tx_actor;
 mod tx_callback;
 pub mod util;
 
 use crate::generator::NamedTxRequest;
-use alloy::{consensus::TxEnvelope, primitives::FixedBytes};
+use alloy::{consensus::TxEnvelope, primitives::FixedBytes, rpc::types::TransactionRequest};
+pub use backpressure::{Backpressure, TxpoolStatus};
 pub use blockwise::BlockwiseSpammer;
+pub use control::SpamControl;
+pub use fee_bump::{FeeBumpPolicy, PendingSpamTx, PendingSpamTxs};
+pub use latency::LatencyRecorder;
+pub use ramp::{Ramp, RampSpammer};
+pub use read::{ReadSpammer, ReadSummary};
 pub use spammer_trait::Spammer;
+pub use stats::{ErrorRateBreaker, ErrorRateLimit, SpamProgress, SpamStats};
 pub use timed::TimedSpammer;
 pub use tx_callback::{LogCallback, NilCallback, OnTxSent};
 
@@ -16,6 +31,8 @@ pub use tx_callback::{LogCallback, NilCallback, OnTxSent};
 pub enum ExecutionPayload {
     SignedTx(TxEnvelope, NamedTxRequest),
     SignedTxBundle(Vec<TxEnvelope>, Vec<NamedTxRequest>),
+    /// Tx to be signed by the node via `eth_sendTransaction`; see [`TestScenario::unlocked`](crate::test_scenario::TestScenario::unlocked).
+    UnsignedTx(TransactionRequest, NamedTxRequest),
 }
 
 #[derive(Clone, Copy, Debug)]
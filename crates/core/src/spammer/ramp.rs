@@ -0,0 +1,111 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::DbOps,
+    generator::{seeder::Seeder, templater::Templater, types::Phase, PlanConfig},
+    test_scenario::TestScenario,
+};
+
+use super::{OnTxSent, SpamTrigger, Spammer, TimedSpammer};
+
+/// Linear increase (or decrease) of the send rate over a run, in steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Ramp {
+    /// Txs per period sent during the first step.
+    pub start_rate: usize,
+    /// Txs per period sent during the last step.
+    pub end_rate: usize,
+    /// Number of periods each step lasts before the rate changes.
+    pub step_periods: usize,
+}
+
+impl Ramp {
+    /// Splits a run of `num_periods` periods into one phase per step, with rates evenly spaced
+    /// from `start_rate` to `end_rate`. The last step is cut short if the run ends mid-step.
+    pub fn phases(&self, num_periods: usize) -> Vec<Phase> {
+        let step_periods = self.step_periods.max(1);
+        let num_steps = num_periods.div_ceil(step_periods);
+        (0..num_steps)
+            .map(|step| {
+                let progress = if num_steps > 1 {
+                    step as f64 / (num_steps - 1) as f64
+                } else {
+                    0.0
+                };
+                let rate = self.start_rate as f64
+                    + (self.end_rate as f64 - self.start_rate as f64) * progress;
+                Phase {
+                    name: Some(format!("ramp step {}", step + 1)),
+                    duration: step_periods.min(num_periods - step * step_periods),
+                    rate: rate.round() as usize,
+                    steps: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Sends txs every `wait_interval` like [`TimedSpammer`], stepping the rate along a [`Ramp`], so a
+/// node's breaking point can be found in a single run.
+pub struct RampSpammer {
+    timed: TimedSpammer,
+    ramp: Ramp,
+}
+
+impl RampSpammer {
+    pub fn new(wait_interval: Duration, ramp: Ramp) -> Self {
+        Self {
+            timed: TimedSpammer::new(wait_interval),
+            ramp,
+        }
+    }
+}
+
+impl<F, D, S, P> Spammer<F, D, S, P> for RampSpammer
+where
+    F: OnTxSent + Send + Sync + 'static,
+    D: DbOps + Send + Sync + 'static,
+    S: Seeder + Send + Sync,
+    P: PlanConfig<String> + Templater<String> + Send + Sync,
+{
+    fn on_spam(
+        &self,
+        scenario: &mut TestScenario<D, S, P>,
+    ) -> impl std::future::Future<Output = crate::Result<Pin<Box<dyn Stream<Item = SpamTrigger> + Send>>>>
+    {
+        <TimedSpammer as Spammer<F, D, S, P>>::on_spam(&self.timed, scenario)
+    }
+
+    fn phases(&self, num_periods: usize) -> Vec<Phase> {
+        self.ramp.phases(num_periods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_rate_linearly() {
+        let ramp = Ramp {
+            start_rate: 50,
+            end_rate: 500,
+            step_periods: 30,
+        };
+        let phases = ramp.phases(100);
+        assert_eq!(
+            phases
+                .iter()
+                .map(|phase| (phase.rate, phase.duration))
+                .collect::<Vec<_>>(),
+            vec![(50, 30), (200, 30), (350, 30), (500, 10)]
+        );
+        assert_eq!(ramp.phases(10).len(), 1);
+        assert_eq!(ramp.phases(10)[0].rate, 50);
+        assert!(ramp.phases(0).is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use alloy::providers::Provider;
+use alloy::rpc::types::WithOtherFields;
+use alloy::transports::TransportResult;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    db::{DbOps, LatencySnapshot},
+    error::ContenderError,
+    generator::{
+        seeder::{SeedValue, Seeder},
+        templater::Templater,
+        types::{AnyProvider, ReadRequestStrict},
+        Generator, PlanConfig,
+    },
+    test_scenario::TestScenario,
+    Result,
+};
+
+use super::LatencyRecorder;
+
+/// Sends a scenario's `[[read]]` RPCs at a fixed rate, recording the latency of each call, to
+/// load-test an RPC provider's read capacity.
+pub struct ReadSpammer {
+    reads_per_second: usize,
+}
+
+/// Totals of a read-only load test, per RPC method.
+#[derive(Debug, Default)]
+pub struct ReadSummary {
+    /// Latency percentiles of successful calls over the whole run.
+    pub latency: Vec<LatencySnapshot>,
+    /// Number of failed calls.
+    pub errors: BTreeMap<String, u64>,
+}
+
+impl ReadSpammer {
+    pub fn new(reads_per_second: usize) -> Self {
+        Self { reads_per_second }
+    }
+
+    /// Sends reads for `num_seconds`, cycling through the scenario's read steps. If `run_id` is
+    /// set, per-second latency snapshots are saved to the run.
+    pub async fn spam_reads<D, S, P>(
+        &self,
+        scenario: &TestScenario<D, S, P>,
+        num_seconds: usize,
+        run_id: Option<u64>,
+    ) -> Result<ReadSummary>
+    where
+        D: DbOps + Send + Sync + 'static,
+        S: Seeder + Send + Sync,
+        P: PlanConfig<String> + Templater<String> + Send + Sync,
+    {
+        let reads = scenario.load_reads()?;
+        if reads.is_empty() {
+            return Err(ContenderError::SpamError(
+                "no [[read]] steps found in the scenario",
+                None,
+            ));
+        }
+        let needs_head = reads.iter().any(|read| {
+            matches!(
+                read,
+                ReadRequestStrict::Logs { .. } | ReadRequestStrict::Block { .. }
+            )
+        });
+        // log ranges & block numbers are drawn from the scenario's seed, so runs are reproducible
+        let seed = scenario
+            .rand_seed
+            .seed_values(1, None, None)
+            .next()
+            .map(|value| value.as_u256().as_limbs()[0])
+            .unwrap_or_default();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let totals = Arc::new(LatencyRecorder::new());
+        let errors = Arc::new(Mutex::new(BTreeMap::<String, u64>::new()));
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        let mut tasks = vec![];
+        let mut num_sent = 0;
+
+        for _ in 0..num_seconds {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = tokio::signal::ctrl_c() => {
+                    println!("CTRL-C received, stopping reads and collecting results...");
+                    break;
+                }
+            }
+            let head = if needs_head {
+                scenario
+                    .rpc_client
+                    .get_block_number()
+                    .await
+                    .map_err(|e| ContenderError::with_err(e, "failed to get block number"))?
+            } else {
+                0
+            };
+
+            for _ in 0..self.reads_per_second {
+                let read = sample_read(&reads[num_sent % reads.len()], head, &mut rng);
+                num_sent += 1;
+                let client = scenario.rpc_client.clone();
+                let latency = scenario.latency.clone();
+                let (totals, errors) = (totals.clone(), errors.clone());
+                tasks.push(tokio::task::spawn(async move {
+                    let method = read.method();
+                    let start = Instant::now();
+                    match send_read(&client, &read).await {
+                        Ok(()) => {
+                            latency.record(method, start.elapsed());
+                            totals.record(method, start.elapsed());
+                        }
+                        Err(e) => {
+                            eprintln!("{} failed: {}", method, e);
+                            let mut errors = errors.lock().expect("read errors lock poisoned");
+                            *errors.entry(method.to_owned()).or_default() += 1;
+                        }
+                    }
+                }));
+            }
+
+            if let Some(run_id) = run_id {
+                scenario
+                    .db_writer
+                    .insert_latency_snapshots(run_id, scenario.latency.snapshot())
+                    .await?;
+            }
+        }
+
+        for task in tasks {
+            if let Err(e) = task.await {
+                eprintln!("read task failed: {:?}", e);
+            }
+        }
+        if let Some(run_id) = run_id {
+            scenario
+                .db_writer
+                .insert_latency_snapshots(run_id, scenario.latency.snapshot())
+                .await?;
+            scenario.db_writer.flush().await?;
+        }
+
+        let errors = std::mem::take(&mut *errors.lock().expect("read errors lock poisoned"));
+        Ok(ReadSummary {
+            latency: totals.snapshot(),
+            errors,
+        })
+    }
+}
+
+/// Returns `read` ready to send; log & block queries get a random block range (or block) ending at
+/// or before `head`.
+fn sample_read(read: &ReadRequestStrict, head: u64, rng: &mut impl Rng) -> ReadRequestStrict {
+    match read {
+        ReadRequestStrict::Logs {
+            filter,
+            max_range,
+            lookback,
+        } => {
+            let (from, to) = sample_range(head, *max_range, *lookback, rng);
+            ReadRequestStrict::Logs {
+                filter: filter.to_owned().from_block(from).to_block(to),
+                max_range: *max_range,
+                lookback: *lookback,
+            }
+        }
+        ReadRequestStrict::Block {
+            lookback, full_txs, ..
+        } => {
+            let (number, _) = sample_range(head, 1, *lookback, rng);
+            ReadRequestStrict::Block {
+                number,
+                lookback: *lookback,
+                full_txs: *full_txs,
+            }
+        }
+        read => read.to_owned(),
+    }
+}
+
+/// Random inclusive block range of at most `max_range` blocks, within the latest `lookback` blocks.
+fn sample_range(head: u64, max_range: u64, lookback: u64, rng: &mut impl Rng) -> (u64, u64) {
+    let oldest = head.saturating_sub(lookback.saturating_sub(1));
+    let len = rng.gen_range(1..=max_range.min(head - oldest + 1));
+    let from = rng.gen_range(oldest..=head + 1 - len);
+    (from, from + len - 1)
+}
+
+async fn send_read(client: &AnyProvider, read: &ReadRequestStrict) -> TransportResult<()> {
+    match read {
+        ReadRequestStrict::Call(tx) => client
+            .call(&WithOtherFields::new(tx.to_owned()))
+            .await
+            .map(|_| ()),
+        ReadRequestStrict::Logs { filter, .. } => client.get_logs(filter).await.map(|_| ()),
+        ReadRequestStrict::Balance(address) => client.get_balance(*address).await.map(|_| ()),
+        ReadRequestStrict::Block {
+            number, full_txs, ..
+        } => client
+            .get_block_by_number((*number).into(), *full_txs)
+            .await
+            .map(|_| ()),
+        ReadRequestStrict::EstimateGas(tx) => client
+            .estimate_gas(&WithOtherFields::new(tx.to_owned()))
+            .await
+            .map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_ranges_within_lookback() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let (from, to) = sample_range(20_000, 100, 10_000, &mut rng);
+            assert!(from <= to);
+            assert!(to - from < 100);
+            assert!(from > 10_000 && to <= 20_000);
+        }
+        // near genesis, ranges are clamped to the blocks that exist
+        for _ in 0..100 {
+            let (from, to) = sample_range(5, 100, 10_000, &mut rng);
+            assert!(from <= to && to <= 5);
+        }
+        assert_eq!(sample_range(0, 100, 10_000, &mut rng), (0, 0));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    error::ContenderError,
+    generator::types::{Phase, StepRate},
+    Result,
+};
+
+/// Returns the send rate of each spam step in txs per period. Steps with a `per_period` rate keep
+/// it; the rest split what's left of `txs_per_period` by their shares (1 if they don't set a rate).
+pub fn step_rates(rates: &[Option<StepRate>], txs_per_period: usize) -> Vec<f64> {
+    let fixed = rates
+        .iter()
+        .filter_map(|rate| match rate {
+            Some(StepRate::PerPeriod(rate)) => Some(*rate),
+            _ => None,
+        })
+        .sum::<f64>();
+    let remaining = (txs_per_period as f64 - fixed).max(0.0);
+    let share = |rate: &Option<StepRate>| match rate {
+        Some(StepRate::PerPeriod(_)) => 0.0,
+        Some(StepRate::Share(share)) => share.max(0.0),
+        None => 1.0,
+    };
+    let total_shares = rates.iter().map(share).sum::<f64>();
+
+    rates
+        .iter()
+        .map(|rate| match rate {
+            Some(StepRate::PerPeriod(rate)) => rate.max(0.0),
+            _ if total_shares > 0.0 => remaining * share(rate) / total_shares,
+            _ => 0.0,
+        })
+        .collect()
+}
+
+/// Number of txs a step sends in the first `num_periods` periods at `rate` txs per period.
+pub fn txs_in_periods(rate: f64, num_periods: usize) -> usize {
+    (num_periods as f64 * rate).floor() as usize
+}
+
+/// Returns the rate of each step in txs per period during each phase, with the phase's duration.
+/// Steps which aren't enabled in a phase don't send txs during it.
+pub fn phase_rates(rates: &[Option<StepRate>], phases: &[Phase]) -> Result<Vec<(Vec<f64>, usize)>> {
+    phases
+        .iter()
+        .map(|phase| {
+            if let Some(step) = phase
+                .steps
+                .iter()
+                .flatten()
+                .find(|step| **step >= rates.len())
+            {
+                return Err(ContenderError::SpamError(
+                    "phase enables a spam step that doesn't exist",
+                    Some(format!(
+                        "phase={}, step={}, num_steps={}",
+                        phase.name.as_deref().unwrap_or_default(),
+                        step,
+                        rates.len()
+                    )),
+                ));
+            }
+            let enabled_rates = rates
+                .iter()
+                .enumerate()
+                .map(|(i, rate)| match &phase.steps {
+                    Some(steps) if !steps.contains(&i) => Some(StepRate::Share(0.0)),
+                    _ => *rate,
+                })
+                .collect::<Vec<_>>();
+            Ok((step_rates(&enabled_rates, phase.rate), phase.duration))
+        })
+        .collect()
+}
+
+/// Number of txs the busiest step sends over all phases.
+pub fn max_step_txs(phase_rates: &[(Vec<f64>, usize)]) -> usize {
+    let num_steps = phase_rates
+        .first()
+        .map(|(rates, _)| rates.len())
+        .unwrap_or_default();
+    (0..num_steps)
+        .map(|step| {
+            phase_rates
+                .iter()
+                .map(|(rates, duration)| txs_in_periods(rates[step], *duration))
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Schedules the txs generated for each step into the periods of each phase in turn. Each phase
+/// continues from the txs left by the previous one.
+pub fn schedule_phases<T: Clone>(
+    step_txs: &[&[T]],
+    phase_rates: &[(Vec<f64>, usize)],
+) -> Vec<Vec<T>> {
+    let mut offsets = vec![0; step_txs.len()];
+    let mut periods = vec![];
+    for (rates, duration) in phase_rates {
+        let remaining = step_txs
+            .iter()
+            .zip(&offsets)
+            .map(|(txs, offset)| &txs[(*offset).min(txs.len())..])
+            .collect::<Vec<_>>();
+        periods.extend(schedule_periods(&remaining, rates, *duration));
+        for (offset, rate) in offsets.iter_mut().zip(rates) {
+            *offset += txs_in_periods(*rate, *duration);
+        }
+    }
+    periods
+}
+
+/// Interleaves the txs generated for each step into `num_periods` periods, taking txs from each
+/// step at its rate. Fractional rates carry over, so a step at `0.5` sends a tx every other period.
+/// Steps cycle through their txs if they run out.
+fn schedule_periods<T: Clone>(step_txs: &[&[T]], rates: &[f64], num_periods: usize) -> Vec<Vec<T>> {
+    (0..num_periods)
+        .map(|period| {
+            step_txs
+                .iter()
+                .zip(rates)
+                .filter(|(txs, _)| !txs.is_empty())
+                .flat_map(|(txs, rate)| {
+                    let start = txs_in_periods(*rate, period);
+                    let end = txs_in_periods(*rate, period + 1);
+                    (start..end).map(|i| txs[i % txs.len()].to_owned())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_rate_between_steps() {
+        // an oracle update every other period, with the rest split 3:1
+        let rates = step_rates(
+            &[
+                Some(StepRate::PerPeriod(0.5)),
+                Some(StepRate::Share(3.0)),
+                None,
+            ],
+            100,
+        );
+        assert_eq!(rates, vec![0.5, 74.625, 24.875]);
+        // without overrides, the rate is split evenly
+        assert_eq!(step_rates(&[None, None], 10), vec![5.0, 5.0]);
+    }
+
+    #[test]
+    fn schedules_phases_in_turn() {
+        let phases = [
+            Phase {
+                name: Some("warmup".to_owned()),
+                duration: 2,
+                rate: 1,
+                steps: Some(vec![0]),
+            },
+            Phase {
+                name: Some("peak".to_owned()),
+                duration: 1,
+                rate: 4,
+                steps: None,
+            },
+        ];
+        let rates = phase_rates(&[None, None], &phases).unwrap();
+        assert_eq!(rates, vec![(vec![1.0, 0.0], 2), (vec![2.0, 2.0], 1)]);
+        assert_eq!(max_step_txs(&rates), 4);
+
+        let transfers = ["t1", "t2", "t3", "t4"];
+        let swaps = ["s1", "s2", "s3", "s4"];
+        assert_eq!(
+            schedule_phases(&[&transfers[..], &swaps[..]], &rates),
+            vec![vec!["t1"], vec!["t2"], vec!["t3", "t4", "s1", "s2"]]
+        );
+
+        let unknown_step = Phase {
+            steps: Some(vec![2]),
+            ..phases[0].to_owned()
+        };
+        assert!(phase_rates(&[None, None], &[unknown_step]).is_err());
+    }
+
+    #[test]
+    fn schedules_steps_at_their_rates() {
+        let oracle = ["o1", "o2"];
+        let swaps = ["s1", "s2", "s3", "s4", "s5", "s6"];
+        let periods = schedule_periods(&[&oracle[..], &swaps[..]], &[0.5, 2.0], 4);
+        assert_eq!(
+            periods,
+            vec![
+                vec!["s1", "s2"],
+                vec!["o1", "s3", "s4"],
+                vec!["s5", "s6"],
+                vec!["o2", "s1", "s2"],
+            ]
+        );
+        assert_eq!(txs_in_periods(0.5, 4), 2);
+        assert_eq!(txs_in_periods(2.0, 4), 8);
+    }
+}
@@ -1,20 +1,40 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use std::{pin::Pin, sync::Arc};
 
 use alloy::providers::Provider;
 use futures::Stream;
 use futures::StreamExt;
+use tokio::task::JoinHandle;
 
 use crate::{
-    db::DbOps,
+    db::{AsyncDb, DbOps, DbWriter, DEFAULT_FLUSH_INTERVAL, DEFAULT_WRITER_CAPACITY},
     error::ContenderError,
-    generator::{seeder::Seeder, templater::Templater, types::AnyProvider, Generator, PlanConfig},
+    generator::{
+        named_txs::ExecutionRequest,
+        seeder::Seeder,
+        templater::Templater,
+        types::{AnyProvider, Phase, SpamRequest},
+        Generator, PlanConfig,
+    },
     test_scenario::TestScenario,
     Result,
 };
 
 use super::SpamTrigger;
-use super::{tx_actor::TxActorHandle, OnTxSent};
+use super::{
+    backpressure::{txpool_pending, BACKPRESSURE_POLL_INTERVAL},
+    control::CONTROL_POLL_INTERVAL,
+    schedule,
+    stats::{ErrorRateBreaker, SpamProgress},
+    tx_actor::{TxActorConfig, TxActorHandle, HEAD_POLL_INTERVAL},
+    NilCallback, OnTxSent,
+};
+
+/// How long result collection waits for the last txs of a [`TestScenario::total_txs`] run to land
+/// before counting them as timed out, unless a [`TestScenario::cooldown`] is set.
+const TOTAL_TXS_TIMEOUT: Duration = Duration::from_secs(120);
 
 pub trait Spammer<F, D, S, P>
 where
@@ -24,7 +44,8 @@ where
     P: PlanConfig<String> + Templater<String> + Send + Sync,
 {
     fn get_msg_handler(&self, db: Arc<D>, rpc_client: Arc<AnyProvider>) -> TxActorHandle {
-        TxActorHandle::new(12, db.clone(), rpc_client.clone())
+        let db_writer = DbWriter::new(db, DEFAULT_WRITER_CAPACITY, DEFAULT_FLUSH_INTERVAL);
+        TxActorHandle::new(TxActorConfig::default(), db_writer, rpc_client.clone())
     }
 
     fn on_spam(
@@ -32,6 +53,12 @@ where
         scenario: &mut TestScenario<D, S, P>,
     ) -> impl std::future::Future<Output = Result<Pin<Box<dyn Stream<Item = SpamTrigger> + Send>>>>;
 
+    /// Traffic profile the spammer imposes on a run of `num_periods` periods, e.g. a ramp. Like a
+    /// scenario's `[[phases]]`, it replaces the flat rate; the two can't be combined.
+    fn phases(&self, _num_periods: usize) -> Vec<Phase> {
+        vec![]
+    }
+
     fn spam_rpc(
         &self,
         scenario: &mut TestScenario<D, S, P>,
@@ -52,23 +79,201 @@ where
         });
 
         async move {
-            let tx_requests = scenario
-                .load_txs(crate::generator::PlanType::Spam(
-                    txs_per_period * num_periods,
-                    |_named_req| Ok(None), // we can look at the named request here if needed
-                ))
-                .await?;
-            let tx_req_chunks = tx_requests.chunks(txs_per_period).collect::<Vec<&[_]>>();
+            if scenario.no_track && run_id.is_some() {
+                return Err(ContenderError::SpamError(
+                    "untracked runs can't be recorded",
+                    None,
+                ));
+            }
+            if scenario.total_txs.is_some() && scenario.gas_budget.is_some() {
+                return Err(ContenderError::SpamError(
+                    "a tx count and a gas budget can't both be set",
+                    None,
+                ));
+            }
+            let spam_steps = scenario.config.get_spam_steps()?;
+            let step_rates = spam_steps.iter().map(SpamRequest::rate).collect::<Vec<_>>();
+            let mut phases = scenario.config.get_phases();
+            let spammer_phases = self.phases(num_periods);
+            if !spammer_phases.is_empty() {
+                if !phases.is_empty() {
+                    return Err(ContenderError::SpamError(
+                        "the spammer's rate profile (e.g. a ramp) can't be combined with the scenario's phases",
+                        None,
+                    ));
+                }
+                phases = spammer_phases;
+            }
+            let num_phases = phases.len();
+            let scheduled = !phases.is_empty() || step_rates.iter().any(Option::is_some);
+            let has_safe_steps = spam_steps.iter().any(|step| match step {
+                SpamRequest::Tx(tx) => tx.safe.is_some(),
+                SpamRequest::Bundle(req) => req.txs.iter().any(|tx| tx.safe.is_some()),
+                SpamRequest::Transfer(_) => false,
+            });
+            let mut period_txs = if scheduled {
+                if scenario.total_txs.is_some() {
+                    return Err(ContenderError::SpamError(
+                        "phases & spam steps with their own rate can't be sent with a tx count",
+                        None,
+                    ));
+                }
+                if scenario.config.get_multicall_config().is_some() {
+                    return Err(ContenderError::SpamError(
+                        "phases & spam steps with their own rate can't be aggregated into multicalls",
+                        None,
+                    ));
+                }
+                if spam_steps
+                    .iter()
+                    .any(|step| step.weight() != spam_steps[0].weight())
+                {
+                    return Err(ContenderError::SpamError(
+                        "spam step weights can't be combined with phases or step rates; use `rate = { share = N }` instead",
+                        None,
+                    ));
+                }
+                // without phases, every step is sent at the spammer's rate for the whole run
+                if phases.is_empty() {
+                    phases.push(Phase {
+                        name: None,
+                        duration: num_periods,
+                        rate: txs_per_period,
+                        steps: None,
+                    });
+                }
+                let phase_rates = schedule::phase_rates(&step_rates, &phases)?;
+                // every step gets as many txs as the busiest one needs; others use a prefix
+                let txs_per_step = schedule::max_step_txs(&phase_rates).max(1);
+                let tx_requests = scenario
+                    .load_txs(crate::generator::PlanType::Spam(
+                        txs_per_step * step_rates.len(),
+                        |_named_req| Ok(None),
+                    ))
+                    .await?;
+                // txs are grouped by step
+                let step_txs = tx_requests.chunks(txs_per_step).collect::<Vec<_>>();
+                PeriodTxs::Pregenerated(schedule::schedule_phases(&step_txs, &phase_rates))
+            } else if has_safe_steps {
+                // Safe nonces are counted up as txs are generated, so all of them are generated at once
+                let num_txs = scenario.total_txs.unwrap_or(txs_per_period * num_periods);
+                let mut tx_requests = scenario
+                    .load_txs(crate::generator::PlanType::Spam(
+                        num_txs,
+                        |_named_req| Ok(None), // we can look at the named request here if needed
+                    ))
+                    .await?;
+                // more txs may be generated than requested, to cover every spam step
+                if scenario.total_txs.is_some() {
+                    tx_requests.truncate(num_txs);
+                }
+                PeriodTxs::Pregenerated(
+                    tx_requests
+                        .chunks(txs_per_period.max(1))
+                        .map(<[_]>::to_vec)
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                PeriodTxs::batched(txs_per_period, spam_steps.len(), scenario.total_txs)
+            };
+            // phases set their own durations, and a tx count is sent until it runs out
+            let num_periods = match &period_txs {
+                PeriodTxs::Pregenerated(chunks) if scenario.total_txs.is_some() || scheduled => {
+                    chunks.len()
+                }
+                PeriodTxs::Batched {
+                    remaining: Some(_), ..
+                } => usize::MAX,
+                _ => num_periods,
+            };
+            if scenario.gas_budget.is_some() {
+                if run_id.is_none() {
+                    return Err(ContenderError::SpamError(
+                        "a gas budget requires the run to be recorded, to count landed gas",
+                        None,
+                    ));
+                }
+                if matches!(&period_txs, PeriodTxs::Pregenerated(chunks) if chunks.is_empty()) {
+                    return Err(ContenderError::SpamError(
+                        "no spam txs were generated to spend the gas budget on",
+                        None,
+                    ));
+                }
+            }
             let block_num = scenario
                 .rpc_client
                 .get_block_number()
                 .await
                 .map_err(|e| ContenderError::with_err(e, "failed to get block number"))?;
+            scenario.msg_handle.set_head_block(block_num);
+            // txs are counted as included when their block is first seen, between flushes too
+            let _head_watcher = run_id.map(|_| {
+                AbortOnDrop(
+                    scenario
+                        .msg_handle
+                        .clone()
+                        .watch_heads(scenario.rpc_client.clone()),
+                )
+            });
 
+            // landed txs are saved as new blocks are seen, so they don't pile up in the cache over
+            // long runs, without holding up the periods being sent
+            let mut flusher = run_id.map(|run_id| {
+                LandedTxFlusher::spawn(scenario.msg_handle.clone(), run_id, block_num)
+            });
+
+            let db = AsyncDb::new(scenario.db.clone());
             let mut tick = 0;
-            let mut cursor = self.on_spam(scenario).await?.take(num_periods);
+            let mut error_breaker = scenario
+                .error_rate_limit
+                .map(|limit| ErrorRateBreaker::new(limit, Instant::now()));
+
+            // with a gas budget, periods repeat until it's used up
+            let max_periods = if scenario.gas_budget.is_some() {
+                usize::MAX
+            } else {
+                num_periods
+            };
+            let mut cursor = self.on_spam(scenario).await?;
+            // warmup periods are sent before the measured periods, and aren't recorded
+            let mut warmup_end = scenario.warmup.map(|warmup| Instant::now() + warmup);
+            let mut warmup_tick = 0;
+            let mut use_txpool = true;
+
+            // log progress until spamming & result collection are done, or the run fails
+            let _progress_logger = scenario.stats_interval.map(|interval| {
+                let stats = scenario.stats.clone();
+                let msg_handle = scenario.msg_handle.clone();
+                AbortOnDrop(tokio::task::spawn(async move {
+                    let mut ticker = tokio::time::interval(interval);
+                    // the first tick completes immediately
+                    ticker.tick().await;
+                    let mut prev = SpamProgress::default();
+                    let mut prev_time = Instant::now();
+                    loop {
+                        ticker.tick().await;
+                        let progress = stats.progress(&msg_handle.metrics());
+                        println!("{}", progress.log_line(&prev, prev_time.elapsed()));
+                        prev = progress;
+                        prev_time = Instant::now();
+                    }
+                }))
+            });
+
+            // the first period of each phase defined by the scenario, to log when it starts
+            let phase_starts = phases[..num_phases]
+                .iter()
+                .scan(0, |start, phase| {
+                    let phase_start = *start;
+                    *start += phase.duration;
+                    Some(phase_start)
+                })
+                .collect::<Vec<_>>();
 
-            while let Some(trigger) = cursor.next().await {
+            while tick < max_periods {
+                let Some(trigger) = cursor.next().await else {
+                    break;
+                };
                 if *quit.lock().expect("lock failure") {
                     println!("CTRL-C received, stopping spam and collecting results...");
                     let mut quit = quit.lock().expect("lock failure");
@@ -76,26 +281,205 @@ where
                     break;
                 }
 
+                if let Some(end) = warmup_end {
+                    if Instant::now() < end {
+                        // sent without a callback, so the txs aren't tracked or saved
+                        let txs = period_txs
+                            .next(scenario, warmup_tick, true)
+                            .await?
+                            .unwrap_or_default();
+                        let payloads = scenario.prepare_spam(&txs).await?;
+                        let spam_tasks = scenario
+                            .execute_spam(trigger, &payloads, Arc::new(NilCallback))
+                            .await?;
+                        for task in spam_tasks {
+                            if let Err(e) = task.await {
+                                eprintln!("warmup spam task failed: {:?}", e);
+                                scenario.stats.record_error(&ContenderError::SpamError(
+                                    "warmup spam task failed",
+                                    Some(e.to_string()),
+                                ));
+                            }
+                        }
+                        warmup_tick += 1;
+                        continue;
+                    }
+                    warmup_end = None;
+                    // drop the latencies & bundles recorded during the warmup
+                    scenario.latency.snapshot();
+                    scenario.sent_bundles.lock().expect("lock failure").clear();
+                    println!(
+                        "warmup done after {} periods, recording results...",
+                        warmup_tick
+                    );
+                }
+
+                if let Some(i) = phase_starts.iter().position(|start| *start == tick) {
+                    let phase = &phases[i];
+                    println!(
+                        "starting phase {}/{} {}: {} periods at {} txs/period",
+                        i + 1,
+                        num_phases,
+                        phase.name.as_deref().unwrap_or_default(),
+                        phase.duration,
+                        phase.rate
+                    );
+                }
+
+                if scenario.control.is_paused() {
+                    let pause_start = Instant::now();
+                    println!("spam paused, waiting to resume...");
+                    while scenario.control.is_paused() && !*quit.lock().expect("lock failure") {
+                        tokio::time::sleep(CONTROL_POLL_INTERVAL).await;
+                    }
+                    if scenario.control.is_paused() {
+                        // stopped while paused; the quit check at the top of the loop ends the run
+                        continue;
+                    }
+                    println!("spam resumed after {:?}", pause_start.elapsed());
+                }
+
+                if let Some(backpressure) = scenario.backpressure {
+                    let pause_start = Instant::now();
+                    let mut paused = false;
+                    loop {
+                        // without `txpool_status`, fall back to our own txs that haven't landed
+                        let pending = if use_txpool {
+                            txpool_pending(&scenario.rpc_client).await
+                        } else {
+                            None
+                        };
+                        let pending = match pending {
+                            Some(pending) => pending,
+                            None => {
+                                if use_txpool {
+                                    println!("txpool_status isn't supported by the node; throttling on the run's pending txs instead");
+                                    use_txpool = false;
+                                }
+                                scenario.msg_handle.metrics().cache_size as u64
+                            }
+                        };
+                        if !backpressure.should_pause(pending, paused) {
+                            break;
+                        }
+                        if !paused {
+                            println!(
+                                "{} txs pending, pausing spam until they drain to {}...",
+                                pending, backpressure.resume_pending
+                            );
+                            paused = true;
+                        }
+                        if *quit.lock().expect("lock failure") {
+                            break;
+                        }
+                        tokio::time::sleep(BACKPRESSURE_POLL_INTERVAL).await;
+                    }
+                    if paused {
+                        println!("resuming spam after {:?}", pause_start.elapsed());
+                    }
+                }
+
+                let Some(txs) = period_txs.next(scenario, tick, false).await? else {
+                    if scenario.gas_budget.is_some() {
+                        return Err(ContenderError::SpamError(
+                            "no spam txs were generated to spend the gas budget on",
+                            None,
+                        ));
+                    }
+                    // the tx count has been sent
+                    break;
+                };
                 let trigger = trigger.to_owned();
-                let payloads = scenario.prepare_spam(tx_req_chunks[tick]).await?;
-                let spam_tasks = scenario
-                    .execute_spam(trigger, &payloads, sent_tx_callback.clone())
-                    .await?;
+                let payloads = scenario.prepare_spam(&txs).await?;
+                let spam_tasks = if scenario.no_track {
+                    scenario
+                        .execute_spam(trigger, &payloads, Arc::new(NilCallback))
+                        .await?
+                } else {
+                    scenario
+                        .execute_spam(trigger, &payloads, sent_tx_callback.clone())
+                        .await?
+                };
                 for task in spam_tasks {
                     let res = task.await;
                     if let Err(e) = res {
                         eprintln!("spam task failed: {:?}", e);
+                        scenario.stats.record_error(&ContenderError::SpamError(
+                            "spam task failed",
+                            Some(e.to_string()),
+                        ));
                     }
                 }
+                if !scenario.no_track {
+                    scenario.bump_stuck_txs(sent_tx_callback.clone()).await?;
+                }
+                if let Some(run_id) = run_id {
+                    scenario
+                        .db_writer
+                        .insert_latency_snapshots(run_id, scenario.latency.snapshot())
+                        .await?;
+                    let bundles =
+                        std::mem::take(&mut *scenario.sent_bundles.lock().expect("lock failure"));
+                    db.insert_bundles(run_id, bundles).await?;
+                }
+                if flusher.as_ref().is_some_and(LandedTxFlusher::is_finished) {
+                    // it only stops early on errors, which are returned when it's stopped below
+                    break;
+                }
+
+                let metrics = scenario.msg_handle.metrics();
                 tick += 1;
+
+                if let Some(breaker) = &mut error_breaker {
+                    let progress = scenario.stats.progress(&metrics);
+                    if let Some(rate) = breaker.check(Instant::now(), progress) {
+                        println!(
+                            "error rate {:.1}% exceeded the limit, stopping spam and collecting results...",
+                            rate * 100.0
+                        );
+                        break;
+                    }
+                }
+
+                if let Some(gas_budget) = scenario.gas_budget {
+                    if metrics.landed_gas >= gas_budget {
+                        println!(
+                            "gas budget reached ({} / {} gas), stopping spam and collecting results...",
+                            metrics.landed_gas, gas_budget
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if scenario.no_track {
+                let progress = scenario.stats.progress(&Default::default());
+                println!(
+                    "untracked run done: {} txs accepted, {} rejected",
+                    progress.sent_txs, progress.errors
+                );
+            }
+            let error_breakdown = scenario.stats.error_breakdown();
+            if !error_breakdown.is_empty() {
+                println!("spam errors: {}", error_breakdown);
             }
 
-            let mut block_counter = 0;
-            if let Some(run_id) = run_id {
+            if let (Some(run_id), Some(flusher)) = (run_id, flusher.as_mut()) {
+                let mut next_flush_block = flusher.stop().await?;
+                let collection_start = Instant::now();
+                // a cooldown caps how long pending txs get to land; a tx count has a cap of its own
+                let timeout = scenario
+                    .cooldown
+                    .or(scenario.total_txs.map(|_| TOTAL_TXS_TIMEOUT));
+                if let Some(cooldown) = scenario.cooldown {
+                    println!("cooling down for {:?} while pending txs land...", cooldown);
+                }
                 loop {
+                    // keep stuck txs moving while we wait for the rest to land
+                    scenario.bump_stuck_txs(sent_tx_callback.clone()).await?;
                     let cache_size = scenario
                         .msg_handle
-                        .flush_cache(run_id, block_num + block_counter as u64)
+                        .flush_cache(run_id, next_flush_block)
                         .await
                         .expect("failed to flush cache");
                     if cache_size == 0 {
@@ -105,7 +489,27 @@ where
                         println!("CTRL-C received, stopping result collection...");
                         break;
                     }
-                    block_counter += 1;
+                    if let Some(timeout) = timeout.filter(|t| collection_start.elapsed() >= *t) {
+                        println!(
+                            "{} txs didn't land within {:?}; counting them as timed out",
+                            cache_size, timeout
+                        );
+                        for _ in 0..cache_size {
+                            scenario.stats.record_error(&ContenderError::Timeout(
+                                "tx didn't land before result collection ended".to_owned(),
+                            ));
+                        }
+                        break;
+                    }
+                    next_flush_block += 1;
+                }
+                scenario.db_writer.flush().await?;
+                // saved so reports can break errors down by tx kind
+                let run_errors = scenario.stats.run_errors();
+                if !run_errors.is_empty() {
+                    AsyncDb::new(scenario.db.clone())
+                        .call(move |db| db.insert_run_errors(run_id, &run_errors))
+                        .await?;
                 }
                 println!("done. run_id={}", run_id);
             }
@@ -114,3 +518,186 @@ where
         }
     }
 }
+
+/// Where the spam txs of each period come from.
+enum PeriodTxs {
+    /// Generated up front, e.g. scheduled into phases. Sent in order, repeating with a gas budget.
+    Pregenerated(Vec<Vec<ExecutionRequest>>),
+    /// Generated in batches as periods are sent, so long runs don't hold every tx in memory.
+    Batched {
+        txs_per_period: usize,
+        /// Number of txs generated at once; at least one per spam step.
+        batch_size: usize,
+        /// Txs left to send of a [`TestScenario::total_txs`] run.
+        remaining: Option<usize>,
+        next_batch: usize,
+        /// Generated txs that haven't been sent yet; a batch may not split evenly into periods.
+        buffer: Vec<ExecutionRequest>,
+    },
+}
+
+impl PeriodTxs {
+    /// Generates `txs_per_period` txs per period (until `total_txs` have been sent, if set), in
+    /// batches of at least one tx per spam step. Periods with fewer txs than there are steps are
+    /// sent from the surplus of earlier batches.
+    fn batched(txs_per_period: usize, num_steps: usize, total_txs: Option<usize>) -> Self {
+        PeriodTxs::Batched {
+            txs_per_period,
+            batch_size: txs_per_period.max(num_steps),
+            remaining: total_txs,
+            next_batch: 0,
+            buffer: vec![],
+        }
+    }
+
+    /// Returns the txs of the `period`th period, or `None` once a tx count has been sent. Warmup
+    /// periods are counted separately, and don't count towards a tx count.
+    async fn next<D, S, P>(
+        &mut self,
+        scenario: &mut TestScenario<D, S, P>,
+        period: usize,
+        warmup: bool,
+    ) -> Result<Option<Vec<ExecutionRequest>>>
+    where
+        D: DbOps + Send + Sync + 'static,
+        S: Seeder + Send + Sync,
+        P: PlanConfig<String> + Templater<String> + Send + Sync,
+    {
+        match self {
+            PeriodTxs::Pregenerated(chunks) => Ok(Some(if chunks.is_empty() {
+                vec![]
+            } else {
+                chunks[period % chunks.len()].to_owned()
+            })),
+            PeriodTxs::Batched {
+                txs_per_period,
+                batch_size,
+                remaining,
+                next_batch,
+                buffer,
+            } => {
+                let num_txs = match remaining {
+                    Some(0) if !warmup => return Ok(None),
+                    Some(remaining) if !warmup => (*txs_per_period).min(*remaining),
+                    _ => *txs_per_period,
+                };
+                while buffer.len() < num_txs {
+                    scenario.spam_batch = *next_batch;
+                    *next_batch += 1;
+                    let txs = scenario
+                        .load_txs(crate::generator::PlanType::Spam(
+                            *batch_size,
+                            |_named_req| Ok(None), // we can look at the named request here if needed
+                        ))
+                        .await?;
+                    if txs.is_empty() {
+                        // would otherwise end the run as if its tx count had been sent
+                        return Err(ContenderError::SpamError(
+                            "no spam txs were generated for the batch",
+                            Some(format!("batch {} of {} txs", *next_batch - 1, batch_size)),
+                        ));
+                    }
+                    buffer.extend(txs);
+                }
+                let txs = buffer.drain(..num_txs).collect::<Vec<_>>();
+                if let Some(remaining) = remaining.as_mut().filter(|_| !warmup) {
+                    *remaining -= txs.len();
+                }
+                Ok(Some(txs))
+            }
+        }
+    }
+}
+
+/// Saves the run's landed txs in the background as new blocks are seen, so the RPC calls made by
+/// each flush don't hold up sending the next period.
+struct LandedTxFlusher {
+    stop: Arc<AtomicBool>,
+    task: JoinHandle<Result<u64>>,
+}
+
+impl LandedTxFlusher {
+    /// Flushes blocks from `next_flush_block` on, staying a block behind the latest one seen by
+    /// `msg_handle`: a tx may land before its send callback has cached it, and the extra block
+    /// gives the callback time to.
+    fn spawn(msg_handle: Arc<TxActorHandle>, run_id: u64, mut next_flush_block: u64) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let task = tokio::task::spawn({
+            let stop = stop.clone();
+            async move {
+                let mut ticker = tokio::time::interval(HEAD_POLL_INTERVAL);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                while !stop.load(Ordering::Relaxed) {
+                    while next_flush_block < msg_handle.head_block()
+                        && !stop.load(Ordering::Relaxed)
+                    {
+                        msg_handle
+                            .flush_cache(run_id, next_flush_block)
+                            .await
+                            .map_err(|e| {
+                                ContenderError::with_err(e.as_ref(), "failed to flush cache")
+                            })?;
+                        next_flush_block += 1;
+                    }
+                    ticker.tick().await;
+                }
+                Ok(next_flush_block)
+            }
+        });
+        Self { stop, task }
+    }
+
+    /// Whether flushing stopped on its own, which only happens if it failed.
+    fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Stops flushing once the block being flushed is done, returning the next block to flush.
+    async fn stop(&mut self) -> Result<u64> {
+        self.stop.store(true, Ordering::Relaxed);
+        (&mut self.task)
+            .await
+            .map_err(|e| ContenderError::with_err(e, "landed tx flusher crashed"))?
+    }
+}
+
+impl Drop for LandedTxFlusher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Aborts a background task when dropped.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generator::util::test::spawn_anvil, test_scenario::tests::get_test_scenario};
+
+    #[tokio::test]
+    async fn sends_periods_with_fewer_txs_than_spam_steps() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        let num_steps = scenario.config.get_spam_steps().unwrap().len();
+        assert!(num_steps > 1);
+        let total_txs = num_steps * 2 + 1;
+        let mut period_txs = PeriodTxs::batched(1, num_steps, Some(total_txs));
+        for period in 0..total_txs {
+            let txs = period_txs.next(&mut scenario, period, false).await.unwrap();
+            assert_eq!(txs.map(|txs| txs.len()), Some(1));
+        }
+        // the tx count has been sent
+        let txs = period_txs
+            .next(&mut scenario, total_txs, false)
+            .await
+            .unwrap();
+        assert!(txs.is_none());
+    }
+}
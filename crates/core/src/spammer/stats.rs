@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::tx_actor::TxActorMetrics;
+use crate::{db::RunError, error::ContenderError};
+
+/// Running totals of a spam run, counted by the spam tasks.
+#[derive(Debug, Default)]
+pub struct SpamStats {
+    sent_txs: AtomicU64,
+    errors: AtomicU64,
+    /// Maps (tx kind, [`ContenderError::kind`]) to the number of errors of that kind.
+    errors_by_kind: Mutex<BTreeMap<(Option<String>, &'static str), u64>>,
+}
+
+impl SpamStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_sent(&self, num_txs: u64) {
+        self.sent_txs.fetch_add(num_txs, Ordering::Relaxed);
+    }
+
+    /// Counts an error that isn't tied to a tx, e.g. a spam task that crashed.
+    pub fn record_error(&self, err: &ContenderError) {
+        self.record_tx_error(None, err);
+    }
+
+    /// Counts a failed send (or a tx that never landed) of a tx with the given kind.
+    pub fn record_tx_error(&self, tx_kind: Option<&str>, err: &ContenderError) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        *self
+            .errors_by_kind
+            .lock()
+            .expect("errors_by_kind lock poisoned")
+            .entry((tx_kind.map(|kind| kind.to_owned()), err.kind()))
+            .or_default() += 1;
+    }
+
+    /// Formats the number of errors of each kind, e.g. `nonce_conflict=3 transport=1`; empty if
+    /// there were no errors.
+    pub fn error_breakdown(&self) -> String {
+        let mut counts = BTreeMap::<&str, u64>::new();
+        for ((_, error_kind), count) in self
+            .errors_by_kind
+            .lock()
+            .expect("errors_by_kind lock poisoned")
+            .iter()
+        {
+            *counts.entry(error_kind).or_default() += count;
+        }
+        counts
+            .iter()
+            .map(|(kind, count)| format!("{}={}", kind, count))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Number of errors of each kind hit by each tx kind, to save with the run.
+    pub fn run_errors(&self) -> Vec<RunError> {
+        self.errors_by_kind
+            .lock()
+            .expect("errors_by_kind lock poisoned")
+            .iter()
+            .map(|((kind, error_kind), count)| RunError {
+                kind: kind.to_owned(),
+                error_kind: error_kind.to_string(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Reads the totals so far, with landed & pending counts from the tx actor.
+    pub fn progress(&self, tx_actor: &TxActorMetrics) -> SpamProgress {
+        SpamProgress {
+            sent_txs: self.sent_txs.load(Ordering::Relaxed),
+            landed_txs: tx_actor.landed_txs,
+            pending_txs: tx_actor.cache_size,
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Point-in-time totals of a spam run.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpamProgress {
+    pub sent_txs: u64,
+    pub landed_txs: u64,
+    /// Sent txs which haven't landed yet. Only tracked when the run is recorded.
+    pub pending_txs: usize,
+    pub errors: u64,
+}
+
+impl SpamProgress {
+    /// Formats a progress log line, with send & inclusion rates over the `elapsed` time since
+    /// `prev` was taken.
+    pub fn log_line(&self, prev: &SpamProgress, elapsed: Duration) -> String {
+        let secs = elapsed.as_secs_f64().max(0.001);
+        format!(
+            "progress: send_rate={:.1}/s inclusion_rate={:.1}/s sent={} landed={} pending={} errors={}",
+            self.sent_txs.saturating_sub(prev.sent_txs) as f64 / secs,
+            self.landed_txs.saturating_sub(prev.landed_txs) as f64 / secs,
+            self.sent_txs,
+            self.landed_txs,
+            self.pending_txs,
+            self.errors
+        )
+    }
+}
+
+/// Max share of failed sends over a rolling window before a run is halted.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ErrorRateLimit {
+    /// Between 0 and 1.
+    pub max_rate: f64,
+    pub window: Duration,
+}
+
+/// Tracks a run's error rate over the rolling window of an [`ErrorRateLimit`].
+#[derive(Debug)]
+pub struct ErrorRateBreaker {
+    limit: ErrorRateLimit,
+    /// Progress samples, oldest first.
+    samples: VecDeque<(Instant, SpamProgress)>,
+}
+
+impl ErrorRateBreaker {
+    pub fn new(limit: ErrorRateLimit, start: Instant) -> Self {
+        Self {
+            limit,
+            samples: VecDeque::from([(start, SpamProgress::default())]),
+        }
+    }
+
+    /// Records `progress` taken at `now`, and returns the error rate over the last window if it
+    /// exceeds the limit. The rate isn't checked until a full window has passed.
+    pub fn check(&mut self, now: Instant, progress: SpamProgress) -> Option<f64> {
+        // keep the newest sample that's at least a window old, to measure from
+        while self.samples.len() >= 2
+            && now.saturating_duration_since(self.samples[1].0) >= self.limit.window
+        {
+            self.samples.pop_front();
+        }
+        let (since, base) = self.samples.front().copied()?;
+        self.samples.push_back((now, progress));
+        if now.saturating_duration_since(since) < self.limit.window {
+            return None;
+        }
+
+        let errors = progress.errors.saturating_sub(base.errors);
+        let attempts = progress.sent_txs.saturating_sub(base.sent_txs) + errors;
+        if attempts == 0 {
+            return None;
+        }
+        let rate = errors as f64 / attempts as f64;
+        (rate > self.limit.max_rate).then_some(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_rolling_rates() {
+        let stats = SpamStats::new();
+        stats.record_sent(10);
+        stats.record_sent(40);
+        stats.record_error(&ContenderError::Transport("connection reset".to_owned()));
+        let tx_actor = TxActorMetrics {
+            cache_size: 20,
+            landed_txs: 30,
+            ..Default::default()
+        };
+        let prev = SpamProgress {
+            sent_txs: 25,
+            landed_txs: 20,
+            ..Default::default()
+        };
+        let progress = stats.progress(&tx_actor);
+        assert_eq!(progress.sent_txs, 50);
+        assert_eq!(progress.errors, 1);
+        assert_eq!(
+            progress.log_line(&prev, Duration::from_secs(5)),
+            "progress: send_rate=5.0/s inclusion_rate=2.0/s sent=50 landed=30 pending=20 errors=1"
+        );
+        stats.record_tx_error(
+            Some("swap"),
+            &ContenderError::NonceConflict("nonce too low".to_owned()),
+        );
+        stats.record_tx_error(
+            Some("swap"),
+            &ContenderError::Transport("connection reset".to_owned()),
+        );
+        assert_eq!(stats.error_breakdown(), "nonce_conflict=1 transport=2");
+        assert_eq!(
+            stats.run_errors(),
+            vec![
+                RunError {
+                    kind: None,
+                    error_kind: "transport".to_owned(),
+                    count: 1,
+                },
+                RunError {
+                    kind: Some("swap".to_owned()),
+                    error_kind: "nonce_conflict".to_owned(),
+                    count: 1,
+                },
+                RunError {
+                    kind: Some("swap".to_owned()),
+                    error_kind: "transport".to_owned(),
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn trips_when_error_rate_exceeds_limit_over_window() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let progress = |sent_txs, errors| SpamProgress {
+            sent_txs,
+            errors,
+            ..Default::default()
+        };
+        let mut breaker = ErrorRateBreaker::new(
+            ErrorRateLimit {
+                max_rate: 0.2,
+                window: Duration::from_secs(30),
+            },
+            start,
+        );
+        // not checked until a full window has passed
+        assert_eq!(breaker.check(at(10), progress(0, 10)), None);
+        // 10 errors / 100 attempts since the start
+        assert_eq!(breaker.check(at(30), progress(90, 10)), None);
+        // 30 errors / 100 attempts since t=30
+        assert_eq!(breaker.check(at(60), progress(160, 40)), Some(0.3));
+    }
+}
@@ -1,19 +1,189 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
-use alloy::{network::ReceiptResponse, primitives::TxHash, providers::Provider};
-use tokio::sync::{mpsc, oneshot};
+use alloy::{
+    network::ReceiptResponse,
+    primitives::{Address, TxHash},
+    providers::Provider,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
 
 use crate::{
-    db::{DbOps, RunTx},
+    db::{DbWriter, ReplacedTx, RunTx},
     error::ContenderError,
-    generator::types::AnyProvider,
+    generator::types::{AnyProvider, ExpectStatus},
 };
 
+/// How often [`TxActorHandle::watch_heads`] polls for new blocks. Inclusion times are measured to
+/// within this, plus the RPC's latency.
+pub const HEAD_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Max number of blocks whose first-seen time is kept until they're flushed, so runs which aren't
+/// recorded (and never flush) don't grow it forever.
+const MAX_SEEN_BLOCKS: usize = 1024;
+
+/// Sizing for the tx actor's queues.
+///
+/// Overflow policy:
+/// - when `queue_capacity` messages are waiting, senders wait until the actor catches up, which
+///   slows down the spam tasks instead of growing memory.
+/// - when `max_cache_size` unconfirmed txs are cached, the oldest one is evicted to make room.
+///   Evicted txs are never saved to the DB; the number evicted is reported in [`TxActorMetrics`].
+#[derive(Debug, Clone, Copy)]
+pub struct TxActorConfig {
+    pub queue_capacity: usize,
+    /// Max number of unconfirmed txs to track; unbounded if `None`.
+    pub max_cache_size: Option<usize>,
+}
+
+impl Default for TxActorConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 12,
+            max_cache_size: None,
+        }
+    }
+}
+
+/// Point-in-time view of the tx actor's queues.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TxActorMetrics {
+    /// Messages waiting to be handled by the actor.
+    pub queue_depth: usize,
+    /// Unconfirmed txs currently cached.
+    pub cache_size: usize,
+    /// Unconfirmed txs evicted because the cache was full.
+    pub dropped_txs: u64,
+    /// Txs confirmed so far.
+    pub landed_txs: u64,
+    /// Gas used by the txs confirmed so far.
+    pub landed_gas: u128,
+    /// Time taken by the latest cache flush, in milliseconds.
+    pub last_flush_ms: u64,
+}
+
+impl TxActorMetrics {
+    /// Formats the metrics in the Prometheus text exposition format, for scraping while spamming.
+    pub fn to_prometheus(&self) -> String {
+        [
+            (
+                "contender_tx_actor_queue_depth",
+                "gauge",
+                "Messages waiting to be handled by the tx actor.",
+                self.queue_depth.to_string(),
+            ),
+            (
+                "contender_tx_actor_cache_size",
+                "gauge",
+                "Unconfirmed txs currently cached.",
+                self.cache_size.to_string(),
+            ),
+            (
+                "contender_tx_actor_dropped_txs_total",
+                "counter",
+                "Unconfirmed txs evicted because the cache was full.",
+                self.dropped_txs.to_string(),
+            ),
+            (
+                "contender_tx_actor_landed_txs_total",
+                "counter",
+                "Txs confirmed so far.",
+                self.landed_txs.to_string(),
+            ),
+            (
+                "contender_tx_actor_landed_gas_total",
+                "counter",
+                "Gas used by the txs confirmed so far.",
+                self.landed_gas.to_string(),
+            ),
+            (
+                "contender_tx_actor_last_flush_milliseconds",
+                "gauge",
+                "Time taken by the latest cache flush.",
+                self.last_flush_ms.to_string(),
+            ),
+        ]
+        .iter()
+        .map(|(name, kind, help, value)| {
+            format!(
+                "# HELP {} {}\n# TYPE {} {}\n{} {}\n",
+                name, help, name, kind, name, value
+            )
+        })
+        .collect()
+    }
+}
+
+/// Counters shared between the actor and its handle.
+#[derive(Debug, Default)]
+struct TxActorStats {
+    cache_size: AtomicUsize,
+    dropped_txs: AtomicU64,
+    landed_txs: AtomicU64,
+    /// There's no stable `AtomicU128`, so this is locked instead.
+    landed_gas: Mutex<u128>,
+    last_flush_ms: AtomicU64,
+    /// Latest block the spammer has seen; 0 until it's set.
+    head_block: AtomicU64,
+    /// When each block (that hasn't been flushed yet) was first seen, in milliseconds since the
+    /// unix epoch.
+    block_seen_at: Mutex<BTreeMap<u64, usize>>,
+}
+
+impl TxActorStats {
+    /// Records `block_num` as the latest block, first seen at `seen_at` if it's new. Blocks mined
+    /// since the previous head weren't seen before either, so they get the same time.
+    fn set_head_block(&self, block_num: u64, seen_at: usize) {
+        let prev = self.head_block.fetch_max(block_num, Ordering::Relaxed);
+        if block_num <= prev {
+            return;
+        }
+        let first = if prev == 0 { block_num } else { prev + 1 };
+        let first = first.max(block_num.saturating_sub(MAX_SEEN_BLOCKS as u64 - 1));
+        let mut block_seen_at = self
+            .block_seen_at
+            .lock()
+            .expect("block seen_at lock poisoned");
+        for n in first..=block_num {
+            block_seen_at.insert(n, seen_at);
+        }
+        while block_seen_at.len() > MAX_SEEN_BLOCKS {
+            block_seen_at.pop_first();
+        }
+    }
+
+    /// Returns when `block_num` was first seen, forgetting it and every block before it.
+    fn take_block_seen_at(&self, block_num: u64) -> Option<usize> {
+        let mut block_seen_at = self
+            .block_seen_at
+            .lock()
+            .expect("block seen_at lock poisoned");
+        let seen_at = block_seen_at.get(&block_num).copied();
+        *block_seen_at = block_seen_at.split_off(&(block_num + 1));
+        seen_at
+    }
+}
+
+/// Milliseconds since the unix epoch.
+fn unix_millis() -> usize {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_millis() as usize
+}
+
 enum TxActorMessage {
     SentRunTx {
-        tx_hash: TxHash,
-        start_timestamp: usize,
-        kind: Option<String>,
+        run_tx: PendingRunTx,
         on_receipt: oneshot::Sender<()>,
     },
     FlushCache {
@@ -21,23 +191,36 @@ enum TxActorMessage {
         on_flush: oneshot::Sender<usize>, // returns the number of txs remaining in cache
         target_block_num: u64,
     },
+    EvictNonce {
+        sender: Address,
+        nonce: u64,
+        on_evict: oneshot::Sender<usize>, // returns the number of txs evicted
+    },
 }
 
-struct TxActor<D>
-where
-    D: DbOps,
-{
+struct TxActor {
     receiver: mpsc::Receiver<TxActorMessage>,
-    db: Arc<D>,
-    cache: Vec<PendingRunTx>,
+    db_writer: DbWriter,
+    cache: VecDeque<PendingRunTx>,
+    max_cache_size: Option<usize>,
+    stats: Arc<TxActorStats>,
     rpc: Arc<AnyProvider>,
 }
 
+/// A sent tx waiting to land; see [`RunTx`] for what's saved once it does.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PendingRunTx {
-    tx_hash: TxHash,
-    start_timestamp: usize,
-    kind: Option<String>,
+    pub tx_hash: TxHash,
+    pub start_timestamp: usize,
+    pub kind: Option<String>,
+    /// Sender & nonce of the tx, if known; used to detect when it's been replaced.
+    pub sender_nonce: Option<(Address, u64)>,
+    pub ack_latency_ms: Option<u64>,
+    pub from_pool: Option<String>,
+    pub expect_status: Option<ExpectStatus>,
+    pub max_gas_used: Option<u64>,
+    /// Latest block seen when the tx was sent; set from the spammer's head block when cached.
+    pub sent_block: Option<u64>,
 }
 
 impl PendingRunTx {
@@ -46,48 +229,109 @@ impl PendingRunTx {
             tx_hash,
             start_timestamp,
             kind: kind.map(|s| s.to_owned()),
+            sender_nonce: None,
+            ack_latency_ms: None,
+            from_pool: None,
+            expect_status: None,
+            max_gas_used: None,
+            sent_block: None,
         }
     }
+
+    pub fn with_sender_nonce(mut self, sender: Address, nonce: u64) -> Self {
+        self.sender_nonce = Some((sender, nonce));
+        self
+    }
 }
 
-impl<D> TxActor<D>
-where
-    D: DbOps + Send + Sync + 'static,
-{
+/// Removes txs from `cache` whose nonce has been used by another tx, given each sender's
+/// confirmed tx count. Txs without a known sender & nonce are kept.
+fn take_replaced_txs(
+    cache: &mut VecDeque<PendingRunTx>,
+    confirmed_nonces: &HashMap<Address, u64>,
+) -> Vec<PendingRunTx> {
+    let is_replaced = |tx: &PendingRunTx| {
+        tx.sender_nonce.is_some_and(|(sender, nonce)| {
+            confirmed_nonces
+                .get(&sender)
+                .is_some_and(|confirmed| nonce < *confirmed)
+        })
+    };
+    let (replaced, pending): (Vec<_>, VecDeque<_>) =
+        std::mem::take(cache).into_iter().partition(is_replaced);
+    *cache = pending;
+    replaced
+}
+
+impl TxActor {
     pub fn new(
         receiver: mpsc::Receiver<TxActorMessage>,
-        db: Arc<D>,
+        db_writer: DbWriter,
+        max_cache_size: Option<usize>,
+        stats: Arc<TxActorStats>,
         rpc: Arc<AnyProvider>,
     ) -> Self {
         Self {
             receiver,
-            db,
-            cache: Vec::new(),
+            db_writer,
+            cache: VecDeque::new(),
+            max_cache_size,
+            stats,
             rpc,
         }
     }
 
+    /// Caches an unconfirmed tx, evicting the oldest one if the cache is full.
+    fn cache_tx(&mut self, mut run_tx: PendingRunTx) {
+        let head_block = self.stats.head_block.load(Ordering::Relaxed);
+        if run_tx.sent_block.is_none() && head_block > 0 {
+            run_tx.sent_block = Some(head_block);
+        }
+        if let Some(max) = self.max_cache_size {
+            while !self.cache.is_empty() && self.cache.len() >= max {
+                self.cache.pop_front();
+                self.stats.dropped_txs.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.cache.push_back(run_tx);
+        self.stats
+            .cache_size
+            .store(self.cache.len(), Ordering::Relaxed);
+    }
+
+    /// Stops waiting for every version of a sender's tx (e.g. fee bumps), returning how many were
+    /// cached.
+    fn evict_nonce(&mut self, sender: Address, nonce: u64) -> usize {
+        let num_cached = self.cache.len();
+        self.cache
+            .retain(|tx| tx.sender_nonce != Some((sender, nonce)));
+        self.stats
+            .cache_size
+            .store(self.cache.len(), Ordering::Relaxed);
+        num_cached - self.cache.len()
+    }
+
     async fn handle_message(
         &mut self,
         message: TxActorMessage,
     ) -> Result<(), Box<dyn std::error::Error>> {
         match message {
-            TxActorMessage::SentRunTx {
-                tx_hash,
-                start_timestamp,
-                kind,
-                on_receipt,
-            } => {
-                let run_tx = PendingRunTx {
-                    tx_hash,
-                    start_timestamp,
-                    kind,
-                };
-                self.cache.push(run_tx.to_owned());
+            TxActorMessage::SentRunTx { run_tx, on_receipt } => {
+                self.cache_tx(run_tx);
                 on_receipt.send(()).map_err(|_| {
                     ContenderError::SpamError("failed to join TxActor callback", None)
                 })?;
             }
+            TxActorMessage::EvictNonce {
+                sender,
+                nonce,
+                on_evict,
+            } => {
+                let num_evicted = self.evict_nonce(sender, nonce);
+                on_evict.send(num_evicted).map_err(|_| {
+                    ContenderError::SpamError("failed to join TxActor on_evict", None)
+                })?;
+            }
             TxActorMessage::FlushCache {
                 on_flush,
                 run_id,
@@ -108,6 +352,12 @@ where
                     println!("waiting for block {}", target_block_num);
                     std::thread::sleep(Duration::from_secs(1));
                 }
+                let flush_start = Instant::now();
+                // txs landed when their block was first seen, which may be well before this flush
+                let seen_at = self
+                    .stats
+                    .take_block_seen_at(target_block_num)
+                    .unwrap_or_else(unix_millis);
                 let target_block = maybe_block
                     .expect("this should never happen")
                     .expect("this should never happen");
@@ -125,22 +375,56 @@ where
                 let receipt_tx_hashes = receipts
                     .iter()
                     .map(|r| r.transaction_hash)
-                    .collect::<Vec<_>>();
-                let confirmed_txs = self
-                    .cache
-                    .iter()
-                    .filter(|tx| receipt_tx_hashes.contains(&tx.tx_hash))
-                    .map(|tx| tx.to_owned())
-                    .collect::<Vec<_>>();
+                    .collect::<HashSet<_>>();
 
-                // refill cache with any txs that were not included in pending_txs
-                let new_txs = &self
+                // move confirmed txs out of the cache; they're dropped once they've been sent to the DB
+                let (confirmed_txs, new_txs): (Vec<_>, VecDeque<_>) =
+                    std::mem::take(&mut self.cache)
+                        .into_iter()
+                        .partition(|tx| receipt_tx_hashes.contains(&tx.tx_hash));
+                self.cache = new_txs;
+
+                // a tx still pending after its sender's nonce was used in this block was replaced
+                let senders = self
                     .cache
                     .iter()
-                    .filter(|tx| !confirmed_txs.contains(tx))
-                    .map(|tx| tx.to_owned())
+                    .filter_map(|tx| tx.sender_nonce.map(|(sender, _)| sender))
+                    .collect::<HashSet<_>>();
+                let mut confirmed_nonces = HashMap::new();
+                for sender in senders {
+                    let nonce = self
+                        .rpc
+                        .get_transaction_count(sender)
+                        .block_id(target_block_num.into())
+                        .await?;
+                    confirmed_nonces.insert(sender, nonce);
+                }
+                let replaced_txs = take_replaced_txs(&mut self.cache, &confirmed_nonces)
+                    .into_iter()
+                    .filter_map(|tx| {
+                        let (sender, nonce) = tx.sender_nonce?;
+                        println!(
+                            "tx replaced. hash={}\tsender={}\tnonce={}",
+                            tx.tx_hash, sender, nonce
+                        );
+                        Some(ReplacedTx {
+                            tx_hash: tx.tx_hash,
+                            sender,
+                            nonce,
+                            start_timestamp: tx.start_timestamp,
+                            block_number: target_block_num,
+                            kind: tx.kind,
+                        })
+                    })
                     .collect::<Vec<_>>();
-                self.cache = new_txs.to_vec();
+                if !replaced_txs.is_empty() {
+                    self.db_writer
+                        .insert_replaced_txs(run_id, replaced_txs)
+                        .await?;
+                }
+                self.stats
+                    .cache_size
+                    .store(self.cache.len(), Ordering::Relaxed);
 
                 // ready to go to the DB
                 let run_txs = confirmed_txs
@@ -150,32 +434,76 @@ where
                             .iter()
                             .find(|r| r.transaction_hash == pending_tx.tx_hash)
                             .expect("this should never happen");
-                        if !receipt.status() {
-                            println!("tx failed: {:?}", pending_tx.tx_hash);
-                        } else {
-                            println!(
-                                "tx landed. hash={}\tgas_used={}\tblock_num={}",
-                                pending_tx.tx_hash,
-                                receipt.gas_used,
-                                receipt
-                                    .block_number
-                                    .map(|n| n.to_string())
-                                    .unwrap_or("N/A".to_owned())
-                            );
+                        match pending_tx.expect_status {
+                            Some(expected) if !expected.matches(receipt.status()) => {
+                                println!(
+                                    "UNEXPECTED TX STATUS: expected {}, but tx {}. hash={}",
+                                    expected.as_str(),
+                                    if receipt.status() {
+                                        "succeeded"
+                                    } else {
+                                        "reverted"
+                                    },
+                                    pending_tx.tx_hash
+                                );
+                            }
+                            Some(ExpectStatus::Revert) => {
+                                println!("tx reverted as expected: {:?}", pending_tx.tx_hash);
+                            }
+                            _ if !receipt.status() => {
+                                println!("tx failed: {:?}", pending_tx.tx_hash);
+                            }
+                            _ => {
+                                println!(
+                                    "tx landed. hash={}\tgas_used={}\tblock_num={}",
+                                    pending_tx.tx_hash,
+                                    receipt.gas_used,
+                                    receipt
+                                        .block_number
+                                        .map(|n| n.to_string())
+                                        .unwrap_or("N/A".to_owned())
+                                );
+                            }
+                        }
+                        if let Some(max_gas_used) = pending_tx.max_gas_used {
+                            if receipt.gas_used > max_gas_used as u128 {
+                                println!(
+                                    "GAS ASSERTION FAILED: tx used {} gas, more than max_gas_used={}. hash={}",
+                                    receipt.gas_used, max_gas_used, pending_tx.tx_hash
+                                );
+                            }
                         }
                         RunTx {
                             tx_hash: pending_tx.tx_hash,
-                            start_timestamp: pending_tx.start_timestamp / 1000,
-                            end_timestamp: target_block.header.timestamp as usize,
+                            start_timestamp: pending_tx.start_timestamp,
+                            end_timestamp: seen_at,
                             block_number: target_block.header.number,
+                            block_timestamp: target_block.header.timestamp,
                             gas_used: receipt.gas_used,
                             kind: pending_tx.kind,
+                            ack_latency_ms: pending_tx.ack_latency_ms,
+                            from_pool: pending_tx.from_pool,
+                            expect_status: pending_tx.expect_status,
+                            max_gas_used: pending_tx.max_gas_used,
+                            sent_block: pending_tx.sent_block,
                         }
                     })
                     .collect::<Vec<_>>();
 
-                self.db.insert_run_txs(run_id, run_txs)?;
-                on_flush.send(new_txs.len()).map_err(|_| {
+                self.stats
+                    .landed_txs
+                    .fetch_add(run_txs.len() as u64, Ordering::Relaxed);
+                *self
+                    .stats
+                    .landed_gas
+                    .lock()
+                    .expect("landed gas lock poisoned") +=
+                    run_txs.iter().map(|tx| tx.gas_used).sum::<u128>();
+                self.db_writer.insert_run_txs(run_id, run_txs).await?;
+                self.stats
+                    .last_flush_ms
+                    .store(flush_start.elapsed().as_millis() as u64, Ordering::Relaxed);
+                on_flush.send(self.cache.len()).map_err(|_| {
                     ContenderError::SpamError("failed to join TxActor on_flush", None)
                 })?;
             }
@@ -194,34 +522,78 @@ where
 #[derive(Debug)]
 pub struct TxActorHandle {
     sender: mpsc::Sender<TxActorMessage>,
+    stats: Arc<TxActorStats>,
 }
 
 impl TxActorHandle {
-    pub fn new<D: DbOps + Send + Sync + 'static>(
-        bufsize: usize,
-        db: Arc<D>,
-        rpc: Arc<AnyProvider>,
-    ) -> Self {
-        let (sender, receiver) = mpsc::channel(bufsize);
-        let mut actor = TxActor::new(receiver, db, rpc);
+    pub fn new(config: TxActorConfig, db_writer: DbWriter, rpc: Arc<AnyProvider>) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity);
+        let stats = Arc::new(TxActorStats::default());
+        let mut actor = TxActor::new(
+            receiver,
+            db_writer,
+            config.max_cache_size,
+            stats.clone(),
+            rpc,
+        );
         tokio::task::spawn(async move {
             actor.run().await.expect("tx actor crashed");
         });
-        Self { sender }
+        Self { sender, stats }
+    }
+
+    pub fn metrics(&self) -> TxActorMetrics {
+        TxActorMetrics {
+            queue_depth: self.sender.max_capacity() - self.sender.capacity(),
+            cache_size: self.stats.cache_size.load(Ordering::Relaxed),
+            dropped_txs: self.stats.dropped_txs.load(Ordering::Relaxed),
+            landed_txs: self.stats.landed_txs.load(Ordering::Relaxed),
+            landed_gas: *self
+                .stats
+                .landed_gas
+                .lock()
+                .expect("landed gas lock poisoned"),
+            last_flush_ms: self.stats.last_flush_ms.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Records the latest block seen by the spammer; txs cached from now on are counted as sent
+    /// in it, so their inclusion latency can be measured in blocks. Txs landing in a new block are
+    /// counted as included when it's first seen here.
+    pub fn set_head_block(&self, block_num: u64) {
+        self.stats.set_head_block(block_num, unix_millis());
+    }
+
+    /// Latest block seen by the spammer; 0 until it's set.
+    pub fn head_block(&self) -> u64 {
+        self.stats.head_block.load(Ordering::Relaxed)
+    }
+
+    /// Polls `rpc` for new blocks every [`HEAD_POLL_INTERVAL`] until the returned task is aborted,
+    /// so the inclusion time of txs is taken when their block is first seen rather than when the
+    /// cache is flushed.
+    pub fn watch_heads(self: Arc<Self>, rpc: Arc<AnyProvider>) -> JoinHandle<()> {
+        tokio::task::spawn(async move {
+            let mut ticker = tokio::time::interval(HEAD_POLL_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match rpc.get_block_number().await {
+                    Ok(block_num) => self.set_head_block(block_num),
+                    Err(e) => eprintln!("failed to poll for new blocks: {:?}", e),
+                }
+            }
+        })
     }
 
     pub async fn cache_run_tx(
         &self,
-        tx_hash: TxHash,
-        start_timestamp: usize,
-        kind: Option<String>,
+        run_tx: PendingRunTx,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let (sender, receiver) = oneshot::channel();
         self.sender
             .send(TxActorMessage::SentRunTx {
-                tx_hash,
-                start_timestamp,
-                kind,
+                run_tx,
                 on_receipt: sender,
             })
             .await?;
@@ -229,6 +601,24 @@ impl TxActorHandle {
         Ok(())
     }
 
+    /// Stops waiting for a tx that was given up on, so result collection doesn't wait for it to
+    /// land. Every cached version of the sender's nonce is evicted; returns how many there were.
+    pub async fn evict_nonce(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let (on_evict, receiver) = oneshot::channel();
+        self.sender
+            .send(TxActorMessage::EvictNonce {
+                sender,
+                nonce,
+                on_evict,
+            })
+            .await?;
+        Ok(receiver.await?)
+    }
+
     pub async fn flush_cache(
         &self,
         run_id: u64,
@@ -245,3 +635,125 @@ impl TxActorHandle {
         Ok(receiver.await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MockDb;
+    use alloy::{network::AnyNetwork, providers::ProviderBuilder};
+
+    /// Builds an actor that isn't running, pointed at an RPC that doesn't need to be up.
+    fn test_actor(max_cache_size: Option<usize>) -> (TxActor, Arc<TxActorStats>) {
+        let (_sender, receiver) = mpsc::channel(1);
+        let stats = Arc::new(TxActorStats::default());
+        let rpc = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .on_http("http://localhost:8545".parse().unwrap())
+            .boxed();
+        let db_writer = DbWriter::new(Arc::new(MockDb), 1, Duration::from_secs(1));
+        let actor = TxActor::new(
+            receiver,
+            db_writer,
+            max_cache_size,
+            stats.clone(),
+            Arc::new(rpc),
+        );
+        (actor, stats)
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_tx_when_cache_is_full() {
+        let (mut actor, stats) = test_actor(Some(2));
+
+        for n in 0..3 {
+            actor.cache_tx(PendingRunTx::new(TxHash::repeat_byte(n), 0, None));
+        }
+        assert_eq!(actor.cache.len(), 2);
+        assert_eq!(actor.cache[0].tx_hash, TxHash::repeat_byte(1));
+        assert_eq!(stats.cache_size.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.dropped_txs.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn stamps_txs_with_head_block() {
+        let (mut actor, stats) = test_actor(None);
+
+        actor.cache_tx(PendingRunTx::new(TxHash::repeat_byte(0), 0, None));
+        stats.head_block.fetch_max(7, Ordering::Relaxed);
+        actor.cache_tx(PendingRunTx::new(TxHash::repeat_byte(1), 0, None));
+        assert_eq!(actor.cache[0].sent_block, None);
+        assert_eq!(actor.cache[1].sent_block, Some(7));
+    }
+
+    #[tokio::test]
+    async fn evicts_every_version_of_a_nonce() {
+        let (mut actor, stats) = test_actor(None);
+        let sender = Address::repeat_byte(1);
+
+        // the original tx & its fee bump
+        for n in 0..2 {
+            actor.cache_tx(
+                PendingRunTx::new(TxHash::repeat_byte(n), 0, None).with_sender_nonce(sender, 4),
+            );
+        }
+        actor.cache_tx(
+            PendingRunTx::new(TxHash::repeat_byte(2), 0, None).with_sender_nonce(sender, 5),
+        );
+
+        assert_eq!(actor.evict_nonce(sender, 4), 2);
+        assert_eq!(actor.cache.len(), 1);
+        assert_eq!(actor.cache[0].tx_hash, TxHash::repeat_byte(2));
+        assert_eq!(stats.cache_size.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn formats_metrics_for_prometheus() {
+        let metrics = TxActorMetrics {
+            queue_depth: 3,
+            dropped_txs: 2,
+            landed_gas: 42_000,
+            ..Default::default()
+        };
+        let text = metrics.to_prometheus();
+        assert!(text.starts_with(
+            "# HELP contender_tx_actor_queue_depth Messages waiting to be handled by the tx actor.\n# TYPE contender_tx_actor_queue_depth gauge\ncontender_tx_actor_queue_depth 3\n"
+        ));
+        assert!(text.contains("\ncontender_tx_actor_dropped_txs_total 2\n"));
+        assert!(text.contains("\ncontender_tx_actor_landed_gas_total 42000\n"));
+        assert_eq!(text.lines().count(), 18);
+    }
+
+    #[test]
+    fn records_when_blocks_are_first_seen() {
+        let stats = TxActorStats::default();
+        stats.set_head_block(10, 1_000);
+        // seen again later; still first seen at 1000
+        stats.set_head_block(10, 1_200);
+        // 11 was mined between polls
+        stats.set_head_block(12, 1_500);
+        assert_eq!(stats.take_block_seen_at(11), Some(1_500));
+        // earlier blocks are forgotten once a later one is flushed
+        assert_eq!(stats.take_block_seen_at(10), None);
+        assert_eq!(stats.take_block_seen_at(12), Some(1_500));
+    }
+
+    #[test]
+    fn takes_txs_whose_nonce_was_used() {
+        let sender = Address::repeat_byte(1);
+        let other_sender = Address::repeat_byte(2);
+        let mut cache = VecDeque::from(vec![
+            PendingRunTx::new(TxHash::repeat_byte(1), 0, None).with_sender_nonce(sender, 4),
+            PendingRunTx::new(TxHash::repeat_byte(2), 0, None).with_sender_nonce(sender, 5),
+            PendingRunTx::new(TxHash::repeat_byte(3), 0, None).with_sender_nonce(other_sender, 0),
+            // sender & nonce unknown (e.g. unlocked mode)
+            PendingRunTx::new(TxHash::repeat_byte(4), 0, None),
+        ]);
+        // nonce 4 was used by a tx we didn't send, e.g. a fee bump
+        let confirmed_nonces = HashMap::from([(sender, 5), (other_sender, 0)]);
+
+        let replaced = take_replaced_txs(&mut cache, &confirmed_nonces);
+        assert_eq!(replaced.len(), 1);
+        assert_eq!(replaced[0].tx_hash, TxHash::repeat_byte(1));
+        assert_eq!(cache.len(), 3);
+    }
+}
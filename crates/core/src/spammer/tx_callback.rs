@@ -1,11 +1,11 @@
 use std::{collections::HashMap, sync::Arc};
 
-use alloy::providers::PendingTransactionConfig;
+use alloy::{primitives::Address, providers::PendingTransactionConfig};
 use tokio::task::JoinHandle;
 
 use crate::generator::{types::AnyProvider, NamedTxRequest};
 
-use super::tx_actor::TxActorHandle;
+use super::tx_actor::{PendingRunTx, TxActorHandle};
 
 pub trait OnTxSent<K = String, V = String>
 where
@@ -50,7 +50,7 @@ impl OnTxSent for LogCallback {
     fn on_tx_sent(
         &self,
         tx_response: PendingTransactionConfig,
-        _req: &NamedTxRequest,
+        req: &NamedTxRequest,
         extra: Option<HashMap<String, String>>,
         tx_actor: Option<Arc<TxActorHandle>>,
     ) -> Option<JoinHandle<()>> {
@@ -60,11 +60,32 @@ impl OnTxSent for LogCallback {
             .unwrap_or(0);
         let kind = extra
             .as_ref()
-            .and_then(|e| e.get("kind").map(|k| k.to_string()));
+            .and_then(|e| e.get("kind").map(|k| k.to_string()))
+            .or(req.kind.to_owned());
+        let sender_nonce = extra.as_ref().and_then(|e| {
+            let sender = e.get("from")?.parse::<Address>().ok()?;
+            let nonce = e.get("nonce")?.parse::<u64>().ok()?;
+            Some((sender, nonce))
+        });
+        let ack_latency_ms = extra
+            .as_ref()
+            .and_then(|e| e.get("ack_latency_ms")?.parse::<u64>().ok());
+        let from_pool = extra.as_ref().and_then(|e| e.get("from_pool").cloned());
+        let run_tx = PendingRunTx {
+            tx_hash: *tx_response.tx_hash(),
+            start_timestamp,
+            kind,
+            sender_nonce,
+            ack_latency_ms,
+            from_pool,
+            expect_status: req.expect_status,
+            max_gas_used: req.max_gas_used,
+            sent_block: None,
+        };
         let handle = tokio::task::spawn(async move {
             if let Some(tx_actor) = tx_actor {
                 tx_actor
-                    .cache_run_tx(*tx_response.tx_hash(), start_timestamp, kind)
+                    .cache_run_tx(run_tx)
                     .await
                     .expect("failed to cache run tx");
             }
@@ -1,26 +1,44 @@
 use crate::agent_controller::AgentStore;
-use crate::db::{DbOps, NamedTx};
-use crate::error::ContenderError;
+use crate::db::{
+    AsyncDb, BundleSubmission, ContractInfo, DbOps, DbWriter, NamedTx, DEFAULT_FLUSH_INTERVAL,
+    DEFAULT_WRITER_CAPACITY,
+};
+use crate::error::{with_retries, with_retry_policy, ContenderError, RetryPolicy};
 use crate::generator::named_txs::ExecutionRequest;
 use crate::generator::templater::Templater;
-use crate::generator::types::{AnyProvider, EthProvider};
+use crate::generator::types::{AnyProvider, EthProvider, ExpectStatus};
 use crate::generator::NamedTxRequest;
 use crate::generator::{seeder::Seeder, types::PlanType, Generator, PlanConfig};
-use crate::spammer::tx_actor::TxActorHandle;
-use crate::spammer::{ExecutionPayload, OnTxSent, SpamTrigger};
+use crate::provider::{self, EndpointRouter, Routing};
+use crate::spammer::tx_actor::{TxActorConfig, TxActorHandle};
+use crate::spammer::{
+    Backpressure, ErrorRateLimit, ExecutionPayload, FeeBumpPolicy, LatencyRecorder, OnTxSent,
+    PendingSpamTx, PendingSpamTxs, SpamControl, SpamStats, SpamTrigger,
+};
 use crate::Result;
-use alloy::consensus::Transaction;
+use alloy::consensus::{Transaction, TxEnvelope};
 use alloy::eips::eip2718::Encodable2718;
 use alloy::hex::ToHexExt;
-use alloy::network::{AnyNetwork, EthereumWallet, TransactionBuilder};
-use alloy::primitives::{keccak256, Address, FixedBytes};
-use alloy::providers::{PendingTransactionConfig, Provider, ProviderBuilder};
-use alloy::rpc::types::TransactionRequest;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::{keccak256, Address, FixedBytes, U256};
+use alloy::providers::{PendingTransactionConfig, Provider, ProviderBuilder, RootProvider};
+use alloy::rpc::types::{TransactionRequest, WithOtherFields};
 use alloy::signers::local::PrivateKeySigner;
 use alloy::transports::http::reqwest::Url;
 use contender_bundle_provider::{BundleClient, EthSendBundle};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Time to wait for each tx to land in [`TestScenario::strict_ordering`] mode before giving up on
+/// the sender's remaining txs.
+const STRICT_ORDERING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Max number of gas estimates to cache. Fuzzed calldata makes most keys unique unless estimates
+/// are cached per call shape, so the cache is cleared once it's full to keep memory flat over long
+/// runs.
+const MAX_CACHED_GAS_LIMITS: usize = 10_000;
 
 /// A test scenario can be used to run a test with a specific configuration, database, and RPC provider.
 #[derive(Clone, Debug)]
@@ -35,17 +53,85 @@ where
     pub rpc_url: Url,
     pub rpc_client: Arc<AnyProvider>,
     pub eth_client: Arc<EthProvider>,
+    /// Spreads spam txs across several endpoints; they're all sent with `rpc_client` if `None`.
+    pub spam_router: Option<Arc<EndpointRouter>>,
+    /// Client that setup & deployment txs are sent with.
+    pub setup_client: EthProvider,
     pub bundle_client: Option<Arc<BundleClient>>,
     pub builder_rpc_url: Option<Url>,
     pub rand_seed: S,
     /// Wallets explicitly given by the user
     pub wallet_map: HashMap<Address, EthereumWallet>,
+    /// Keys explicitly given by the user, for signing EIP-712 typed data
+    pub user_signers: HashMap<Address, PrivateKeySigner>,
     /// Wallets generated by the system
     pub agent_store: AgentStore,
     pub nonces: HashMap<Address, u64>,
+    /// Senders whose [`Self::strict_ordering`] txs were cut short, so their local nonces are ahead
+    /// of the txs they've sent. Resynced from the node before the next txs are signed.
+    stale_nonces: Arc<Mutex<HashSet<Address>>>,
     pub chain_id: u64,
     pub gas_limits: HashMap<FixedBytes<32>, u128>,
     pub msg_handle: Arc<TxActorHandle>,
+    /// Batches run_tx & latency inserts; flushed at the end of each spam run.
+    pub db_writer: DbWriter,
+    /// Latencies of RPC calls made while spamming.
+    pub latency: Arc<LatencyRecorder>,
+    /// Bundles sent while spamming that haven't been saved to the DB yet.
+    pub sent_bundles: Arc<Mutex<Vec<BundleSubmission>>>,
+    /// Send spam txs unsigned via `eth_sendTransaction`, letting the node sign them with accounts
+    /// it has unlocked (e.g. on dev chains). Nonces & gas limits are left for the node to fill.
+    pub unlocked: bool,
+    /// Send each sender's spam txs in nonce order with at most one in flight, instead of
+    /// dispatching all txs concurrently. For scenarios which break when submissions are reordered.
+    pub strict_ordering: bool,
+    /// Re-broadcast spam txs that stay pending too long with bumped fees. Off if `None`.
+    pub fee_bump: Option<FeeBumpPolicy>,
+    /// Timeout & retries of spam tx sends. A send that still fails after its retries is dropped
+    /// and counted as an error.
+    pub retry_policy: RetryPolicy,
+    /// Signed spam txs tracked for `fee_bump`.
+    pub pending_spam_txs: PendingSpamTxs,
+    /// Txs sent & errors counted while spamming.
+    pub stats: Arc<SpamStats>,
+    /// Log progress (send & inclusion rates, pending txs, errors) at this interval while spamming.
+    /// Off if `None`.
+    pub stats_interval: Option<Duration>,
+    /// Keep spamming until txs landed in the run have used this much gas, instead of stopping
+    /// after the given number of periods.
+    /// The run must be recorded to count landed gas.
+    pub gas_budget: Option<u128>,
+    /// Send exactly this many spam txs (bundles count as one) at the given rate per period,
+    /// instead of rate × number of periods. Result collection gives up on txs that haven't landed
+    /// a while after the last one was sent.
+    pub total_txs: Option<usize>,
+    /// Batch of spam txs generated next, when spam is generated one period at a time.
+    pub spam_batch: usize,
+    /// Rounding remainders of weighted spam steps, carried between batches.
+    spam_step_credit: Arc<Mutex<Vec<f64>>>,
+    /// Stop spamming (then collect results as usual) when the share of failed sends over a
+    /// rolling window exceeds the limit. Off if `None`.
+    pub error_rate_limit: Option<ErrorRateLimit>,
+    /// Spam for this long before the measured periods, without tracking or saving the txs, so
+    /// connection & cache warmup doesn't skew the results. Off if `None`.
+    pub warmup: Option<Duration>,
+    /// Follow new blocks for up to this long after the last spam tx is sent, so late inclusions
+    /// are captured; txs that still haven't landed are then counted as timed out. Results are
+    /// collected until every tx lands if `None`.
+    pub cooldown: Option<Duration>,
+    /// Pause spamming while too many txs are pending, and resume once they've drained.
+    /// Off if `None`.
+    pub backpressure: Option<Backpressure>,
+    /// Pauses & resumes spamming while the run is in progress.
+    pub control: Arc<SpamControl>,
+    /// Fire-and-forget: only count accepted & rejected sends. Sent txs aren't passed to the spam
+    /// callback, so receipts aren't tracked, and stuck txs aren't bumped. The run can't be recorded.
+    pub no_track: bool,
+    /// Name of the scenario, recorded in the contract registry for contracts it deploys.
+    pub scenario_name: Option<String>,
+    /// Namespace of the named txs saved & looked up by the scenario, so scenarios using the same
+    /// contract names on the same chain don't resolve each other's addresses. Unscoped if `None`.
+    pub namespace: Option<String>,
 }
 
 impl<D, S, P> TestScenario<D, S, P>
@@ -63,11 +149,9 @@ where
         signers: &[PrivateKeySigner],
         agent_store: AgentStore,
     ) -> Result<Self> {
-        let rpc_client = Arc::new(
-            ProviderBuilder::new()
-                .network::<AnyNetwork>()
-                .on_http(rpc_url.to_owned()),
-        );
+        // HTTP, WebSocket or IPC, depending on the URL's scheme
+        let client = provider::connect(&rpc_url).await?;
+        let rpc_client: Arc<AnyProvider> = Arc::new(RootProvider::new(client.clone()));
 
         let mut wallet_map = HashMap::new();
         let wallets = signers.iter().map(|s| {
@@ -104,26 +188,115 @@ where
             .as_ref()
             .map(|url| Arc::new(BundleClient::new(url.clone())));
 
-        let msg_handle = Arc::new(TxActorHandle::new(12, db.clone(), rpc_client.clone()));
+        let db_writer = DbWriter::new(db.clone(), DEFAULT_WRITER_CAPACITY, DEFAULT_FLUSH_INTERVAL);
+        let msg_handle = Arc::new(TxActorHandle::new(
+            TxActorConfig::default(),
+            db_writer.clone(),
+            rpc_client.clone(),
+        ));
 
         Ok(Self {
             config,
             db: db.clone(),
             rpc_url: rpc_url.to_owned(),
             rpc_client: rpc_client.clone(),
-            eth_client: Arc::new(ProviderBuilder::new().on_http(rpc_url)),
+            eth_client: Arc::new(RootProvider::new(client.clone())),
+            spam_router: None,
+            setup_client: RootProvider::new(client),
             bundle_client,
             builder_rpc_url,
             rand_seed,
             wallet_map,
+            user_signers: signers.iter().map(|s| (s.address(), s.clone())).collect(),
             agent_store,
             chain_id,
             nonces,
+            stale_nonces: Default::default(),
             gas_limits,
             msg_handle,
+            db_writer,
+            latency: Arc::new(LatencyRecorder::new()),
+            sent_bundles: Default::default(),
+            unlocked: false,
+            strict_ordering: false,
+            fee_bump: None,
+            retry_policy: RetryPolicy::default(),
+            pending_spam_txs: Default::default(),
+            stats: Arc::new(SpamStats::new()),
+            stats_interval: None,
+            gas_budget: None,
+            total_txs: None,
+            spam_batch: 0,
+            spam_step_credit: Default::default(),
+            error_rate_limit: None,
+            warmup: None,
+            cooldown: None,
+            backpressure: None,
+            control: Arc::new(SpamControl::new()),
+            no_track: false,
+            scenario_name: None,
+            namespace: None,
         })
     }
 
+    /// Replaces the tx actor with one using the given queue sizes.
+    /// Txs cached by the previous actor are discarded, so this should be called before spamming.
+    pub fn set_tx_actor_config(&mut self, config: TxActorConfig) {
+        self.msg_handle = Arc::new(TxActorHandle::new(
+            config,
+            self.db_writer.clone(),
+            self.rpc_client.clone(),
+        ));
+    }
+
+    /// Sends spam, setup & deployment txs (and the RPC calls made while sending them) over a
+    /// persistent WebSocket connection to `ws_url`, instead of a new HTTP request per call.
+    /// `ws_url` should point to the same node as `rpc_url`, which is still used for bundles,
+    /// gas estimates & the tx actor created before this is called.
+    pub async fn connect_ws(&mut self, ws_url: Url) -> Result<()> {
+        let client = provider::connect(&ws_url).await?;
+        self.rpc_client = Arc::new(RootProvider::new(client.clone()));
+        self.setup_client = RootProvider::new(client);
+        Ok(())
+    }
+
+    /// Spreads spam txs across `rpc_client` & the nodes at `urls` by `routing`, e.g. to load a
+    /// cluster behind several endpoints in one run. Receipts, nonces & setup still go through
+    /// `rpc_client`, so call [`TestScenario::connect_ws`] first to include its connection.
+    pub async fn route_spam(&mut self, urls: &[Url], routing: Routing) -> Result<()> {
+        let mut endpoints = vec![(self.rpc_url.to_owned(), self.rpc_client.clone())];
+        for url in urls {
+            let client = Arc::new(provider::any_provider(url).await?);
+            let chain_id = client
+                .get_chain_id()
+                .await
+                .map_err(|e| ContenderError::with_err(e, "failed to get chain id"))?;
+            if chain_id != self.chain_id {
+                return Err(ContenderError::SpamError(
+                    "spam endpoints must serve the same chain",
+                    Some(format!(
+                        "{} has chain id {}, expected {}",
+                        url, chain_id, self.chain_id
+                    )),
+                ));
+            }
+            endpoints.push((url.to_owned(), client));
+        }
+        self.spam_router = Some(Arc::new(EndpointRouter::new(endpoints, routing)));
+        Ok(())
+    }
+
+    /// Picks the client to send the next spam tx with, and its index in `spam_router`, if set.
+    fn spam_client(&self) -> (Option<(Arc<EndpointRouter>, usize)>, Arc<AnyProvider>) {
+        match &self.spam_router {
+            Some(router) => {
+                let (idx, client) = router.pick();
+                (Some((router.clone(), idx)), client)
+            }
+            None => (None, self.rpc_client.clone()),
+        }
+    }
+
     pub async fn sync_nonces(&mut self) -> Result<()> {
         let all_addrs = self.wallet_map.keys().copied().collect::<Vec<Address>>();
         for addr in &all_addrs {
@@ -137,6 +310,48 @@ where
         Ok(())
     }
 
+    /// Resyncs the nonces of senders whose strictly ordered txs were cut short, from their pending
+    /// tx count, so their next txs don't leave a nonce gap.
+    async fn resync_stale_nonces(&mut self) -> Result<()> {
+        let senders = std::mem::take(&mut *self.stale_nonces.lock().expect("lock failure"));
+        for sender in senders {
+            let nonce = self
+                .rpc_client
+                .get_transaction_count(sender)
+                .pending()
+                .await
+                .map_err(|e| ContenderError::with_err(e, "failed to retrieve nonce from RPC"))?;
+            self.nonces.insert(sender, nonce);
+        }
+        Ok(())
+    }
+
+    /// Takes a snapshot of the chain state with `evm_snapshot` and returns the snapshot ID.
+    /// Only supported by Anvil/Hardhat-style dev nodes.
+    pub async fn evm_snapshot(&self) -> Result<U256> {
+        self.rpc_client
+            .raw_request("evm_snapshot".into(), ())
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to take evm snapshot"))
+    }
+
+    /// Reverts the chain state to the given snapshot with `evm_revert`, then re-syncs nonces.
+    /// Only supported by Anvil/Hardhat-style dev nodes.
+    pub async fn evm_revert(&mut self, snapshot_id: U256) -> Result<()> {
+        let reverted: bool = self
+            .rpc_client
+            .raw_request("evm_revert".into(), (snapshot_id,))
+            .await
+            .map_err(|e| ContenderError::with_err(e, "failed to revert to evm snapshot"))?;
+        if !reverted {
+            return Err(ContenderError::SpamError(
+                "node refused to revert to snapshot",
+                Some(snapshot_id.to_string()),
+            ));
+        }
+        self.sync_nonces().await
+    }
+
     pub async fn deploy_contracts(&mut self) -> Result<()> {
         let pub_provider = &self.rpc_client;
         let gas_price = pub_provider
@@ -147,12 +362,18 @@ where
             .get_chain_id()
             .await
             .map_err(|e| ContenderError::with_err(e, "failed to get chain id"))?;
+        let abis = self
+            .config
+            .get_create_steps()?
+            .into_iter()
+            .filter_map(|step| step.abi.map(|abi| (step.name, abi)))
+            .collect::<HashMap<_, _>>();
 
         // we do everything in the callback so no need to actually capture the returned txs
         self.load_txs(PlanType::Create(|tx_req| {
             /* callback */
             // copy data/refs from self before spawning the task
-            let db = self.db.clone();
+            let db = AsyncDb::new(self.db.clone());
             let from = tx_req.tx.from.to_owned().ok_or(ContenderError::SetupError(
                 "failed to get 'from' address",
                 None,
@@ -166,18 +387,26 @@ where
                 // simple_nonce_management is unperformant but it's OK bc we're just deploying
                 .with_simple_nonce_management()
                 .wallet(wallet_conf)
-                .on_http(self.rpc_url.to_owned());
+                .on_provider(self.setup_client.to_owned());
 
             println!(
                 "deploying contract: {:?}",
                 tx_req.name.as_ref().unwrap_or(&"".to_string())
             );
             let rpc_url = self.rpc_url.to_owned();
+            let gas_config = self.config.get_gas_estimate_config();
+            let name = tx_req.name.clone().unwrap_or_default();
+            let namespace = self.namespace.to_owned();
+            let contract_info = ContractInfo {
+                code_hash: None,
+                deploy_data: tx_req.tx.input.input().map(|data| data.encode_hex()),
+                abi: abis.get(&name).cloned(),
+                scenario_name: self.scenario_name.to_owned(),
+            };
             let handle = tokio::task::spawn(async move {
                 // estimate gas limit
-                let gas_limit = wallet
-                    .estimate_gas(&tx_req.tx)
-                    .await
+                let gas_limit = gas_config
+                    .gas_limit(wallet.estimate_gas(&tx_req.tx).await)
                     .expect("failed to estimate gas");
 
                 // inject missing fields into tx_req.tx
@@ -187,23 +416,32 @@ where
                     .with_chain_id(chain_id)
                     .with_gas_limit(gas_limit);
 
-                let res = wallet.send_transaction(tx).await;
+                let (wallet, tx) = (&wallet, &tx);
+                let res = with_retries(|| async move {
+                    wallet
+                        .send_transaction(tx.to_owned())
+                        .await
+                        .map_err(ContenderError::from_rpc_error)
+                })
+                .await;
                 if let Err(err) = res {
-                    let err = err.to_string();
-                    if err.to_lowercase().contains("already known") {
-                        eprintln!("Transaction already known. You may be using the same seed (or private key) as another spammer. Try modifying seed with `-s`, or waiting if you set `-p`. JSON-RPC Error: {:?}", err);
-                    } else if err.to_lowercase().contains("insufficient funds") {
-                        eprintln!(
-                            "Insufficient funds for transaction (account: {}). Try passing a funded private key with `-p`. JSON-RPC Error: {:?}",
-                            from,
-                            err
-                        );
-                    } else if err.to_lowercase().contains("replacement transaction underpriced") {
-                        eprintln!("Replacement transaction underpriced. You may have to wait, or replace the currently-pending transactions manually. JSON-RPC Error: {:?}", err);
-                    } else {
-                        eprintln!("failed to send tx: {:?}", err);
+                    match err {
+                        ContenderError::NonceConflict(err) if err.to_lowercase().contains("already known") => {
+                            eprintln!("Transaction already known. You may be using the same seed (or private key) as another spammer. Try modifying seed with `-s`, or waiting if you set `-p`. JSON-RPC Error: {:?}", err);
+                        }
+                        ContenderError::NonceConflict(err) => {
+                            eprintln!("Replacement transaction underpriced. You may have to wait, or replace the currently-pending transactions manually. JSON-RPC Error: {:?}", err);
+                        }
+                        ContenderError::InsufficientFunds(err) => {
+                            eprintln!(
+                                "Insufficient funds for transaction (account: {}). Try passing a funded private key with `-p`. JSON-RPC Error: {:?}",
+                                from,
+                                err
+                            );
+                        }
+                        err => eprintln!("failed to send tx: {:?}", err),
                     }
-                    return;
+                    return Ok(());
                 }
                 let res =
                     res.expect("this will never happen. If it does, I'm a terrible programmer.");
@@ -212,16 +450,27 @@ where
                     "contract address: {}",
                     receipt.contract_address.unwrap_or_default()
                 );
+                let code_hash = match receipt.contract_address {
+                    Some(address) => wallet
+                        .get_code_at(address)
+                        .await
+                        .ok()
+                        .map(keccak256),
+                    None => None,
+                };
                 db.insert_named_txs(
-                    NamedTx::new(
-                        tx_req.name.unwrap_or_default(),
-                        receipt.transaction_hash,
-                        receipt.contract_address,
-                    )
-                    .into(),
-                    rpc_url.as_str(),
+                    NamedTx::new(name, receipt.transaction_hash, receipt.contract_address)
+                        .with_contract(ContractInfo {
+                            code_hash,
+                            ..contract_info
+                        })
+                        .with_namespace(namespace)
+                        .into(),
+                    rpc_url.to_string(),
                 )
+                .await
                 .expect("failed to insert tx into db");
+                Ok::<_, ContenderError>(())
             });
             Ok(Some(handle))
         }))
@@ -250,52 +499,115 @@ where
                     from.encode_hex().into(),
                 ))?
                 .to_owned();
-            let db = self.db.clone();
+            let db = AsyncDb::new(self.db.clone());
             let rpc_url = self.rpc_url.clone();
+            let setup_client = self.setup_client.to_owned();
+            let gas_config = self.config.get_gas_estimate_config();
+            let namespace = self.namespace.to_owned();
 
             let handle = tokio::task::spawn(async move {
                 let wallet = ProviderBuilder::new()
                     .with_simple_nonce_management()
                     .wallet(wallet)
-                    .on_http(rpc_url.to_owned());
+                    .on_provider(setup_client);
 
-                let chain_id = wallet.get_chain_id().await.expect("failed to get chain id");
+                let chain_id = wallet
+                    .get_chain_id()
+                    .await
+                    .map_err(|e| {
+                        ContenderError::SetupError("failed to get chain id", Some(e.to_string()))
+                    })?;
                 let tx_label = tx_req
                     .name
                     .as_deref()
                     .or(tx_req.kind.as_deref())
                     .unwrap_or("")
                     .to_string();
-                let gas_price = wallet.get_gas_price().await.unwrap_or_else(|_| {
-                    panic!("failed to get gas price for setup step '{}'", tx_label)
-                });
-                let gas_limit = wallet.estimate_gas(&tx_req.tx).await.unwrap_or_else(|_| {
-                    panic!("failed to estimate gas for setup step '{}'", tx_label)
-                });
-                let tx = tx_req
-                    .tx
-                    .with_gas_price(gas_price)
-                    .with_chain_id(chain_id)
-                    .with_gas_limit(gas_limit);
-                let res = wallet
-                    .send_transaction(tx)
-                    .await
-                    .unwrap_or_else(|_| panic!("failed to send setup tx '{}'", tx_label));
+                let gas_price = wallet.get_gas_price().await.map_err(|e| {
+                    ContenderError::SetupError(
+                        "failed to get gas price for setup step",
+                        Some(format!("{}: {}", tx_label, e)),
+                    )
+                })?;
+                let gas_limit = match tx_req.tx.gas {
+                    Some(gas_limit) => gas_limit,
+                    None => gas_config
+                        .gas_limit(wallet.estimate_gas(&tx_req.tx).await)
+                        .map_err(|e| {
+                            ContenderError::SetupError(
+                                "failed to estimate gas for setup step",
+                                Some(format!("{}: {}", tx_label, e)),
+                            )
+                        })?,
+                };
+                let tx = if tx_req.tx.max_fee_per_gas.is_some()
+                    || tx_req.tx.max_priority_fee_per_gas.is_some()
+                {
+                    // fees set by the scenario
+                    let (max_fee_per_gas, max_priority_fee_per_gas) =
+                        tx_fees(&tx_req.tx, gas_price).map_err(|e| {
+                            ContenderError::SetupError(
+                                "invalid fees for setup step",
+                                Some(format!("{}: {}", tx_label, e)),
+                            )
+                        })?;
+                    tx_req
+                        .tx
+                        .with_max_fee_per_gas(max_fee_per_gas)
+                        .with_max_priority_fee_per_gas(max_priority_fee_per_gas)
+                } else {
+                    tx_req.tx.with_gas_price(gas_price)
+                };
+                let tx = tx.with_chain_id(chain_id).with_gas_limit(gas_limit);
+                let (wallet, tx) = (&wallet, &tx);
+                let res = with_retries(|| async move {
+                    wallet
+                        .send_transaction(tx.to_owned())
+                        .await
+                        .map_err(ContenderError::from_rpc_error)
+                })
+                .await
+                .unwrap_or_else(|e| panic!("failed to send setup tx '{}': {}", tx_label, e));
 
                 // get receipt using provider (not wallet) to allow any receipt type (support non-eth chains)
                 let receipt = res
                     .get_receipt()
                     .await
                     .unwrap_or_else(|_| panic!("failed to get receipt for tx '{}'", tx_label));
+                if let Some(expected) = tx_req.expect_status {
+                    if !expected.matches(receipt.status()) {
+                        println!(
+                            "UNEXPECTED TX STATUS: expected setup tx '{}' to {}. hash={}",
+                            tx_label,
+                            if expected == ExpectStatus::Success {
+                                "succeed"
+                            } else {
+                                "revert"
+                            },
+                            receipt.transaction_hash
+                        );
+                    }
+                }
+                if let Some(max_gas_used) = tx_req.max_gas_used {
+                    if receipt.gas_used > max_gas_used as u128 {
+                        println!(
+                            "GAS ASSERTION FAILED: setup tx '{}' used {} gas, more than max_gas_used={}. hash={}",
+                            tx_label, receipt.gas_used, max_gas_used, receipt.transaction_hash
+                        );
+                    }
+                }
 
                 if let Some(name) = tx_req.name {
                     db.insert_named_txs(
                         NamedTx::new(name, receipt.transaction_hash, receipt.contract_address)
+                            .with_namespace(namespace)
                             .into(),
-                        rpc_url.as_str(),
+                        rpc_url.to_string(),
                     )
+                    .await
                     .expect("failed to insert tx into db");
                 }
+                Ok::<_, ContenderError>(())
             });
             Ok(Some(handle))
         }))
@@ -325,24 +637,10 @@ where
             .to_owned();
         self.nonces.insert(from.to_owned(), nonce + 1);
 
-        let key = keccak256(tx_req.input.input.to_owned().unwrap_or_default());
-
-        if let std::collections::hash_map::Entry::Vacant(_) = self.gas_limits.entry(key) {
-            let gas_limit = self
-                .eth_client
-                .estimate_gas(tx_req)
-                .await
-                .map_err(|e| ContenderError::with_err(e, "failed to estimate gas for tx"))?;
-            self.gas_limits.insert(key, gas_limit);
-        }
-        let gas_limit = self
-            .gas_limits
-            .get(&key)
-            .ok_or(ContenderError::SetupError(
-                "failed to lookup gas limit",
-                None,
-            ))?
-            .to_owned();
+        let gas_limit = match tx_req.gas {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas_limit(tx_req).await?,
+        };
         let signer = self
             .wallet_map
             .get(&from)
@@ -351,21 +649,68 @@ where
                 None,
             ))?
             .to_owned();
+        let (max_fee_per_gas, max_priority_fee_per_gas) = tx_fees(tx_req, gas_price)?;
         let full_tx = tx_req
             .to_owned()
             .with_nonce(nonce)
-            .with_max_fee_per_gas(gas_price + (gas_price / 5))
-            .with_max_priority_fee_per_gas(gas_price)
+            .with_max_fee_per_gas(max_fee_per_gas)
+            .with_max_priority_fee_per_gas(max_priority_fee_per_gas)
             .with_chain_id(self.chain_id)
             .with_gas_limit(gas_limit);
 
         Ok((full_tx, signer))
     }
 
+    /// Estimates the gas limit for `tx_req`, applying the scenario's
+    /// [`GasEstimateConfig`](crate::generator::types::GasEstimateConfig).
+    /// Estimates are cached per calldata, or per call shape (target & function selector, or bytecode
+    /// for deployments) with `per_call_shape`, so fuzzed calls don't each need an `eth_estimateGas`
+    /// call.
+    async fn estimate_gas_limit(&mut self, tx_req: &TransactionRequest) -> Result<u128> {
+        let gas_config = self.config.get_gas_estimate_config();
+        let key = gas_limit_key(tx_req, gas_config.per_call_shape);
+        if let Some(gas_limit) = self.gas_limits.get(&key) {
+            return Ok(*gas_limit);
+        }
+        if self.gas_limits.len() >= MAX_CACHED_GAS_LIMITS {
+            self.gas_limits.clear();
+        }
+
+        let estimate = self.eth_client.estimate_gas(tx_req).await;
+        if let Err(e) = &estimate {
+            eprintln!("failed to estimate gas for tx: {:?}", e);
+        }
+        let gas_limit = gas_config
+            .gas_limit(estimate)
+            .map_err(|e| ContenderError::with_err(e, "failed to estimate gas for tx"))?;
+        self.gas_limits.insert(key, gas_limit);
+        Ok(gas_limit)
+    }
+
+    /// Sets fees on a tx to be signed by the node. Nonce & gas limit (unless fixed) are left unset.
+    fn prepare_unlocked_tx_request(
+        &self,
+        tx_req: &TransactionRequest,
+        gas_price: u128,
+    ) -> Result<TransactionRequest> {
+        if tx_req.from.is_none() {
+            return Err(ContenderError::SpamError(
+                "missing 'from' address in tx request; unlocked txs must set `from` to an account unlocked on the node",
+                None,
+            ));
+        }
+        let (max_fee_per_gas, max_priority_fee_per_gas) = tx_fees(tx_req, gas_price)?;
+        Ok(tx_req
+            .to_owned()
+            .with_max_fee_per_gas(max_fee_per_gas)
+            .with_max_priority_fee_per_gas(max_priority_fee_per_gas))
+    }
+
     pub async fn prepare_spam(
         &mut self,
         tx_requests: &[ExecutionRequest],
     ) -> Result<Vec<ExecutionPayload>> {
+        self.resync_stale_nonces().await?;
         let gas_price = self
             .rpc_client
             .get_gas_price()
@@ -374,6 +719,31 @@ where
         let mut payloads = vec![];
         for tx in tx_requests {
             let payload = match tx {
+                ExecutionRequest::Bundle(_) if self.unlocked => {
+                    return Err(ContenderError::SpamError(
+                        "bundles can't be sent in unlocked mode; bundle txs must be signed locally",
+                        None,
+                    ));
+                }
+                ExecutionRequest::Tx(req) if self.unlocked => {
+                    let tx_req = self.prepare_unlocked_tx_request(&req.tx, gas_price)?;
+                    println!(
+                        "sending unsigned tx from={} to={:?} input={} value={}",
+                        tx_req.from.map(|s| s.encode_hex()).unwrap_or_default(),
+                        tx_req.to.and_then(|to| to.to().copied()),
+                        tx_req
+                            .input
+                            .input
+                            .as_ref()
+                            .map(|s| s.encode_hex())
+                            .unwrap_or_default(),
+                        tx_req
+                            .value
+                            .map(|s| s.to_string())
+                            .unwrap_or_else(|| "0".to_owned()),
+                    );
+                    ExecutionPayload::UnsignedTx(tx_req, req.to_owned())
+                }
                 ExecutionRequest::Bundle(reqs) => {
                     if self.bundle_client.is_none() {
                         return Err(ContenderError::SpamError(
@@ -436,6 +806,17 @@ where
                             .unwrap_or_else(|| "N/A".to_owned())
                     );
 
+                    if self.fee_bump.is_some() {
+                        self.pending_spam_txs.insert(PendingSpamTx {
+                            tx_hash: *tx_envelope.tx_hash(),
+                            tx_req,
+                            req: req.to_owned(),
+                            start_timestamp: unix_millis(),
+                            sent_at: std::time::Instant::now(),
+                            num_bumps: 0,
+                        });
+                    }
+
                     ExecutionPayload::SignedTx(tx_envelope, req.to_owned())
                 }
             };
@@ -444,42 +825,256 @@ where
         Ok(payloads)
     }
 
+    /// Re-broadcasts spam txs which have been pending longer than the [`FeeBumpPolicy`]'s timeout,
+    /// with bumped fees at the same nonce. Txs still pending after `max_bumps` re-broadcasts are
+    /// declared failed and no longer tracked; a re-broadcast the node rejects is retried on a later
+    /// call. Returns the number of txs re-broadcast.
+    pub async fn bump_stuck_txs(
+        &mut self,
+        callback_handler: Arc<impl OnTxSent + Send + Sync + 'static>,
+    ) -> Result<usize> {
+        let Some(policy) = self.fee_bump else {
+            return Ok(0);
+        };
+        if self.pending_spam_txs.is_empty() {
+            return Ok(0);
+        }
+
+        // stop tracking txs whose nonce has been used; one of their versions landed. Bumps of txs
+        // which land between checks are rejected as `nonce too low`
+        if self
+            .pending_spam_txs
+            .take_nonce_check(&policy, std::time::Instant::now())
+        {
+            let mut confirmed_nonces = HashMap::new();
+            for sender in self.pending_spam_txs.senders() {
+                let nonce = self
+                    .rpc_client
+                    .get_transaction_count(sender)
+                    .await
+                    .map_err(|e| {
+                        ContenderError::with_err(e, "failed to retrieve nonce from RPC")
+                    })?;
+                confirmed_nonces.insert(sender, nonce);
+            }
+            self.pending_spam_txs.remove_confirmed(&confirmed_nonces);
+        }
+
+        let pools = self.agent_store.pools_by_address();
+        let mut num_bumped = 0;
+        for tx in self
+            .pending_spam_txs
+            .take_stuck(&policy, std::time::Instant::now())
+        {
+            if tx.num_bumps >= policy.max_bumps {
+                println!(
+                    "tx {} still pending after {} fee bumps; declaring it failed",
+                    tx.tx_hash, tx.num_bumps
+                );
+                // result collection stops waiting for it (& the versions it replaced)
+                if let Some((from, nonce)) = tx.sender_nonce() {
+                    self.msg_handle
+                        .evict_nonce(from, nonce)
+                        .await
+                        .map_err(|e| ContenderError::with_err(e.as_ref(), "failed to evict tx"))?;
+                }
+                self.stats.record_tx_error(
+                    tx.req.kind.as_deref(),
+                    &ContenderError::Timeout(format!(
+                        "tx {} still pending after {} fee bumps",
+                        tx.tx_hash, tx.num_bumps
+                    )),
+                );
+                continue;
+            }
+            let Some((from, nonce)) = tx.sender_nonce() else {
+                continue;
+            };
+            let signer = self
+                .wallet_map
+                .get(&from)
+                .ok_or(ContenderError::SpamError(
+                    "failed to get signer from scenario wallet_map",
+                    Some(from.to_string()),
+                ))?
+                .to_owned();
+            let (max_fee_per_gas, max_priority_fee_per_gas) = policy.bump_fees(
+                tx.tx_req.max_fee_per_gas.unwrap_or_default(),
+                tx.tx_req.max_priority_fee_per_gas.unwrap_or_default(),
+            );
+            let tx_req = tx
+                .tx_req
+                .to_owned()
+                .with_max_fee_per_gas(max_fee_per_gas)
+                .with_max_priority_fee_per_gas(max_priority_fee_per_gas);
+            let tx_envelope = tx_req
+                .to_owned()
+                .build(&signer)
+                .await
+                .map_err(|e| ContenderError::with_err(e, "bad request: failed to build tx"))?;
+            let tx_hash = *tx_envelope.tx_hash();
+            println!(
+                "bumping fees for tx {} (from={} nonce={}): max_fee={} priority_fee={} -> {}",
+                tx.tx_hash, from, nonce, max_fee_per_gas, max_priority_fee_per_gas, tx_hash
+            );
+            let sent_at = std::time::Instant::now();
+            let res = self
+                .latency
+                .time(
+                    "eth_sendRawTransaction",
+                    self.rpc_client.send_tx_envelope(tx_envelope),
+                )
+                .await;
+            let res = match res.map_err(ContenderError::from_rpc_error) {
+                Ok(res) => res,
+                // the original (or an earlier bump) landed since we checked
+                Err(ContenderError::NonceConflict(msg))
+                    if msg.to_lowercase().contains("nonce too low") =>
+                {
+                    continue;
+                }
+                Err(e) => {
+                    // keep tracking the tx as it was, so the bump is retried on the next check
+                    eprintln!(
+                        "failed to send fee bump for tx {}; retrying later: {}",
+                        tx.tx_hash, e
+                    );
+                    self.pending_spam_txs.insert(tx);
+                    continue;
+                }
+            };
+
+            // keep the original start time so time-to-inclusion covers the whole wait
+            let mut extra = HashMap::new();
+            extra.insert("start_timestamp".to_owned(), tx.start_timestamp.to_string());
+            add_sender_nonce(&mut extra, &tx.req, nonce);
+            add_from_pool(&mut extra, &tx.req, &pools);
+            add_ack_latency(&mut extra, sent_at);
+            if let Some(handle) = callback_handler.on_tx_sent(
+                res.into_inner(),
+                &tx.req,
+                Some(extra),
+                Some(self.msg_handle.clone()),
+            ) {
+                handle
+                    .await
+                    .map_err(|e| ContenderError::with_err(e, "fee bump callback failed"))?;
+            }
+            self.pending_spam_txs.insert(PendingSpamTx {
+                tx_hash,
+                tx_req,
+                sent_at: std::time::Instant::now(),
+                num_bumps: tx.num_bumps + 1,
+                ..tx
+            });
+            num_bumped += 1;
+        }
+        Ok(num_bumped)
+    }
+
     pub async fn execute_spam(
         &mut self,
         trigger: SpamTrigger,
         payloads: &[ExecutionPayload],
         callback_handler: Arc<impl OnTxSent + Send + Sync + 'static>,
     ) -> Result<Vec<tokio::task::JoinHandle<()>>> {
+        if self.strict_ordering {
+            return self.execute_spam_ordered(payloads, callback_handler);
+        }
         let payloads = payloads.to_owned();
+        let pools = Arc::new(self.agent_store.pools_by_address());
+        let retry_policy = Arc::new(self.retry_policy.to_owned());
 
         let mut tasks: Vec<tokio::task::JoinHandle<()>> = vec![];
 
         for payload in payloads {
-            let rpc_client = self.rpc_client.clone();
+            let (endpoint, rpc_client) = self.spam_client();
             let bundle_client = self.bundle_client.clone();
             let callback_handler = callback_handler.clone();
             let tx_handler = self.msg_handle.clone();
+            let latency = self.latency.clone();
+            let sent_bundles = self.sent_bundles.clone();
+            let stats = self.stats.clone();
+            let pools = pools.clone();
+            let retry_policy = retry_policy.clone();
 
             tasks.push(tokio::task::spawn(async move {
                 let mut extra = HashMap::new();
-                let start_timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("time went backwards")
-                    .as_millis();
-                extra.insert("start_timestamp".to_owned(), start_timestamp.to_string());
+                extra.insert("start_timestamp".to_owned(), unix_millis().to_string());
                 let handles = match payload.to_owned() {
                     ExecutionPayload::SignedTx(signed_tx, req) => {
-                        let res = rpc_client
-                            .send_tx_envelope(signed_tx.to_owned())
-                            .await
-                            .expect("failed to send tx envelope");
-                        let maybe_handle = callback_handler.on_tx_sent(
-                            res.into_inner(),
-                            &req,
-                            Some(extra),
-                            Some(tx_handler.clone()),
-                        );
-                        vec![maybe_handle]
+                        add_sender_nonce(&mut extra, &req, signed_tx.nonce());
+                        add_from_pool(&mut extra, &req, &pools);
+                        let sent_at = std::time::Instant::now();
+                        let res =
+                            send_signed_tx(&retry_policy, &rpc_client, &latency, &signed_tx).await;
+                        if let Some((router, idx)) = &endpoint {
+                            match &res {
+                                Ok(_) => router.record_latency(*idx, sent_at.elapsed()),
+                                Err(_) => router.record_error(*idx),
+                            }
+                        }
+                        match res {
+                            Ok(res) => {
+                                stats.record_sent(1);
+                                add_ack_latency(&mut extra, sent_at);
+                                vec![callback_handler.on_tx_sent(
+                                    res,
+                                    &req,
+                                    Some(extra),
+                                    Some(tx_handler.clone()),
+                                )]
+                            }
+                            Err(e) => {
+                                eprintln!("failed to send tx {}: {:?}", signed_tx.tx_hash(), e);
+                                stats.record_tx_error(req.kind.as_deref(), &e);
+                                vec![]
+                            }
+                        }
+                    }
+                    ExecutionPayload::UnsignedTx(tx_req, req) => {
+                        let from = tx_req.from.unwrap_or_default();
+                        add_from_pool(&mut extra, &req, &pools);
+                        let sent_at = std::time::Instant::now();
+                        let (rpc_client, latency, tx_req) = (&rpc_client, &latency, &tx_req);
+                        let res = with_retry_policy(&retry_policy.without_retries(), || async move {
+                            latency
+                                .time(
+                                    "eth_sendTransaction",
+                                    rpc_client
+                                        .send_transaction(WithOtherFields::new(tx_req.to_owned())),
+                                )
+                                .await
+                                .map_err(ContenderError::from_rpc_error)
+                        })
+                        .await;
+                        if let Some((router, idx)) = &endpoint {
+                            match &res {
+                                Ok(_) => router.record_latency(*idx, sent_at.elapsed()),
+                                Err(_) => router.record_error(*idx),
+                            }
+                        }
+                        match res {
+                            Ok(res) => {
+                                stats.record_sent(1);
+                                add_ack_latency(&mut extra, sent_at);
+                                vec![callback_handler.on_tx_sent(
+                                    res.into_inner(),
+                                    &req,
+                                    Some(extra),
+                                    Some(tx_handler.clone()),
+                                )]
+                            }
+                            Err(e) => {
+                                stats.record_tx_error(req.kind.as_deref(), &e);
+                                // most likely `from` isn't unlocked on the node; keep spamming with the other accounts
+                                eprintln!(
+                                    "eth_sendTransaction failed for {} (is the account unlocked on the node?): {:?}",
+                                    from, e
+                                );
+                                vec![]
+                            }
+                        }
                     }
                     ExecutionPayload::SignedTxBundle(signed_txs, reqs) => {
                         let mut bundle_txs = vec![];
@@ -491,18 +1086,21 @@ where
                         let block_num = match trigger {
                             SpamTrigger::BlockNumber(n) => n,
                             SpamTrigger::BlockHash(h) => {
-                                let block = rpc_client
-                                    .get_block_by_hash(
-                                        h,
-                                        alloy::rpc::types::BlockTransactionsKind::Hashes,
+                                let block = latency
+                                    .time(
+                                        "eth_getBlockByHash",
+                                        rpc_client.get_block_by_hash(
+                                            h,
+                                            alloy::rpc::types::BlockTransactionsKind::Hashes,
+                                        ),
                                     )
                                     .await
                                     .expect("failed to get block")
                                     .expect("block not found");
                                 block.header.number
                             }
-                            _ => rpc_client
-                                .get_block_number()
+                            _ => latency
+                                .time("eth_blockNumber", rpc_client.get_block_number())
                                 .await
                                 .expect("failed to get block number"),
                         };
@@ -512,25 +1110,53 @@ where
                         );
                         if let Some(bundle_client) = bundle_client {
                             println!("spamming bundle: {:?}", rpc_bundle);
+                            let mut num_submissions = 0;
                             for i in 1..4 {
                                 let mut rpc_bundle = rpc_bundle.clone();
                                 rpc_bundle.block_number = block_num + i as u64;
 
-                                let res = rpc_bundle.send_to_builder(&bundle_client).await;
+                                let res = latency
+                                    .time(
+                                        "eth_sendBundle",
+                                        rpc_bundle.send_to_builder(&bundle_client),
+                                    )
+                                    .await;
                                 if let Err(e) = res {
                                     eprintln!("failed to send bundle: {:?}", e);
+                                    stats.record_error(&ContenderError::from_error_message(e));
+                                } else {
+                                    num_submissions += 1;
                                 }
                             }
+                            let tx_hashes = signed_txs
+                                .iter()
+                                .map(|tx| *tx.tx_hash())
+                                .collect::<Vec<_>>();
+                            sent_bundles
+                                .lock()
+                                .expect("sent_bundles lock poisoned")
+                                .push(BundleSubmission {
+                                    bundle_hash: keccak256(
+                                        tx_hashes.iter().flat_map(|h| h.0).collect::<Vec<u8>>(),
+                                    ),
+                                    target_block: block_num + 1,
+                                    num_submissions,
+                                    tx_hashes,
+                                });
                         } else {
                             panic!("bundle client not found");
                         }
 
+                        stats.record_sent(signed_txs.len() as u64);
                         let mut tx_handles = vec![];
                         for (tx, req) in signed_txs.into_iter().zip(reqs) {
+                            let mut extra = extra.clone();
+                            add_sender_nonce(&mut extra, &req, tx.nonce());
+                            add_from_pool(&mut extra, &req, &pools);
                             let maybe_handle = callback_handler.on_tx_sent(
                                 PendingTransactionConfig::new(*tx.tx_hash()),
                                 &req,
-                                Some(extra.clone()),
+                                Some(extra),
                                 Some(tx_handler.clone()),
                             );
                             tx_handles.push(maybe_handle);
@@ -549,6 +1175,156 @@ where
         Ok(tasks)
     }
 
+    /// Sends each sender's txs in nonce order, waiting for each tx to land before sending the
+    /// sender's next one. Senders are still spammed concurrently; one task is spawned per sender.
+    fn execute_spam_ordered(
+        &self,
+        payloads: &[ExecutionPayload],
+        callback_handler: Arc<impl OnTxSent + Send + Sync + 'static>,
+    ) -> Result<Vec<tokio::task::JoinHandle<()>>> {
+        let mut lanes: BTreeMap<Address, Vec<ExecutionPayload>> = BTreeMap::new();
+        for payload in payloads {
+            let from = match payload {
+                ExecutionPayload::SignedTx(_, req) => req.tx.from,
+                ExecutionPayload::UnsignedTx(tx_req, _) => tx_req.from,
+                ExecutionPayload::SignedTxBundle(_, _) => {
+                    return Err(ContenderError::SpamError(
+                        "bundles can't be sent with strict ordering",
+                        None,
+                    ));
+                }
+            };
+            lanes
+                .entry(from.unwrap_or_default())
+                .or_default()
+                .push(payload.to_owned());
+        }
+
+        let pools = self.agent_store.pools_by_address();
+        let retry_policy = Arc::new(self.retry_policy.to_owned());
+        let mut tasks = vec![];
+        for (sender, mut lane) in lanes {
+            let from_pool = pools.get(&sender).cloned();
+            lane.sort_by_key(|payload| match payload {
+                ExecutionPayload::SignedTx(signed_tx, _) => signed_tx.nonce(),
+                // the node assigns nonces in the order txs are received
+                _ => 0,
+            });
+            // each sender sticks to one endpoint, so its txs reach the node in order
+            let (endpoint, rpc_client) = self.spam_client();
+            let callback_handler = callback_handler.clone();
+            let tx_handler = self.msg_handle.clone();
+            let latency = self.latency.clone();
+            let stats = self.stats.clone();
+            let retry_policy = retry_policy.clone();
+            let stale_nonces = self.stale_nonces.clone();
+            // the sender's remaining txs were signed with nonces after the one that failed
+            let mark_stale = move || {
+                stale_nonces.lock().expect("lock failure").insert(sender);
+            };
+
+            tasks.push(tokio::task::spawn(async move {
+                for payload in lane {
+                    let mut extra = HashMap::new();
+                    extra.insert("start_timestamp".to_owned(), unix_millis().to_string());
+                    if let Some(from_pool) = &from_pool {
+                        extra.insert("from_pool".to_owned(), from_pool.to_owned());
+                    }
+                    let sent_at = std::time::Instant::now();
+                    let (rpc_client, latency) = (&rpc_client, &latency);
+                    let (res, req) = match payload {
+                        ExecutionPayload::SignedTx(signed_tx, req) => {
+                            add_sender_nonce(&mut extra, &req, signed_tx.nonce());
+                            let res =
+                                send_signed_tx(&retry_policy, rpc_client, latency, &signed_tx)
+                                    .await;
+                            (res, req)
+                        }
+                        ExecutionPayload::UnsignedTx(tx_req, req) => {
+                            let tx_req = &tx_req;
+                            let res =
+                                with_retry_policy(&retry_policy.without_retries(), || async move {
+                                    latency
+                                        .time(
+                                            "eth_sendTransaction",
+                                            rpc_client.send_transaction(WithOtherFields::new(
+                                                tx_req.to_owned(),
+                                            )),
+                                        )
+                                        .await
+                                        .map_err(ContenderError::from_rpc_error)
+                                })
+                                .await
+                                .map(|res| res.into_inner());
+                            (res, req)
+                        }
+                        ExecutionPayload::SignedTxBundle(_, _) => unreachable!(),
+                    };
+                    if let Some((router, idx)) = &endpoint {
+                        match &res {
+                            Ok(_) => router.record_latency(*idx, sent_at.elapsed()),
+                            Err(_) => router.record_error(*idx),
+                        }
+                    }
+                    let pending_tx = match res {
+                        Ok(res) => {
+                            stats.record_sent(1);
+                            add_ack_latency(&mut extra, sent_at);
+                            res
+                        }
+                        Err(e) => {
+                            stats.record_tx_error(req.kind.as_deref(), &e);
+                            // later txs from this sender would be stuck behind the failed one
+                            eprintln!(
+                                "failed to send tx from {}; skipping its remaining txs: {:?}",
+                                sender, e
+                            );
+                            mark_stale();
+                            break;
+                        }
+                    };
+                    let tx_hash = *pending_tx.tx_hash();
+                    if let Some(handle) = callback_handler.on_tx_sent(
+                        pending_tx,
+                        &req,
+                        Some(extra),
+                        Some(tx_handler.clone()),
+                    ) {
+                        handle.await.expect("msg handle failed");
+                    }
+
+                    // wait for the tx to land before sending the sender's next tx
+                    let landed = match rpc_client
+                        .watch_pending_transaction(
+                            PendingTransactionConfig::new(tx_hash)
+                                .with_timeout(Some(STRICT_ORDERING_TIMEOUT)),
+                        )
+                        .await
+                    {
+                        Ok(pending) => pending.await.is_ok(),
+                        Err(_) => false,
+                    };
+                    if !landed {
+                        stats.record_tx_error(
+                            req.kind.as_deref(),
+                            &ContenderError::Timeout(format!(
+                                "tx {} didn't land within {:?}",
+                                tx_hash, STRICT_ORDERING_TIMEOUT
+                            )),
+                        );
+                        eprintln!(
+                            "tx {} from {} didn't land within {:?}; skipping its remaining txs",
+                            tx_hash, sender, STRICT_ORDERING_TIMEOUT
+                        );
+                        mark_stale();
+                        break;
+                    }
+                }
+            }));
+        }
+        Ok(tasks)
+    }
+
     fn format_setup_log(&self, tx_req: &NamedTxRequest) -> String {
         let to_address = tx_req.tx.to.unwrap_or_default();
         let to_address = to_address.to();
@@ -612,12 +1388,144 @@ where
     fn get_rpc_url(&self) -> String {
         self.rpc_url.to_string()
     }
+
+    fn get_namespace(&self) -> Option<String> {
+        self.namespace.to_owned()
+    }
+
+    fn get_spam_batch(&self) -> usize {
+        self.spam_batch
+    }
+
+    fn get_spam_step_credit(&self) -> Option<&Mutex<Vec<f64>>> {
+        Some(&self.spam_step_credit)
+    }
+
+    fn get_signer(&self, address: &Address) -> Option<PrivateKeySigner> {
+        self.user_signers
+            .get(address)
+            .or_else(|| self.agent_store.find_signer(address))
+            .cloned()
+    }
+}
+
+/// Current time in milliseconds since the epoch.
+fn unix_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_millis()
+}
+
+/// Records how long the node took to acknowledge a tx sent at `sent_at`.
+fn add_ack_latency(extra: &mut HashMap<String, String>, sent_at: std::time::Instant) {
+    extra.insert(
+        "ack_latency_ms".to_owned(),
+        sent_at.elapsed().as_millis().to_string(),
+    );
+}
+
+/// Records the agent pool of `req`'s sender, so txs can be told apart by pool in reports.
+fn add_from_pool(
+    extra: &mut HashMap<String, String>,
+    req: &NamedTxRequest,
+    pools: &HashMap<Address, String>,
+) {
+    if let Some(pool) = req.tx.from.and_then(|from| pools.get(&from)) {
+        extra.insert("from_pool".to_owned(), pool.to_owned());
+    }
+}
+
+/// Adds the sender & nonce of a signed tx to `extra`, so the tx actor can tell when it's been replaced.
+fn add_sender_nonce(extra: &mut HashMap<String, String>, req: &NamedTxRequest, nonce: u64) {
+    if let Some(from) = req.tx.from {
+        extra.insert("from".to_owned(), from.to_string());
+        extra.insert("nonce".to_owned(), nonce.to_string());
+    }
+}
+
+/// Returns (max fee, max priority fee) for `tx_req`. Fees not set by the scenario are derived from
+/// the node's `gas_price`. The priority fee is capped at the max fee.
+/// Sends a signed tx, retrying by `policy`. If a retry is rejected with `already known`, an earlier
+/// attempt (e.g. one that timed out) reached the node, so the tx is counted as sent.
+async fn send_signed_tx(
+    policy: &RetryPolicy,
+    rpc_client: &AnyProvider,
+    latency: &LatencyRecorder,
+    signed_tx: &TxEnvelope,
+) -> Result<PendingTransactionConfig> {
+    let attempts = &AtomicUsize::new(0);
+    with_retry_policy(policy, || async move {
+        let is_retry = attempts.fetch_add(1, Ordering::Relaxed) > 0;
+        let res = latency
+            .time(
+                "eth_sendRawTransaction",
+                rpc_client.send_tx_envelope(signed_tx.to_owned()),
+            )
+            .await;
+        match res.map_err(ContenderError::from_rpc_error) {
+            Ok(pending_tx) => Ok(pending_tx.into_inner()),
+            Err(ContenderError::NonceConflict(msg))
+                if is_retry && msg.to_lowercase().contains("already known") =>
+            {
+                Ok(PendingTransactionConfig::new(*signed_tx.tx_hash()))
+            }
+            Err(e) => Err(e),
+        }
+    })
+    .await
+}
+
+/// Returns (max fee, max priority fee) for `tx_req`, using the fees it sets and filling in the rest
+/// from `gas_price`. Fails if it sets a priority fee above its max fee.
+fn tx_fees(tx_req: &TransactionRequest, gas_price: u128) -> Result<(u128, u128)> {
+    match (tx_req.max_fee_per_gas, tx_req.max_priority_fee_per_gas) {
+        (Some(max_fee_per_gas), Some(max_priority_fee_per_gas))
+            if max_priority_fee_per_gas > max_fee_per_gas =>
+        {
+            Err(ContenderError::GenericError(
+                "max_priority_fee_per_gas exceeds max_fee_per_gas",
+                format!("{} > {}", max_priority_fee_per_gas, max_fee_per_gas),
+            ))
+        }
+        (Some(max_fee_per_gas), max_priority_fee_per_gas) => Ok((
+            max_fee_per_gas,
+            max_priority_fee_per_gas
+                .unwrap_or(gas_price)
+                .min(max_fee_per_gas),
+        )),
+        // the default max fee leaves room for the priority fee
+        (None, max_priority_fee_per_gas) => {
+            let max_priority_fee_per_gas = max_priority_fee_per_gas.unwrap_or(gas_price);
+            let max_fee_per_gas = (gas_price + (gas_price / 5)).max(max_priority_fee_per_gas);
+            Ok((max_fee_per_gas, max_priority_fee_per_gas))
+        }
+    }
+}
+
+/// Key identifying txs which can share a gas estimate. With `per_call_shape`, that's calls to the
+/// same function on the same contract; otherwise calls must also have the same args, since args
+/// (e.g. fuzzed iteration counts) may change gas usage. Deployments are keyed by their bytecode.
+fn gas_limit_key(tx_req: &TransactionRequest, per_call_shape: bool) -> FixedBytes<32> {
+    let input = tx_req.input.input().cloned().unwrap_or_default();
+    match tx_req.to.and_then(|to| to.to().copied()) {
+        Some(to) => {
+            let calldata = if per_call_shape {
+                &input[..input.len().min(4)]
+            } else {
+                &input[..]
+            };
+            keccak256([to.as_slice(), calldata].concat())
+        }
+        None => keccak256(&input),
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use crate::agent_controller::{AgentStore, SignerStore};
     use crate::db::MockDb;
+    use crate::error::ContenderError;
     use crate::generator::named_txs::ExecutionRequest;
     use crate::generator::templater::Templater;
     use crate::generator::types::{
@@ -625,14 +1533,16 @@ pub mod tests {
     };
     use crate::generator::{types::PlanType, util::test::spawn_anvil, RandSeed};
     use crate::generator::{Generator, PlanConfig};
-    use crate::spammer::util::test::{fund_account, get_test_signers};
-    use crate::test_scenario::TestScenario;
+    use crate::spammer::util::test::{fund_account, get_test_signers, MockCallback};
+    use crate::spammer::{ExecutionPayload, FeeBumpPolicy, SpamTrigger};
+    use crate::test_scenario::{gas_limit_key, tx_fees, TestScenario};
     use crate::Result;
     use alloy::consensus::constants::ETH_TO_WEI;
+    use alloy::eips::eip2718::Encodable2718;
     use alloy::hex::ToHexExt;
     use alloy::network::{Ethereum, EthereumWallet, TransactionBuilder};
     use alloy::node_bindings::AnvilInstance;
-    use alloy::primitives::{Address, U256};
+    use alloy::primitives::{Address, Bytes, U256};
     use alloy::providers::{Provider, ProviderBuilder};
     use alloy::rpc::types::TransactionRequest;
     use std::collections::HashMap;
@@ -658,30 +1568,45 @@ pub mod tests {
                     name: "test_counter".to_string(),
                     from: Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_owned()),
                     from_pool: None,
+                    source: None,
+                    contract: None,
+                    abi: None,
                 },
                 CreateDefinition {
                     bytecode: COUNTER_BYTECODE.to_string(),
                     name: "test_counter2".to_string(),
                     from: None,
                     from_pool: Some("admin1".to_owned()),
+                    source: None,
+                    contract: None,
+                    abi: None,
                 },
                 CreateDefinition {
                     bytecode: COUNTER_BYTECODE.to_string(),
                     name: "test_counter3".to_string(),
                     from: None,
                     from_pool: Some("admin2".to_owned()),
+                    source: None,
+                    contract: None,
+                    abi: None,
                 },
                 CreateDefinition {
                     bytecode: UNI_V2_FACTORY_BYTECODE.to_string(),
                     name: "univ2_factory".to_string(),
                     from: None,
                     from_pool: Some("admin1".to_owned()),
+                    source: None,
+                    contract: None,
+                    abi: None,
                 },
                 CreateDefinition {
                     bytecode: UNI_V2_FACTORY_BYTECODE.to_string(),
                     name: "univ2_factory".to_string(),
                     from: Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_owned()),
                     from_pool: None,
+                    source: None,
+                    contract: None,
+                    abi: None,
                 },
             ])
         }
@@ -689,7 +1614,10 @@ pub mod tests {
         fn get_setup_steps(&self) -> Result<Vec<FunctionCallDefinition>> {
             Ok(vec![
                 FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_owned()),
                     from_pool: None,
                     value: Some("4096".to_owned()),
@@ -703,9 +1631,20 @@ pub mod tests {
                     .into(),
                     fuzz: None,
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 },
                 FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_owned()),
                     from_pool: None,
                     value: Some("0x1000".to_owned()),
@@ -719,9 +1658,20 @@ pub mod tests {
                     .into(),
                     fuzz: None,
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 },
                 FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: None,
                     from_pool: Some("pool1".to_owned()),
                     value: None,
@@ -729,6 +1679,14 @@ pub mod tests {
                     args: vec![].into(),
                     fuzz: None,
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 },
             ])
         }
@@ -736,7 +1694,10 @@ pub mod tests {
         fn get_spam_steps(&self) -> Result<Vec<SpamRequest>> {
             let fn_call = |data: &str, from_addr: &str| {
                 SpamRequest::Tx(FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: Some(from_addr.to_owned()),
                     from_pool: None,
                     value: None,
@@ -757,6 +1718,14 @@ pub mod tests {
                     }]
                     .into(),
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 })
             };
             Ok(vec![
@@ -764,7 +1733,10 @@ pub mod tests {
                 fn_call("0xea75", "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
                 fn_call("0xf00d", "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"),
                 SpamRequest::Tx(FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: None,
                     from_pool: Some("pool1".to_owned()),
                     value: None,
@@ -785,9 +1757,20 @@ pub mod tests {
                     }]
                     .into(),
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 }),
                 SpamRequest::Tx(FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: None,
                     from_pool: Some("pool2".to_owned()),
                     value: None,
@@ -808,6 +1791,14 @@ pub mod tests {
                     }]
                     .into(),
                     kind: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 }),
             ])
         }
@@ -848,7 +1839,8 @@ pub mod tests {
         let signers = get_test_signers();
         let provider = ProviderBuilder::new()
             .network::<Ethereum>()
-            .on_http(anvil.endpoint_url());
+            .on_http(anvil.endpoint_url())
+            .boxed();
         let mut agents = AgentStore::new();
         let pool1 = SignerStore::new_random(10, &seed, "0x0defa117");
         let pool2 = SignerStore::new_random(10, &seed, "0xf00d1337");
@@ -976,6 +1968,47 @@ pub mod tests {
         assert!(input.encode_hex().contains(&from.encode_hex()));
     }
 
+    #[tokio::test]
+    async fn spam_batches_continue_fuzzed_values() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        let calldata = |txs: Vec<ExecutionRequest>| {
+            txs.into_iter()
+                .flat_map(|req| match req {
+                    ExecutionRequest::Tx(tx) => vec![tx],
+                    ExecutionRequest::Bundle(txs) => txs,
+                })
+                .filter_map(|tx| tx.tx.input.input)
+                .collect::<Vec<_>>()
+        };
+
+        let first = calldata(
+            scenario
+                .load_txs(PlanType::Spam(10, |_| Ok(None)))
+                .await
+                .unwrap(),
+        );
+        scenario.spam_batch = 1;
+        let second = calldata(
+            scenario
+                .load_txs(PlanType::Spam(10, |_| Ok(None)))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(first.len(), second.len());
+        assert!(second.iter().all(|input| !first.contains(input)));
+
+        // the same batch is generated the same way
+        scenario.spam_batch = 0;
+        let again = calldata(
+            scenario
+                .load_txs(PlanType::Spam(10, |_| Ok(None)))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(again, first);
+    }
+
     #[tokio::test]
     async fn create_replaces_sender_placeholder_with_from_address() {
         let anvil = spawn_anvil();
@@ -1093,4 +2126,339 @@ pub mod tests {
         println!("{:?}", res);
         assert!(res.is_ok());
     }
+
+    #[tokio::test]
+    async fn scenario_reverts_to_snapshot() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        let admin = get_test_signers()[0].address();
+        let nonce_before = scenario.nonces[&admin];
+
+        let snapshot_id = scenario.evm_snapshot().await.unwrap();
+        scenario.deploy_contracts().await.unwrap();
+        assert!(scenario.nonces[&admin] > nonce_before);
+
+        scenario.evm_revert(snapshot_id).await.unwrap();
+        assert_eq!(scenario.nonces[&admin], nonce_before);
+    }
+
+    #[tokio::test]
+    async fn unlocked_scenario_sends_unsigned_txs() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        scenario.unlocked = true;
+        let nonces_before = scenario.nonces.clone();
+
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(5, |_| Ok(None)))
+            .await
+            .unwrap();
+        let payloads = scenario.prepare_spam(&spam_txs).await.unwrap();
+        for payload in &payloads {
+            match payload {
+                ExecutionPayload::UnsignedTx(tx_req, _) => {
+                    assert!(tx_req.from.is_some());
+                    assert!(tx_req.nonce.is_none());
+                }
+                _ => panic!("expected unsigned tx"),
+            }
+        }
+        assert_eq!(scenario.nonces, nonces_before);
+
+        // anvil only unlocks its default accounts, so leave out txs sent from any others
+        let unlocked_accounts = get_test_signers()
+            .iter()
+            .map(|signer| signer.address())
+            .collect::<Vec<_>>();
+        let payloads = payloads
+            .into_iter()
+            .filter(|payload| {
+                matches!(payload, ExecutionPayload::UnsignedTx(tx_req, _)
+                    if tx_req.from.is_some_and(|from| unlocked_accounts.contains(&from)))
+            })
+            .collect::<Vec<_>>();
+        assert!(!payloads.is_empty());
+        let tasks = scenario
+            .execute_spam(SpamTrigger::Nil, &payloads, MockCallback.into())
+            .await
+            .unwrap();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let progress = scenario.stats.progress(&Default::default());
+        assert_eq!(progress.sent_txs, payloads.len() as u64);
+        assert_eq!(progress.errors, 0);
+    }
+
+    #[tokio::test]
+    async fn unlocked_scenario_counts_rejected_sends_as_errors() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        scenario.unlocked = true;
+
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(5, |_| Ok(None)))
+            .await
+            .unwrap();
+        // anvil rejects txs from accounts it hasn't unlocked
+        let locked_account = Address::repeat_byte(0x42);
+        let payloads = scenario
+            .prepare_spam(&spam_txs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|payload| match payload {
+                ExecutionPayload::UnsignedTx(tx_req, req) => {
+                    ExecutionPayload::UnsignedTx(tx_req.with_from(locked_account), req)
+                }
+                _ => panic!("expected unsigned tx"),
+            })
+            .collect::<Vec<_>>();
+
+        // rejected sends are counted as errors without failing the spam tasks
+        let tasks = scenario
+            .execute_spam(SpamTrigger::Nil, &payloads, MockCallback.into())
+            .await
+            .unwrap();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let progress = scenario.stats.progress(&Default::default());
+        assert_eq!(progress.sent_txs, 0);
+        assert_eq!(progress.errors, payloads.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn strict_ordering_sends_each_senders_txs_in_order() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        scenario.strict_ordering = true;
+
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(6, |_| Ok(None)))
+            .await
+            .unwrap();
+        let payloads = scenario.prepare_spam(&spam_txs).await.unwrap();
+        let tasks = scenario
+            .execute_spam(SpamTrigger::Nil, &payloads, MockCallback.into())
+            .await
+            .unwrap();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // every tx landed, so each sender's onchain nonce caught up with the scenario's
+        for (sender, nonce) in &scenario.nonces {
+            let onchain_nonce = scenario
+                .rpc_client
+                .get_transaction_count(*sender)
+                .await
+                .unwrap();
+            assert_eq!(onchain_nonce, *nonce);
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_ordering_resyncs_nonces_of_cut_lanes() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        scenario.strict_ordering = true;
+
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(6, |_| Ok(None)))
+            .await
+            .unwrap();
+        let payloads = scenario.prepare_spam(&spam_txs).await.unwrap();
+        // send a sender's first tx behind the spammer's back, so sending it again fails and the
+        // sender's second tx is dropped
+        let (cut_sender, first_tx) = payloads
+            .iter()
+            .find_map(|payload| match payload {
+                ExecutionPayload::SignedTx(signed_tx, req) => {
+                    Some((req.tx.from.unwrap(), signed_tx.to_owned()))
+                }
+                _ => None,
+            })
+            .unwrap();
+        let mut raw_tx = vec![];
+        first_tx.encode_2718(&mut raw_tx);
+        scenario
+            .rpc_client
+            .send_raw_transaction(&raw_tx)
+            .await
+            .unwrap()
+            .watch()
+            .await
+            .unwrap();
+        let tasks = scenario
+            .execute_spam(SpamTrigger::Nil, &payloads, MockCallback.into())
+            .await
+            .unwrap();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let onchain_nonce = |scenario: &TestScenario<_, _, _>, sender| {
+            let rpc_client = scenario.rpc_client.clone();
+            async move { rpc_client.get_transaction_count(sender).await.unwrap() }
+        };
+        assert!(onchain_nonce(&scenario, cut_sender).await < scenario.nonces[&cut_sender]);
+
+        // the next period continues from the sender's onchain nonce instead of leaving a gap
+        let payloads = scenario.prepare_spam(&spam_txs).await.unwrap();
+        let tasks = scenario
+            .execute_spam(SpamTrigger::Nil, &payloads, MockCallback.into())
+            .await
+            .unwrap();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        for (sender, nonce) in scenario.nonces.clone() {
+            assert_eq!(onchain_nonce(&scenario, sender).await, nonce);
+        }
+    }
+
+    #[tokio::test]
+    async fn bumps_fees_of_stuck_txs() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        scenario.fee_bump = Some(FeeBumpPolicy {
+            pending_tx_timeout_secs: 0,
+            max_bumps: 1,
+            bump_percent: 10,
+        });
+
+        // prepared but never sent, so they're pending as far as the tracker knows
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(3, |_| Ok(None)))
+            .await
+            .unwrap();
+        let payloads = scenario.prepare_spam(&spam_txs).await.unwrap();
+        assert_eq!(scenario.pending_spam_txs.len(), payloads.len());
+
+        let num_bumped = scenario.bump_stuck_txs(MockCallback.into()).await.unwrap();
+        assert_eq!(num_bumped, payloads.len());
+
+        // anvil mined the bumped txs, so there's nothing left to bump
+        let num_bumped = scenario.bump_stuck_txs(MockCallback.into()).await.unwrap();
+        assert_eq!(num_bumped, 0);
+        assert!(scenario.pending_spam_txs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn setup_errors_instead_of_panicking_when_the_node_is_down() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        drop(anvil);
+        let err = scenario.run_setup().await.unwrap_err();
+        assert!(matches!(err, ContenderError::SetupError(..)), "{:?}", err);
+    }
+
+    #[test]
+    fn keys_gas_estimates_by_calldata_unless_per_call_shape() {
+        let to = Address::repeat_byte(0x11);
+        let call = |arg: u8| {
+            TransactionRequest::default()
+                .with_to(to)
+                .with_input([[0x3f, 0xb5, 0xc1, 0xcb].as_slice(), &[arg; 32]].concat())
+        };
+        assert_ne!(
+            gas_limit_key(&call(0), false),
+            gas_limit_key(&call(5), false)
+        );
+        assert_eq!(gas_limit_key(&call(0), true), gas_limit_key(&call(5), true));
+    }
+
+    #[tokio::test]
+    async fn fuzzed_gas_args_are_not_under_estimated() {
+        let anvil = spawn_anvil();
+        let mut scenario = get_test_scenario(&anvil).await;
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::new(get_test_signers()[0].clone()))
+            .on_http(anvil.endpoint_url());
+        let counter = provider
+            .send_transaction(
+                TransactionRequest::default()
+                    .with_deploy_code(COUNTER_BYTECODE.parse::<Bytes>().unwrap()),
+            )
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap()
+            .contract_address
+            .unwrap();
+
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(3, |_| Ok(None)))
+            .await
+            .unwrap();
+        let ExecutionRequest::Tx(req) = &spam_txs[0] else {
+            panic!("expected tx");
+        };
+        // setNumber(0) leaves the counter's slot empty; setNumber(5) writes to it
+        let set_number = |n: u64| {
+            req.tx.clone().with_to(counter).with_input(
+                [
+                    [0x3f, 0xb5, 0xc1, 0xcb].as_slice(),
+                    &U256::from(n).to_be_bytes::<32>(),
+                ]
+                .concat(),
+            )
+        };
+        let (cheap_tx, _) = scenario
+            .prepare_tx_request(&set_number(0), 1_000_000_000)
+            .await
+            .unwrap();
+        let (costly_tx, _) = scenario
+            .prepare_tx_request(&set_number(5), 1_000_000_000)
+            .await
+            .unwrap();
+        let costly_gas = provider.estimate_gas(&set_number(5)).await.unwrap();
+        assert!(costly_tx.gas.unwrap() >= costly_gas);
+        assert!(costly_tx.gas > cheap_tx.gas);
+
+        // fixed gas limits aren't estimated
+        let tx = req.tx.clone().with_gas_limit(123_456);
+        let (tx, _) = scenario
+            .prepare_tx_request(&tx, 1_000_000_000)
+            .await
+            .unwrap();
+        assert_eq!(tx.gas, Some(123_456));
+    }
+
+    #[test]
+    fn scenario_fees_override_node_gas_price() {
+        let gas_price = 1_000_000_000;
+        let tx = TransactionRequest::default();
+        assert_eq!(
+            tx_fees(&tx, gas_price).unwrap(),
+            (1_200_000_000, 1_000_000_000)
+        );
+
+        let tx = TransactionRequest::default()
+            .with_max_fee_per_gas(5_000_000_000)
+            .with_max_priority_fee_per_gas(2_000_000_000);
+        assert_eq!(
+            tx_fees(&tx, gas_price).unwrap(),
+            (5_000_000_000, 2_000_000_000)
+        );
+
+        // the default priority fee is capped at the max fee
+        let tx = TransactionRequest::default().with_max_fee_per_gas(500_000_000);
+        assert_eq!(tx_fees(&tx, gas_price).unwrap(), (500_000_000, 500_000_000));
+
+        // the default max fee is raised to cover the priority fee
+        let tx = TransactionRequest::default().with_max_priority_fee_per_gas(3_000_000_000);
+        assert_eq!(
+            tx_fees(&tx, gas_price).unwrap(),
+            (3_000_000_000, 3_000_000_000)
+        );
+
+        // a priority fee above the scenario's max fee is a mistake
+        let tx = TransactionRequest::default()
+            .with_max_fee_per_gas(1_000_000_000)
+            .with_max_priority_fee_per_gas(2_000_000_000);
+        assert!(tx_fees(&tx, gas_price).is_err());
+    }
 }
@@ -0,0 +1,199 @@
+use contender_core::{
+    db::{DbOps, RunTx},
+    generator::types::LatencySlo,
+};
+use contender_testfile::TestConfig;
+use serde::{Deserialize, Serialize};
+
+use super::util::kind_label;
+
+/// A tx that used more gas than its step's `max_gas_used`, listed in the report so gas
+/// regressions can be traced to the call that caused them.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct GasViolation {
+    pub tx_hash: String,
+    pub kind: String,
+    pub block_number: u64,
+    pub gas_used: u128,
+    pub max_gas_used: u64,
+}
+
+impl GasViolation {
+    /// Returns the txs in `run_txs` that exceeded their gas limit, in the order they landed.
+    pub fn build_all(run_txs: &[RunTx]) -> Vec<Self> {
+        let mut violations = run_txs
+            .iter()
+            .filter(|tx| tx.exceeds_max_gas_used())
+            .map(|tx| Self {
+                tx_hash: tx.tx_hash.to_string(),
+                kind: kind_label(&tx.kind),
+                block_number: tx.block_number,
+                gas_used: tx.gas_used,
+                max_gas_used: tx.max_gas_used.unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        violations.sort_by_key(|v| v.block_number);
+        violations
+    }
+}
+
+/// Result of checking one kind's txs against its [`LatencySlo`].
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SloSummary {
+    pub run_id: u64,
+    pub kind: String,
+    /// The target, e.g. "p95 <= 2 blocks".
+    pub target: String,
+    /// Landed txs of the kind.
+    pub num_txs: usize,
+    /// Latency of the kind's txs at the target's percentile, e.g. "3 blocks, 4200 ms".
+    pub observed: String,
+    pub passed: bool,
+}
+
+impl SloSummary {
+    /// Checks the landed txs of run `run_id` against each of `slos`. An SLO fails if none of its
+    /// txs landed, or if it has a block target and the txs' send blocks weren't recorded.
+    pub fn build_run(run_id: u64, slos: &[LatencySlo], run_txs: &[RunTx]) -> Vec<Self> {
+        slos.iter()
+            .map(|slo| {
+                let txs = run_txs
+                    .iter()
+                    .filter(|tx| tx.kind.as_deref() == Some(slo.kind.as_str()))
+                    .collect::<Vec<_>>();
+                let percentile = slo.percentile.unwrap_or(100.0);
+                let mut target = vec![];
+                let mut observed = vec![];
+                let mut passed = !txs.is_empty();
+                if let Some(max_blocks) = slo.max_blocks {
+                    target.push(format!("{} blocks", max_blocks));
+                    let blocks = txs
+                        .iter()
+                        .filter_map(|tx| tx.blocks_to_inclusion())
+                        .collect::<Vec<_>>();
+                    match at_percentile(blocks, percentile) {
+                        Some(blocks) => {
+                            observed.push(format!("{} blocks", blocks));
+                            passed &= blocks <= max_blocks;
+                        }
+                        None => passed = false,
+                    }
+                }
+                if let Some(max_ms) = slo.max_ms {
+                    target.push(format!("{} ms", max_ms));
+                    let times = txs.iter().map(|tx| tx.time_to_inclusion_ms()).collect();
+                    if let Some(ms) = at_percentile(times, percentile) {
+                        observed.push(format!("{} ms", ms));
+                        passed &= ms <= max_ms;
+                    }
+                }
+                Self {
+                    run_id,
+                    kind: slo.kind.to_owned(),
+                    target: format!("p{} <= {}", percentile, target.join(" & ")),
+                    num_txs: txs.len(),
+                    observed: if txs.is_empty() {
+                        "no txs landed".to_owned()
+                    } else if observed.is_empty() {
+                        "unknown".to_owned()
+                    } else {
+                        observed.join(", ")
+                    },
+                    passed,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Latency SLOs of the scenario run `run_id` was sent from; empty for runs recorded before
+/// scenario snapshots were saved.
+pub fn run_slos(
+    db: &impl DbOps,
+    run_id: u64,
+) -> Result<Vec<LatencySlo>, Box<dyn std::error::Error>> {
+    Ok(match db.get_run_artifacts(run_id)? {
+        Some(artifacts) => TestConfig::from_snapshot(&artifacts.scenario)?
+            .slo
+            .unwrap_or_default(),
+        None => vec![],
+    })
+}
+
+/// Nearest-rank percentile of `values`, or `None` if there are none.
+pub(super) fn at_percentile(mut values: Vec<u64>, percentile: f64) -> Option<u64> {
+    values.sort();
+    let rank = (values.len() as f64 * percentile / 100.0).ceil() as usize;
+    values.get(rank.clamp(1, values.len().max(1)) - 1).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::TxHash;
+
+    fn run_tx(n: u8, gas_used: u128, max_gas_used: Option<u64>) -> RunTx {
+        RunTx {
+            block_number: 10 - n as u64,
+            gas_used,
+            kind: Some("swap".to_owned()),
+            max_gas_used,
+            ..RunTx::mock(n)
+        }
+    }
+
+    #[test]
+    fn lists_txs_exceeding_max_gas_used() {
+        let txs = vec![
+            run_tx(1, 120_000, Some(100_000)),
+            run_tx(2, 100_000, Some(100_000)),
+            run_tx(3, 500_000, None),
+            run_tx(4, 150_000, Some(100_000)),
+        ];
+        let violations = GasViolation::build_all(&txs);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].tx_hash, TxHash::repeat_byte(4).to_string());
+        assert_eq!(violations[0].gas_used, 150_000);
+        assert_eq!(violations[1].tx_hash, TxHash::repeat_byte(1).to_string());
+        assert_eq!(violations[1].max_gas_used, 100_000);
+        assert_eq!(violations[1].kind, "swap");
+    }
+
+    #[test]
+    fn checks_latency_slos_per_kind() {
+        let tx = |n: u8, blocks: u64, kind: &str| RunTx {
+            sent_block: Some(10 - n as u64 - blocks),
+            kind: Some(kind.to_owned()),
+            ..run_tx(n, 21_000, None)
+        };
+        let txs = vec![
+            tx(1, 1, "oracle"),
+            tx(2, 2, "oracle"),
+            tx(3, 1, "oracle"),
+            tx(4, 4, "oracle"),
+            tx(5, 3, "swap"),
+        ];
+        let slo = |kind: &str, max_blocks, percentile| LatencySlo {
+            kind: kind.to_owned(),
+            max_blocks: Some(max_blocks),
+            max_ms: None,
+            percentile,
+        };
+        let slos = vec![
+            slo("oracle", 2, None),
+            slo("oracle", 2, Some(75.0)),
+            slo("swap", 3, None),
+            slo("mint", 1, None),
+        ];
+        let summaries = SloSummary::build_run(1, &slos, &txs);
+        assert_eq!(summaries[0].target, "p100 <= 2 blocks");
+        assert_eq!(summaries[0].observed, "4 blocks");
+        assert!(!summaries[0].passed);
+        assert_eq!(summaries[1].observed, "2 blocks");
+        assert!(summaries[1].passed);
+        assert_eq!(summaries[2].num_txs, 1);
+        assert!(summaries[2].passed);
+        assert_eq!(summaries[3].observed, "no txs landed");
+        assert!(!summaries[3].passed);
+    }
+}
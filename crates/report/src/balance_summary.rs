@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::primitives::{utils::format_ether, Address, U256};
+use contender_core::db::AccountBalance;
+use serde::{Deserialize, Serialize};
+
+/// Change in one pool's balance over a run.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct BalanceSummary {
+    pub run_id: u64,
+    pub pool: String,
+    pub num_accounts: usize,
+    /// Sum of the pool's balances at the start of the run (in ETH).
+    pub start_eth: String,
+    /// Sum of the pool's balances at the end of the run (in ETH).
+    pub end_eth: String,
+    /// `end_eth - start_eth`, prefixed with its sign.
+    pub delta_eth: String,
+}
+
+impl BalanceSummary {
+    /// Sums the balances taken at the start & end of a run per pool.
+    /// Accounts missing from either snapshot are skipped.
+    pub fn build_run(run_id: u64, start: &[AccountBalance], end: &[AccountBalance]) -> Vec<Self> {
+        let end_balances: HashMap<(&str, Address), U256> = end
+            .iter()
+            .map(|b| ((b.pool.as_str(), b.address), b.balance))
+            .collect();
+        // (num_accounts, start balance, end balance) per pool
+        let mut by_pool: BTreeMap<&str, (usize, U256, U256)> = BTreeMap::new();
+        for start_balance in start {
+            let Some(end_balance) =
+                end_balances.get(&(start_balance.pool.as_str(), start_balance.address))
+            else {
+                continue;
+            };
+            let entry = by_pool.entry(&start_balance.pool).or_default();
+            entry.0 += 1;
+            entry.1 += start_balance.balance;
+            entry.2 += end_balance;
+        }
+
+        by_pool
+            .into_iter()
+            .map(|(pool, (num_accounts, start, end))| Self {
+                run_id,
+                pool: pool.to_owned(),
+                num_accounts,
+                start_eth: format_ether(start),
+                end_eth: format_ether(end),
+                delta_eth: if end >= start {
+                    format!("+{}", format_ether(end - start))
+                } else {
+                    format!("-{}", format_ether(start - end))
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(pool: &str, n: u8, wei: u64) -> AccountBalance {
+        AccountBalance {
+            pool: pool.to_owned(),
+            address: Address::repeat_byte(n),
+            balance: U256::from(wei),
+        }
+    }
+
+    #[test]
+    fn sums_balance_delta_per_pool() {
+        let eth = 1_000_000_000_000_000_000;
+        let start = vec![
+            balance("admin", 1, 10 * eth),
+            balance("pool1", 2, eth),
+            balance("pool1", 3, eth),
+            // no end balance; skipped
+            balance("pool2", 4, eth),
+        ];
+        let end = vec![
+            balance("admin", 1, 11 * eth),
+            balance("pool1", 2, eth / 2),
+            balance("pool1", 3, eth / 4),
+        ];
+
+        let summaries = BalanceSummary::build_run(1, &start, &end);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].pool, "admin");
+        assert_eq!(summaries[0].delta_eth, "+1.000000000000000000");
+        assert_eq!(summaries[1].num_accounts, 2);
+        assert_eq!(summaries[1].start_eth, "2.000000000000000000");
+        assert_eq!(summaries[1].delta_eth, "-1.250000000000000000");
+    }
+}
@@ -0,0 +1,120 @@
+use contender_core::db::{DbOps, RunTx, SpamRun};
+
+use super::chart::{BaselineTrendChart, ChartFormat, DrawableChart, ReportChartId};
+
+/// Gas/sec of one run in a baseline series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselinePoint {
+    pub run_id: u64,
+    /// Time the run started, in milliseconds since the unix epoch.
+    pub timestamp: usize,
+    pub scenario_name: String,
+    /// Client the run was sent to; unknown for runs recorded before it was collected.
+    pub client_version: Option<String>,
+    pub gas_per_second: u128,
+}
+
+impl BaselinePoint {
+    /// The run is timed from its first tx being sent to its last tx landing.
+    pub fn build(run: &SpamRun, txs: &[RunTx], client_version: Option<String>) -> Self {
+        let start = txs.iter().map(|tx| tx.start_timestamp).min();
+        let end = txs.iter().map(|tx| tx.end_timestamp).max();
+        let total_ms = match (start, end) {
+            (Some(start), Some(end)) => end.saturating_sub(start).max(1) as u128,
+            _ => 1000,
+        };
+        let total_gas = txs.iter().map(|tx| tx.gas_used).sum::<u128>();
+        Self {
+            run_id: run.id,
+            timestamp: run.timestamp,
+            scenario_name: run.scenario_name.to_owned(),
+            client_version,
+            gas_per_second: total_gas * 1000 / total_ms,
+        }
+    }
+}
+
+/// Prints gas/sec for each run in a baseline series and charts it over time, per scenario, in
+/// `out_dir`.
+pub fn report_baseline(
+    db: &impl DbOps,
+    series: &str,
+    out_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let run_ids = db.get_baseline_runs(series)?;
+    let (Some(&first_run_id), Some(&last_run_id)) = (run_ids.first(), run_ids.last()) else {
+        println!("No runs found in baseline series \"{}\".", series);
+        return Ok(());
+    };
+
+    let mut points = vec![];
+    for id in run_ids {
+        let Some(run) = db.get_run(id)? else {
+            continue;
+        };
+        let client_version = db.get_run_metadata(id)?.map(|m| m.client_version);
+        points.push(BaselinePoint::build(
+            &run,
+            &db.get_run_txs(id)?,
+            client_version,
+        ));
+    }
+
+    println!(
+        "{:>6}  {:<17} {:<30} {:<24} {:>14}",
+        "run", "date", "scenario", "client", "gas/sec"
+    );
+    for point in &points {
+        let date = chrono::DateTime::from_timestamp_millis(point.timestamp as i64)
+            .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        println!(
+            "{:>6}  {:<17} {:<30} {:<24} {:>14}",
+            point.run_id,
+            date,
+            point.scenario_name,
+            point.client_version.as_deref().unwrap_or("-"),
+            point.gas_per_second
+        );
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+    let filename = ReportChartId::BaselineTrend(series.to_owned()).filename(
+        out_dir,
+        first_run_id,
+        last_run_id,
+        ChartFormat::Png,
+    );
+    BaselineTrendChart::build(&points).draw(&filename, ChartFormat::Png)?;
+    println!("saved chart to {}", filename);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_run_gas_per_second() {
+        let run = SpamRun {
+            id: 4,
+            timestamp: 1_700_000_000_000,
+            tx_count: 3,
+            scenario_name: "uniV2.toml".to_owned(),
+            rpc_url: "http://localhost:8545".to_owned(),
+        };
+        let tx = |start_timestamp, end_timestamp| RunTx {
+            gas_used: 100_000,
+            ..RunTx::mock(0).with_times(start_timestamp, end_timestamp)
+        };
+        let point = BaselinePoint::build(
+            &run,
+            &[tx(10_000, 12_000), tx(11_000, 14_000), tx(12_000, 13_500)],
+            None,
+        );
+        assert_eq!(point.run_id, 4);
+        assert_eq!(point.gas_per_second, 75_000);
+        assert_eq!(BaselinePoint::build(&run, &[], None).gas_per_second, 0);
+    }
+}
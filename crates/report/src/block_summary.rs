@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use alloy::rpc::types::Block;
+use contender_core::db::RunTx;
+use csv::WriterBuilder;
+use serde::Serialize;
+
+/// Per-block aggregates exported alongside the per-tx CSVs.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BlockSummary {
+    pub block_number: u64,
+    pub timestamp: u64,
+    /// Total number of txs in the block.
+    pub num_txs: usize,
+    /// Number of txs in the block that were sent by the run(s) in this report.
+    pub num_run_txs: usize,
+    pub gas_used: u128,
+    pub gas_limit: u128,
+    pub base_fee_per_gas: Option<u128>,
+    pub blob_gas_used: Option<u128>,
+}
+
+impl BlockSummary {
+    pub fn build_all(blocks: &[Block], run_txs: &[RunTx]) -> Vec<Self> {
+        let mut run_txs_per_block = HashMap::<u64, usize>::new();
+        for tx in run_txs {
+            *run_txs_per_block.entry(tx.block_number).or_default() += 1;
+        }
+
+        blocks
+            .iter()
+            .map(|block| Self {
+                block_number: block.header.number,
+                timestamp: block.header.timestamp,
+                num_txs: block.transactions.len(),
+                num_run_txs: run_txs_per_block
+                    .get(&block.header.number)
+                    .copied()
+                    .unwrap_or_default(),
+                gas_used: block.header.gas_used,
+                gas_limit: block.header.gas_limit,
+                base_fee_per_gas: block.header.base_fee_per_gas,
+                blob_gas_used: block.header.blob_gas_used,
+            })
+            .collect()
+    }
+}
+
+/// Saves per-block aggregates to `out_path` as CSV.
+pub fn save_block_csv(
+    out_path: &str,
+    summaries: &[BlockSummary],
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Exporting block summary to {:?}", out_path);
+    let mut writer = WriterBuilder::new().has_headers(true).from_path(out_path)?;
+    for summary in summaries {
+        writer.serialize(summary)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_run_txs_per_block() {
+        let mut block = Block::default();
+        block.header.number = 7;
+        block.header.gas_used = 42_000;
+        let run_tx = |n: u8, block_number: u64| RunTx {
+            block_number,
+            ..RunTx::mock(n)
+        };
+
+        let summaries =
+            BlockSummary::build_all(&[block], &[run_tx(1, 7), run_tx(2, 7), run_tx(3, 8)]);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].block_number, 7);
+        assert_eq!(summaries[0].num_run_txs, 2);
+        assert_eq!(summaries[0].gas_used, 42_000);
+    }
+}
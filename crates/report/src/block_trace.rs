@@ -0,0 +1,459 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::cache::{CachedBlock, TraceCache, MAX_CACHE_BYTES};
+use crate::chart::OpcodeGasChart;
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::ext::DebugApi;
+use alloy::rpc::types::Block;
+use alloy::{
+    primitives::{Address, TxHash, B256},
+    providers::Provider,
+    rpc::types::{
+        trace::geth::{
+            AccountState, GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
+            GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, PreStateFrame,
+            PreStateMode,
+        },
+        TransactionReceipt,
+    },
+};
+
+use contender_core::error::ContenderError;
+use contender_core::{db::RunTx, generator::types::EthProvider};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TxTraceReceipt {
+    /// preState trace of the tx; `None` if the node doesn't support any tracing method.
+    pub trace: Option<GethTrace>,
+    pub receipt: TransactionReceipt,
+}
+
+impl TxTraceReceipt {
+    pub fn new(trace: Option<GethTrace>, receipt: TransactionReceipt) -> Self {
+        Self { trace, receipt }
+    }
+}
+
+/// The RPC method used to collect storage traces, in order of preference.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TraceMode {
+    /// `debug_traceBlockByNumber` with the preState tracer.
+    DebugTraceBlock,
+    /// `debug_traceTransaction` with the preState tracer, one request per tx.
+    DebugTraceTransaction,
+    /// `trace_replayBlockTransactions` (parity-style) with `stateDiff`.
+    /// Only slots that were changed are reported, so reads don't show up in the heatmap.
+    ParityStateDiff,
+    /// No tracing method is available; only receipts are collected.
+    ReceiptsOnly,
+}
+
+impl std::fmt::Display for TraceMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TraceMode::DebugTraceBlock => "debug_traceBlockByNumber (preState)",
+            TraceMode::DebugTraceTransaction => "debug_traceTransaction (preState)",
+            TraceMode::ParityStateDiff => "trace_replayBlockTransactions (stateDiff)",
+            TraceMode::ReceiptsOnly => "receipts only",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl TraceMode {
+    const FALLBACK_ORDER: [TraceMode; 4] = [
+        TraceMode::DebugTraceBlock,
+        TraceMode::DebugTraceTransaction,
+        TraceMode::ParityStateDiff,
+        TraceMode::ReceiptsOnly,
+    ];
+
+    pub fn has_traces(&self) -> bool {
+        *self != TraceMode::ReceiptsOnly
+    }
+}
+
+fn pre_state_tracing_options() -> GethDebugTracingOptions {
+    GethDebugTracingOptions {
+        config: GethDefaultTracingOptions::default(),
+        tracer: Some(GethDebugTracerType::BuiltInTracer(
+            GethDebugBuiltInTracerType::PreStateTracer,
+        )),
+        tracer_config: GethDebugTracerConfig::default(),
+        timeout: None,
+    }
+}
+
+/// Single tx result from `debug_traceBlockByNumber`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockTraceResult {
+    tx_hash: Option<TxHash>,
+    result: Option<GethTrace>,
+    error: Option<String>,
+}
+
+/// Single tx result from `trace_replayBlockTransactions`; only the fields we use.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayedTx {
+    transaction_hash: TxHash,
+    state_diff: Option<BTreeMap<Address, ParityAccountDiff>>,
+}
+
+#[derive(Deserialize)]
+struct ParityAccountDiff {
+    storage: BTreeMap<B256, serde_json::Value>,
+}
+
+/// Converts a parity-style state diff into a preState trace containing the touched slots,
+/// so it can be charted the same way as geth traces.
+fn state_diff_to_pre_state(diff: BTreeMap<Address, ParityAccountDiff>) -> GethTrace {
+    let accounts = diff
+        .into_iter()
+        .map(|(address, account)| {
+            let storage = account
+                .storage
+                .into_keys()
+                .map(|slot| (slot, B256::ZERO))
+                .collect();
+            (
+                address,
+                AccountState {
+                    storage,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+    GethTrace::PreStateTracer(PreStateFrame::Default(PreStateMode(accounts)))
+}
+
+/// Returns a trace for each tx in `block` using the given mode.
+async fn trace_block(
+    block: &Block,
+    mode: TraceMode,
+    rpc_client: &EthProvider,
+) -> Result<Vec<(TxHash, Option<GethTrace>)>, Box<dyn std::error::Error>> {
+    let tx_hashes = block.transactions.hashes().collect::<Vec<_>>();
+    let block_num = BlockNumberOrTag::Number(block.header.number);
+
+    let traces = match mode {
+        TraceMode::DebugTraceBlock => {
+            let results: Vec<BlockTraceResult> = rpc_client
+                .raw_request(
+                    "debug_traceBlockByNumber".into(),
+                    (block_num, pre_state_tracing_options()),
+                )
+                .await?;
+            if results.len() != tx_hashes.len() {
+                return Err(
+                    "debug_traceBlockByNumber returned an unexpected number of traces".into(),
+                );
+            }
+            results
+                .into_iter()
+                .zip(&tx_hashes)
+                .map(|(res, tx_hash)| {
+                    if let Some(err) = res.error {
+                        println!("failed to trace tx {:?}: {}", tx_hash, err);
+                    }
+                    (res.tx_hash.unwrap_or(*tx_hash), res.result)
+                })
+                .collect()
+        }
+        TraceMode::DebugTraceTransaction => {
+            let mut traces = vec![];
+            for tx_hash in tx_hashes {
+                println!("tracing tx {:?}", tx_hash);
+                let trace = rpc_client
+                    .debug_trace_transaction(tx_hash, pre_state_tracing_options())
+                    .await?;
+                traces.push((tx_hash, Some(trace)));
+            }
+            traces
+        }
+        TraceMode::ParityStateDiff => {
+            let results: Vec<ReplayedTx> = rpc_client
+                .raw_request(
+                    "trace_replayBlockTransactions".into(),
+                    (block_num, ["stateDiff"]),
+                )
+                .await?;
+            results
+                .into_iter()
+                .map(|tx| {
+                    (
+                        tx.transaction_hash,
+                        tx.state_diff.map(state_diff_to_pre_state),
+                    )
+                })
+                .collect()
+        }
+        TraceMode::ReceiptsOnly => tx_hashes.into_iter().map(|h| (h, None)).collect(),
+    };
+
+    Ok(traces)
+}
+
+/// Finds the first tracing method the node supports, using `block` as a probe.
+/// Returns the mode along with the probe block's traces.
+async fn probe_trace_mode(
+    block: &Block,
+    rpc_client: &EthProvider,
+) -> (TraceMode, Vec<(TxHash, Option<GethTrace>)>) {
+    for mode in TraceMode::FALLBACK_ORDER {
+        match trace_block(block, mode, rpc_client).await {
+            Ok(traces) => {
+                println!("collecting traces with {}", mode);
+                return (mode, traces);
+            }
+            Err(e) => println!("{} unavailable: {}", mode, e),
+        }
+    }
+    unreachable!("receipts-only mode never fails")
+}
+
+/// Fetches receipts for the given traces, ignoring txs without a (decodable) receipt.
+async fn get_receipts(
+    traces: Vec<(TxHash, Option<GethTrace>)>,
+    rpc_client: &EthProvider,
+) -> Vec<TxTraceReceipt> {
+    let mut trace_receipts = vec![];
+    for (tx_hash, trace) in traces {
+        // receipt might fail if we target a non-ETH chain
+        // so if it does fail, we just ignore it
+        let receipt = rpc_client.get_transaction_receipt(tx_hash).await;
+        if let Ok(receipt) = receipt {
+            if let Some(receipt) = receipt {
+                println!("got receipt for tx {:?}", tx_hash);
+                trace_receipts.push(TxTraceReceipt::new(trace, receipt));
+            } else {
+                println!("no receipt for tx {:?}", tx_hash);
+            }
+        } else {
+            println!("ignored receipt for tx {:?} (failed to decode)", tx_hash);
+        }
+    }
+    trace_receipts
+}
+
+/// Collects blocks, traces, and receipts for the block range spanned by `txs`.
+/// If `cache_dir` is set, blocks are served from the trace cache in it unless the chain has
+/// reorged since they were cached, and the least recently used blocks are pruned once it grows
+/// past 1 GiB. If `opcode_gas` is set, the blocks including `txs` are also traced with the struct
+/// logger to profile the gas `txs` spent per opcode; those traces are too large to cache.
+///
+/// The returned mode is the least-detailed tracing method used for any block.
+pub async fn get_block_trace_data(
+    txs: &[RunTx],
+    rpc_client: &EthProvider,
+    rpc_url: &str,
+    cache_dir: Option<&str>,
+    mut opcode_gas: Option<&mut OpcodeGasChart>,
+) -> Result<(Vec<TxTraceReceipt>, Vec<Block>, TraceMode), Box<dyn std::error::Error>> {
+    let cache = match cache_dir {
+        Some(cache_dir) => {
+            let genesis = rpc_client
+                .get_block_by_number(0.into(), false)
+                .await?
+                .ok_or("genesis block not found")?;
+            Some(TraceCache::open(cache_dir, rpc_url, genesis.header.hash)?)
+        }
+        None => None,
+    };
+
+    // find block range of txs
+    let (min_block, max_block) = txs.iter().fold((u64::MAX, 0), |(min, max), tx| {
+        (min.min(tx.block_number), max.max(tx.block_number))
+    });
+
+    // pad block range on each side
+    let block_padding = 3;
+    let min_block = min_block.saturating_sub(block_padding);
+    let max_block = max_block + block_padding;
+
+    let tx_hashes = txs.iter().map(|tx| tx.tx_hash).collect::<HashSet<_>>();
+    let mut all_blocks = vec![];
+    let mut all_traces = vec![];
+    let mut chosen_mode: Option<TraceMode> = None;
+    let mut worst_mode: Option<TraceMode> = None;
+
+    for block_num in min_block..=max_block {
+        // fetch the header first to check the cache against the canonical chain
+        let Some(header_block) = rpc_client
+            .get_block_by_number(block_num.into(), false)
+            .await?
+        else {
+            continue;
+        };
+
+        let cached = match cache
+            .as_ref()
+            .and_then(|cache| cache.load_block(block_num, header_block.header.hash))
+        {
+            Some(cached) => {
+                println!("using cached traces for block {}", block_num);
+                cached
+            }
+            None => {
+                let block = rpc_client
+                    .get_block_by_number(block_num.into(), true)
+                    .await?
+                    .ok_or(format!("block {} not found", block_num))?;
+                println!("read block {}", block.header.number);
+
+                let (trace_mode, traces) = match chosen_mode {
+                    Some(mode) => (
+                        mode,
+                        trace_block(&block, mode, rpc_client).await.map_err(|e| {
+                            ContenderError::GenericError(
+                                "failed to trace block:",
+                                format!("block={}, mode={}, error={}", block_num, mode, e),
+                            )
+                        })?,
+                    ),
+                    // empty blocks can't tell us which tracing methods work
+                    None if block.transactions.hashes().next().is_none() => {
+                        (TraceMode::ReceiptsOnly, vec![])
+                    }
+                    None => {
+                        let (mode, traces) = probe_trace_mode(&block, rpc_client).await;
+                        chosen_mode = Some(mode);
+                        (mode, traces)
+                    }
+                };
+
+                let cached = CachedBlock {
+                    traces: get_receipts(traces, rpc_client).await,
+                    block,
+                    trace_mode,
+                };
+                if let Some(cache) = &cache {
+                    cache.save_block(&cached)?;
+                }
+                cached
+            }
+        };
+
+        if let Some(opcode_gas) = opcode_gas.as_deref_mut() {
+            add_block_struct_logs(&cached.block, &tx_hashes, opcode_gas, rpc_client).await?;
+        }
+        if !cached.traces.is_empty() {
+            worst_mode = worst_mode.max(Some(cached.trace_mode));
+        }
+        all_traces.extend(cached.traces);
+        all_blocks.push(cached.block);
+    }
+
+    if let Some(cache_dir) = cache_dir {
+        if let Err(e) = TraceCache::prune(cache_dir, MAX_CACHE_BYTES) {
+            println!("failed to prune the trace cache: {}", e);
+        }
+    }
+
+    Ok((
+        all_traces,
+        all_blocks,
+        worst_mode.unwrap_or(TraceMode::ReceiptsOnly),
+    ))
+}
+
+/// Struct-logger options for opcode profiling. Memory, storage, and return data are omitted to
+/// keep traces small; the stack is needed to identify call targets.
+fn struct_log_tracing_options() -> GethDebugTracingOptions {
+    GethDebugTracingOptions {
+        config: GethDefaultTracingOptions {
+            enable_memory: Some(false),
+            disable_storage: Some(true),
+            disable_stack: Some(false),
+            enable_return_data: Some(false),
+            ..Default::default()
+        },
+        tracer: None,
+        tracer_config: GethDebugTracerConfig::default(),
+        timeout: None,
+    }
+}
+
+/// Traces `block` with the struct logger and adds the steps of the txs in `tx_hashes` to
+/// `opcode_gas`. The block is traced in one request; each tx's steps are aggregated & dropped
+/// before the next is read.
+async fn add_block_struct_logs(
+    block: &Block,
+    tx_hashes: &HashSet<TxHash>,
+    opcode_gas: &mut OpcodeGasChart,
+    rpc_client: &EthProvider,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let block_tx_hashes = block.transactions.hashes().collect::<Vec<_>>();
+    if !block_tx_hashes.iter().any(|hash| tx_hashes.contains(hash)) {
+        return Ok(());
+    }
+    println!("tracing opcodes in block {}", block.header.number);
+    let results: Vec<BlockTraceResult> = rpc_client
+        .raw_request(
+            "debug_traceBlockByNumber".into(),
+            (
+                BlockNumberOrTag::Number(block.header.number),
+                struct_log_tracing_options(),
+            ),
+        )
+        .await
+        .map_err(|e| {
+            ContenderError::with_err(
+                e,
+                "debug_traceBlockByNumber failed. Make sure geth-style tracing is enabled on your node.",
+            )
+        })?;
+    if results.len() != block_tx_hashes.len() {
+        return Err("debug_traceBlockByNumber returned an unexpected number of traces".into());
+    }
+    for (res, tx_hash) in results.into_iter().zip(block_tx_hashes) {
+        let tx_hash = res.tx_hash.unwrap_or(tx_hash);
+        if !tx_hashes.contains(&tx_hash) {
+            continue;
+        }
+        match (res.result, res.error) {
+            (Some(GethTrace::Default(frame)), _) => opcode_gas.add_struct_logs(&frame.struct_logs),
+            (_, Some(err)) => println!("failed to trace opcodes of tx {:?}: {}", tx_hash, err),
+            _ => return Err(format!("unexpected trace format for tx {:?}", tx_hash).into()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_parity_state_diff_to_pre_state() {
+        let replayed: ReplayedTx = serde_json::from_value(serde_json::json!({
+            "output": "0x",
+            "stateDiff": {
+                "0x1111111111111111111111111111111111111111": {
+                    "balance": "=",
+                    "code": "=",
+                    "nonce": "=",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": {
+                            "*": { "from": "0x00", "to": "0x01" }
+                        }
+                    }
+                }
+            },
+            "trace": [],
+            "vmTrace": null,
+            "transactionHash": "0x2222222222222222222222222222222222222222222222222222222222222222"
+        }))
+        .unwrap();
+
+        let trace = state_diff_to_pre_state(replayed.state_diff.unwrap());
+        let frame = trace.try_into_pre_state_frame().unwrap();
+        let accounts = &frame.as_default().unwrap().0;
+        let account = &accounts[&Address::repeat_byte(0x11)];
+        assert_eq!(account.storage.len(), 1);
+        assert!(account.storage.contains_key(&B256::with_last_byte(1)));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use contender_core::db::{BundleSubmission, RunTx};
+use serde::{Deserialize, Serialize};
+
+use super::{assertions::at_percentile, block_trace::TxTraceReceipt};
+
+/// Per-block bundle stats displayed in the report's bundle table.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct BundleSummary {
+    /// First block targeted by the bundles in this row.
+    pub target_block: u64,
+    pub num_submitted: usize,
+    /// Bundles whose txs all landed in the same block.
+    pub num_landed: usize,
+    /// Average number of times each bundle was sent to the builder.
+    pub avg_submissions: String,
+    /// Landed bundles containing at least one reverted tx.
+    pub num_reverted: usize,
+}
+
+impl BundleSummary {
+    /// Summarizes `bundles` by target block. Landed txs are taken from `run_txs`, and reverts are
+    /// counted from the receipts in `traces`.
+    pub fn build_all(
+        bundles: &[BundleSubmission],
+        run_txs: &[RunTx],
+        traces: &[TxTraceReceipt],
+    ) -> Vec<Self> {
+        let landed_blocks = run_txs
+            .iter()
+            .map(|tx| (tx.tx_hash, tx.block_number))
+            .collect::<HashMap<_, _>>();
+        let statuses = traces
+            .iter()
+            .map(|t| (t.receipt.transaction_hash, t.receipt.status()))
+            .collect::<HashMap<_, _>>();
+
+        let mut by_block: BTreeMap<u64, Vec<&BundleSubmission>> = BTreeMap::new();
+        for bundle in bundles {
+            by_block
+                .entry(bundle.target_block)
+                .or_default()
+                .push(bundle);
+        }
+
+        by_block
+            .into_iter()
+            .map(|(target_block, bundles)| {
+                let landed = bundles
+                    .iter()
+                    .filter(|bundle| {
+                        let blocks = bundle
+                            .tx_hashes
+                            .iter()
+                            .map(|h| landed_blocks.get(h))
+                            .collect::<Vec<_>>();
+                        !blocks.is_empty()
+                            && blocks[0].is_some()
+                            && blocks.iter().all(|b| *b == blocks[0])
+                    })
+                    .collect::<Vec<_>>();
+                let num_reverted = landed
+                    .iter()
+                    .filter(|bundle| {
+                        bundle
+                            .tx_hashes
+                            .iter()
+                            .any(|h| statuses.get(h) == Some(&false))
+                    })
+                    .count();
+                let total_submissions = bundles.iter().map(|b| b.num_submissions).sum::<u64>();
+                Self {
+                    target_block,
+                    num_submitted: bundles.len(),
+                    num_landed: landed.len(),
+                    avg_submissions: format!(
+                        "{:.2}",
+                        total_submissions as f64 / bundles.len() as f64
+                    ),
+                    num_reverted,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Inclusion stats of landed txs by how they were sent: to the builder in bundles, or to the
+/// public mempool. Displayed when a run used both, to compare their inclusion latency.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct RouteSummary {
+    pub route: String,
+    pub num_landed: usize,
+    /// In milliseconds.
+    pub p50_time_to_inclusion: u64,
+    /// In milliseconds.
+    pub p95_time_to_inclusion: u64,
+    /// Average blocks from sending to inclusion; "-" for runs recorded before it was tracked.
+    pub avg_blocks_to_inclusion: String,
+}
+
+impl RouteSummary {
+    /// Splits landed `run_txs` into those sent in `bundles` and the rest. Empty unless txs of both
+    /// routes landed.
+    pub fn build_all(bundles: &[BundleSubmission], run_txs: &[RunTx]) -> Vec<Self> {
+        let bundled = bundles
+            .iter()
+            .flat_map(|bundle| &bundle.tx_hashes)
+            .collect::<HashSet<_>>();
+        let (builder, mempool): (Vec<&RunTx>, Vec<&RunTx>) =
+            run_txs.iter().partition(|tx| bundled.contains(&tx.tx_hash));
+        if builder.is_empty() || mempool.is_empty() {
+            return vec![];
+        }
+        [("builder", builder), ("mempool", mempool)]
+            .into_iter()
+            .map(|(route, txs)| {
+                let times = txs
+                    .iter()
+                    .map(|tx| tx.time_to_inclusion_ms())
+                    .collect::<Vec<_>>();
+                let blocks = txs
+                    .iter()
+                    .filter_map(|tx| tx.blocks_to_inclusion())
+                    .collect::<Vec<_>>();
+                Self {
+                    route: route.to_owned(),
+                    num_landed: txs.len(),
+                    p50_time_to_inclusion: at_percentile(times.to_owned(), 50.0)
+                        .unwrap_or_default(),
+                    p95_time_to_inclusion: at_percentile(times, 95.0).unwrap_or_default(),
+                    avg_blocks_to_inclusion: if blocks.is_empty() {
+                        "-".to_owned()
+                    } else {
+                        format!(
+                            "{:.2}",
+                            blocks.iter().sum::<u64>() as f64 / blocks.len() as f64
+                        )
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{TxHash, B256};
+
+    fn bundle(target_block: u64, num_submissions: u64, txs: &[u8]) -> BundleSubmission {
+        BundleSubmission {
+            bundle_hash: B256::ZERO,
+            target_block,
+            num_submissions,
+            tx_hashes: txs.iter().map(|n| TxHash::repeat_byte(*n)).collect(),
+        }
+    }
+
+    fn run_tx(n: u8, block_number: u64) -> RunTx {
+        RunTx {
+            block_number,
+            ..RunTx::mock(n)
+        }
+    }
+
+    #[test]
+    fn counts_landed_bundles_per_target_block() {
+        let bundles = vec![
+            bundle(10, 3, &[1, 2]),
+            // only one of its txs landed
+            bundle(10, 2, &[3, 4]),
+            // txs landed in different blocks, so the bundle wasn't included atomically
+            bundle(11, 3, &[5, 6]),
+        ];
+        let run_txs = vec![
+            run_tx(1, 11),
+            run_tx(2, 11),
+            run_tx(3, 11),
+            run_tx(5, 11),
+            run_tx(6, 12),
+        ];
+        let summaries = BundleSummary::build_all(&bundles, &run_txs, &[]);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].target_block, 10);
+        assert_eq!(summaries[0].num_submitted, 2);
+        assert_eq!(summaries[0].num_landed, 1);
+        assert_eq!(summaries[0].avg_submissions, "2.50");
+        assert_eq!(summaries[1].num_landed, 0);
+    }
+
+    #[test]
+    fn compares_builder_and_mempool_inclusion() {
+        let bundles = vec![bundle(10, 3, &[1]), bundle(10, 3, &[2])];
+        let mut run_txs = vec![run_tx(1, 11), run_tx(2, 11), run_tx(3, 12)];
+        run_txs[2].end_timestamp = 3000;
+        run_txs[2].sent_block = Some(10);
+        let summaries = RouteSummary::build_all(&bundles, &run_txs);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].route, "builder");
+        assert_eq!(summaries[0].num_landed, 2);
+        assert_eq!(summaries[0].p95_time_to_inclusion, 1000);
+        assert_eq!(summaries[0].avg_blocks_to_inclusion, "-");
+        assert_eq!(summaries[1].route, "mempool");
+        assert_eq!(summaries[1].p50_time_to_inclusion, 3000);
+        assert_eq!(summaries[1].avg_blocks_to_inclusion, "2.00");
+        // nothing to compare without mempool txs
+        assert!(RouteSummary::build_all(&bundles, &run_txs[..2]).is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use alloy::{
+    hex::ToHexExt,
+    primitives::{keccak256, B256},
+    rpc::types::Block,
+};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+use super::block_trace::{TraceMode, TxTraceReceipt};
+
+/// Most disk space the trace caches in a cache dir may take up; see [`TraceCache::prune`].
+pub const MAX_CACHE_BYTES: u64 = 1 << 30;
+
+/// Trace data for a single block.
+#[derive(Serialize, Deserialize)]
+pub struct CachedBlock {
+    pub block: Block,
+    pub trace_mode: TraceMode,
+    pub traces: Vec<TxTraceReceipt>,
+}
+
+/// Per-block trace cache for a single chain, as seen by a single RPC endpoint.
+///
+/// Each block is saved to its own file as soon as it's traced, so an interrupted report
+/// picks up where it left off. Callers should compare the cached block hash against the
+/// chain before using an entry; see [`TraceCache::load_block`].
+pub struct TraceCache {
+    dir: String,
+}
+
+impl TraceCache {
+    /// Opens (or creates) the cache for the chain with `genesis_hash` at `rpc_url`, under
+    /// `cache_dir`, which holds the caches of every chain & endpoint.
+    pub fn open(
+        cache_dir: &str,
+        rpc_url: &str,
+        genesis_hash: B256,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = format!("{}/{}", cache_dir, cache_key(rpc_url, genesis_hash));
+        Self::in_dir(dir)
+    }
+
+    fn in_dir(dir: String) -> Result<Self, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn block_path(&self, block_num: u64) -> String {
+        format!("{}/{}.json", self.dir, block_num)
+    }
+
+    /// Returns the cached data for `block_num` if it was traced from the block with `block_hash`,
+    /// marking it as recently used. Entries from a different block (i.e. the chain reorged) are
+    /// deleted.
+    pub fn load_block(&self, block_num: u64, block_hash: B256) -> Option<CachedBlock> {
+        let path = self.block_path(block_num);
+        let file = std::fs::File::options()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .ok()?;
+        let cached: Option<CachedBlock> = serde_json::from_reader(&file).ok();
+        match cached {
+            Some(cached) if cached.block.header.hash == block_hash => {
+                file.set_modified(SystemTime::now()).ok();
+                Some(cached)
+            }
+            _ => {
+                println!("invalidating cached traces for block {}", block_num);
+                std::fs::remove_file(&path).ok();
+                None
+            }
+        }
+    }
+
+    pub fn save_block(&self, cached: &CachedBlock) -> Result<(), Box<dyn std::error::Error>> {
+        // write to a temp file first so an interruption never leaves a partial entry behind
+        let path = self.block_path(cached.block.header.number);
+        let tmp_path = format!("{}.tmp", path);
+        let file = std::fs::File::create(&tmp_path)?;
+        serde_json::to_writer(file, cached)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Deletes the least recently used blocks in `cache_dir`, across every chain & endpoint,
+    /// until the caches in it take up at most `max_bytes`. Caches left empty are removed.
+    pub fn prune(cache_dir: &str, max_bytes: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let mut total_bytes = 0;
+        // (last used, size, path) of every cached block
+        let mut entries = vec![];
+        for chain_dir in std::fs::read_dir(cache_dir)? {
+            let chain_dir = chain_dir?.path();
+            if !chain_dir.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&chain_dir)? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if metadata.is_file() {
+                    total_bytes += metadata.len();
+                    entries.push((metadata.modified()?, metadata.len(), entry.path()));
+                }
+            }
+        }
+
+        entries.sort();
+        for (_, len, path) in entries {
+            if total_bytes <= max_bytes {
+                break;
+            }
+            std::fs::remove_file(path)?;
+            total_bytes -= len;
+        }
+
+        for chain_dir in std::fs::read_dir(cache_dir)? {
+            let chain_dir = chain_dir?.path();
+            if chain_dir.is_dir() && std::fs::read_dir(&chain_dir)?.next().is_none() {
+                std::fs::remove_dir(chain_dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Identifies a chain as seen by a specific RPC endpoint.
+fn cache_key(rpc_url: &str, genesis_hash: B256) -> String {
+    keccak256(format!("{}:{}", rpc_url, genesis_hash)).encode_hex()[..16].to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn cache_key_depends_on_rpc_url_and_genesis() {
+        let genesis = B256::repeat_byte(1);
+        let key = cache_key("http://localhost:8545", genesis);
+        assert_eq!(key, cache_key("http://localhost:8545", genesis));
+        assert_ne!(key, cache_key("http://localhost:9545", genesis));
+        assert_ne!(
+            key,
+            cache_key("http://localhost:8545", B256::repeat_byte(2))
+        );
+    }
+
+    #[test]
+    fn invalidates_missing_and_corrupt_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = TraceCache::in_dir(temp_dir.path().to_str().unwrap().to_owned()).unwrap();
+        assert!(cache.load_block(1, B256::ZERO).is_none());
+
+        std::fs::write(cache.block_path(2), "not json").unwrap();
+        assert!(cache.load_block(2, B256::ZERO).is_none());
+        assert!(!std::path::Path::new(&cache.block_path(2)).exists());
+    }
+
+    #[test]
+    fn prunes_least_recently_used_blocks() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().to_str().unwrap();
+        let node1 = TraceCache::open(cache_dir, "http://localhost:8545", B256::ZERO).unwrap();
+        let node2 = TraceCache::open(cache_dir, "http://localhost:9545", B256::ZERO).unwrap();
+        let write_block = |cache: &TraceCache, block_num: u64, secs_since_used: u64| {
+            let path = cache.block_path(block_num);
+            std::fs::write(&path, [0u8; 100]).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(secs_since_used))
+                .unwrap();
+        };
+        write_block(&node1, 1, 30);
+        write_block(&node1, 2, 10);
+        write_block(&node2, 1, 20);
+
+        TraceCache::prune(cache_dir, 250).unwrap();
+        assert!(!std::path::Path::new(&node1.block_path(1)).exists());
+        assert!(std::path::Path::new(&node1.block_path(2)).exists());
+        assert!(std::path::Path::new(&node2.block_path(1)).exists());
+
+        TraceCache::prune(cache_dir, 100).unwrap();
+        assert!(std::path::Path::new(&node1.block_path(2)).exists());
+        assert!(!std::path::Path::new(&node2.dir).exists());
+    }
+}
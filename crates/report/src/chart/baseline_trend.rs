@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    element::{Circle, PathElement},
+    series::{LineSeries, PointSeries},
+    style::{Color, Palette, Palette99, RGBColor, BLACK, WHITE},
+};
+
+use super::DrawableChart;
+use crate::{baseline::BaselinePoint, util::abbreviate_num};
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Gas/sec of each run in a baseline series over time, with a series per scenario.
+pub struct BaselineTrendChart {
+    /// Maps scenario name to (days since the series' first run, gas/sec) points.
+    series: BTreeMap<String, Vec<(f64, u128)>>,
+}
+
+impl BaselineTrendChart {
+    pub fn build(points: &[BaselinePoint]) -> Self {
+        let start = points.iter().map(|p| p.timestamp).min().unwrap_or_default();
+        let mut series: BTreeMap<String, Vec<(f64, u128)>> = BTreeMap::new();
+        for point in points {
+            let days = (point.timestamp - start) as f64 / MS_PER_DAY;
+            series
+                .entry(point.scenario_name.to_owned())
+                .or_default()
+                .push((days, point.gas_per_second));
+        }
+        for points in series.values_mut() {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        Self { series }
+    }
+}
+
+impl DrawableChart for BaselineTrendChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let points = self.series.values().flatten();
+        let max_days = points.clone().map(|p| p.0).fold(0.0, f64::max).max(1.0);
+        let max_gas = points.map(|p| p.1).max().unwrap_or_default().max(1);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(60)
+            .y_label_area_size(80)
+            .build_cartesian_2d(0.0..max_days, 0..max_gas + max_gas / 10)?;
+
+        chart
+            .configure_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("Days Since First Run")
+            .y_desc("Gas Per Second")
+            .y_label_formatter(&|y| abbreviate_num(*y))
+            .draw()?;
+
+        for (i, (scenario, points)) in self.series.iter().enumerate() {
+            let color = Palette99::pick(i);
+            let style = color.stroke_width(2);
+            chart
+                .draw_series(LineSeries::new(points.iter().copied(), style))?
+                .label(scenario)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], style));
+            chart.draw_series(PointSeries::of_element(
+                points.iter().copied(),
+                4,
+                color.filled(),
+                &|coord, size, style| Circle::new(coord, size, style),
+            ))?;
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_series_per_scenario() {
+        let point = |run_id, timestamp, scenario: &str, gas_per_second| BaselinePoint {
+            run_id,
+            timestamp,
+            scenario_name: scenario.to_owned(),
+            client_version: None,
+            gas_per_second,
+        };
+        let chart = BaselineTrendChart::build(&[
+            point(3, 1000 + 2 * 86_400_000, "uniV2.toml", 900),
+            point(1, 1000, "uniV2.toml", 1000),
+            point(2, 1000 + 43_200_000, "fill-block", 5000),
+        ]);
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series["uniV2.toml"], vec![(0.0, 1000), (2.0, 900)]);
+        assert_eq!(chart.series["fill-block"], vec![(0.5, 5000)]);
+    }
+}
@@ -0,0 +1,106 @@
+use super::ChartFormat;
+
+pub enum ReportChartId {
+    Heatmap,
+    /// Heatmap of storage updates to the named contract.
+    ContractHeatmap(String),
+    GasPerBlock,
+    TimeToInclusion,
+    /// Time for the node to acknowledge each tx's send call.
+    AckLatency,
+    TxGasUsed,
+    OpcodeGas,
+    PriorityFee,
+    RpcLatency,
+    /// Time to inclusion, with a series per RPC endpoint.
+    EndpointTimeToInclusion,
+    EndpointGasPerSecond,
+    EndpointErrorRate,
+    /// Gas/sec over time of the runs in the named baseline series.
+    BaselineTrend(String),
+}
+
+impl std::fmt::Display for ReportChartId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ReportChartId::Heatmap => "heatmap".to_owned(),
+            ReportChartId::ContractHeatmap(name) => format!("heatmap_{}", name),
+            ReportChartId::GasPerBlock => "gas_per_block".to_owned(),
+            ReportChartId::TimeToInclusion => "time_to_inclusion".to_owned(),
+            ReportChartId::AckLatency => "ack_latency".to_owned(),
+            ReportChartId::TxGasUsed => "tx_gas_used".to_owned(),
+            ReportChartId::OpcodeGas => "opcode_gas".to_owned(),
+            ReportChartId::PriorityFee => "priority_fee".to_owned(),
+            ReportChartId::RpcLatency => "rpc_latency".to_owned(),
+            ReportChartId::EndpointTimeToInclusion => "endpoint_time_to_inclusion".to_owned(),
+            ReportChartId::EndpointGasPerSecond => "endpoint_gas_per_second".to_owned(),
+            ReportChartId::EndpointErrorRate => "endpoint_error_rate".to_owned(),
+            ReportChartId::BaselineTrend(series) => format!("baseline_{}", series),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ReportChartId {
+    /// Path to draw the chart to in `out_dir`.
+    pub fn filename(
+        &self,
+        out_dir: &str,
+        start_run_id: u64,
+        end_run_id: u64,
+        format: ChartFormat,
+    ) -> String {
+        self.data_filename(out_dir, start_run_id, end_run_id, format.extension())
+    }
+
+    /// Path to export the chart's data to in `out_dir`, with the given file extension.
+    pub fn data_filename(
+        &self,
+        out_dir: &str,
+        start_run_id: u64,
+        end_run_id: u64,
+        extension: &str,
+    ) -> String {
+        format!(
+            "{}/{}_run-{}-{}.{}",
+            out_dir, self, start_run_id, end_run_id, extension
+        )
+    }
+
+    pub fn proper_name(&self) -> String {
+        match self {
+            ReportChartId::Heatmap => "Storage Slot Heatmap".to_owned(),
+            ReportChartId::ContractHeatmap(name) => format!("Storage Slot Heatmap ({})", name),
+            ReportChartId::GasPerBlock => "Gas Per Block".to_owned(),
+            ReportChartId::TimeToInclusion => "Time To Inclusion".to_owned(),
+            ReportChartId::AckLatency => "RPC Ack Latency".to_owned(),
+            ReportChartId::TxGasUsed => "Tx Gas Used".to_owned(),
+            ReportChartId::OpcodeGas => "Gas Used By Opcode".to_owned(),
+            ReportChartId::PriorityFee => "Priority Fee vs. Time To Inclusion".to_owned(),
+            ReportChartId::RpcLatency => "RPC Latency (p50/p95)".to_owned(),
+            ReportChartId::EndpointTimeToInclusion => "Time To Inclusion By Endpoint".to_owned(),
+            ReportChartId::EndpointGasPerSecond => "Gas Per Second By Endpoint".to_owned(),
+            ReportChartId::EndpointErrorRate => "Error Rate By Endpoint".to_owned(),
+            ReportChartId::BaselineTrend(series) => {
+                format!("Gas Per Second Over Time ({})", series)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_charts_in_out_dir() {
+        assert_eq!(
+            ReportChartId::GasPerBlock.filename("/srv/reports", 3, 5, ChartFormat::Svg),
+            "/srv/reports/gas_per_block_run-3-5.svg"
+        );
+        assert_eq!(
+            ReportChartId::Heatmap.data_filename("out", 1, 1, "csv"),
+            "out/heatmap_run-1-1.csv"
+        );
+    }
+}
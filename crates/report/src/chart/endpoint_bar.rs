@@ -0,0 +1,89 @@
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    prelude::{IntoSegmentedCoord, SegmentValue},
+    series::Histogram,
+    style::{Color, Palette, Palette99, RGBColor},
+};
+
+use super::DrawableChart;
+use crate::util::abbreviate_num;
+
+/// Bar chart comparing a single metric across RPC endpoints.
+pub struct EndpointBarChart {
+    y_desc: String,
+    /// (endpoint, value) pairs, in display order.
+    values: Vec<(String, f64)>,
+}
+
+impl EndpointBarChart {
+    pub fn new(y_desc: impl AsRef<str>, values: Vec<(String, f64)>) -> Self {
+        Self {
+            y_desc: y_desc.as_ref().to_owned(),
+            values,
+        }
+    }
+}
+
+impl DrawableChart for EndpointBarChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let max_value = self
+            .values
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(0.0, f64::max)
+            .max(1.0);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(80)
+            .build_cartesian_2d(
+                (0..self.values.len()).into_segmented(),
+                0.0..max_value * 1.1,
+            )?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("RPC Endpoint")
+            .x_label_formatter(&|x| match x {
+                SegmentValue::CenterOf(i) => self
+                    .values
+                    .get(*i)
+                    .map(|(endpoint, _)| endpoint.to_owned())
+                    .unwrap_or_default(),
+                _ => String::default(),
+            })
+            .y_desc(&self.y_desc)
+            .y_label_formatter(&|v| {
+                if *v >= 1000.0 {
+                    abbreviate_num(*v as u128)
+                } else {
+                    format!("{:.1}", v)
+                }
+            })
+            .draw()?;
+
+        for (i, (_, value)) in self.values.iter().enumerate() {
+            chart.draw_series(
+                Histogram::vertical(&chart)
+                    .style(Palette99::pick(i).filled())
+                    .margin(20)
+                    .data([(i, *value)]),
+            )?;
+        }
+
+        Ok(())
+    }
+}
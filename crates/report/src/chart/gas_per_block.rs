@@ -2,9 +2,9 @@ use std::collections::BTreeMap;
 
 use alloy::rpc::types::Block;
 use plotters::{
-    backend::BitMapBackend,
     chart::ChartBuilder,
-    drawing::IntoDrawingArea,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
     prelude::Circle,
     series::LineSeries,
     style::{
@@ -13,7 +13,8 @@ use plotters::{
     },
 };
 
-use crate::commands::report::util::abbreviate_num;
+use super::DrawableChart;
+use crate::util::abbreviate_num;
 
 pub struct GasPerBlockChart {
     /// Maps `block_num` to `gas_used`
@@ -46,9 +47,16 @@ impl GasPerBlockChart {
     fn set_gas_used(&mut self, block_num: u64, gas_used: u128) {
         self.gas_used_per_block.insert(block_num, gas_used);
     }
+}
 
-    pub fn draw(&self, filepath: impl AsRef<str>) -> Result<(), Box<dyn std::error::Error>> {
-        let root = BitMapBackend::new(filepath.as_ref(), (1024, 768)).into_drawing_area();
+impl DrawableChart for GasPerBlockChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
         root.fill(&RGBColor(240, 240, 240))?;
 
         let start_block = self
@@ -64,7 +72,7 @@ impl GasPerBlockChart {
             .copied()
             .unwrap_or_default();
 
-        let mut chart = ChartBuilder::on(&root)
+        let mut chart = ChartBuilder::on(root)
             .margin(15)
             .margin_bottom(25)
             .x_label_area_size(100)
@@ -82,13 +90,13 @@ impl GasPerBlockChart {
             .x_label_formatter(&|block| format!("            {}", block))
             .x_label_style(
                 ("sans-serif", 15)
-                    .into_text_style(&root)
+                    .into_text_style(root)
                     .transform(FontTransform::Rotate90),
             )
             .y_desc("Gas Used")
             .y_labels(25)
             .y_max_light_lines(1)
-            .y_label_formatter(&|gas| abbreviate_num(*gas as u64))
+            .y_label_formatter(&|gas| abbreviate_num(*gas))
             .draw()?;
 
         // draw line chart
@@ -103,9 +111,6 @@ impl GasPerBlockChart {
             |c: (u64, u128)| Circle::new(c, 3, Into::<ShapeStyle>::into(BLUEGREY_500).filled());
         chart.draw_series(chart_data.map(|(x, y)| mk_dot((x, y))))?;
 
-        root.present()?;
-        println!("saved chart to {}", filepath.as_ref());
-
         Ok(())
     }
 }
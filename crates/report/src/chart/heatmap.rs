@@ -1,11 +1,26 @@
-use alloy::primitives::FixedBytes;
-use plotters::prelude::*;
+use alloy::primitives::{Address, FixedBytes};
+use csv::WriterBuilder;
+use plotters::{coord::Shift, prelude::*};
+use serde::Serialize;
 use std::collections::BTreeMap;
 
-use crate::commands::report::block_trace::TxTraceReceipt;
+use super::DrawableChart;
+use crate::block_trace::TxTraceReceipt;
 
 pub struct HeatMapChart {
     updates_per_slot_per_block: BTreeMap<u64, BTreeMap<FixedBytes<32>, u64>>,
+    /// Updates per (block_num, contract, slot); the chart merges slots of different contracts.
+    updates_per_contract_slot: BTreeMap<(u64, Address, FixedBytes<32>), u64>,
+}
+
+/// A cell of the heatmap's underlying matrix, exported for analysis with other tools.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct HeatMapEntry {
+    pub contract: Address,
+    pub slot: FixedBytes<32>,
+    pub block_number: u64,
+    /// Number of txs in the block that accessed the slot.
+    pub count: u64,
 }
 
 impl Default for HeatMapChart {
@@ -19,10 +34,16 @@ impl HeatMapChart {
     fn new() -> Self {
         Self {
             updates_per_slot_per_block: Default::default(),
+            updates_per_contract_slot: Default::default(),
         }
     }
 
-    pub fn build(trace_data: &[TxTraceReceipt]) -> Result<Self, Box<dyn std::error::Error>> {
+    /// Builds a heatmap from storage updates in `trace_data`.
+    /// If `contract` is given, only updates to that contract's storage are counted.
+    pub fn build(
+        trace_data: &[TxTraceReceipt],
+        contract: Option<Address>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let mut heatmap = HeatMapChart::new();
 
         for t in trace_data {
@@ -31,7 +52,10 @@ impl HeatMapChart {
                 .block_number
                 .expect("block number not found in receipt");
 
-            let trace_frame = t.trace.to_owned().try_into_pre_state_frame();
+            let Some(trace) = &t.trace else {
+                continue;
+            };
+            let trace_frame = trace.to_owned().try_into_pre_state_frame();
             if let Err(e) = trace_frame {
                 println!("failed to decode frame (preState mode): {:?}", e);
                 continue;
@@ -44,24 +68,34 @@ impl HeatMapChart {
 
             // "for each account in this transaction trace"
             for key in account_map.keys() {
+                if contract.is_some_and(|c| c != *key) {
+                    continue;
+                }
                 let update = account_map
                     .get(key)
                     .expect("invalid key; this should never happen");
                 // for every storage slot in this frame, increment the count for the slot at this block number
                 update.storage.iter().for_each(|(slot, _)| {
-                    heatmap.add_update(block_num, *slot);
+                    heatmap.add_update(block_num, *key, *slot);
                 });
             }
         }
 
         if heatmap.get_num_slots() == 0 {
+            if let Some(contract) = contract {
+                return Err(format!("No storage updates found for contract {}.", contract).into());
+            }
             return Err("No trace data was collected. Ensure your target node supports geth-style preState traces.".into());
         }
 
         Ok(heatmap)
     }
 
-    fn add_update(&mut self, block_num: u64, slot: FixedBytes<32>) {
+    fn add_update(&mut self, block_num: u64, contract: Address, slot: FixedBytes<32>) {
+        *self
+            .updates_per_contract_slot
+            .entry((block_num, contract, slot))
+            .or_default() += 1;
         if let Some(slot_map) = self.updates_per_slot_per_block.get_mut(&block_num) {
             let value = slot_map.get(&slot).map(|v| v + 1).unwrap_or(1);
             slot_map.insert(slot, value);
@@ -72,6 +106,42 @@ impl HeatMapChart {
         }
     }
 
+    /// Returns the heatmap's data as (contract, slot, block, count) entries, ordered by block.
+    pub fn entries(&self) -> Vec<HeatMapEntry> {
+        self.updates_per_contract_slot
+            .iter()
+            .map(|((block_number, contract, slot), count)| HeatMapEntry {
+                contract: *contract,
+                slot: *slot,
+                block_number: *block_number,
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Saves the heatmap's data to a CSV file at `filepath`.
+    pub fn save_csv(&self, filepath: impl AsRef<str>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = WriterBuilder::new()
+            .has_headers(true)
+            .from_path(filepath.as_ref())?;
+        for entry in self.entries() {
+            writer.serialize(entry)?;
+        }
+        writer.flush()?;
+        println!("saved heatmap data to {}", filepath.as_ref());
+        Ok(())
+    }
+
+    /// Saves the heatmap's data to a JSON file at `filepath`.
+    pub fn save_json(&self, filepath: impl AsRef<str>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(
+            filepath.as_ref(),
+            serde_json::to_string_pretty(&self.entries())?,
+        )?;
+        println!("saved heatmap data to {}", filepath.as_ref());
+        Ok(())
+    }
+
     fn get_block_numbers(&self) -> Vec<u64> {
         self.updates_per_slot_per_block.keys().cloned().collect()
     }
@@ -138,12 +208,19 @@ impl HeatMapChart {
         slots.sort();
         slots.iter().map(|s| format!("{:?}", s)).collect()
     }
+}
 
-    pub fn draw(&self, filepath: impl AsRef<str>) -> Result<(), Box<dyn std::error::Error>> {
+impl DrawableChart for HeatMapChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
         let matrix = self.get_matrix();
 
         // plotters
-        let root = BitMapBackend::new(filepath.as_ref(), (1024, 768)).into_drawing_area();
         root.fill(&RGBColor(240, 240, 240))?;
 
         let (chart_area, legend_area) = root.split_horizontally(900);
@@ -251,9 +328,6 @@ impl HeatMapChart {
                 .transform(FontTransform::Rotate90),
         ))?;
 
-        root.present()?;
-        println!("saved chart to {}", filepath.as_ref());
-
         Ok(())
     }
 }
@@ -265,3 +339,45 @@ fn rgb_gradient(value: u8) -> (u8, u8, u8) {
         171..=255 => (255, 255, (value - 170) * 3), // Yellow to White (B increases)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_updates_per_contract() {
+        let mut heatmap = HeatMapChart::new();
+        let (a, b) = (Address::repeat_byte(0xa), Address::repeat_byte(0xb));
+        let slot = FixedBytes::<32>::with_last_byte(1);
+        heatmap.add_update(2, a, slot);
+        heatmap.add_update(2, a, slot);
+        heatmap.add_update(2, b, slot);
+        heatmap.add_update(1, b, slot);
+
+        // the chart merges the contracts' slots
+        assert_eq!(heatmap.get_matrix(), vec![vec![1], vec![3]]);
+        assert_eq!(
+            heatmap.entries(),
+            vec![
+                HeatMapEntry {
+                    contract: b,
+                    slot,
+                    block_number: 1,
+                    count: 1
+                },
+                HeatMapEntry {
+                    contract: a,
+                    slot,
+                    block_number: 2,
+                    count: 2
+                },
+                HeatMapEntry {
+                    contract: b,
+                    slot,
+                    block_number: 2,
+                    count: 1
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+mod baseline_trend;
+mod chart_id;
+mod endpoint_bar;
+mod gas_per_block;
+mod heatmap;
+mod opcode_gas;
+mod priority_fee;
+mod rpc_latency;
+mod time_to_inclusion;
+mod tx_gas_used;
+
+pub use baseline_trend::BaselineTrendChart;
+pub use chart_id::ReportChartId;
+pub use endpoint_bar::EndpointBarChart;
+pub use gas_per_block::GasPerBlockChart;
+pub use heatmap::HeatMapChart;
+pub use opcode_gas::OpcodeGasChart;
+pub use priority_fee::PriorityFeeChart;
+pub use rpc_latency::RpcLatencyChart;
+pub use time_to_inclusion::TimeToInclusionChart;
+pub use tx_gas_used::TxGasUsedChart;
+
+use plotters::{
+    backend::{BitMapBackend, SVGBackend},
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend, IntoDrawingArea},
+};
+
+/// Size of rendered charts, in pixels.
+const CHART_SIZE: (u32, u32) = (1024, 768);
+
+/// Image format report charts are rendered in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChartFormat {
+    /// Raster images.
+    #[default]
+    Png,
+    /// Vector images, which stay crisp when resized and can be embedded in other docs.
+    Svg,
+}
+
+impl ChartFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChartFormat::Png => "png",
+            ChartFormat::Svg => "svg",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ChartFormat::Png => "image/png",
+            ChartFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// A chart which can be drawn with any plotters backend.
+pub trait DrawableChart {
+    /// Draws the chart on `root`.
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static;
+
+    /// Renders the chart to an image file at `filepath`.
+    fn draw(
+        &self,
+        filepath: impl AsRef<str>,
+        format: ChartFormat,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match format {
+            ChartFormat::Png => {
+                let root = BitMapBackend::new(filepath.as_ref(), CHART_SIZE).into_drawing_area();
+                self.draw_on(&root)?;
+                root.present()?;
+            }
+            ChartFormat::Svg => {
+                let root = SVGBackend::new(filepath.as_ref(), CHART_SIZE).into_drawing_area();
+                self.draw_on(&root)?;
+                root.present()?;
+            }
+        }
+        println!("saved chart to {}", filepath.as_ref());
+        Ok(())
+    }
+}
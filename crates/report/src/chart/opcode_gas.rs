@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use alloy::{primitives::U256, rpc::types::trace::geth::StructLog};
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    prelude::{IntoSegmentedCoord, SegmentValue},
+    series::Histogram,
+    style::{full_palette::BLUE, Color, RGBColor},
+};
+
+use super::DrawableChart;
+use crate::util::abbreviate_num;
+
+/// Highest address considered a precompile (BLS12-381 precompiles end at 0x11).
+const MAX_PRECOMPILE_ADDRESS: u64 = 0x11;
+
+/// Groups of opcodes that gas usage is aggregated into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OpcodeFamily {
+    Sstore,
+    Sload,
+    Call,
+    Precompile,
+    Create,
+    Keccak,
+    Log,
+    Other,
+}
+
+impl OpcodeFamily {
+    fn from_op(op: &str) -> Self {
+        match op {
+            "SSTORE" => Self::Sstore,
+            "SLOAD" => Self::Sload,
+            "CALL" | "CALLCODE" | "DELEGATECALL" | "STATICCALL" => Self::Call,
+            "CREATE" | "CREATE2" => Self::Create,
+            "KECCAK256" | "SHA3" => Self::Keccak,
+            "LOG0" | "LOG1" | "LOG2" | "LOG3" | "LOG4" => Self::Log,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for OpcodeFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Sstore => "SSTORE",
+            Self::Sload => "SLOAD",
+            Self::Call => "CALL",
+            Self::Precompile => "PRECOMPILE",
+            Self::Create => "CREATE",
+            Self::Keccak => "KECCAK",
+            Self::Log => "LOG",
+            Self::Other => "OTHER",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+pub struct OpcodeGasChart {
+    /// Maps opcode family to the total gas spent on it.
+    gas_per_family: BTreeMap<OpcodeFamily, u128>,
+}
+
+impl Default for OpcodeGasChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpcodeGasChart {
+    pub fn new() -> Self {
+        Self {
+            gas_per_family: Default::default(),
+        }
+    }
+
+    /// Adds the gas spent by each step of a struct-logger trace.
+    ///
+    /// Call opcodes report the gas forwarded to the callee as part of their cost. When the call
+    /// enters a new frame, the callee's starting gas is subtracted and its own steps are counted
+    /// instead. Otherwise (precompile & EOA calls) the unused gas is returned right away, so the
+    /// cost is the gas left before the call minus the gas left after it. The stack is used to tell
+    /// precompile calls apart.
+    pub fn add_struct_logs(&mut self, logs: &[StructLog]) {
+        for (i, log) in logs.iter().enumerate() {
+            let mut family = OpcodeFamily::from_op(&log.op);
+            let mut gas_cost = log.gas_cost;
+
+            if matches!(family, OpcodeFamily::Call | OpcodeFamily::Create) {
+                match logs.get(i + 1) {
+                    Some(next) if next.depth > log.depth => {
+                        gas_cost = gas_cost.saturating_sub(next.gas);
+                    }
+                    next => {
+                        if let Some(next) = next.filter(|next| next.depth == log.depth) {
+                            gas_cost = log.gas.saturating_sub(next.gas);
+                        }
+                        if family == OpcodeFamily::Call && is_precompile_call(log) {
+                            family = OpcodeFamily::Precompile;
+                        }
+                    }
+                }
+            }
+
+            *self.gas_per_family.entry(family).or_default() += gas_cost as u128;
+        }
+    }
+
+    pub fn total_gas(&self) -> u128 {
+        self.gas_per_family.values().sum()
+    }
+}
+
+impl DrawableChart for OpcodeGasChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let families = self.gas_per_family.keys().copied().collect::<Vec<_>>();
+        let max_gas = self.gas_per_family.values().max().copied().unwrap_or(1);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(80)
+            .build_cartesian_2d(
+                (0..families.len()).into_segmented(),
+                0..max_gas + max_gas / 10,
+            )?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("Opcode Family")
+            .x_label_formatter(&|x| match x {
+                SegmentValue::CenterOf(i) => {
+                    families.get(*i).map(|f| f.to_string()).unwrap_or_default()
+                }
+                _ => String::default(),
+            })
+            .y_desc("Gas Used")
+            .y_label_formatter(&|gas| abbreviate_num(*gas))
+            .draw()?;
+
+        chart.draw_series(
+            Histogram::vertical(&chart)
+                .style(BLUE.filled())
+                .margin(20)
+                .data(
+                    self.gas_per_family
+                        .values()
+                        .enumerate()
+                        .map(|(i, gas)| (i, *gas)),
+                ),
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Returns true if the call-type opcode in `log` targets a precompile.
+fn is_precompile_call(log: &StructLog) -> bool {
+    // the target address is the 2nd item from the top of the stack for all call opcodes
+    let target = log
+        .stack
+        .as_ref()
+        .and_then(|stack| stack.len().checked_sub(2).map(|i| stack[i]));
+    target.is_some_and(|addr| addr > U256::ZERO && addr <= U256::from(MAX_PRECOMPILE_ADDRESS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(op: &str, gas: u64, gas_cost: u64, depth: u64, stack: Vec<u64>) -> StructLog {
+        StructLog {
+            pc: 0,
+            op: op.to_owned(),
+            gas,
+            gas_cost,
+            depth,
+            error: None,
+            stack: Some(stack.into_iter().map(U256::from).collect()),
+            return_data: None,
+            memory: None,
+            memory_size: None,
+            storage: None,
+            refund_counter: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_gas_by_family() {
+        let logs = vec![
+            step("SLOAD", 100_000, 2100, 1, vec![]),
+            // call into a contract; 60k of the cost is forwarded to the callee
+            step(
+                "CALL",
+                97_900,
+                62_600,
+                1,
+                vec![0, 0, 0, 0, 0, 0xbeef, 60_000],
+            ),
+            step("SSTORE", 60_000, 20_000, 2, vec![]),
+            step("STOP", 40_000, 0, 2, vec![]),
+            // call to the sha256 precompile; no new frame is entered, and the 1000 gas forwarded
+            // to it is part of its cost until the unused gas is returned
+            step("STATICCALL", 75_000, 1100, 1, vec![0, 0, 0, 0, 0x2, 1000]),
+            step("KECCAK256", 74_840, 36, 1, vec![]),
+            // value transfer to an EOA
+            step(
+                "CALL",
+                74_804,
+                30_000,
+                1,
+                vec![0, 0, 0, 0, 1, 0xbeef, 20_000],
+            ),
+            step("POP", 65_804, 2, 1, vec![]),
+        ];
+        let mut chart = OpcodeGasChart::new();
+        chart.add_struct_logs(&logs);
+
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Sload], 2100);
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Call], 2600 + 9000);
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Sstore], 20_000);
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Precompile], 160);
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Keccak], 36);
+        assert_eq!(chart.gas_per_family[&OpcodeFamily::Other], 2);
+        assert_eq!(
+            chart.total_gas(),
+            2100 + 2600 + 20_000 + 160 + 36 + 9000 + 2
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::rpc::types::Block;
+use contender_core::db::RunTx;
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    element::Circle,
+    style::{Color, Palette, Palette99, RGBColor, BLACK, WHITE},
+};
+
+use super::DrawableChart;
+use crate::{block_trace::TxTraceReceipt, util::kind_label};
+
+const GWEI: f64 = 1_000_000_000.0;
+
+pub struct PriorityFeeChart {
+    /// Maps tx kind to (time to inclusion, effective priority fee in gwei) points.
+    points: BTreeMap<String, Vec<(u64, f64)>>,
+}
+
+impl PriorityFeeChart {
+    /// Builds the chart from the effective gas price in each tx's receipt, less its block's base fee.
+    /// Txs without a receipt in `traces` are skipped.
+    pub fn build(run_txs: &[RunTx], traces: &[TxTraceReceipt], blocks: &[Block]) -> Self {
+        let gas_prices = traces
+            .iter()
+            .map(|t| (t.receipt.transaction_hash, t.receipt.effective_gas_price))
+            .collect::<HashMap<_, _>>();
+        let base_fees = blocks
+            .iter()
+            .map(|b| {
+                (
+                    b.header.number,
+                    b.header.base_fee_per_gas.unwrap_or_default(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut points: BTreeMap<String, Vec<(u64, f64)>> = BTreeMap::new();
+        for tx in run_txs {
+            let Some(gas_price) = gas_prices.get(&tx.tx_hash) else {
+                continue;
+            };
+            let base_fee = base_fees.get(&tx.block_number).copied().unwrap_or_default();
+            let priority_fee = gas_price.saturating_sub(base_fee) as f64 / GWEI;
+            let tti = tx.time_to_inclusion_ms();
+            points
+                .entry(kind_label(&tx.kind))
+                .or_default()
+                .push((tti, priority_fee));
+        }
+
+        Self { points }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+impl DrawableChart for PriorityFeeChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let all_points = self.points.values().flatten();
+        let max_tti = all_points.clone().map(|p| p.0).max().unwrap_or_default();
+        let max_fee = all_points.map(|p| p.1).fold(0.0, f64::max);
+        // leave headroom so points on the edges aren't clipped
+        let max_fee = if max_fee > 0.0 { max_fee * 1.1 } else { 1.0 };
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(60)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0..max_tti + 1, 0.0..max_fee)?;
+
+        chart
+            .configure_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("Time to Inclusion (ms)")
+            .y_desc("Effective Priority Fee (gwei)")
+            .draw()?;
+
+        for (i, (kind, points)) in self.points.iter().enumerate() {
+            let style = Palette99::pick(i).mix(0.7).filled();
+            chart
+                .draw_series(points.iter().map(|p| Circle::new(*p, 4, style)))?
+                .label(kind)
+                .legend(move |(x, y)| Circle::new((x + 5, y), 4, style));
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+}
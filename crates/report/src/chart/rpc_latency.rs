@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use contender_core::db::LatencySnapshot;
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    element::PathElement,
+    series::LineSeries,
+    style::{Color, Palette, Palette99, RGBColor, BLACK, WHITE},
+};
+
+pub struct RpcLatencyChart {
+    /// Maps RPC method to (seconds since the first snapshot, p50 ms, p95 ms) points.
+    series: BTreeMap<String, Vec<(f64, u64, u64)>>,
+}
+
+impl RpcLatencyChart {
+    pub fn build(snapshots: &[LatencySnapshot]) -> Self {
+        let start = snapshots
+            .iter()
+            .map(|s| s.timestamp)
+            .min()
+            .unwrap_or_default();
+        let mut series: BTreeMap<String, Vec<(f64, u64, u64)>> = BTreeMap::new();
+        for snapshot in snapshots {
+            let elapsed = (snapshot.timestamp - start) as f64 / 1000.0;
+            series.entry(snapshot.method.to_owned()).or_default().push((
+                elapsed,
+                snapshot.p50_ms,
+                snapshot.p95_ms,
+            ));
+        }
+        for points in series.values_mut() {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        Self { series }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+impl DrawableChart for RpcLatencyChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let points = self.series.values().flatten();
+        let max_time = points.clone().map(|p| p.0).fold(0.0, f64::max).max(1.0);
+        let max_latency = points.map(|p| p.2).max().unwrap_or_default().max(1);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(60)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0.0..max_time, 0..max_latency + max_latency / 10)?;
+
+        chart
+            .configure_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("Time Since Start (seconds)")
+            .y_desc("Latency (ms)")
+            .draw()?;
+
+        for (i, (method, points)) in self.series.iter().enumerate() {
+            let color = Palette99::pick(i);
+            // p50 is drawn as a solid line, p95 as a lighter line of the same color
+            let p50_style = color.stroke_width(2);
+            let p95_style = color.mix(0.4).stroke_width(2);
+            chart
+                .draw_series(LineSeries::new(
+                    points.iter().map(|(t, p50, _)| (*t, *p50)),
+                    p50_style,
+                ))?
+                .label(format!("{} p50", method))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], p50_style));
+            chart
+                .draw_series(LineSeries::new(
+                    points.iter().map(|(t, _, p95)| (*t, *p95)),
+                    p95_style,
+                ))?
+                .label(format!("{} p95", method))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], p95_style));
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_series_per_method() {
+        let snapshot = |timestamp, method: &str, p50_ms| LatencySnapshot {
+            timestamp,
+            method: method.to_owned(),
+            p50_ms,
+            p95_ms: p50_ms * 2,
+            count: 1,
+        };
+        let chart = RpcLatencyChart::build(&[
+            snapshot(5000, "eth_sendRawTransaction", 4),
+            snapshot(4000, "eth_sendRawTransaction", 3),
+            snapshot(4000, "eth_blockNumber", 1),
+        ]);
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(
+            chart.series["eth_sendRawTransaction"],
+            vec![(0.0, 3, 6), (1.0, 4, 8)]
+        );
+    }
+}
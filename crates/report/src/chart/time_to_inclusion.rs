@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use contender_core::db::RunTx;
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    element::Rectangle,
+    series::Histogram,
+    style::{full_palette::BLUE, Color, Palette, Palette99, RGBColor, BLACK, WHITE},
+};
+
+use super::DrawableChart;
+use crate::util::kind_label;
+
+/// Max number of bars drawn for each series; inclusion times are bucketed to fit.
+const MAX_BUCKETS: u64 = 50;
+
+pub struct TimeToInclusionChart {
+    /// Maps series name to the inclusion times of its txs, in milliseconds.
+    inclusion_times: BTreeMap<String, Vec<u64>>,
+    x_desc: &'static str,
+}
+
+impl TimeToInclusionChart {
+    fn new() -> Self {
+        Self {
+            inclusion_times: Default::default(),
+            x_desc: "Time to Inclusion (ms)",
+        }
+    }
+
+    /// Builds a chart of how long the node took to acknowledge each tx's send call, rather than
+    /// to include it. Txs without a recorded ack latency are skipped.
+    pub fn build_ack_latency(run_txs: &[RunTx], group_by_kind: bool) -> Self {
+        let mut chart = TimeToInclusionChart {
+            x_desc: "RPC Ack Latency (ms)",
+            ..TimeToInclusionChart::new()
+        };
+
+        for tx in run_txs {
+            let Some(ack_latency_ms) = tx.ack_latency_ms else {
+                continue;
+            };
+            let series = if group_by_kind {
+                kind_label(&tx.kind)
+            } else {
+                "all".to_owned()
+            };
+            chart.add_inclusion_time(series, ack_latency_ms);
+        }
+
+        chart
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inclusion_times.is_empty()
+    }
+
+    /// Builds the chart. If `group_by_kind` is set, each tx kind is drawn as a separate series.
+    pub fn build(run_txs: &[RunTx], group_by_kind: bool) -> Self {
+        let mut chart = TimeToInclusionChart::new();
+
+        for tx in run_txs {
+            let series = if group_by_kind {
+                kind_label(&tx.kind)
+            } else {
+                "all".to_owned()
+            };
+            chart.add_inclusion_time(series, tx.time_to_inclusion_ms());
+        }
+
+        chart
+    }
+
+    /// Builds the chart with each group of txs drawn as a separate series.
+    pub fn build_grouped<'a>(groups: impl IntoIterator<Item = (String, &'a [RunTx])>) -> Self {
+        let mut chart = TimeToInclusionChart::new();
+
+        for (series, run_txs) in groups {
+            for tx in run_txs {
+                chart.add_inclusion_time(series.to_owned(), tx.time_to_inclusion_ms());
+            }
+        }
+
+        chart
+    }
+
+    fn add_inclusion_time(&mut self, series: String, time_to_include: u64) {
+        self.inclusion_times
+            .entry(series)
+            .or_default()
+            .push(time_to_include);
+    }
+}
+
+impl DrawableChart for TimeToInclusionChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let all_times = self.inclusion_times.values().flatten();
+        let min_tti = all_times
+            .clone()
+            .min()
+            .expect("no time-to-inclusion data found");
+        let max_tti = all_times
+            .clone()
+            .max()
+            .expect("no time-to-inclusion data found");
+        // bars are drawn per bucket, so sub-second times don't each get a hairline bar
+        let bucket_ms = (max_tti - min_tti).div_ceil(MAX_BUCKETS).max(1);
+        let bucket = move |tti: &u64| tti / bucket_ms;
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(60)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                bucket(min_tti)..bucket(max_tti) + 1,
+                0..all_times.count() as u32,
+            )?;
+
+        chart
+            .configure_mesh()
+            .label_style(("sans-serif", 15))
+            .x_label_offset(10)
+            .x_label_formatter(&|b| (b * bucket_ms).to_string())
+            .x_desc(self.x_desc)
+            .y_desc("# Transactions")
+            .draw()?;
+
+        if self.inclusion_times.len() == 1 {
+            let times = self.inclusion_times.values().next().expect("no series");
+            chart.draw_series(
+                Histogram::vertical(&chart)
+                    .style(BLUE.filled())
+                    .data(times.iter().map(|x| (bucket(x), 1))),
+            )?;
+        } else {
+            for (i, (kind, times)) in self.inclusion_times.iter().enumerate() {
+                let color = Palette99::pick(i).mix(0.6);
+                chart
+                    .draw_series(
+                        Histogram::vertical(&chart)
+                            .style(color.filled())
+                            .data(times.iter().map(|x| (bucket(x), 1))),
+                    )?
+                    .label(kind)
+                    .legend(move |(x, y)| {
+                        Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled())
+                    });
+            }
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()?;
+        }
+
+        Ok(())
+    }
+}
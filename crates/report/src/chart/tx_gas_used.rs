@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::primitives::TxHash;
+use plotters::{
+    chart::ChartBuilder,
+    coord::Shift,
+    drawing::{DrawingArea, DrawingBackend},
+    element::Rectangle,
+    series::Histogram,
+    style::{full_palette::BLUE, Color, Palette, Palette99, RGBColor, BLACK, WHITE},
+};
+
+use super::DrawableChart;
+use crate::{block_trace::TxTraceReceipt, util::abbreviate_num};
+
+pub struct TxGasUsedChart {
+    /// Maps series name to the gas used by each of its txs.
+    gas_used: BTreeMap<String, Vec<u128>>,
+}
+
+impl Default for TxGasUsedChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxGasUsedChart {
+    fn new() -> Self {
+        Self {
+            gas_used: Default::default(),
+        }
+    }
+
+    /// Builds the chart. If `tx_kinds` is given, txs are drawn as a separate series per kind;
+    /// txs that weren't sent by contender are grouped as "other".
+    pub fn build(
+        trace_data: &[TxTraceReceipt],
+        tx_kinds: Option<&HashMap<TxHash, String>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut chart = TxGasUsedChart::new();
+
+        for t in trace_data {
+            let gas = t.receipt.gas_used;
+            let series = tx_kinds
+                .map(|kinds| {
+                    kinds
+                        .get(&t.receipt.transaction_hash)
+                        .cloned()
+                        .unwrap_or("other".to_owned())
+                })
+                .unwrap_or("all".to_owned());
+            chart.add_gas_used(series, gas + (1000 - (gas % 1000)));
+        }
+
+        Ok(chart)
+    }
+
+    fn add_gas_used(&mut self, series: String, gas_used: u128) {
+        self.gas_used.entry(series).or_default().push(gas_used);
+    }
+}
+
+impl DrawableChart for TxGasUsedChart {
+    fn draw_on<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&RGBColor(240, 240, 240))?;
+
+        let all_gas_used = self.gas_used.values().flatten();
+        let max_gas_used = all_gas_used.clone().max().copied().unwrap_or_default();
+
+        let mut gas_used_counts = HashMap::new();
+        for &gas in all_gas_used {
+            *gas_used_counts.entry(gas).or_insert(0) += 1;
+        }
+        let highest_peak = gas_used_counts.values().max().unwrap_or(&0);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(15)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(
+                0..max_gas_used + (10_000 - (max_gas_used % 10_000)),
+                0..highest_peak + (5 - (highest_peak % 5)),
+            )?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .label_style(("sans-serif", 15))
+            .x_desc("Gas Used")
+            .x_label_formatter(&|x| abbreviate_num(*x))
+            .y_desc("# Transactions")
+            .draw()?;
+
+        if self.gas_used.len() == 1 {
+            let gas_used = self.gas_used.values().next().expect("no series");
+            chart.draw_series(
+                Histogram::vertical(&chart)
+                    .style(BLUE.filled())
+                    .data(gas_used.iter().map(|&x| (x, 1))),
+            )?;
+        } else {
+            for (i, (kind, gas_used)) in self.gas_used.iter().enumerate() {
+                let color = Palette99::pick(i).mix(0.6);
+                chart
+                    .draw_series(
+                        Histogram::vertical(&chart)
+                            .style(color.filled())
+                            .data(gas_used.iter().map(|&x| (x, 1))),
+                    )?
+                    .label(kind)
+                    .legend(move |(x, y)| {
+                        Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled())
+                    });
+            }
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()?;
+        }
+
+        Ok(())
+    }
+}
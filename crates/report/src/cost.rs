@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::{
+    primitives::{utils::format_ether, Address, U256},
+    providers::Provider,
+    rpc::types::TransactionReceipt,
+    transports::http::reqwest::Url,
+};
+use contender_core::{
+    db::{DbOps, FundingTx},
+    provider::eth_provider,
+};
+use serde::{Deserialize, Serialize};
+
+use super::run_range;
+
+/// Pool name for senders that weren't recorded with the run.
+pub const UNKNOWN_POOL: &str = "other";
+
+/// Pool name for the gas spent funding the run's accounts.
+const FUNDING_POOL: &str = "funding";
+
+/// ETH spent on gas by one pool within a run.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct CostSummary {
+    pub run_id: u64,
+    /// Agent pool name, "total" for the sum of all pools in the run, or "funding" for the gas
+    /// spent funding the run's accounts.
+    pub pool: String,
+    pub num_txs: usize,
+    pub gas_used: u128,
+    /// Total fees paid (in ETH), including blob gas.
+    pub eth_spent: String,
+}
+
+/// Fee paid by a single tx.
+pub struct TxFee {
+    pub from: Address,
+    pub gas_used: u128,
+    /// Total fee in wei, including blob gas.
+    pub wei: U256,
+}
+
+impl From<&TransactionReceipt> for TxFee {
+    fn from(receipt: &TransactionReceipt) -> Self {
+        let execution = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+        let blob = U256::from(receipt.blob_gas_used.unwrap_or_default())
+            * U256::from(receipt.blob_gas_price.unwrap_or_default());
+        Self {
+            from: receipt.from,
+            gas_used: receipt.gas_used,
+            wei: execution + blob,
+        }
+    }
+}
+
+impl From<&FundingTx> for TxFee {
+    fn from(tx: &FundingTx) -> Self {
+        Self {
+            from: tx.sender,
+            gas_used: tx.gas_used,
+            wei: U256::from(tx.gas_used) * U256::from(tx.effective_gas_price),
+        }
+    }
+}
+
+impl CostSummary {
+    /// Summarizes the fees paid in a run per pool, followed by a total row.
+    /// `accounts` maps senders to pool names.
+    /// If the run funded any accounts, a funding row follows the total; it's not counted in the
+    /// total, so the scenario's own traffic can be told apart from funding overhead.
+    pub fn build_run(
+        run_id: u64,
+        fees: impl IntoIterator<Item = TxFee>,
+        funding_txs: &[FundingTx],
+        accounts: &[(String, Address)],
+    ) -> Vec<Self> {
+        let pools = accounts
+            .iter()
+            .map(|(pool, address)| (*address, pool.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        // (num_txs, gas_used, wei spent) per pool
+        let mut by_pool: BTreeMap<&str, (usize, u128, U256)> = BTreeMap::new();
+        for fee in fees {
+            let pool = pools.get(&fee.from).copied().unwrap_or(UNKNOWN_POOL);
+            let entry = by_pool.entry(pool).or_default();
+            entry.0 += 1;
+            entry.1 += fee.gas_used;
+            entry.2 += fee.wei;
+        }
+
+        let total = by_pool
+            .values()
+            .fold((0, 0, U256::ZERO), |(num_txs, gas_used, wei), (n, g, w)| {
+                (num_txs + n, gas_used + g, wei + w)
+            });
+        let funding = funding_txs
+            .iter()
+            .map(TxFee::from)
+            .fold((0, 0, U256::ZERO), |(num_txs, gas_used, wei), fee| {
+                (num_txs + 1, gas_used + fee.gas_used, wei + fee.wei)
+            });
+        by_pool
+            .into_iter()
+            .chain([("total", total)])
+            .chain((funding.0 > 0).then_some((FUNDING_POOL, funding)))
+            .map(|(pool, (num_txs, gas_used, wei))| Self {
+                run_id,
+                pool: pool.to_owned(),
+                num_txs,
+                gas_used,
+                eth_spent: format_ether(wei),
+            })
+            .collect()
+    }
+}
+
+/// Prints the ETH spent on gas by each pool in the given runs.
+pub async fn report_cost(
+    db: &impl DbOps,
+    rpc_url: &str,
+    last_run_id: Option<u64>,
+    preceding_runs: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some((start_run_id, end_run_id)) = run_range(db, last_run_id, preceding_runs)? else {
+        println!("No runs found in the database. Exiting.");
+        return Ok(());
+    };
+    let rpc_client = eth_provider(&Url::parse(rpc_url)?).await?;
+
+    println!(
+        "{:>6}  {:<20} {:>8} {:>14} {:>24}",
+        "run", "pool", "txs", "gas used", "ETH spent"
+    );
+    for id in start_run_id..=end_run_id {
+        let mut fees = vec![];
+        for tx in db.get_run_txs(id)? {
+            if let Some(receipt) = rpc_client.get_transaction_receipt(tx.tx_hash).await? {
+                fees.push(TxFee::from(&receipt));
+            }
+        }
+
+        let funding_txs = db.get_funding_txs(id)?;
+        let accounts = db.get_run_accounts(id)?;
+        for row in CostSummary::build_run(id, fees, &funding_txs, &accounts) {
+            println!(
+                "{:>6}  {:<20} {:>8} {:>14} {:>24}",
+                row.run_id, row.pool, row.num_txs, row.gas_used, row.eth_spent
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(from: Address, gas_used: u128, gas_price: u128) -> TxFee {
+        TxFee {
+            from,
+            gas_used,
+            wei: U256::from(gas_used * gas_price),
+        }
+    }
+
+    #[test]
+    fn sums_cost_per_pool() {
+        let admin = Address::repeat_byte(1);
+        let agent = Address::repeat_byte(2);
+        let stranger = Address::repeat_byte(3);
+        let gwei = 1_000_000_000;
+        let fees = vec![
+            fee(admin, 21_000, 10 * gwei),
+            fee(agent, 50_000, 2 * gwei),
+            fee(agent, 50_000, 2 * gwei),
+            fee(stranger, 21_000, gwei),
+        ];
+        let accounts = vec![("admin".to_owned(), admin), ("pool1".to_owned(), agent)];
+
+        let summary = CostSummary::build_run(7, fees, &[], &accounts);
+        let pools = summary.iter().map(|s| s.pool.as_str()).collect::<Vec<_>>();
+        assert_eq!(pools, vec!["admin", "other", "pool1", "total"]);
+        assert_eq!(summary[0].eth_spent, "0.000210000000000000");
+        assert_eq!(summary[2].num_txs, 2);
+        assert_eq!(summary[2].eth_spent, "0.000200000000000000");
+        assert_eq!(summary[3].num_txs, 4);
+        assert_eq!(summary[3].gas_used, 142_000);
+        assert_eq!(summary[3].eth_spent, "0.000431000000000000");
+    }
+
+    #[test]
+    fn reports_funding_separately() {
+        let admin = Address::repeat_byte(1);
+        let agent = Address::repeat_byte(2);
+        let gwei = 1_000_000_000;
+        let funding_txs = vec![FundingTx {
+            tx_hash: Default::default(),
+            sender: admin,
+            recipient: agent,
+            amount: U256::from(10).pow(U256::from(18)),
+            gas_used: 21_000,
+            effective_gas_price: 5 * gwei,
+        }];
+        let accounts = vec![("pool1".to_owned(), agent)];
+
+        let summary =
+            CostSummary::build_run(1, vec![fee(agent, 21_000, gwei)], &funding_txs, &accounts);
+        let pools = summary.iter().map(|s| s.pool.as_str()).collect::<Vec<_>>();
+        assert_eq!(pools, vec!["pool1", "total", "funding"]);
+        // funding isn't counted in the total
+        assert_eq!(summary[1].eth_spent, "0.000021000000000000");
+        assert_eq!(summary[2].num_txs, 1);
+        assert_eq!(summary[2].eth_spent, "0.000105000000000000");
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use contender_core::db::{DbOps, RunTx, SpamRun};
+use serde::{Deserialize, Serialize};
+
+use crate::util::NumFormat;
+
+use super::{
+    chart::{ChartFormat, DrawableChart, EndpointBarChart, ReportChartId, TimeToInclusionChart},
+    gen_html::{build_html_report, ReportMetadata},
+    save_csv_report, scenario_title,
+};
+
+/// Per-endpoint stats displayed in the differential report's summary table.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct EndpointSummary {
+    pub endpoint: String,
+    pub run_ids: String,
+    /// Number of txs the runs intended to send.
+    pub num_sent: usize,
+    /// Number of txs that landed onchain.
+    pub num_landed: usize,
+    /// Number of txs replaced by another tx with the same sender & nonce.
+    pub num_replaced: usize,
+    /// Percentage of txs that never landed and weren't replaced.
+    pub error_rate: f64,
+    pub avg_time_to_inclusion: String,
+    /// Gas used by landed txs divided by the time spent sending them.
+    pub gas_per_second: u128,
+}
+
+/// Runs sent to a single endpoint.
+struct EndpointRuns<'a> {
+    endpoint: String,
+    runs: Vec<(&'a SpamRun, &'a [RunTx])>,
+}
+
+impl EndpointRuns<'_> {
+    fn txs(&self) -> Vec<RunTx> {
+        self.runs
+            .iter()
+            .flat_map(|(_, txs)| txs.iter().cloned())
+            .collect()
+    }
+}
+
+/// Groups runs by endpoint, in the order each endpoint first appears.
+fn group_by_endpoint(runs: &[(SpamRun, Vec<RunTx>)]) -> Vec<EndpointRuns<'_>> {
+    let mut groups: Vec<EndpointRuns> = vec![];
+    for (run, txs) in runs {
+        let endpoint = if run.rpc_url.is_empty() {
+            "unknown".to_owned()
+        } else {
+            run.rpc_url.to_owned()
+        };
+        match groups.iter_mut().find(|g| g.endpoint == endpoint) {
+            Some(group) => group.runs.push((run, txs)),
+            None => groups.push(EndpointRuns {
+                endpoint,
+                runs: vec![(run, txs)],
+            }),
+        }
+    }
+    groups
+}
+
+impl EndpointSummary {
+    /// `num_replaced` maps run IDs to the number of txs replaced in each run.
+    fn build(group: &EndpointRuns, num_replaced: &HashMap<u64, usize>) -> Self {
+        let num_sent = group
+            .runs
+            .iter()
+            .map(|(run, _)| run.tx_count)
+            .sum::<usize>();
+        let txs = group.txs();
+        let num_landed = txs.len();
+        let num_replaced = group
+            .runs
+            .iter()
+            .filter_map(|(run, _)| num_replaced.get(&run.id))
+            .sum::<usize>();
+        let error_rate = if num_sent == 0 {
+            0.0
+        } else {
+            num_sent.saturating_sub(num_landed + num_replaced) as f64 / num_sent as f64 * 100.0
+        };
+        let avg_time_to_inclusion = if num_landed == 0 {
+            0.0
+        } else {
+            txs.iter()
+                .map(|tx| tx.time_to_inclusion_ms() as f64)
+                .sum::<f64>()
+                / num_landed as f64
+        };
+
+        // each run is timed from its first tx being sent to its last tx landing
+        let total_ms = group
+            .runs
+            .iter()
+            .filter_map(|(_, txs)| {
+                let start = txs.iter().map(|tx| tx.start_timestamp).min()?;
+                let end = txs.iter().map(|tx| tx.end_timestamp).max()?;
+                Some(end.saturating_sub(start).max(1) as u128)
+            })
+            .sum::<u128>();
+        let total_gas = txs.iter().map(|tx| tx.gas_used).sum::<u128>();
+
+        Self {
+            endpoint: group.endpoint.to_owned(),
+            run_ids: group
+                .runs
+                .iter()
+                .map(|(run, _)| run.id.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            num_sent,
+            num_landed,
+            num_replaced,
+            error_rate,
+            avg_time_to_inclusion: format!("{:.2}", avg_time_to_inclusion),
+            gas_per_second: (total_gas * 1000).checked_div(total_ms).unwrap_or_default(),
+        }
+    }
+}
+
+/// Builds a report comparing runs sent to different RPC endpoints in `out_dir`. Returns the path
+/// to the report.
+pub fn report_diff(
+    run_ids: &[u64],
+    db: &impl DbOps,
+    archive: bool,
+    num_format: NumFormat,
+    out_dir: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if run_ids.len() < 2 {
+        return Err("A differential report requires at least 2 run IDs.".into());
+    }
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut runs = vec![];
+    let mut run_metadata = vec![];
+    let mut run_scores = vec![];
+    let mut scenario_hashes = vec![];
+    let mut csv_files = vec![];
+    let mut num_replaced = HashMap::new();
+    for &id in run_ids {
+        let run = db.get_run(id)?.ok_or(format!("Invalid run ID: {}", id))?;
+        let txs = db.get_run_txs(id)?;
+        csv_files.push(save_csv_report(id, &txs, out_dir)?);
+        num_replaced.insert(id, db.get_replaced_txs(id)?.len());
+        if let Some(metadata) = db.get_run_metadata(id)? {
+            run_metadata.push(metadata);
+        }
+        if let Some(score) = db.get_run_score(id)? {
+            run_scores.push(score);
+        }
+        if let Some(artifacts) = db.get_run_artifacts(id)? {
+            scenario_hashes.push(artifacts.scenario_hash);
+        }
+        runs.push((run, txs));
+    }
+
+    let scenario_name = scenario_title(runs.iter().map(|(run, _)| run));
+    if scenario_name.contains(", ") {
+        println!(
+            "WARNING: comparing runs of different scenarios ({}).",
+            scenario_name
+        );
+    }
+
+    let groups = group_by_endpoint(&runs);
+    if groups.len() < 2 {
+        println!("WARNING: all runs were sent to the same endpoint.");
+    }
+    let summaries = groups
+        .iter()
+        .map(|group| EndpointSummary::build(group, &num_replaced))
+        .collect::<Vec<_>>();
+
+    let start_run_id = *run_ids.iter().min().expect("run_ids is not empty");
+    let end_run_id = *run_ids.iter().max().expect("run_ids is not empty");
+
+    // draw charts
+    let chart_format = ChartFormat::default();
+    let group_txs = groups
+        .iter()
+        .map(|g| (g.endpoint.to_owned(), g.txs()))
+        .collect::<Vec<_>>();
+    let all_txs = group_txs
+        .iter()
+        .flat_map(|(_, txs)| txs)
+        .collect::<Vec<_>>();
+    if all_txs.is_empty() {
+        return Err("No landed txs found for the given runs.".into());
+    }
+    let time_to_inclusion = TimeToInclusionChart::build_grouped(
+        group_txs
+            .iter()
+            .map(|(endpoint, txs)| (endpoint.to_owned(), txs.as_slice())),
+    );
+    time_to_inclusion.draw(
+        ReportChartId::EndpointTimeToInclusion.filename(
+            out_dir,
+            start_run_id,
+            end_run_id,
+            chart_format,
+        ),
+        chart_format,
+    )?;
+    EndpointBarChart::new(
+        "Gas Per Second",
+        summaries
+            .iter()
+            .map(|s| (s.endpoint.to_owned(), s.gas_per_second as f64))
+            .collect(),
+    )
+    .draw(
+        ReportChartId::EndpointGasPerSecond.filename(
+            out_dir,
+            start_run_id,
+            end_run_id,
+            chart_format,
+        ),
+        chart_format,
+    )?;
+    EndpointBarChart::new(
+        "Txs Not Landed (%)",
+        summaries
+            .iter()
+            .map(|s| (s.endpoint.to_owned(), s.error_rate))
+            .collect(),
+    )
+    .draw(
+        ReportChartId::EndpointErrorRate.filename(out_dir, start_run_id, end_run_id, chart_format),
+        chart_format,
+    )?;
+
+    build_html_report(ReportMetadata {
+        scenario_name,
+        start_run_id,
+        end_run_id,
+        start_block: all_txs
+            .iter()
+            .map(|tx| tx.block_number)
+            .min()
+            .unwrap_or_default(),
+        end_block: all_txs
+            .iter()
+            .map(|tx| tx.block_number)
+            .max()
+            .unwrap_or_default(),
+        rpc_url: summaries
+            .iter()
+            .map(|s| s.endpoint.to_owned())
+            .collect::<Vec<_>>()
+            .join(", "),
+        run_metadata,
+        run_scores,
+        scenario_hashes,
+        kinds: vec![],
+        kind_summaries: vec![],
+        pool_summaries: vec![],
+        gas_violations: vec![],
+        slo_summaries: vec![],
+        endpoint_summaries: summaries,
+        bundle_summaries: vec![],
+        route_summaries: vec![],
+        cost_summaries: vec![],
+        balance_summaries: vec![],
+        charts: vec![
+            ReportChartId::EndpointTimeToInclusion,
+            ReportChartId::EndpointGasPerSecond,
+            ReportChartId::EndpointErrorRate,
+        ],
+        chart_format,
+        trace_mode: None,
+        csv_files,
+        archive,
+        num_format,
+        out_dir: out_dir.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: u64, tx_count: usize, rpc_url: &str) -> SpamRun {
+        SpamRun {
+            id,
+            timestamp: 0,
+            tx_count,
+            scenario_name: "test".to_owned(),
+            rpc_url: rpc_url.to_owned(),
+        }
+    }
+
+    fn run_tx(n: u8, start: usize, end: usize, gas_used: u128) -> RunTx {
+        RunTx {
+            gas_used,
+            ..RunTx::mock(n).with_times(start, end)
+        }
+    }
+
+    #[test]
+    fn summarizes_runs_by_endpoint() {
+        let runs = vec![
+            (
+                run(1, 4, "http://reth:8545"),
+                vec![
+                    run_tx(1, 100_000, 102_000, 21_000),
+                    run_tx(2, 101_000, 104_000, 21_000),
+                ],
+            ),
+            (
+                run(2, 2, "http://geth:8545"),
+                vec![
+                    run_tx(3, 100_000, 100_500, 50_000),
+                    run_tx(4, 100_000, 101_000, 50_000),
+                ],
+            ),
+            (
+                run(3, 4, "http://reth:8545"),
+                vec![
+                    run_tx(5, 200_000, 201_000, 42_000),
+                    run_tx(6, 200_000, 202_000, 42_000),
+                ],
+            ),
+        ];
+        let groups = group_by_endpoint(&runs);
+        // one of run 1's missing txs was replaced
+        let num_replaced = HashMap::from([(1, 1)]);
+        let summaries = groups
+            .iter()
+            .map(|group| EndpointSummary::build(group, &num_replaced))
+            .collect::<Vec<_>>();
+
+        assert_eq!(summaries.len(), 2);
+        let reth = &summaries[0];
+        assert_eq!(reth.endpoint, "http://reth:8545");
+        assert_eq!(reth.run_ids, "1, 3");
+        assert_eq!(reth.num_sent, 8);
+        assert_eq!(reth.num_landed, 4);
+        assert_eq!(reth.num_replaced, 1);
+        assert_eq!(reth.error_rate, 37.5);
+        assert_eq!(reth.avg_time_to_inclusion, "2000.00");
+        // 126k gas over (104 - 100) + (202 - 200) seconds
+        assert_eq!(reth.gas_per_second, 21_000);
+
+        let geth = &summaries[1];
+        assert_eq!(geth.error_rate, 0.0);
+        assert_eq!(geth.avg_time_to_inclusion, "750.00");
+        assert_eq!(geth.gas_per_second, 100_000);
+    }
+}
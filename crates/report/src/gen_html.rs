@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use alloy::primitives::B256;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use contender_core::db::{RunMetadata, RunScore};
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderErrorReason,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assertions::{GasViolation, SloSummary},
+    balance_summary::BalanceSummary,
+    block_trace::TraceMode,
+    bundle_summary::{BundleSummary, RouteSummary},
+    cost::CostSummary,
+    diff::EndpointSummary,
+    kind_summary::KindSummary,
+    pool_summary::PoolSummary,
+    ChartFormat, ReportChartId,
+};
+use crate::util::{grade, NumFormat};
+
+pub struct ReportMetadata {
+    pub scenario_name: String,
+    pub start_run_id: u64,
+    pub end_run_id: u64,
+    pub start_block: u64,
+    pub end_block: u64,
+    pub rpc_url: String,
+    /// Node & chain metadata recorded for each run; empty for runs recorded before it was collected.
+    pub run_metadata: Vec<RunMetadata>,
+    /// Scores of the runs that were scored (e.g. by `contender bench`).
+    pub run_scores: Vec<RunScore>,
+    /// Hashes of the scenarios the runs were sent from; empty for runs recorded before scenario
+    /// snapshots were saved.
+    pub scenario_hashes: Vec<B256>,
+    /// Tx kinds the report was filtered to; empty if unfiltered.
+    pub kinds: Vec<String>,
+    pub kind_summaries: Vec<KindSummary>,
+    /// Per-pool stats; empty if no txs were sent from agent pools.
+    pub pool_summaries: Vec<PoolSummary>,
+    /// Txs that used more gas than their step's `max_gas_used`.
+    pub gas_violations: Vec<GasViolation>,
+    /// Results of the runs' latency SLOs; empty if their scenarios didn't set any.
+    pub slo_summaries: Vec<SloSummary>,
+    /// Per-endpoint stats; only set for differential reports.
+    pub endpoint_summaries: Vec<EndpointSummary>,
+    /// Per-block bundle landing stats; empty if the runs didn't send bundles.
+    pub bundle_summaries: Vec<BundleSummary>,
+    /// Inclusion stats of builder vs. mempool txs; empty unless the runs sent both.
+    pub route_summaries: Vec<RouteSummary>,
+    /// ETH spent per run & pool.
+    pub cost_summaries: Vec<CostSummary>,
+    /// Change in each pool's balance per run; empty for runs recorded before balances were tracked.
+    pub balance_summaries: Vec<BalanceSummary>,
+    /// Charts to include in the report, in display order.
+    pub charts: Vec<ReportChartId>,
+    /// Image format the charts were rendered in.
+    pub chart_format: ChartFormat,
+    /// Method used to collect storage traces, if any were collected.
+    pub trace_mode: Option<TraceMode>,
+    /// Paths to the CSV exports for each run.
+    pub csv_files: Vec<String>,
+    /// Inline charts and CSVs into the HTML file so it can be shared on its own.
+    pub archive: bool,
+    pub num_format: NumFormat,
+    /// Directory to save the report in; its charts must have been drawn there too.
+    pub out_dir: String,
+}
+
+/// Template helpers formatting numbers with [`NumFormat`]: `{{si value "gas/s"}}` and
+/// `{{duration value_ms}}`, plus `{{percent value}}` for percentages (to 2 decimal places).
+/// Values may be numbers or numeric strings.
+#[derive(Clone, Copy)]
+enum NumHelper {
+    Si(NumFormat),
+    Duration(NumFormat),
+    Percent,
+}
+
+impl HelperDef for NumHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let param = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("number", 0))?
+            .value();
+        let value = param
+            .as_f64()
+            .or_else(|| param.as_str().and_then(|s| s.parse().ok()))
+            .ok_or_else(|| {
+                RenderErrorReason::InvalidParamType("expected a number or numeric string")
+            })?;
+        let formatted = match self {
+            NumHelper::Si(fmt) => {
+                let unit = h.param(1).and_then(|p| p.value().as_str()).unwrap_or("");
+                fmt.si(value, unit)
+            }
+            NumHelper::Duration(fmt) => fmt.duration_ms(value),
+            NumHelper::Percent => format!("{:.2}", value),
+        };
+        out.write(&formatted)?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct TemplateData {
+    scenario_name: String,
+    date: String,
+    rpc_url: String,
+    client_version: String,
+    chain_id: String,
+    genesis_hash: String,
+    fork: String,
+    scenario_hash: String,
+    /// Headline grade & mean score of the scored runs, e.g. "B (84.2/100)"; empty if none were scored.
+    grade: String,
+    start_block: String,
+    end_block: String,
+    kinds: String,
+    trace_mode: Option<String>,
+    kind_summaries: Vec<KindSummary>,
+    pool_summaries: Vec<PoolSummary>,
+    gas_violations: Vec<GasViolation>,
+    slo_summaries: Vec<SloSummary>,
+    endpoint_summaries: Vec<EndpointSummary>,
+    bundle_summaries: Vec<BundleSummary>,
+    route_summaries: Vec<RouteSummary>,
+    cost_summaries: Vec<CostSummary>,
+    balance_summaries: Vec<BalanceSummary>,
+    charts: Vec<(String, String)>,
+    csv_files: Vec<(String, String)>,
+}
+
+impl TemplateData {
+    pub fn new(
+        meta: ReportMetadata,
+        charts: Vec<(String, String)>,
+        csv_files: Vec<(String, String)>,
+    ) -> Self {
+        let runs = &meta.run_metadata;
+        Self {
+            scenario_name: meta.scenario_name,
+            date: chrono::Local::now().to_rfc2822(),
+            rpc_url: meta.rpc_url,
+            client_version: join_distinct(runs.iter().map(|m| m.client_version.to_owned())),
+            chain_id: join_distinct(runs.iter().map(|m| m.chain_id.to_string())),
+            genesis_hash: join_distinct(runs.iter().map(|m| m.genesis_hash.to_string())),
+            fork: join_distinct(runs.iter().map(|m| m.fork.to_owned())),
+            scenario_hash: join_distinct(meta.scenario_hashes.iter().map(|h| h.to_string())),
+            grade: headline_grade(&meta.run_scores),
+            start_block: meta.start_block.to_string(),
+            end_block: meta.end_block.to_string(),
+            kinds: meta.kinds.join(", "),
+            trace_mode: meta.trace_mode.map(|mode| mode.to_string()),
+            kind_summaries: meta.kind_summaries,
+            pool_summaries: meta.pool_summaries,
+            gas_violations: meta.gas_violations,
+            slo_summaries: meta.slo_summaries,
+            endpoint_summaries: meta.endpoint_summaries,
+            bundle_summaries: meta.bundle_summaries,
+            route_summaries: meta.route_summaries,
+            cost_summaries: meta.cost_summaries,
+            balance_summaries: meta.balance_summaries,
+            charts,
+            csv_files,
+        }
+    }
+}
+
+/// Joins unique values in order of first appearance, so runs against differing nodes are visible.
+fn join_distinct(values: impl Iterator<Item = String>) -> String {
+    let mut unique: Vec<String> = vec![];
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique.join(", ")
+}
+
+/// Grades the mean score of `scores`, e.g. "B (84.2/100)"; empty if there are no scores.
+fn headline_grade(scores: &[RunScore]) -> String {
+    if scores.is_empty() {
+        return String::new();
+    }
+    let score = scores.iter().map(|s| s.score).sum::<f64>() / scores.len() as f64;
+    format!("{} ({:.1}/100)", grade(score), score)
+}
+
+/// Returns a `data:` URI containing the file at `path`.
+fn data_uri(path: &str, mime_type: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let data = std::fs::read(path)?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime_type,
+        STANDARD.encode(data)
+    ))
+}
+
+/// Builds an HTML report for the given run IDs in `meta.out_dir`, next to its charts. Returns the
+/// path to the report.
+///
+/// If `meta.archive` is set, charts and CSVs are embedded in the HTML file rather than linked.
+pub fn build_html_report(meta: ReportMetadata) -> Result<String, Box<dyn std::error::Error>> {
+    let out_dir = meta.out_dir.to_owned();
+    let link = |path: String, mime_type: &str| {
+        if meta.archive {
+            data_uri(&path, mime_type)
+        } else {
+            Ok(path)
+        }
+    };
+
+    let mut charts = Vec::new();
+    for chart_id in &meta.charts {
+        let filename = chart_id.filename(
+            &out_dir,
+            meta.start_run_id,
+            meta.end_run_id,
+            meta.chart_format,
+        );
+        charts.push((
+            chart_id.proper_name(),
+            link(filename, meta.chart_format.mime_type())?,
+        ));
+    }
+
+    let mut csv_files = Vec::new();
+    for path in &meta.csv_files {
+        let name = std::path::Path::new(path)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        csv_files.push((name, link(path.to_owned(), "text/csv")?));
+    }
+
+    let template = include_str!("template.html");
+
+    let path = format!(
+        "{}/report-{}{}-{}{}.html",
+        out_dir,
+        if meta.endpoint_summaries.is_empty() {
+            ""
+        } else {
+            "diff-"
+        },
+        meta.start_run_id,
+        meta.end_run_id,
+        if meta.archive { "-archive" } else { "" }
+    );
+
+    let mut handlebars = Handlebars::new();
+    handlebars.register_helper("si", Box::new(NumHelper::Si(meta.num_format)));
+    handlebars.register_helper("duration", Box::new(NumHelper::Duration(meta.num_format)));
+    handlebars.register_helper("percent", Box::new(NumHelper::Percent));
+
+    let mut data = HashMap::new();
+    let template_data = TemplateData::new(meta, charts, csv_files);
+    data.insert("data", template_data);
+    let html = handlebars.render_template(template, &data)?;
+    std::fs::write(&path, html)?;
+    println!("saved report to {}", path);
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{data_uri, headline_grade, join_distinct, NumHelper};
+    use crate::util::NumFormat;
+    use contender_core::db::RunScore;
+    use handlebars::Handlebars;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn encodes_files_as_data_uris() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "run_id,tx_hash\n").unwrap();
+        let uri = data_uri(file.path().to_str().unwrap(), "text/csv").unwrap();
+        assert_eq!(uri, "data:text/csv;base64,cnVuX2lkLHR4X2hhc2gK");
+    }
+
+    #[test]
+    fn joins_distinct_values_in_order() {
+        let values = ["reth/v1.1.0", "geth/v1.14.11", "reth/v1.1.0"].map(String::from);
+        assert_eq!(
+            join_distinct(values.into_iter()),
+            "reth/v1.1.0, geth/v1.14.11"
+        );
+        assert_eq!(join_distinct(std::iter::empty()), "");
+    }
+
+    #[test]
+    fn formats_numbers_in_templates() {
+        let render = |fmt| {
+            let mut handlebars = Handlebars::new();
+            handlebars.register_helper("si", Box::new(NumHelper::Si(fmt)));
+            handlebars.register_helper("duration", Box::new(NumHelper::Duration(fmt)));
+            handlebars.register_helper("percent", Box::new(NumHelper::Percent));
+            handlebars
+                .render_template(
+                    r#"{{si gas "gas/s"}}, {{duration ms}}, {{percent rate}}"#,
+                    &serde_json::json!({ "gas": 1_250_000, "ms": "12500.00", "rate": 37.5 }),
+                )
+                .unwrap()
+        };
+        assert_eq!(render(NumFormat::default()), "1.2 Mgas/s, 12.5 s, 37.50");
+        assert_eq!(render(NumFormat { raw: true }), "1250000, 12500, 37.50");
+    }
+
+    #[test]
+    fn grades_mean_run_score() {
+        let score = |score| RunScore {
+            score,
+            grade: String::new(),
+            throughput: 1.0,
+            latency: 1.0,
+            reliability: 1.0,
+        };
+        assert_eq!(headline_grade(&[]), "");
+        assert_eq!(headline_grade(&[score(90.0), score(80.0)]), "B (85.0/100)");
+    }
+}
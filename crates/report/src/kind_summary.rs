@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::primitives::TxHash;
+use contender_core::db::{ReplacedTx, RunError, RunTx};
+use serde::{Deserialize, Serialize};
+
+use super::{block_trace::TxTraceReceipt, util::kind_label};
+
+/// Per-kind stats displayed in the report's summary table.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct KindSummary {
+    pub kind: String,
+    pub num_txs: usize,
+    /// Reverted txs, not counting those whose step expected them to revert.
+    pub num_reverted: usize,
+    /// Reverted txs whose step set `expect_status = "revert"`.
+    pub num_expected_reverts: usize,
+    /// Txs whose receipt status didn't match their step's `expect_status`.
+    pub num_unexpected_status: usize,
+    /// Txs that never landed because another tx from the same sender used their nonce.
+    pub num_replaced: usize,
+    /// Errors hit while sending the kind's txs, e.g. failed sends & txs that never landed.
+    pub num_errors: u64,
+    /// Number of errors of each kind, e.g. `nonce_conflict=3 transport=1`.
+    pub error_breakdown: String,
+    /// In milliseconds.
+    pub avg_time_to_inclusion: String,
+    /// In milliseconds.
+    pub max_time_to_inclusion: u64,
+    pub avg_gas_used: u128,
+}
+
+impl KindSummary {
+    /// Summarizes `run_txs` by kind. Reverts are counted from the receipts in `traces`.
+    /// Kinds which only have replaced txs or errors are included with `num_txs = 0`.
+    pub fn build_all(
+        run_txs: &[RunTx],
+        replaced_txs: &[ReplacedTx],
+        run_errors: &[RunError],
+        traces: &[TxTraceReceipt],
+    ) -> Vec<Self> {
+        let statuses = traces
+            .iter()
+            .map(|t| (t.receipt.transaction_hash, t.receipt.status()))
+            .collect::<HashMap<_, _>>();
+        Self::build_with_statuses(run_txs, replaced_txs, run_errors, &statuses)
+    }
+
+    /// Like [`Self::build_all`], with the receipt status of each landed tx given by hash.
+    fn build_with_statuses(
+        run_txs: &[RunTx],
+        replaced_txs: &[ReplacedTx],
+        run_errors: &[RunError],
+        statuses: &HashMap<TxHash, bool>,
+    ) -> Vec<Self> {
+        let mut by_kind: BTreeMap<String, Vec<&RunTx>> = BTreeMap::new();
+        for tx in run_txs {
+            by_kind.entry(kind_label(&tx.kind)).or_default().push(tx);
+        }
+        let mut num_replaced: BTreeMap<String, usize> = BTreeMap::new();
+        for tx in replaced_txs {
+            let kind = kind_label(&tx.kind);
+            by_kind.entry(kind.to_owned()).or_default();
+            *num_replaced.entry(kind).or_default() += 1;
+        }
+        // error kind -> count, by tx kind
+        let mut errors: BTreeMap<String, BTreeMap<&str, u64>> = BTreeMap::new();
+        for error in run_errors {
+            let kind = kind_label(&error.kind);
+            by_kind.entry(kind.to_owned()).or_default();
+            *errors
+                .entry(kind)
+                .or_default()
+                .entry(&error.error_kind)
+                .or_default() += error.count;
+        }
+
+        by_kind
+            .into_iter()
+            .map(|(kind, txs)| {
+                let num_txs = txs.len();
+                let times = txs
+                    .iter()
+                    .map(|tx| tx.time_to_inclusion_ms())
+                    .collect::<Vec<_>>();
+                let num_replaced = num_replaced.get(&kind).copied().unwrap_or_default();
+                let errors = errors.remove(&kind).unwrap_or_default();
+                let reverted = txs
+                    .iter()
+                    .filter(|tx| statuses.get(&tx.tx_hash) == Some(&false))
+                    .collect::<Vec<_>>();
+                let num_expected_reverts = reverted.iter().filter(|tx| tx.expects_revert()).count();
+                let num_unexpected_status = txs
+                    .iter()
+                    .filter(|tx| {
+                        let status = statuses.get(&tx.tx_hash);
+                        tx.expect_status
+                            .zip(status)
+                            .is_some_and(|(expected, status)| !expected.matches(*status))
+                    })
+                    .count();
+                Self {
+                    kind,
+                    num_txs,
+                    num_reverted: reverted.len() - num_expected_reverts,
+                    num_expected_reverts,
+                    num_unexpected_status,
+                    num_replaced,
+                    num_errors: errors.values().sum(),
+                    error_breakdown: errors
+                        .iter()
+                        .map(|(kind, count)| format!("{}={}", kind, count))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    avg_time_to_inclusion: format!(
+                        "{:.2}",
+                        times.iter().sum::<u64>() as f64 / num_txs.max(1) as f64
+                    ),
+                    max_time_to_inclusion: times.into_iter().max().unwrap_or_default(),
+                    avg_gas_used: txs.iter().map(|tx| tx.gas_used).sum::<u128>()
+                        / num_txs.max(1) as u128,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+    use contender_core::generator::types::ExpectStatus;
+
+    fn run_tx(n: u8, tti: usize, gas_used: u128, kind: Option<&str>) -> RunTx {
+        RunTx {
+            gas_used,
+            kind: kind.map(|k| k.to_owned()),
+            ..RunTx::mock(n).with_times(100_000, 100_000 + tti)
+        }
+    }
+
+    #[test]
+    fn counts_expected_reverts_separately() {
+        let expect = |n: u8, expect_status: Option<ExpectStatus>| RunTx {
+            expect_status,
+            ..run_tx(n, 1_000, 21_000, Some("revert"))
+        };
+        let txs = vec![
+            expect(1, Some(ExpectStatus::Revert)),
+            expect(2, Some(ExpectStatus::Revert)),
+            expect(3, Some(ExpectStatus::Success)),
+            expect(4, None),
+        ];
+        // tx 2 was expected to revert, but succeeded
+        let statuses = HashMap::from([
+            (TxHash::repeat_byte(1), false),
+            (TxHash::repeat_byte(2), true),
+            (TxHash::repeat_byte(3), false),
+            (TxHash::repeat_byte(4), false),
+        ]);
+        let summary = KindSummary::build_with_statuses(&txs, &[], &[], &statuses);
+        assert_eq!(summary[0].num_reverted, 2);
+        assert_eq!(summary[0].num_expected_reverts, 1);
+        assert_eq!(summary[0].num_unexpected_status, 2);
+    }
+
+    #[test]
+    fn summarizes_txs_by_kind() {
+        let txs = vec![
+            run_tx(1, 1_000, 21_000, Some("swap")),
+            run_tx(2, 4_250, 41_000, Some("swap")),
+            run_tx(3, 2_000, 50_000, None),
+        ];
+        let replaced = vec![ReplacedTx {
+            tx_hash: TxHash::repeat_byte(4),
+            sender: Address::repeat_byte(1),
+            nonce: 0,
+            start_timestamp: 100,
+            block_number: 1,
+            kind: Some("swap".to_owned()),
+        }];
+        let errors = vec![
+            RunError {
+                kind: Some("swap".to_owned()),
+                error_kind: "nonce_conflict".to_owned(),
+                count: 2,
+            },
+            RunError {
+                kind: Some("swap".to_owned()),
+                error_kind: "transport".to_owned(),
+                count: 1,
+            },
+            RunError {
+                kind: Some("mint".to_owned()),
+                error_kind: "timeout".to_owned(),
+                count: 4,
+            },
+        ];
+        let summary = KindSummary::build_all(&txs, &replaced, &errors, &[]);
+        assert_eq!(summary.len(), 3);
+        // kinds whose txs all failed are still listed
+        assert_eq!(summary[0].kind, "mint");
+        assert_eq!(summary[0].num_txs, 0);
+        assert_eq!(summary[0].num_errors, 4);
+        assert_eq!(summary[0].error_breakdown, "timeout=4");
+        let summary = &summary[1..];
+        assert_eq!(summary[0].kind, "swap");
+        assert_eq!(summary[0].num_txs, 2);
+        assert_eq!(summary[0].num_replaced, 1);
+        assert_eq!(summary[0].avg_time_to_inclusion, "2625.00");
+        assert_eq!(summary[0].max_time_to_inclusion, 4_250);
+        assert_eq!(summary[0].avg_gas_used, 31_000);
+        assert_eq!(summary[0].num_errors, 3);
+        assert_eq!(summary[0].error_breakdown, "nonce_conflict=2 transport=1");
+        assert_eq!(summary[1].kind, "unlabeled");
+        assert_eq!(summary[1].num_reverted, 0);
+        assert_eq!(summary[1].num_replaced, 0);
+        assert_eq!(summary[1].num_errors, 0);
+    }
+
+    #[test]
+    fn includes_kinds_with_only_replaced_txs() {
+        let replaced = vec![ReplacedTx {
+            tx_hash: TxHash::repeat_byte(1),
+            sender: Address::repeat_byte(1),
+            nonce: 0,
+            start_timestamp: 100,
+            block_number: 1,
+            kind: None,
+        }];
+        let summary = KindSummary::build_all(&[], &replaced, &[], &[]);
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].num_txs, 0);
+        assert_eq!(summary[0].num_replaced, 1);
+        assert_eq!(summary[0].avg_time_to_inclusion, "0.00");
+    }
+}
@@ -0,0 +1,480 @@
+mod assertions;
+mod balance_summary;
+mod baseline;
+mod block_summary;
+mod block_trace;
+mod bundle_summary;
+mod cache;
+mod chart;
+mod cost;
+mod diff;
+mod gen_html;
+mod kind_summary;
+mod openmetrics;
+mod pool_summary;
+mod util;
+
+use crate::util::write_run_txs;
+use alloy::transports::http::reqwest::Url;
+use assertions::GasViolation;
+use balance_summary::BalanceSummary;
+use block_summary::{save_block_csv, BlockSummary};
+use block_trace::get_block_trace_data;
+use bundle_summary::{BundleSummary, RouteSummary};
+use chart::{DrawableChart, ReportChartId};
+use chart::{
+    GasPerBlockChart, HeatMapChart, OpcodeGasChart, PriorityFeeChart, RpcLatencyChart,
+    TimeToInclusionChart, TxGasUsedChart,
+};
+use contender_core::{
+    db::{DbOps, RunTx, SpamRun},
+    provider::eth_provider,
+};
+use csv::WriterBuilder;
+use gen_html::{build_html_report, ReportMetadata};
+use kind_summary::KindSummary;
+use openmetrics::report_openmetrics;
+use pool_summary::PoolSummary;
+
+use cost::{CostSummary, TxFee};
+
+pub use assertions::{run_slos, SloSummary};
+pub use baseline::report_baseline;
+pub use chart::ChartFormat;
+pub use cost::report_cost;
+pub use diff::report_diff;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+pub use util::{grade, NumFormat};
+
+#[derive(Debug, Default)]
+pub struct ReportCommandArgs {
+    /// The last run to include in the report. Defaults to the latest run.
+    pub last_run_id: Option<u64>,
+    /// The number of runs preceding `last_run_id` to include in the report.
+    pub preceding_runs: u64,
+    /// Only include txs with these kinds. All txs are included if empty.
+    pub kinds: Vec<String>,
+    /// Draw a separate series for each tx kind in the time-to-inclusion and gas-used charts.
+    pub group_by_kind: bool,
+    /// Names of contracts (from `[[create]]` steps) to draw separate storage heatmaps for.
+    /// A single heatmap of all storage updates is drawn if empty.
+    pub contracts: Vec<String>,
+    /// Trace the blocks of the run's txs with the struct logger to chart gas used per opcode family.
+    pub opcode_profile: bool,
+    /// Save a self-contained HTML report with charts and CSVs embedded.
+    pub archive: bool,
+    /// What to generate for the runs.
+    pub format: ReportFormat,
+    /// Image format to render charts in.
+    pub chart_format: ChartFormat,
+    pub num_format: NumFormat,
+    /// Directory to cache block traces in, so blocks aren't re-traced for every report. It's pruned
+    /// to 1 GiB, least recently used blocks first. Blocks are always traced from the RPC if `None`.
+    pub trace_cache_dir: Option<String>,
+}
+
+/// Output format of `contender report`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// HTML report with charts & CSVs.
+    #[default]
+    Html,
+    /// OpenMetrics text file of the runs' counters & latency histograms, for Prometheus-compatible
+    /// tooling. Doesn't need the RPC.
+    #[value(name = "openmetrics")]
+    OpenMetrics,
+}
+
+/// Returns the (first, last) run IDs to report on, or `None` if there are no runs.
+fn run_range(
+    db: &impl DbOps,
+    last_run_id: Option<u64>,
+    preceding_runs: u64,
+) -> Result<Option<(u64, u64)>, Box<dyn std::error::Error>> {
+    let num_runs = db.num_runs()?;
+    if num_runs == 0 {
+        return Ok(None);
+    }
+
+    // if id is provided, check if it's valid
+    let end_run_id = if let Some(id) = last_run_id {
+        if id == 0 || id > num_runs {
+            return Err(format!("Invalid run ID: {}", id).into());
+        }
+        id
+    } else {
+        // get latest run
+        println!("No run ID provided. Using latest run ID: {}", num_runs);
+        num_runs
+    };
+
+    let start_run_id = end_run_id
+        .checked_sub(preceding_runs)
+        .filter(|id| *id > 0)
+        .ok_or(format!(
+            "Can't include {} runs before run {}; run IDs start at 1.",
+            preceding_runs, end_run_id
+        ))?;
+    Ok(Some((start_run_id, end_run_id)))
+}
+
+/// Generates a report of the runs selected by `args` in `out_dir`, along with its charts & CSVs.
+/// Returns the path to the report, or `None` if there are no runs. Nothing is written outside of
+/// `out_dir` and `args.trace_cache_dir`.
+pub async fn report(
+    args: ReportCommandArgs,
+    db: &(impl DbOps + Clone + Send + Sync + 'static),
+    rpc_url: &str,
+    out_dir: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let ReportCommandArgs {
+        last_run_id,
+        preceding_runs,
+        kinds,
+        group_by_kind,
+        contracts,
+        opcode_profile,
+        archive,
+        format,
+        chart_format,
+        num_format,
+        trace_cache_dir,
+    } = args;
+    let Some((start_run_id, end_run_id)) = run_range(db, last_run_id, preceding_runs)? else {
+        println!("No runs found in the database. Exiting.");
+        return Ok(None);
+    };
+    std::fs::create_dir_all(out_dir)?;
+    if format == ReportFormat::OpenMetrics {
+        return report_openmetrics(db, start_run_id, end_run_id, &kinds, out_dir).map(Some);
+    }
+
+    // collect CSV report for each run_id
+    let mut all_txs = vec![];
+    let mut replaced_txs = vec![];
+    let mut run_errors = vec![];
+    let mut csv_files = vec![];
+    let mut slo_summaries = vec![];
+    // every tx of each run, including ones filtered out by kind, to sum the fees each run paid
+    let mut run_tx_hashes = vec![];
+    for id in start_run_id..=end_run_id {
+        let txs = db.get_run_txs(id)?;
+        run_tx_hashes.push((id, txs.iter().map(|tx| tx.tx_hash).collect::<Vec<_>>()));
+        let slos = run_slos(db, id)?
+            .into_iter()
+            .filter(|slo| kinds.is_empty() || kinds.contains(&slo.kind))
+            .collect::<Vec<_>>();
+        slo_summaries.extend(SloSummary::build_run(id, &slos, &txs));
+        all_txs.extend_from_slice(&txs);
+        csv_files.push(save_csv_report(id, &txs, out_dir)?);
+        replaced_txs.extend(db.get_replaced_txs(id)?);
+        run_errors.extend(db.get_run_errors(id)?);
+    }
+
+    // drop txs of kinds we're not interested in; CSV reports always include every tx
+    if !kinds.is_empty() {
+        all_txs.retain(|tx| tx.kind.as_ref().is_some_and(|kind| kinds.contains(kind)));
+        replaced_txs.retain(|tx| tx.kind.as_ref().is_some_and(|kind| kinds.contains(kind)));
+        run_errors.retain(|err| err.kind.as_ref().is_some_and(|kind| kinds.contains(kind)));
+        if all_txs.is_empty() {
+            return Err(format!("No txs found with kind(s): {}", kinds.join(", ")).into());
+        }
+    }
+
+    // get run data
+    let mut run_data = vec![];
+    let mut run_metadata = vec![];
+    let mut run_scores = vec![];
+    let mut scenario_hashes = vec![];
+    let mut bundles = vec![];
+    for id in start_run_id..=end_run_id {
+        let run = db.get_run(id)?;
+        if let Some(run) = run {
+            run_data.push(run);
+        }
+        if let Some(metadata) = db.get_run_metadata(id)? {
+            run_metadata.push(metadata);
+        }
+        if let Some(score) = db.get_run_score(id)? {
+            run_scores.push(score);
+        }
+        if let Some(artifacts) = db.get_run_artifacts(id)? {
+            scenario_hashes.push(artifacts.scenario_hash);
+        }
+        bundles.extend(db.get_bundles(id)?);
+    }
+    let scenario_title = scenario_title(run_data.iter());
+
+    // get trace data for reports
+    let url = Url::from_str(rpc_url).map_err(|e| format!("Invalid RPC URL {}: {}", rpc_url, e))?;
+    let rpc_client = eth_provider(&url).await?;
+    let mut opcode_gas = opcode_profile.then(OpcodeGasChart::new);
+    let (mut traces, blocks, trace_mode) = get_block_trace_data(
+        &all_txs,
+        &rpc_client,
+        rpc_url,
+        trace_cache_dir.as_deref(),
+        opcode_gas.as_mut(),
+    )
+    .await?;
+
+    // export per-block aggregates
+    let block_csv = format!("{}/blocks_run-{}-{}.csv", out_dir, start_run_id, end_run_id);
+    save_block_csv(&block_csv, &BlockSummary::build_all(&blocks, &all_txs))?;
+    csv_files.push(block_csv);
+
+    // when filtering by kind, only chart traces for the txs we kept
+    if !kinds.is_empty() {
+        let tx_hashes = all_txs.iter().map(|tx| tx.tx_hash).collect::<HashSet<_>>();
+        traces.retain(|t| tx_hashes.contains(&t.receipt.transaction_hash));
+    }
+    let tx_kinds = all_txs
+        .iter()
+        .map(|tx| (tx.tx_hash, util::kind_label(&tx.kind)))
+        .collect::<HashMap<_, _>>();
+
+    let mut charts = vec![];
+
+    // make heatmap(s); these need storage traces
+    if !trace_mode.has_traces() {
+        println!("no storage traces available; skipping heatmaps");
+    } else if contracts.is_empty() {
+        let heatmap = HeatMapChart::build(&traces, None)?;
+        let chart_id = ReportChartId::Heatmap;
+        heatmap.draw(
+            chart_id.filename(out_dir, start_run_id, end_run_id, chart_format),
+            chart_format,
+        )?;
+        csv_files.push(save_heatmap_data(
+            &heatmap,
+            &chart_id,
+            out_dir,
+            start_run_id,
+            end_run_id,
+        )?);
+        charts.push(chart_id);
+    } else {
+        for name in &contracts {
+            let address = db
+                .get_named_tx(name, rpc_url, None)?
+                .and_then(|tx| tx.address)
+                .ok_or(format!(
+                    "Contract '{}' has not been deployed to {}.",
+                    name, rpc_url
+                ))?;
+            let heatmap = HeatMapChart::build(&traces, Some(address))?;
+            let chart_id = ReportChartId::ContractHeatmap(name.to_owned());
+            heatmap.draw(
+                chart_id.filename(out_dir, start_run_id, end_run_id, chart_format),
+                chart_format,
+            )?;
+            csv_files.push(save_heatmap_data(
+                &heatmap,
+                &chart_id,
+                out_dir,
+                start_run_id,
+                end_run_id,
+            )?);
+            charts.push(chart_id);
+        }
+    }
+
+    // make gasPerBlock chart
+    let gas_per_block = GasPerBlockChart::build(&blocks);
+    gas_per_block.draw(
+        ReportChartId::GasPerBlock.filename(out_dir, start_run_id, end_run_id, chart_format),
+        chart_format,
+    )?;
+    charts.push(ReportChartId::GasPerBlock);
+
+    // make timeToInclusion chart
+    let time_to_inclusion = TimeToInclusionChart::build(&all_txs, group_by_kind);
+    time_to_inclusion.draw(
+        ReportChartId::TimeToInclusion.filename(out_dir, start_run_id, end_run_id, chart_format),
+        chart_format,
+    )?;
+    charts.push(ReportChartId::TimeToInclusion);
+
+    // make ackLatency chart; runs recorded before ack latency was measured have no data
+    let ack_latency = TimeToInclusionChart::build_ack_latency(&all_txs, group_by_kind);
+    if !ack_latency.is_empty() {
+        ack_latency.draw(
+            ReportChartId::AckLatency.filename(out_dir, start_run_id, end_run_id, chart_format),
+            chart_format,
+        )?;
+        charts.push(ReportChartId::AckLatency);
+    }
+
+    // make txGasUsed chart
+    let tx_gas_used = TxGasUsedChart::build(&traces, group_by_kind.then_some(&tx_kinds))?;
+    tx_gas_used.draw(
+        ReportChartId::TxGasUsed.filename(out_dir, start_run_id, end_run_id, chart_format),
+        chart_format,
+    )?;
+    charts.push(ReportChartId::TxGasUsed);
+
+    // make priorityFee chart
+    let priority_fee = PriorityFeeChart::build(&all_txs, &traces, &blocks);
+    if !priority_fee.is_empty() {
+        priority_fee.draw(
+            ReportChartId::PriorityFee.filename(out_dir, start_run_id, end_run_id, chart_format),
+            chart_format,
+        )?;
+        charts.push(ReportChartId::PriorityFee);
+    }
+
+    // make opcodeGas chart from the struct logs collected with the block traces
+    if let Some(opcode_gas) = opcode_gas {
+        println!(
+            "profiled {} gas across {} txs",
+            opcode_gas.total_gas(),
+            all_txs.len()
+        );
+        opcode_gas.draw(
+            ReportChartId::OpcodeGas.filename(out_dir, start_run_id, end_run_id, chart_format),
+            chart_format,
+        )?;
+        charts.push(ReportChartId::OpcodeGas);
+    }
+
+    // make rpcLatency chart; runs recorded before latency tracking was added have no snapshots
+    let mut latency_snapshots = vec![];
+    for id in start_run_id..=end_run_id {
+        latency_snapshots.extend(db.get_latency_snapshots(id)?);
+    }
+    let rpc_latency = RpcLatencyChart::build(&latency_snapshots);
+    if !rpc_latency.is_empty() {
+        rpc_latency.draw(
+            ReportChartId::RpcLatency.filename(out_dir, start_run_id, end_run_id, chart_format),
+            chart_format,
+        )?;
+        charts.push(ReportChartId::RpcLatency);
+    }
+
+    // sum fees paid per run & pool
+    let receipts = traces
+        .iter()
+        .map(|t| (t.receipt.transaction_hash, &t.receipt))
+        .collect::<HashMap<_, _>>();
+    let mut cost_summaries = vec![];
+    for (id, tx_hashes) in run_tx_hashes {
+        let fees = tx_hashes
+            .iter()
+            .filter_map(|hash| receipts.get(hash).map(|r| TxFee::from(*r)))
+            .collect::<Vec<_>>();
+        let funding_txs = db.get_funding_txs(id)?;
+        if !fees.is_empty() || !funding_txs.is_empty() {
+            cost_summaries.extend(CostSummary::build_run(
+                id,
+                fees,
+                &funding_txs,
+                &db.get_run_accounts(id)?,
+            ));
+        }
+    }
+
+    // break txs down by the pool they were sent from
+    let mut run_accounts = vec![];
+    for id in start_run_id..=end_run_id {
+        run_accounts.extend(db.get_run_accounts(id)?);
+    }
+    let pool_summaries = PoolSummary::build_all(&all_txs, &replaced_txs, &traces, &run_accounts);
+
+    // compare balances taken at the start & end of each run
+    let mut balance_summaries = vec![];
+    for id in start_run_id..=end_run_id {
+        balance_summaries.extend(BalanceSummary::build_run(
+            id,
+            &db.get_balances(id, true)?,
+            &db.get_balances(id, false)?,
+        ));
+    }
+
+    // compile report
+    let (Some(first_block), Some(last_block)) = (blocks.first(), blocks.last()) else {
+        return Err(format!(
+            "No blocks found for the txs of runs {}-{}.",
+            start_run_id, end_run_id
+        )
+        .into());
+    };
+    let report_path = build_html_report(ReportMetadata {
+        scenario_name: scenario_title,
+        start_run_id,
+        end_run_id,
+        start_block: first_block.header.number,
+        end_block: last_block.header.number,
+        rpc_url: rpc_url.to_string(),
+        run_metadata,
+        run_scores,
+        scenario_hashes,
+        kinds,
+        kind_summaries: KindSummary::build_all(&all_txs, &replaced_txs, &run_errors, &traces),
+        pool_summaries,
+        gas_violations: GasViolation::build_all(&all_txs),
+        slo_summaries,
+        endpoint_summaries: vec![],
+        bundle_summaries: BundleSummary::build_all(&bundles, &all_txs, &traces),
+        route_summaries: RouteSummary::build_all(&bundles, &all_txs),
+        cost_summaries,
+        balance_summaries,
+        charts,
+        chart_format,
+        trace_mode: Some(trace_mode),
+        csv_files,
+        archive,
+        num_format,
+        out_dir: out_dir.to_owned(),
+    })?;
+
+    Ok(Some(report_path))
+}
+
+/// Joins the unique scenario names of `runs`, without their paths & extensions.
+fn scenario_title<'a>(runs: impl Iterator<Item = &'a SpamRun>) -> String {
+    runs.map(|run| run.scenario_name.clone())
+        .collect::<HashSet<_>>()
+        .iter()
+        .map(|v| {
+            // return only the filename without the path and extension
+            let re = regex::Regex::new(r".*/(.*)\.toml$").unwrap();
+            re.replace(v, "$1").to_string()
+        })
+        .reduce(|acc, v| format!("{}, {}", acc, v))
+        .unwrap_or_default()
+}
+
+/// Saves the heatmap's (contract, slot, block, count) data as CSV & JSON next to its chart.
+/// Returns the path to the CSV file.
+fn save_heatmap_data(
+    heatmap: &HeatMapChart,
+    chart_id: &ReportChartId,
+    out_dir: &str,
+    start_run_id: u64,
+    end_run_id: u64,
+) -> Result<String, Box<dyn std::error::Error>> {
+    heatmap.save_json(chart_id.data_filename(out_dir, start_run_id, end_run_id, "json"))?;
+    let csv_path = chart_id.data_filename(out_dir, start_run_id, end_run_id, "csv");
+    heatmap.save_csv(&csv_path)?;
+    Ok(csv_path)
+}
+
+/// Saves RunTxs to `{out_dir}/{id}.csv`. Returns the path to the CSV file.
+fn save_csv_report(
+    id: u64,
+    txs: &[RunTx],
+    out_dir: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let out_path = format!("{out_dir}/{id}.csv");
+
+    println!("Exporting report for run #{:?} to {:?}", id, out_path);
+    let mut writer = WriterBuilder::new()
+        .has_headers(true)
+        .from_path(&out_path)?;
+    write_run_txs(&mut writer, txs)?;
+
+    Ok(out_path)
+}
@@ -0,0 +1,271 @@
+use contender_core::db::{DbOps, LatencySnapshot, RunTx, SpamRun};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Upper bounds of the time-to-inclusion histogram's buckets, in milliseconds.
+const INCLUSION_BUCKETS_MS: [u64; 11] = [
+    250, 500, 1_000, 2_000, 4_000, 8_000, 12_000, 24_000, 60_000, 120_000, 300_000,
+];
+
+/// Stored results of a run, exported as OpenMetrics metrics labeled with the run's ID.
+pub struct RunMetrics {
+    pub run: SpamRun,
+    pub txs: Vec<RunTx>,
+    pub num_replaced: usize,
+    pub latency: Vec<LatencySnapshot>,
+}
+
+impl RunMetrics {
+    /// Time the run's results are reported at: when its last tx landed, else when it started.
+    fn timestamp_ms(&self) -> usize {
+        self.txs
+            .iter()
+            .map(|tx| tx.end_timestamp)
+            .max()
+            .unwrap_or(self.run.timestamp)
+    }
+}
+
+/// Saves the metrics of runs `start_run_id..=end_run_id` to an OpenMetrics text file in `out_dir`,
+/// and returns its path. Only txs of the given `kinds` are counted, unless it's empty.
+pub fn report_openmetrics(
+    db: &impl DbOps,
+    start_run_id: u64,
+    end_run_id: u64,
+    kinds: &[String],
+    out_dir: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let keep = |kind: &Option<String>| {
+        kinds.is_empty() || kind.as_ref().is_some_and(|kind| kinds.contains(kind))
+    };
+    let mut runs = vec![];
+    for id in start_run_id..=end_run_id {
+        let Some(run) = db.get_run(id)? else {
+            continue;
+        };
+        let mut txs = db.get_run_txs(id)?;
+        txs.retain(|tx| keep(&tx.kind));
+        let num_replaced = db
+            .get_replaced_txs(id)?
+            .iter()
+            .filter(|tx| keep(&tx.kind))
+            .count();
+        runs.push(RunMetrics {
+            run,
+            txs,
+            num_replaced,
+            latency: db.get_latency_snapshots(id)?,
+        });
+    }
+
+    let path = format!("{}/metrics_run-{}-{}.om", out_dir, start_run_id, end_run_id);
+    std::fs::write(&path, encode_openmetrics(&runs)?)?;
+    println!("saved OpenMetrics export to {}", path);
+    Ok(path)
+}
+
+/// Encodes run metrics in the OpenMetrics text format. Samples carry the time they were measured,
+/// so the file can be backfilled into Prometheus-compatible stores.
+pub fn encode_openmetrics(runs: &[RunMetrics]) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    let ts = |ms: usize| format!("{:.3}", ms as f64 / 1000.0);
+
+    writeln!(out, "# TYPE contender_planned_txs gauge")?;
+    writeln!(
+        out,
+        "# HELP contender_planned_txs Txs the run was set to send."
+    )?;
+    for m in runs {
+        writeln!(
+            out,
+            "contender_planned_txs{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            m.run.tx_count,
+            ts(m.run.timestamp)
+        )?;
+    }
+
+    writeln!(out, "# TYPE contender_landed_txs counter")?;
+    writeln!(out, "# HELP contender_landed_txs Txs that landed onchain.")?;
+    for m in runs {
+        writeln!(
+            out,
+            "contender_landed_txs_total{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            m.txs.len(),
+            ts(m.timestamp_ms())
+        )?;
+    }
+
+    writeln!(out, "# TYPE contender_replaced_txs counter")?;
+    writeln!(
+        out,
+        "# HELP contender_replaced_txs Txs whose nonce was used by another tx."
+    )?;
+    for m in runs {
+        writeln!(
+            out,
+            "contender_replaced_txs_total{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            m.num_replaced,
+            ts(m.timestamp_ms())
+        )?;
+    }
+
+    writeln!(out, "# TYPE contender_gas_used counter")?;
+    writeln!(out, "# HELP contender_gas_used Gas used by landed txs.")?;
+    for m in runs {
+        writeln!(
+            out,
+            "contender_gas_used_total{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            m.txs.iter().map(|tx| tx.gas_used).sum::<u128>(),
+            ts(m.timestamp_ms())
+        )?;
+    }
+
+    writeln!(
+        out,
+        "# TYPE contender_time_to_inclusion_milliseconds histogram"
+    )?;
+    writeln!(
+        out,
+        "# UNIT contender_time_to_inclusion_milliseconds milliseconds"
+    )?;
+    writeln!(
+        out,
+        "# HELP contender_time_to_inclusion_milliseconds Time from sending a tx to seeing it land."
+    )?;
+    for m in runs {
+        let latencies = m
+            .txs
+            .iter()
+            .map(RunTx::time_to_inclusion_ms)
+            .collect::<Vec<_>>();
+        let time = ts(m.timestamp_ms());
+        let bounds = INCLUSION_BUCKETS_MS.iter().map(|bound| bound.to_string());
+        for bound in bounds.chain(std::iter::once("+Inf".to_owned())) {
+            let count = match bound.parse::<u64>() {
+                Ok(bound) => latencies.iter().filter(|ms| **ms <= bound).count(),
+                Err(_) => latencies.len(),
+            };
+            writeln!(
+                out,
+                "contender_time_to_inclusion_milliseconds_bucket{{run_id=\"{}\",le=\"{}\"}} {} {}",
+                m.run.id, bound, count, time
+            )?;
+        }
+        writeln!(
+            out,
+            "contender_time_to_inclusion_milliseconds_count{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            latencies.len(),
+            time
+        )?;
+        writeln!(
+            out,
+            "contender_time_to_inclusion_milliseconds_sum{{run_id=\"{}\"}} {} {}",
+            m.run.id,
+            latencies.iter().sum::<u64>(),
+            time
+        )?;
+    }
+
+    writeln!(out, "# TYPE contender_rpc_latency_milliseconds summary")?;
+    writeln!(
+        out,
+        "# UNIT contender_rpc_latency_milliseconds milliseconds"
+    )?;
+    writeln!(
+        out,
+        "# HELP contender_rpc_latency_milliseconds Latency of RPC calls made while spamming, per snapshot window."
+    )?;
+    for m in runs {
+        let mut by_method = BTreeMap::<&str, Vec<&LatencySnapshot>>::new();
+        for snapshot in &m.latency {
+            by_method
+                .entry(&snapshot.method)
+                .or_default()
+                .push(snapshot);
+        }
+        for (method, snapshots) in by_method {
+            let labels = format!("run_id=\"{}\",method=\"{}\"", m.run.id, method);
+            // counts are per window; the summary's count is cumulative
+            let mut count = 0;
+            for snapshot in snapshots {
+                let time = ts(snapshot.timestamp as usize);
+                count += snapshot.count;
+                for (quantile, value) in [("0.5", snapshot.p50_ms), ("0.95", snapshot.p95_ms)] {
+                    writeln!(
+                        out,
+                        "contender_rpc_latency_milliseconds{{{},quantile=\"{}\"}} {} {}",
+                        labels, quantile, value, time
+                    )?;
+                }
+                writeln!(
+                    out,
+                    "contender_rpc_latency_milliseconds_count{{{}}} {} {}",
+                    labels, count, time
+                )?;
+            }
+        }
+    }
+
+    writeln!(out, "# EOF")?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_tx(start_timestamp: usize, end_timestamp: usize) -> RunTx {
+        RunTx::mock(0).with_times(start_timestamp, end_timestamp)
+    }
+
+    #[test]
+    fn encodes_run_metrics() {
+        let metrics = RunMetrics {
+            run: SpamRun {
+                id: 3,
+                timestamp: 1_000,
+                tx_count: 2,
+                scenario_name: "stress".to_owned(),
+                rpc_url: String::new(),
+            },
+            txs: vec![run_tx(1_000, 1_200), run_tx(1_000, 3_000)],
+            num_replaced: 1,
+            latency: vec![
+                LatencySnapshot {
+                    timestamp: 2_000,
+                    method: "eth_sendRawTransaction".to_owned(),
+                    p50_ms: 3,
+                    p95_ms: 9,
+                    count: 10,
+                },
+                LatencySnapshot {
+                    timestamp: 3_000,
+                    method: "eth_sendRawTransaction".to_owned(),
+                    p50_ms: 4,
+                    p95_ms: 12,
+                    count: 5,
+                },
+            ],
+        };
+        let out = encode_openmetrics(&[metrics]).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"contender_landed_txs_total{run_id=\"3\"} 2 3.000"));
+        assert!(lines.contains(&"contender_gas_used_total{run_id=\"3\"} 42000 3.000"));
+        assert!(lines.contains(
+            &"contender_time_to_inclusion_milliseconds_bucket{run_id=\"3\",le=\"250\"} 1 3.000"
+        ));
+        assert!(lines.contains(
+            &"contender_time_to_inclusion_milliseconds_bucket{run_id=\"3\",le=\"+Inf\"} 2 3.000"
+        ));
+        assert!(lines
+            .contains(&"contender_time_to_inclusion_milliseconds_sum{run_id=\"3\"} 2200 3.000"));
+        assert!(lines.contains(
+            &"contender_rpc_latency_milliseconds_count{run_id=\"3\",method=\"eth_sendRawTransaction\"} 15 3.000"
+        ));
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::primitives::{utils::format_ether, Address, U256};
+use contender_core::db::{ReplacedTx, RunTx};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    block_trace::TxTraceReceipt,
+    cost::{TxFee, UNKNOWN_POOL},
+};
+
+/// Per-pool stats displayed in the report's summary table, so degradation can be traced to the
+/// population of senders that experienced it.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct PoolSummary {
+    pub pool: String,
+    pub num_txs: usize,
+    /// Reverted txs, not counting those whose step expected them to revert.
+    pub num_reverted: usize,
+    /// Txs that never landed because another tx from the same sender used their nonce.
+    pub num_replaced: usize,
+    /// In milliseconds.
+    pub avg_time_to_inclusion: String,
+    /// In milliseconds.
+    pub p95_time_to_inclusion: u64,
+    /// Total fees paid by the pool's landed txs (in ETH).
+    pub eth_spent: String,
+}
+
+impl PoolSummary {
+    /// Summarizes `run_txs` by the pool they were sent from. Txs recorded without a pool are
+    /// matched to one by their sender, using the runs' `accounts`; the rest are grouped as
+    /// "other". Reverts & fees come from the receipts in `traces`.
+    /// Returns nothing if no tx was sent from a pool.
+    pub fn build_all(
+        run_txs: &[RunTx],
+        replaced_txs: &[ReplacedTx],
+        traces: &[TxTraceReceipt],
+        accounts: &[(String, Address)],
+    ) -> Vec<Self> {
+        let pools = accounts
+            .iter()
+            .map(|(pool, address)| (*address, pool.as_str()))
+            .collect::<HashMap<_, _>>();
+        let receipts = traces
+            .iter()
+            .map(|t| (t.receipt.transaction_hash, &t.receipt))
+            .collect::<HashMap<_, _>>();
+
+        let mut by_pool: BTreeMap<&str, Vec<&RunTx>> = BTreeMap::new();
+        for tx in run_txs {
+            let pool = tx.from_pool.as_deref().or_else(|| {
+                let receipt = receipts.get(&tx.tx_hash)?;
+                pools.get(&receipt.from).copied()
+            });
+            by_pool
+                .entry(pool.unwrap_or(UNKNOWN_POOL))
+                .or_default()
+                .push(tx);
+        }
+        let mut num_replaced: BTreeMap<&str, usize> = BTreeMap::new();
+        for tx in replaced_txs {
+            let pool = pools.get(&tx.sender).copied().unwrap_or(UNKNOWN_POOL);
+            by_pool.entry(pool).or_default();
+            *num_replaced.entry(pool).or_default() += 1;
+        }
+        if by_pool.keys().all(|pool| *pool == UNKNOWN_POOL) {
+            return vec![];
+        }
+
+        by_pool
+            .into_iter()
+            .map(|(pool, txs)| {
+                let num_txs = txs.len();
+                let mut times = txs
+                    .iter()
+                    .map(|tx| tx.time_to_inclusion_ms())
+                    .collect::<Vec<_>>();
+                times.sort();
+                let tx_receipts = txs
+                    .iter()
+                    .filter_map(|tx| receipts.get(&tx.tx_hash))
+                    .collect::<Vec<_>>();
+                Self {
+                    pool: pool.to_owned(),
+                    num_txs,
+                    num_reverted: txs
+                        .iter()
+                        .filter(|tx| !tx.expects_revert())
+                        .filter_map(|tx| receipts.get(&tx.tx_hash))
+                        .filter(|r| !r.status())
+                        .count(),
+                    num_replaced: num_replaced.get(pool).copied().unwrap_or_default(),
+                    avg_time_to_inclusion: format!(
+                        "{:.2}",
+                        times.iter().sum::<u64>() as f64 / num_txs.max(1) as f64
+                    ),
+                    p95_time_to_inclusion: times
+                        .get(times.len().saturating_sub(1) * 95 / 100)
+                        .copied()
+                        .unwrap_or_default(),
+                    eth_spent: format_ether(
+                        tx_receipts
+                            .iter()
+                            .map(|r| TxFee::from(**r).wei)
+                            .sum::<U256>(),
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::TxHash;
+
+    fn run_tx(n: u8, tti: usize, from_pool: Option<&str>) -> RunTx {
+        RunTx {
+            from_pool: from_pool.map(|p| p.to_owned()),
+            ..RunTx::mock(n).with_times(100_000, 100_000 + tti)
+        }
+    }
+
+    #[test]
+    fn summarizes_txs_by_pool() {
+        let txs = vec![
+            run_tx(1, 1_000, Some("whales")),
+            run_tx(2, 3_000, Some("whales")),
+            run_tx(3, 8_000, Some("retail")),
+            run_tx(4, 2_000, None),
+        ];
+        let accounts = vec![
+            ("whales".to_owned(), Address::repeat_byte(1)),
+            ("retail".to_owned(), Address::repeat_byte(2)),
+        ];
+        let replaced = vec![ReplacedTx {
+            tx_hash: TxHash::repeat_byte(5),
+            sender: Address::repeat_byte(2),
+            nonce: 0,
+            start_timestamp: 100,
+            block_number: 1,
+            kind: None,
+        }];
+        let summary = PoolSummary::build_all(&txs, &replaced, &[], &accounts);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].pool, "other");
+        assert_eq!(summary[0].num_txs, 1);
+        assert_eq!(summary[1].pool, "retail");
+        assert_eq!(summary[1].num_txs, 1);
+        assert_eq!(summary[1].num_replaced, 1);
+        assert_eq!(summary[1].p95_time_to_inclusion, 8_000);
+        assert_eq!(summary[2].pool, "whales");
+        assert_eq!(summary[2].num_txs, 2);
+        assert_eq!(summary[2].num_replaced, 0);
+        assert_eq!(summary[2].avg_time_to_inclusion, "2000.00");
+        assert_eq!(summary[2].eth_spent, "0.000000000000000000");
+    }
+
+    #[test]
+    fn skips_runs_without_pools() {
+        let txs = vec![run_tx(1, 1_000, None), run_tx(2, 2_000, None)];
+        assert!(PoolSummary::build_all(&txs, &[], &[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use contender_core::db::RunTx;
+use csv::Writer;
+
+/// Abbreviates a number to a human-readable format.
+pub fn abbreviate_num(num: u128) -> String {
+    if num >= 1_000_000_000_000 {
+        format!("{:.1}T", num as f64 / 1_000_000_000_000.0)
+    } else if num >= 1_000_000_000 {
+        format!("{:.1}G", num as f64 / 1_000_000_000.0)
+    } else if num >= 1_000_000 {
+        format!("{:.1}M", num as f64 / 1_000_000.0)
+    } else if num >= 1_000 {
+        format!("{}k", num / 1_000)
+    } else {
+        format!("{}", num)
+    }
+}
+
+/// Returns the name of the series a tx of the given kind is grouped into.
+pub fn kind_label(kind: &Option<String>) -> String {
+    kind.to_owned().unwrap_or("unlabeled".to_owned())
+}
+
+/// How numbers are displayed in CLI output & reports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NumFormat {
+    /// Print plain numbers in base units (gas, txs, ms) without abbreviating them, for scripts.
+    pub raw: bool,
+}
+
+impl NumFormat {
+    /// Formats `value` with an SI prefix on `unit`, e.g. `1.2 Mgas/s` or `3.4 ktx`.
+    pub fn si(&self, value: f64, unit: &str) -> String {
+        if self.raw {
+            return value.to_string();
+        }
+        let prefixes = [(1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "k")];
+        match prefixes.iter().find(|(scale, _)| value.abs() >= *scale) {
+            Some((scale, prefix)) => format!("{:.1} {}{}", value / scale, prefix, unit),
+            None if value.fract() == 0.0 => format!("{} {}", value, unit),
+            None => format!("{:.2} {}", value, unit),
+        }
+    }
+
+    /// Formats a duration given in milliseconds, e.g. `850 ms` or `12.5 s`.
+    pub fn duration_ms(&self, ms: f64) -> String {
+        if self.raw {
+            ms.to_string()
+        } else if ms.abs() < 1000.0 {
+            format!("{:.0} ms", ms)
+        } else if ms.abs() < 60_000.0 {
+            format!("{:.1} s", ms / 1000.0)
+        } else {
+            format!("{:.1} min", ms / 60_000.0)
+        }
+    }
+}
+
+/// Returns the letter grade for a score out of 100.
+pub fn grade(score: f64) -> &'static str {
+    match score {
+        s if s >= 90.0 => "A",
+        s if s >= 80.0 => "B",
+        s if s >= 70.0 => "C",
+        s if s >= 60.0 => "D",
+        _ => "F",
+    }
+}
+
+pub fn write_run_txs<T: std::io::Write>(
+    writer: &mut Writer<T>,
+    txs: &[RunTx],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for tx in txs {
+        writer.serialize(tx)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_abbreviate_num() {
+        assert_eq!(abbreviate_num(1_000), "1k");
+        assert_eq!(abbreviate_num(1_000_000), "1.0M");
+        assert_eq!(abbreviate_num(1_234_567), "1.2M");
+        assert_eq!(abbreviate_num(2_500_000_000), "2.5G");
+        assert_eq!(abbreviate_num(30_000_000_000_000), "30.0T");
+    }
+
+    #[test]
+    fn formats_numbers_with_units() {
+        let fmt = NumFormat::default();
+        assert_eq!(fmt.si(1_234_567.0, "gas/s"), "1.2 Mgas/s");
+        assert_eq!(fmt.si(3_400.0, "tx"), "3.4 ktx");
+        assert_eq!(fmt.si(42.0, "tx"), "42 tx");
+        assert_eq!(fmt.si(2.5, "tx/s"), "2.50 tx/s");
+        assert_eq!(fmt.duration_ms(850.0), "850 ms");
+        assert_eq!(fmt.duration_ms(12_500.0), "12.5 s");
+        assert_eq!(fmt.duration_ms(90_000.0), "1.5 min");
+
+        let raw = NumFormat { raw: true };
+        assert_eq!(raw.si(1_234_567.0, "gas/s"), "1234567");
+        assert_eq!(raw.duration_ms(12_500.0), "12500");
+    }
+}
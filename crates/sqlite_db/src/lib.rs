@@ -1,13 +1,34 @@
 use alloy::{
     hex::{FromHex, ToHexExt},
-    primitives::{Address, TxHash},
+    primitives::{Address, TxHash, B256, U256},
+};
+use contender_core::db::{
+    normalize_rpc_url, AccountBalance, BundleSubmission, ContractInfo, DbOps, DeployedContract,
+    FundingTx, LatencySnapshot, NamedTx, ReplacedTx, RunArtifacts, RunError, RunMetadata,
+    RunProvenance, RunScore, RunTx, RunTxStats, SpamRun,
 };
-use contender_core::db::{DbOps, NamedTx, RunTx, SpamRun};
 use contender_core::{error::ContenderError, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, types::FromSql, Row};
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Summary of what's stored in the DB, for `contender db stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbStats {
+    /// Size of the DB in bytes, including free pages.
+    pub size_bytes: u64,
+    /// Number of unused pages, which `VACUUM` would reclaim.
+    pub free_bytes: u64,
+    /// Number of migrations applied to the DB.
+    pub schema_version: u32,
+    /// (table name, row count) for every table, sorted by name.
+    pub table_rows: Vec<(String, u64)>,
+    /// (run ID, number of txs) for the runs with the most txs, largest first.
+    pub largest_runs: Vec<(u64, u64)>,
+}
 
 #[derive(Clone)]
 pub struct SqliteDb {
@@ -56,6 +77,133 @@ impl SqliteDb {
             .query_row(query, params, with_row)
             .map_err(|e| ContenderError::DbError("failed to query row", Some(e.to_string())))
     }
+
+    /// Returns size & row counts for the DB, listing the `num_runs` runs with the most txs.
+    pub fn stats(&self, num_runs: usize) -> Result<DbStats> {
+        let page_size: u64 = self.query_row("PRAGMA page_size", params![], |row| row.get(0))?;
+        let page_count: u64 = self.query_row("PRAGMA page_count", params![], |row| row.get(0))?;
+        let freelist_count: u64 =
+            self.query_row("PRAGMA freelist_count", params![], |row| row.get(0))?;
+        let schema_version: u32 =
+            self.query_row("PRAGMA user_version", params![], |row| row.get(0))?;
+
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+        let tables = stmt
+            .query_map(params![], |row| row.get::<_, String>(0))
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| ContenderError::with_err(e, "failed to convert row"))?;
+        let table_rows = tables
+            .into_iter()
+            .map(|table| {
+                // table names come from sqlite_master, so they're safe to interpolate
+                let count: u64 = self.query_row(
+                    &format!("SELECT COUNT(*) FROM \"{}\"", table),
+                    params![],
+                    |row| row.get(0),
+                )?;
+                Ok((table, count))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut stmt = pool
+            .prepare("SELECT run_id, COUNT(*) AS num_txs FROM run_txs GROUP BY run_id ORDER BY num_txs DESC, run_id ASC LIMIT ?1")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+        let largest_runs = stmt
+            .query_map(params![num_runs as u64], |row| {
+                Ok((row.get::<_, u64>(0)?, row.get::<_, u64>(1)?))
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| ContenderError::with_err(e, "failed to convert row"))?;
+
+        Ok(DbStats {
+            size_bytes: page_size * page_count,
+            free_bytes: page_size * freelist_count,
+            schema_version,
+            table_rows,
+            largest_runs,
+        })
+    }
+
+    /// Rebuilds the DB file, reclaiming space left behind by deleted rows.
+    pub fn vacuum(&self) -> Result<()> {
+        self.execute("VACUUM", params![])
+    }
+
+    /// Returns the ID that named txs for `rpc_url` are saved under, following aliases.
+    /// The URL is added to the `rpc_urls` table if it's not there yet.
+    fn rpc_url_id(&self, rpc_url: &str) -> Result<i64> {
+        let rpc_url = normalize_rpc_url(rpc_url);
+        self.execute(
+            "INSERT OR IGNORE INTO rpc_urls (url) VALUES (?1)",
+            params![rpc_url],
+        )?;
+        self.query_row(
+            "SELECT COALESCE(alias_of, id) FROM rpc_urls WHERE url = ?1",
+            params![rpc_url],
+            |row| row.get(0),
+        )
+    }
+
+    /// Normalizes URLs saved before normalization was added. URLs that normalize to one that's
+    /// already saved become aliases of it, and their named txs are moved to it.
+    fn normalize_saved_rpc_urls(&self) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let rows = {
+            let mut stmt = pool
+                .prepare("SELECT id, url FROM rpc_urls")
+                .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+            stmt.query_map(params![], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| ContenderError::with_err(e, "failed to convert row"))?
+        };
+
+        for (id, url) in rows {
+            let normalized = normalize_rpc_url(&url);
+            if normalized == url {
+                continue;
+            }
+            // the existing URL may itself be an alias
+            let existing: Option<i64> = pool
+                .query_row(
+                    "SELECT COALESCE(alias_of, id) FROM rpc_urls WHERE url = ?1",
+                    params![normalized],
+                    |row| row.get(0),
+                )
+                .ok();
+            let Some(existing_id) = existing else {
+                self.execute(
+                    "UPDATE rpc_urls SET url = ?1 WHERE id = ?2",
+                    params![normalized, id],
+                )?;
+                continue;
+            };
+            let tx = pool
+                .transaction()
+                .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+            // point the alias (and anything aliased to it) at the existing URL
+            tx.execute(
+                "UPDATE rpc_urls SET alias_of = ?1 WHERE id = ?2 OR alias_of = ?2",
+                params![existing_id, id],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to update rpc_url alias"))?;
+            tx.execute(
+                "UPDATE named_txs SET rpc_url_id = ?1 WHERE rpc_url_id = ?2",
+                params![existing_id, id],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to move named txs"))?;
+            tx.commit()
+                .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Debug, Serialize)]
@@ -63,6 +211,7 @@ struct NamedTxRow {
     name: String,
     tx_hash: String,
     contract_address: Option<String>,
+    namespace: Option<String>,
 }
 
 impl From<NamedTxRow> for NamedTx {
@@ -73,7 +222,7 @@ impl From<NamedTxRow> for NamedTx {
             .map(|a| Address::from_hex(&a))
             .transpose()
             .expect("invalid address");
-        NamedTx::new(row.name, tx_hash, contract_address)
+        NamedTx::new(row.name, tx_hash, contract_address).with_namespace(row.namespace)
     }
 }
 
@@ -83,10 +232,65 @@ impl NamedTxRow {
             name: row.get(0)?,
             tx_hash: row.get(1)?,
             contract_address: row.get(2)?,
+            namespace: row.get(3)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+struct DeployedContractRow {
+    name: String,
+    namespace: Option<String>,
+    tx_hash: String,
+    contract_address: String,
+    rpc_url: String,
+    code_hash: Option<String>,
+    deploy_data: Option<String>,
+    abi: Option<String>,
+    scenario_name: Option<String>,
+    deployed_at: Option<u64>,
+}
+
+impl DeployedContractRow {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            name: row.get(0)?,
+            tx_hash: row.get(1)?,
+            contract_address: row.get(2)?,
+            rpc_url: row.get(3)?,
+            code_hash: row.get(4)?,
+            deploy_data: row.get(5)?,
+            abi: row.get(6)?,
+            scenario_name: row.get(7)?,
+            deployed_at: row.get(8)?,
+            namespace: row.get(9)?,
         })
     }
 }
 
+impl From<DeployedContractRow> for DeployedContract {
+    fn from(row: DeployedContractRow) -> Self {
+        DeployedContract {
+            name: row.name,
+            namespace: row.namespace,
+            address: Address::from_hex(&row.contract_address).expect("invalid address"),
+            tx_hash: TxHash::from_hex(&row.tx_hash).expect("invalid tx hash"),
+            rpc_url: row.rpc_url,
+            info: ContractInfo {
+                code_hash: row
+                    .code_hash
+                    .map(|h| B256::from_hex(&h))
+                    .transpose()
+                    .expect("invalid code hash"),
+                deploy_data: row.deploy_data,
+                abi: row.abi,
+                scenario_name: row.scenario_name,
+            },
+            deployed_at: row.deployed_at,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Serialize)]
 struct RunTxRow {
     run_id: i64,
@@ -94,8 +298,14 @@ struct RunTxRow {
     start_timestamp: usize,
     end_timestamp: usize,
     block_number: u64,
+    block_timestamp: u64,
     gas_used: String,
     kind: Option<String>,
+    ack_latency_ms: Option<u64>,
+    from_pool: Option<String>,
+    expect_status: Option<String>,
+    max_gas_used: Option<u64>,
+    sent_block: Option<u64>,
 }
 
 impl RunTxRow {
@@ -106,8 +316,14 @@ impl RunTxRow {
             start_timestamp: row.get(2)?,
             end_timestamp: row.get(3)?,
             block_number: row.get(4)?,
-            gas_used: row.get(5)?,
-            kind: row.get(6)?,
+            block_timestamp: row.get(5)?,
+            gas_used: row.get(6)?,
+            kind: row.get(7)?,
+            ack_latency_ms: row.get(8)?,
+            from_pool: row.get(9)?,
+            expect_status: row.get(10)?,
+            max_gas_used: row.get(11)?,
+            sent_block: row.get(12)?,
         })
     }
 }
@@ -120,8 +336,16 @@ impl From<RunTxRow> for RunTx {
             start_timestamp: row.start_timestamp,
             end_timestamp: row.end_timestamp,
             block_number: row.block_number,
+            block_timestamp: row.block_timestamp,
             gas_used: row.gas_used.parse().expect("invalid gas_used parameter"),
             kind: row.kind,
+            ack_latency_ms: row.ack_latency_ms,
+            from_pool: row.from_pool,
+            expect_status: row
+                .expect_status
+                .map(|status| status.parse().expect("invalid expect_status")),
+            max_gas_used: row.max_gas_used,
+            sent_block: row.sent_block,
         }
     }
 }
@@ -131,6 +355,7 @@ struct SpamRunRow {
     pub timestamp: String,
     pub tx_count: usize,
     pub scenario_name: String,
+    pub rpc_url: String,
 }
 
 impl From<SpamRunRow> for SpamRun {
@@ -140,6 +365,7 @@ impl From<SpamRunRow> for SpamRun {
             timestamp: row.timestamp.parse::<usize>().expect("invalid timestamp"),
             tx_count: row.tx_count,
             scenario_name: row.scenario_name,
+            rpc_url: row.rpc_url,
         }
     }
 }
@@ -200,18 +426,242 @@ impl DbOps for SqliteDb {
                 "ALTER TABLE runs ADD COLUMN scenario_name TEXT NOT NULL DEFAULT '';",
                 params![],
             ),
+            self.execute(
+                "ALTER TABLE runs ADD COLUMN rpc_url TEXT NOT NULL DEFAULT '';",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE run_metadata (
+                    run_id INTEGER PRIMARY KEY,
+                    client_version TEXT NOT NULL,
+                    chain_id INTEGER NOT NULL,
+                    genesis_hash TEXT NOT NULL,
+                    fork TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE run_provenance (
+                    run_id INTEGER PRIMARY KEY,
+                    scenario TEXT NOT NULL,
+                    args TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE rpc_latency (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    timestamp INTEGER NOT NULL,
+                    method TEXT NOT NULL,
+                    p50_ms INTEGER NOT NULL,
+                    p95_ms INTEGER NOT NULL,
+                    count INTEGER NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE bundles (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    bundle_hash TEXT NOT NULL,
+                    target_block INTEGER NOT NULL,
+                    num_submissions INTEGER NOT NULL,
+                    tx_hashes TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE run_accounts (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    pool TEXT NOT NULL,
+                    address TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE funding_txs (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER,
+                    tx_hash TEXT NOT NULL,
+                    sender TEXT NOT NULL,
+                    recipient TEXT NOT NULL,
+                    amount TEXT NOT NULL,
+                    gas_used TEXT NOT NULL,
+                    effective_gas_price TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE balances (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    at_start BOOLEAN NOT NULL,
+                    pool TEXT NOT NULL,
+                    address TEXT NOT NULL,
+                    balance TEXT NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE rpc_urls ADD COLUMN alias_of INTEGER REFERENCES rpc_urls(id);",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE aliases (
+                    name TEXT PRIMARY KEY,
+                    address TEXT NOT NULL
+                )",
+                params![],
+            ),
+            // indexes for reports & lookups over large tables
+            self.execute(
+                "CREATE INDEX IF NOT EXISTS idx_run_txs_run_id_block_number ON run_txs(run_id, block_number)",
+                params![],
+            ),
+            self.execute(
+                "CREATE INDEX IF NOT EXISTS idx_run_txs_tx_hash ON run_txs(tx_hash)",
+                params![],
+            ),
+            self.execute(
+                "CREATE INDEX IF NOT EXISTS idx_named_txs_name_rpc_url_id ON named_txs(name, rpc_url_id)",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE replaced_txs (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    tx_hash TEXT NOT NULL,
+                    sender TEXT NOT NULL,
+                    nonce INTEGER NOT NULL,
+                    start_timestamp INTEGER NOT NULL,
+                    block_number INTEGER NOT NULL,
+                    kind TEXT,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE run_scores (
+                    run_id INTEGER PRIMARY KEY,
+                    score REAL NOT NULL,
+                    grade TEXT NOT NULL,
+                    throughput REAL NOT NULL,
+                    latency REAL NOT NULL,
+                    reliability REAL NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE baseline_runs (
+                    series TEXT NOT NULL,
+                    run_id INTEGER NOT NULL,
+                    PRIMARY KEY(series, run_id),
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE run_txs ADD COLUMN block_timestamp INTEGER;",
+                params![],
+            ),
+            // run txs used to be timed in seconds, with the block timestamp as the end time
+            self.execute(
+                "UPDATE run_txs SET block_timestamp = end_timestamp, start_timestamp = start_timestamp * 1000, end_timestamp = end_timestamp * 1000 WHERE block_timestamp IS NULL",
+                params![],
+            ),
+            // replaced txs have no marker for the old format; timestamps in ms are always past 1e11
+            self.execute(
+                "UPDATE replaced_txs SET start_timestamp = start_timestamp * 1000 WHERE start_timestamp < 100000000000",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE run_txs ADD COLUMN ack_latency_ms INTEGER;",
+                params![],
+            ),
+            self.execute("ALTER TABLE named_txs ADD COLUMN code_hash TEXT;", params![]),
+            self.execute("ALTER TABLE named_txs ADD COLUMN deploy_data TEXT;", params![]),
+            self.execute("ALTER TABLE named_txs ADD COLUMN abi TEXT;", params![]),
+            self.execute(
+                "ALTER TABLE named_txs ADD COLUMN scenario_name TEXT;",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE named_txs ADD COLUMN deployed_at INTEGER;",
+                params![],
+            ),
+            self.execute("ALTER TABLE named_txs ADD COLUMN namespace TEXT;", params![]),
+            self.execute("ALTER TABLE run_txs ADD COLUMN from_pool TEXT;", params![]),
+            self.execute(
+                "CREATE TABLE run_artifacts (
+                    run_id INTEGER PRIMARY KEY,
+                    scenario_hash TEXT NOT NULL,
+                    scenario_gz BLOB NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE run_txs ADD COLUMN expect_status TEXT;",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE run_txs ADD COLUMN max_gas_used INTEGER;",
+                params![],
+            ),
+            self.execute(
+                "ALTER TABLE run_txs ADD COLUMN sent_block INTEGER;",
+                params![],
+            ),
+            self.execute(
+                "CREATE TABLE run_errors (
+                    id INTEGER PRIMARY KEY,
+                    run_id INTEGER NOT NULL,
+                    kind TEXT,
+                    error_kind TEXT NOT NULL,
+                    count INTEGER NOT NULL,
+                    FOREIGN KEY(run_id) REFERENCES runs(id)
+                )",
+                params![],
+            ),
         ];
+        // migrations are only ever appended, so the number of them doubles as the schema version
+        let schema_version = queries.len();
         for query in queries {
             query.or_else(ignore_already_exists)?;
         }
-        Ok(())
+        self.normalize_saved_rpc_urls()?;
+        self.execute(
+            &format!("PRAGMA user_version = {}", schema_version),
+            params![],
+        )
     }
 
     /// Inserts a new run into the database and returns the ID of the new row.
-    fn insert_run(&self, timestamp: u64, tx_count: usize, scenario_name: &str) -> Result<u64> {
+    fn insert_run(
+        &self,
+        timestamp: u64,
+        tx_count: usize,
+        scenario_name: &str,
+        rpc_url: &str,
+    ) -> Result<u64> {
         self.execute(
-            "INSERT INTO runs (timestamp, tx_count, scenario_name) VALUES (?, ?, ?)",
-            params![timestamp, tx_count, scenario_name],
+            "INSERT INTO runs (timestamp, tx_count, scenario_name, rpc_url) VALUES (?, ?, ?, ?)",
+            params![
+                timestamp,
+                tx_count,
+                scenario_name,
+                normalize_rpc_url(rpc_url)
+            ],
         )?;
         // get ID from newly inserted row
         let id: u64 = self.query_row("SELECT last_insert_rowid()", params![], |row| row.get(0))?;
@@ -227,7 +677,7 @@ impl DbOps for SqliteDb {
     fn get_run_txs(&self, run_id: u64) -> Result<Vec<RunTx>> {
         let pool = self.get_pool()?;
         let mut stmt = pool
-            .prepare("SELECT run_id, tx_hash, start_timestamp, end_timestamp, block_number, gas_used, kind FROM run_txs WHERE run_id = ?1")
+            .prepare("SELECT run_id, tx_hash, start_timestamp, end_timestamp, block_number, block_timestamp, gas_used, kind, ack_latency_ms, from_pool, expect_status, max_gas_used, sent_block FROM run_txs WHERE run_id = ?1")
             .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
         let rows = stmt
@@ -241,10 +691,30 @@ impl DbOps for SqliteDb {
         Ok(res)
     }
 
+    fn get_run_tx_stats(&self, run_id: u64) -> Result<RunTxStats> {
+        // gas_used is stored as text to fit u128s, but a run's total fits an i64
+        self.get_pool()?
+            .query_row(
+                "SELECT COUNT(*), SUM(CAST(gas_used AS INTEGER)), MAX(block_number), SUM(MAX(end_timestamp - start_timestamp, 0)) / COUNT(*) FROM run_txs WHERE run_id = ?1 AND block_number > 0",
+                params![run_id],
+                |row| {
+                    Ok(RunTxStats {
+                        landed_txs: row.get(0)?,
+                        gas_used: row.get::<_, Option<i64>>(1)?.unwrap_or_default() as u128,
+                        latest_block: row.get(2)?,
+                        avg_inclusion_ms: row.get(3)?,
+                    })
+                },
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to query run tx stats"))
+    }
+
     fn get_run(&self, run_id: u64) -> Result<Option<SpamRun>> {
         let pool = self.get_pool()?;
         let mut stmt = pool
-            .prepare("SELECT id, timestamp, tx_count, scenario_name FROM runs WHERE id = ?1")
+            .prepare(
+                "SELECT id, timestamp, tx_count, scenario_name, rpc_url FROM runs WHERE id = ?1",
+            )
             .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
         let row = stmt
@@ -254,6 +724,7 @@ impl DbOps for SqliteDb {
                     timestamp: row.get(1)?,
                     tx_count: row.get(2)?,
                     scenario_name: row.get(3)?,
+                    rpc_url: row.get(4)?,
                 })
             })
             .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
@@ -264,146 +735,862 @@ impl DbOps for SqliteDb {
         Ok(res.map(|r| r.into()))
     }
 
-    fn insert_named_txs(&self, named_txs: Vec<NamedTx>, rpc_url: &str) -> Result<()> {
+    fn get_runs(&self) -> Result<Vec<SpamRun>> {
         let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare(
+                "SELECT id, timestamp, tx_count, scenario_name, rpc_url FROM runs ORDER BY id DESC",
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
-        // first check the rpc_urls table; insert if not present
-        pool.execute(
-            "INSERT OR IGNORE INTO rpc_urls (url) VALUES (?)",
-            params![rpc_url],
-        )
-        .map_err(|e| ContenderError::with_err(e, "failed to insert rpc_url into DB"))?;
-
-        // then get the rpc_url ID
-        let rpc_url_id: i64 = self.query_row(
-            "SELECT id FROM rpc_urls WHERE url = ?1",
-            params![rpc_url],
-            |row| row.get(0),
-        )?;
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok(SpamRunRow {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    tx_count: row.get(2)?,
+                    scenario_name: row.get(3)?,
+                    rpc_url: row.get(4)?,
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map(|r| r.into()))
+            .collect::<std::result::Result<Vec<SpamRun>, _>>()
+            .map_err(|e| ContenderError::with_err(e, "failed to collect rows"))
+    }
 
-        let stmts = named_txs.iter().map(|tx| {
-            format!(
-                "INSERT INTO named_txs (name, tx_hash, contract_address, rpc_url_id) VALUES ('{}', '{}', '{}', {});",
-                tx.name,
-                tx.tx_hash.encode_hex(),
-                tx.address.map(|a| a.encode_hex()).unwrap_or_default(),
-                rpc_url_id,
-            )
-        });
-        pool.execute_batch(&format!(
-            "BEGIN;
-            {}
-            COMMIT;",
-            stmts
-                .reduce(|ac, c| format!("{}\n{}", ac, c))
-                .unwrap_or_default(),
-        ))
-        .map_err(|e| ContenderError::with_err(e, "failed to execute batch"))?;
-        Ok(())
+    fn insert_run_metadata(&self, run_id: u64, metadata: &RunMetadata) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO run_metadata (run_id, client_version, chain_id, genesis_hash, fork) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                run_id,
+                metadata.client_version,
+                metadata.chain_id,
+                metadata.genesis_hash.encode_hex(),
+                metadata.fork
+            ],
+        )
     }
 
-    fn get_named_tx(&self, name: &str, rpc_url: &str) -> Result<Option<NamedTx>> {
+    fn get_run_metadata(&self, run_id: u64) -> Result<Option<RunMetadata>> {
         let pool = self.get_pool()?;
         let mut stmt = pool
-            .prepare(
-                "SELECT name, tx_hash, contract_address, rpc_url_id FROM named_txs WHERE name = ?1 AND rpc_url_id = (
-                    SELECT id FROM rpc_urls WHERE url = ?2
-                ) ORDER BY id DESC LIMIT 1",
-            )
+            .prepare("SELECT client_version, chain_id, genesis_hash, fork FROM run_metadata WHERE run_id = ?1")
             .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
         let row = stmt
-            .query_map(params![name, rpc_url], NamedTxRow::from_row)
+            .query_map(params![run_id], |row| {
+                Ok(RunMetadata {
+                    client_version: row.get(0)?,
+                    chain_id: row.get(1)?,
+                    genesis_hash: B256::from_hex(row.get::<_, String>(2)?)
+                        .expect("invalid genesis hash"),
+                    fork: row.get(3)?,
+                })
+            })
             .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
         let res = row
             .last()
             .transpose()
-            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
-            .map(|r| r.into());
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?;
         Ok(res)
     }
 
-    fn get_named_tx_by_address(&self, address: &Address) -> Result<Option<NamedTx>> {
+    fn insert_run_provenance(&self, run_id: u64, provenance: &RunProvenance) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO run_provenance (run_id, scenario, args) VALUES (?1, ?2, ?3)",
+            params![run_id, provenance.scenario, provenance.args],
+        )
+    }
+
+    fn get_run_provenance(&self, run_id: u64) -> Result<Option<RunProvenance>> {
         let pool = self.get_pool()?;
         let mut stmt = pool
-            .prepare(
-                "SELECT name, tx_hash, contract_address FROM named_txs WHERE contract_address = ?1 ORDER BY id DESC LIMIT 1",
-            )
+            .prepare("SELECT scenario, args FROM run_provenance WHERE run_id = ?1")
             .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
         let row = stmt
-            .query_map(params![address.encode_hex()], |row| {
-                NamedTxRow::from_row(row)
+            .query_map(params![run_id], |row| {
+                Ok(RunProvenance {
+                    scenario: row.get(0)?,
+                    args: row.get(1)?,
+                })
             })
-            .map_err(|e| ContenderError::with_err(e, "failed to map row query"))?;
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
         let res = row
             .last()
             .transpose()
-            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
-            .map(|r| r.into());
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?;
         Ok(res)
     }
 
-    fn insert_run_txs(&self, run_id: u64, run_txs: Vec<RunTx>) -> Result<()> {
-        let pool = self.get_pool()?;
-        let stmts = run_txs.iter().map(|tx| {
-            if let Some(kind) = &tx.kind {
-                format!(
-                    "INSERT INTO run_txs (run_id, tx_hash, start_timestamp, end_timestamp, block_number, gas_used, kind) VALUES ({}, '{}', {}, {}, {}, '{}', '{}');",
-                    run_id,
-                    tx.tx_hash.encode_hex(),
-                    tx.start_timestamp,
-                    tx.end_timestamp,
-                    tx.block_number,
-                    tx.gas_used,
-                    kind,
-                )
-            } else {
-                format!(
-                    "INSERT INTO run_txs (run_id, tx_hash, start_timestamp, end_timestamp, block_number, gas_used) VALUES ({}, '{}', {}, {}, {}, '{}');",
-                    run_id,
-                    tx.tx_hash.encode_hex(),
-                    tx.start_timestamp,
-                    tx.end_timestamp,
-                    tx.block_number,
-                    tx.gas_used,
-                )
-            }
-        });
-        pool.execute_batch(&format!(
-            "BEGIN;
-            {}
-            COMMIT;",
-            stmts
-                .reduce(|ac, c| format!("{}\n{}", ac, c))
-                .unwrap_or_default(),
-        ))
-        .map_err(|e| ContenderError::with_err(e, "failed to execute batch"))?;
-        Ok(())
+    /// Saves the run's scenario gzipped, next to its hash.
+    fn insert_run_artifacts(&self, run_id: u64, artifacts: &RunArtifacts) -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(artifacts.scenario.as_bytes())
+            .map_err(|e| ContenderError::with_err(e, "failed to compress scenario"))?;
+        let scenario_gz = encoder
+            .finish()
+            .map_err(|e| ContenderError::with_err(e, "failed to compress scenario"))?;
+        self.execute(
+            "INSERT OR REPLACE INTO run_artifacts (run_id, scenario_hash, scenario_gz) VALUES (?1, ?2, ?3)",
+            params![run_id, artifacts.scenario_hash.encode_hex(), scenario_gz],
+        )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn get_run_artifacts(&self, run_id: u64) -> Result<Option<RunArtifacts>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT scenario_hash, scenario_gz FROM run_artifacts WHERE run_id = ?1")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
 
-    #[test]
-    fn creates_table() {
-        let db = SqliteDb::new_memory();
-        db.create_tables().unwrap();
-        assert_eq!(db.num_runs().unwrap(), 0);
+        let row = stmt
+            .query_map(params![run_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        let Some((scenario_hash, scenario_gz)) = row
+            .last()
+            .transpose()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
+        else {
+            return Ok(None);
+        };
+
+        let mut scenario = String::new();
+        GzDecoder::new(scenario_gz.as_slice())
+            .read_to_string(&mut scenario)
+            .map_err(|e| ContenderError::with_err(e, "failed to decompress scenario"))?;
+        let artifacts = RunArtifacts::new(scenario);
+        if artifacts.scenario_hash.encode_hex() != scenario_hash.trim_start_matches("0x") {
+            return Err(ContenderError::DbError(
+                "saved scenario doesn't match its hash",
+                Some(run_id.to_string()),
+            ));
+        }
+        Ok(Some(artifacts))
+    }
+
+    fn insert_run_score(&self, run_id: u64, score: &RunScore) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO run_scores (run_id, score, grade, throughput, latency, reliability) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                score.score,
+                score.grade,
+                score.throughput,
+                score.latency,
+                score.reliability
+            ],
+        )
+    }
+
+    fn get_run_score(&self, run_id: u64) -> Result<Option<RunScore>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT score, grade, throughput, latency, reliability FROM run_scores WHERE run_id = ?1")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let row = stmt
+            .query_map(params![run_id], |row| {
+                Ok(RunScore {
+                    score: row.get(0)?,
+                    grade: row.get(1)?,
+                    throughput: row.get(2)?,
+                    latency: row.get(3)?,
+                    reliability: row.get(4)?,
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        let res = row
+            .last()
+            .transpose()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?;
+        Ok(res)
+    }
+
+    fn insert_baseline_run(&self, series: &str, run_id: u64) -> Result<()> {
+        self.execute(
+            "INSERT OR IGNORE INTO baseline_runs (series, run_id) VALUES (?1, ?2)",
+            params![series, run_id],
+        )
+    }
+
+    fn get_baseline_runs(&self, series: &str) -> Result<Vec<u64>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT run_id FROM baseline_runs WHERE series = ?1 ORDER BY run_id ASC")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+        let rows = stmt
+            .query_map(params![series], |row| row.get(0))
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.collect::<std::result::Result<Vec<u64>, _>>()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))
+    }
+
+    fn insert_latency_snapshots(&self, run_id: u64, snapshots: &[LatencySnapshot]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for snapshot in snapshots {
+            tx.execute(
+                "INSERT INTO rpc_latency (run_id, timestamp, method, p50_ms, p95_ms, count) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    run_id,
+                    snapshot.timestamp,
+                    snapshot.method,
+                    snapshot.p50_ms,
+                    snapshot.p95_ms,
+                    snapshot.count
+                ],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert latency snapshot"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_latency_snapshots(&self, run_id: u64) -> Result<Vec<LatencySnapshot>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT timestamp, method, p50_ms, p95_ms, count FROM rpc_latency WHERE run_id = ?1 ORDER BY timestamp, method")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok(LatencySnapshot {
+                    timestamp: row.get(0)?,
+                    method: row.get(1)?,
+                    p50_ms: row.get(2)?,
+                    p95_ms: row.get(3)?,
+                    count: row.get(4)?,
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_bundles(&self, run_id: u64, bundles: &[BundleSubmission]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for bundle in bundles {
+            // tx hashes are stored as a comma-separated list
+            let tx_hashes = bundle
+                .tx_hashes
+                .iter()
+                .map(|h| h.encode_hex())
+                .collect::<Vec<_>>()
+                .join(",");
+            tx.execute(
+                "INSERT INTO bundles (run_id, bundle_hash, target_block, num_submissions, tx_hashes) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run_id,
+                    bundle.bundle_hash.encode_hex(),
+                    bundle.target_block,
+                    bundle.num_submissions,
+                    tx_hashes
+                ],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert bundle"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_bundles(&self, run_id: u64) -> Result<Vec<BundleSubmission>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT bundle_hash, target_block, num_submissions, tx_hashes FROM bundles WHERE run_id = ?1 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                let bundle_hash: String = row.get(0)?;
+                let tx_hashes: String = row.get(3)?;
+                Ok(BundleSubmission {
+                    bundle_hash: B256::from_hex(bundle_hash).expect("invalid bundle hash"),
+                    target_block: row.get(1)?,
+                    num_submissions: row.get(2)?,
+                    tx_hashes: tx_hashes
+                        .split(',')
+                        .filter(|h| !h.is_empty())
+                        .map(|h| TxHash::from_hex(h).expect("invalid tx hash"))
+                        .collect(),
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_run_accounts(&self, run_id: u64, accounts: &[(String, Address)]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for (name, address) in accounts {
+            tx.execute(
+                "INSERT INTO run_accounts (run_id, pool, address) VALUES (?1, ?2, ?3)",
+                params![run_id, name, address.encode_hex()],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert run account"))?;
+        }
+        // setup funding is saved before there's a run; it's charged to the first run using the accounts
+        tx.execute(
+            "UPDATE funding_txs SET run_id = ?1 WHERE run_id IS NULL AND recipient IN (SELECT address FROM run_accounts WHERE run_id = ?1)",
+            params![run_id],
+        )
+        .map_err(|e| ContenderError::with_err(e, "failed to link setup funding txs"))?;
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_run_accounts(&self, run_id: u64) -> Result<Vec<(String, Address)>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT pool, address FROM run_accounts WHERE run_id = ?1 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| {
+            r.map(|(name, address)| (name, Address::from_hex(&address).expect("invalid address")))
+                .map_err(|e| ContenderError::with_err(e, "failed to convert row"))
+        })
+        .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_funding_txs(&self, run_id: Option<u64>, txs: &[FundingTx]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for funding_tx in txs {
+            tx.execute(
+                "INSERT INTO funding_txs (run_id, tx_hash, sender, recipient, amount, gas_used, effective_gas_price) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    run_id,
+                    funding_tx.tx_hash.encode_hex(),
+                    funding_tx.sender.encode_hex(),
+                    funding_tx.recipient.encode_hex(),
+                    funding_tx.amount.to_string(),
+                    funding_tx.gas_used.to_string(),
+                    funding_tx.effective_gas_price.to_string(),
+                ],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert funding tx"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_funding_txs(&self, run_id: u64) -> Result<Vec<FundingTx>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT tx_hash, sender, recipient, amount, gas_used, effective_gas_price FROM funding_txs WHERE run_id = ?1 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                let tx_hash: String = row.get(0)?;
+                let sender: String = row.get(1)?;
+                let recipient: String = row.get(2)?;
+                let amount: String = row.get(3)?;
+                let gas_used: String = row.get(4)?;
+                let effective_gas_price: String = row.get(5)?;
+                Ok(FundingTx {
+                    tx_hash: TxHash::from_hex(tx_hash).expect("invalid tx hash"),
+                    sender: Address::from_hex(sender).expect("invalid address"),
+                    recipient: Address::from_hex(recipient).expect("invalid address"),
+                    amount: amount.parse::<U256>().expect("invalid amount"),
+                    gas_used: gas_used.parse().expect("invalid gas_used"),
+                    effective_gas_price: effective_gas_price
+                        .parse()
+                        .expect("invalid effective_gas_price"),
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_replaced_txs(&self, run_id: u64, txs: &[ReplacedTx]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for replaced_tx in txs {
+            tx.execute(
+                "INSERT INTO replaced_txs (run_id, tx_hash, sender, nonce, start_timestamp, block_number, kind) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    run_id,
+                    replaced_tx.tx_hash.encode_hex(),
+                    replaced_tx.sender.encode_hex(),
+                    replaced_tx.nonce,
+                    replaced_tx.start_timestamp,
+                    replaced_tx.block_number,
+                    replaced_tx.kind,
+                ],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert replaced tx"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_replaced_txs(&self, run_id: u64) -> Result<Vec<ReplacedTx>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT tx_hash, sender, nonce, start_timestamp, block_number, kind FROM replaced_txs WHERE run_id = ?1 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                let tx_hash: String = row.get(0)?;
+                let sender: String = row.get(1)?;
+                Ok(ReplacedTx {
+                    tx_hash: TxHash::from_hex(tx_hash).expect("invalid tx hash"),
+                    sender: Address::from_hex(sender).expect("invalid address"),
+                    nonce: row.get(2)?,
+                    start_timestamp: row.get(3)?,
+                    block_number: row.get(4)?,
+                    kind: row.get(5)?,
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_run_errors(&self, run_id: u64, errors: &[RunError]) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for error in errors {
+            tx.execute(
+                "INSERT INTO run_errors (run_id, kind, error_kind, count) VALUES (?1, ?2, ?3, ?4)",
+                params![run_id, error.kind, error.error_kind, error.count],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert run error"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_run_errors(&self, run_id: u64) -> Result<Vec<RunError>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT kind, error_kind, count FROM run_errors WHERE run_id = ?1 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id], |row| {
+                Ok(RunError {
+                    kind: row.get(0)?,
+                    error_kind: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_balances(
+        &self,
+        run_id: u64,
+        at_start: bool,
+        balances: &[AccountBalance],
+    ) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        for balance in balances {
+            tx.execute(
+                "INSERT INTO balances (run_id, at_start, pool, address, balance) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run_id,
+                    at_start,
+                    balance.pool,
+                    balance.address.encode_hex(),
+                    balance.balance.to_string(),
+                ],
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to insert balance"))?;
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_balances(&self, run_id: u64, at_start: bool) -> Result<Vec<AccountBalance>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT pool, address, balance FROM balances WHERE run_id = ?1 AND at_start = ?2 ORDER BY id")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![run_id, at_start], |row| {
+                let address: String = row.get(1)?;
+                let balance: String = row.get(2)?;
+                Ok(AccountBalance {
+                    pool: row.get(0)?,
+                    address: Address::from_hex(address).expect("invalid address"),
+                    balance: balance.parse::<U256>().expect("invalid balance"),
+                })
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| r.map_err(|e| ContenderError::with_err(e, "failed to convert row")))
+            .collect::<Result<Vec<_>>>()
+    }
+
+    fn insert_rpc_url_alias(&self, rpc_url: &str, canonical_url: &str) -> Result<()> {
+        let canonical_id = self.rpc_url_id(canonical_url)?;
+        let rpc_url = normalize_rpc_url(rpc_url);
+        if self.rpc_url_id(&rpc_url)? == canonical_id {
+            return Ok(());
+        }
+        let alias_id: i64 = self.query_row(
+            "SELECT id FROM rpc_urls WHERE url = ?1",
+            params![rpc_url],
+            |row| row.get(0),
+        )?;
+
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        // point the alias (and anything aliased to it) at the canonical URL
+        tx.execute(
+            "UPDATE rpc_urls SET alias_of = ?1 WHERE id = ?2 OR alias_of = ?2",
+            params![canonical_id, alias_id],
+        )
+        .map_err(|e| ContenderError::with_err(e, "failed to update rpc_url alias"))?;
+        tx.execute(
+            "UPDATE named_txs SET rpc_url_id = ?1 WHERE rpc_url_id = ?2",
+            params![canonical_id, alias_id],
+        )
+        .map_err(|e| ContenderError::with_err(e, "failed to move named txs"))?;
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn insert_named_txs(&self, named_txs: Vec<NamedTx>, rpc_url: &str) -> Result<()> {
+        let rpc_url_id = self.rpc_url_id(rpc_url)?;
+        let deployed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    "INSERT INTO named_txs (name, tx_hash, contract_address, rpc_url_id, code_hash, deploy_data, abi, scenario_name, deployed_at, namespace) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                )
+                .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+            for named_tx in &named_txs {
+                let contract = named_tx.contract.clone().unwrap_or_default();
+                stmt.execute(params![
+                    named_tx.name,
+                    named_tx.tx_hash.encode_hex(),
+                    named_tx.address.map(|a| a.encode_hex()).unwrap_or_default(),
+                    rpc_url_id,
+                    contract.code_hash.map(|h| h.encode_hex()),
+                    contract.deploy_data,
+                    contract.abi,
+                    contract.scenario_name,
+                    deployed_at,
+                    named_tx.namespace,
+                ])
+                .map_err(|e| ContenderError::with_err(e, "failed to insert named tx"))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn get_named_tx(
+        &self,
+        name: &str,
+        rpc_url: &str,
+        namespace: Option<&str>,
+    ) -> Result<Option<NamedTx>> {
+        let pool = self.get_pool()?;
+        // txs saved before namespaces were tracked match any namespace, but lose to an exact match
+        let mut stmt = pool
+            .prepare(
+                "SELECT name, tx_hash, contract_address, namespace FROM named_txs WHERE name = ?1 AND rpc_url_id = (
+                    SELECT COALESCE(alias_of, id) FROM rpc_urls WHERE url = ?2
+                ) AND (?3 IS NULL OR namespace IS NULL OR namespace = ?3)
+                ORDER BY (namespace = ?3) DESC, id DESC LIMIT 1",
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let row = stmt
+            .query_map(
+                params![name, normalize_rpc_url(rpc_url), namespace],
+                NamedTxRow::from_row,
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        let res = row
+            .last()
+            .transpose()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
+            .map(|r| r.into());
+        Ok(res)
+    }
+
+    fn get_named_tx_by_address(&self, address: &Address) -> Result<Option<NamedTx>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare(
+                "SELECT name, tx_hash, contract_address, namespace FROM named_txs WHERE contract_address = ?1 ORDER BY id DESC LIMIT 1",
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let row = stmt
+            .query_map(params![address.encode_hex()], |row| {
+                NamedTxRow::from_row(row)
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row query"))?;
+        let res = row
+            .last()
+            .transpose()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
+            .map(|r| r.into());
+        Ok(res)
+    }
+
+    fn get_contracts(&self) -> Result<Vec<DeployedContract>> {
+        let pool = self.get_pool()?;
+        // named txs are appended on every deployment; only the latest one for each name & URL is current
+        let mut stmt = pool
+            .prepare(
+                "SELECT n.name, n.tx_hash, n.contract_address, r.url, n.code_hash, n.deploy_data, n.abi, n.scenario_name, n.deployed_at, n.namespace
+                FROM named_txs n JOIN rpc_urls r ON r.id = n.rpc_url_id
+                WHERE n.contract_address IS NOT NULL AND n.contract_address != ''
+                AND n.id = (
+                    SELECT MAX(id) FROM named_txs
+                    WHERE name = n.name AND rpc_url_id = n.rpc_url_id AND namespace IS n.namespace
+                )
+                ORDER BY n.name, n.namespace, r.url",
+            )
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![], DeployedContractRow::from_row)
+            .map_err(|e| ContenderError::with_err(e, "failed to map row query"))?;
+        rows.map(|row| {
+            row.map(DeployedContract::from)
+                .map_err(|e| ContenderError::with_err(e, "failed to query row"))
+        })
+        .collect()
+    }
+
+    fn insert_run_txs(&self, run_id: u64, run_txs: Vec<RunTx>) -> Result<()> {
+        let mut pool = self.get_pool()?;
+        let tx = pool
+            .transaction()
+            .map_err(|e| ContenderError::with_err(e, "failed to start transaction"))?;
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    "INSERT INTO run_txs (run_id, tx_hash, start_timestamp, end_timestamp, block_number, block_timestamp, gas_used, kind, ack_latency_ms, from_pool, expect_status, max_gas_used, sent_block) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                )
+                .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+            for run_tx in &run_txs {
+                stmt.execute(params![
+                    run_id,
+                    run_tx.tx_hash.encode_hex(),
+                    run_tx.start_timestamp,
+                    run_tx.end_timestamp,
+                    run_tx.block_number,
+                    run_tx.block_timestamp,
+                    run_tx.gas_used.to_string(),
+                    run_tx.kind,
+                    run_tx.ack_latency_ms,
+                    run_tx.from_pool,
+                    run_tx.expect_status.map(|status| status.as_str()),
+                    run_tx.max_gas_used,
+                    run_tx.sent_block,
+                ])
+                .map_err(|e| ContenderError::with_err(e, "failed to insert run tx"))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| ContenderError::with_err(e, "failed to commit transaction"))
+    }
+
+    fn insert_alias(&self, name: &str, address: Address) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO aliases (name, address) VALUES (?1, ?2)",
+            params![name, address.encode_hex()],
+        )
+    }
+
+    fn get_alias(&self, name: &str) -> Result<Option<Address>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT address FROM aliases WHERE name = ?1")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let row = stmt
+            .query_map(params![name], |row| row.get::<_, String>(0))
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        let res = row
+            .last()
+            .transpose()
+            .map_err(|e| ContenderError::with_err(e, "failed to query row"))?
+            .map(|a| Address::from_hex(&a).expect("invalid address"));
+        Ok(res)
+    }
+
+    fn list_aliases(&self) -> Result<Vec<(String, Address)>> {
+        let pool = self.get_pool()?;
+        let mut stmt = pool
+            .prepare("SELECT name, address FROM aliases ORDER BY name")
+            .map_err(|e| ContenderError::with_err(e, "failed to prepare statement"))?;
+
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| ContenderError::with_err(e, "failed to map row"))?;
+        rows.map(|r| {
+            r.map(|(name, address)| (name, Address::from_hex(&address).expect("invalid address")))
+                .map_err(|e| ContenderError::with_err(e, "failed to convert row"))
+        })
+        .collect::<Result<Vec<_>>>()
+    }
+
+    fn remove_alias(&self, name: &str) -> Result<bool> {
+        let removed = self
+            .get_pool()?
+            .execute("DELETE FROM aliases WHERE name = ?1", params![name])
+            .map_err(|e| ContenderError::with_err(e, "failed to delete alias"))?;
+        Ok(removed > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contender_core::{db::DbWriter, generator::types::ExpectStatus};
+    use std::{sync::Arc, time::Duration};
+
+    #[test]
+    fn creates_table() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        assert_eq!(db.num_runs().unwrap(), 0);
+    }
+
+    #[test]
+    fn creates_indexes() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        // running migrations again must not fail
+        db.create_tables().unwrap();
+        let num_indexes: i64 = db
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name IN (
+                    'idx_run_txs_run_id_block_number',
+                    'idx_run_txs_tx_hash',
+                    'idx_named_txs_name_rpc_url_id'
+                )",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(num_indexes, 3);
+    }
+
+    #[test]
+    fn gets_db_stats() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 3, "test", "http://localhost:8545")
+            .unwrap();
+        let other_run_id = db
+            .insert_run(100001, 1, "test", "http://localhost:8545")
+            .unwrap();
+        db.insert_run_txs(run_id, vec![RunTx::mock(1), RunTx::mock(2), RunTx::mock(3)])
+            .unwrap();
+        db.insert_run_txs(other_run_id, vec![RunTx::mock(4)])
+            .unwrap();
+
+        let stats = db.stats(1).unwrap();
+        assert!(stats.size_bytes > 0);
+        assert!(stats.schema_version > 0);
+        assert!(stats.table_rows.contains(&("runs".to_owned(), 2)));
+        assert!(stats.table_rows.contains(&("run_txs".to_owned(), 4)));
+        assert_eq!(stats.largest_runs, vec![(run_id, 3)]);
+        db.vacuum().unwrap();
     }
 
     #[test]
     fn inserts_runs() {
         let db = SqliteDb::new_memory();
         db.create_tables().unwrap();
-        let do_it = |num| db.insert_run(100000, num, "test").unwrap();
+        let do_it = |num| {
+            db.insert_run(100000, num, "test", "http://localhost:8545")
+                .unwrap()
+        };
 
         println!("id: {}", do_it(100));
         println!("id: {}", do_it(101));
         println!("id: {}", do_it(102));
         assert_eq!(db.num_runs().unwrap(), 3);
+        assert_eq!(
+            db.get_run(3).unwrap().unwrap().rpc_url,
+            "http://localhost:8545"
+        );
+        let runs = db.get_runs().unwrap();
+        assert_eq!(runs.iter().map(|run| run.id).collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(runs[0].tx_count, 102);
+    }
+
+    #[test]
+    fn gets_run_tx_stats_of_landed_txs() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 10, "test", "http://localhost:8545")
+            .unwrap();
+        assert_eq!(db.get_run_tx_stats(run_id).unwrap(), RunTxStats::default());
+
+        let run_tx = |n: u8, block_number: u64, end_timestamp: usize| RunTx {
+            block_number,
+            ..RunTx::mock(n).with_times(1000, end_timestamp)
+        };
+        db.insert_run_txs(
+            run_id,
+            vec![run_tx(1, 5, 2000), run_tx(2, 7, 4000), run_tx(3, 0, 0)],
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_run_tx_stats(run_id).unwrap(),
+            RunTxStats {
+                landed_txs: 2,
+                gas_used: 42000,
+                latest_block: Some(7),
+                avg_inclusion_ms: Some(2000),
+            }
+        );
     }
 
     #[test]
@@ -432,35 +1619,157 @@ mod tests {
             .unwrap();
         assert_eq!(count, 2);
 
-        let res1 = db.get_named_tx(&name1, rpc_url).unwrap().unwrap();
+        let res1 = db.get_named_tx(&name1, rpc_url, None).unwrap().unwrap();
         assert_eq!(res1.name, name1);
         assert_eq!(res1.tx_hash, tx_hash);
         assert_eq!(res1.address, contract_address);
-        let res2 = db.get_named_tx(&name1, "http://wrong.url:8545").unwrap();
+        let res2 = db
+            .get_named_tx(&name1, "http://wrong.url:8545", None)
+            .unwrap();
         assert!(res2.is_none());
     }
 
+    #[test]
+    fn finds_named_txs_by_equivalent_or_aliased_url() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let tx_hash = TxHash::from_slice(&[1u8; 32]);
+        let address = Some(Address::from_slice(&[4u8; 20]));
+        db.insert_named_txs(
+            vec![NamedTx::new("weth".to_owned(), tx_hash, address)],
+            "http://LOCALHOST:8545/",
+        )
+        .unwrap();
+
+        // same endpoint, written differently
+        let res = db
+            .get_named_tx("weth", "http://localhost:8545", None)
+            .unwrap();
+        assert_eq!(res.unwrap().address, address);
+        // different endpoint
+        assert!(db
+            .get_named_tx("weth", "http://127.0.0.1:8545", None)
+            .unwrap()
+            .is_none());
+
+        db.insert_rpc_url_alias("http://127.0.0.1:8545", "http://localhost:8545")
+            .unwrap();
+        let res = db
+            .get_named_tx("weth", "http://127.0.0.1:8545/", None)
+            .unwrap();
+        assert_eq!(res.unwrap().address, address);
+    }
+
+    #[test]
+    fn scopes_named_txs_to_namespace() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let rpc_url = "http://localhost:8545";
+        let legacy = Some(Address::from_slice(&[1u8; 20]));
+        let token_a = Some(Address::from_slice(&[2u8; 20]));
+        let token_b = Some(Address::from_slice(&[3u8; 20]));
+        db.insert_named_txs(
+            vec![NamedTx::new("token".to_owned(), TxHash::default(), legacy)],
+            rpc_url,
+        )
+        .unwrap();
+        db.insert_named_txs(
+            vec![
+                NamedTx::new("token".to_owned(), TxHash::default(), token_a)
+                    .with_namespace(Some("a".to_owned())),
+                NamedTx::new("token".to_owned(), TxHash::default(), token_b)
+                    .with_namespace(Some("b".to_owned())),
+            ],
+            rpc_url,
+        )
+        .unwrap();
+
+        let get = |namespace| {
+            db.get_named_tx("token", rpc_url, namespace)
+                .unwrap()
+                .unwrap()
+                .address
+        };
+        assert_eq!(get(Some("a")), token_a);
+        assert_eq!(get(Some("b")), token_b);
+        // falls back to txs saved without a namespace
+        assert_eq!(get(Some("c")), legacy);
+        // unscoped lookups find the latest
+        assert_eq!(get(None), token_b);
+        assert_eq!(db.get_contracts().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn lists_latest_deployment_of_each_contract() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let old_address = Some(Address::from_slice(&[4u8; 20]));
+        let new_address = Some(Address::from_slice(&[5u8; 20]));
+        let info = ContractInfo {
+            code_hash: Some(B256::from_slice(&[9u8; 32])),
+            deploy_data: Some("6080".to_owned()),
+            abi: None,
+            scenario_name: Some("uniV2.toml".to_owned()),
+        };
+        db.insert_named_txs(
+            vec![
+                NamedTx::new(
+                    "weth".to_owned(),
+                    TxHash::from_slice(&[1u8; 32]),
+                    old_address,
+                ),
+                NamedTx::new("setup_tx".to_owned(), TxHash::from_slice(&[2u8; 32]), None),
+            ],
+            "http://localhost:8545",
+        )
+        .unwrap();
+        db.insert_named_txs(
+            vec![NamedTx::new(
+                "weth".to_owned(),
+                TxHash::from_slice(&[3u8; 32]),
+                new_address,
+            )
+            .with_contract(info.clone())],
+            "http://localhost:8545",
+        )
+        .unwrap();
+
+        let contracts = db.get_contracts().unwrap();
+        assert_eq!(contracts.len(), 1);
+        assert_eq!(contracts[0].name, "weth");
+        assert_eq!(Some(contracts[0].address), new_address);
+        assert_eq!(contracts[0].rpc_url, "http://localhost:8545");
+        assert_eq!(contracts[0].info, info);
+        assert!(contracts[0].deployed_at.is_some());
+    }
+
     #[test]
     fn inserts_and_gets_run_txs() {
         let db = SqliteDb::new_memory();
         db.create_tables().unwrap();
-        let run_id = db.insert_run(100000, 100, "test").unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
         let run_txs = vec![
             RunTx {
                 tx_hash: TxHash::from_slice(&[0u8; 32]),
-                start_timestamp: 100,
-                end_timestamp: 200,
+                start_timestamp: 100_000,
+                end_timestamp: 100_250,
                 block_number: 1,
+                block_timestamp: 100,
                 gas_used: 100,
                 kind: Some("test".to_string()),
+                ack_latency_ms: Some(12),
+                from_pool: Some("whales".to_string()),
+                expect_status: Some(ExpectStatus::Revert),
+                max_gas_used: Some(150),
+                sent_block: Some(0),
             },
             RunTx {
-                tx_hash: TxHash::from_slice(&[1u8; 32]),
-                start_timestamp: 200,
-                end_timestamp: 300,
                 block_number: 2,
                 gas_used: 200,
                 kind: Some("test".to_string()),
+                ..RunTx::mock(1).with_times(200_000, 200_500)
             },
         ];
         db.insert_run_txs(run_id, run_txs).unwrap();
@@ -473,5 +1782,496 @@ mod tests {
 
         let res = db.get_run_txs(run_id).unwrap();
         assert_eq!(res.len(), 2);
+        assert_eq!(res[0].time_to_inclusion_ms(), 250);
+        assert_eq!(res[1].block_timestamp, 200);
+        assert_eq!(res[0].ack_latency_ms, Some(12));
+        assert_eq!(res[1].ack_latency_ms, None);
+        assert_eq!(res[0].from_pool.as_deref(), Some("whales"));
+        assert_eq!(res[1].from_pool, None);
+        assert_eq!(res[0].expect_status, Some(ExpectStatus::Revert));
+        assert_eq!(res[1].expect_status, None);
+        assert_eq!(res[0].max_gas_used, Some(150));
+        assert_eq!(res[1].max_gas_used, None);
+        assert_eq!(res[0].blocks_to_inclusion(), Some(1));
+        assert_eq!(res[1].blocks_to_inclusion(), None);
+    }
+
+    #[test]
+    fn migrates_run_tx_timestamps_to_millis() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 1, "test", "http://localhost:8545")
+            .unwrap();
+        // timed in seconds, with no block timestamp
+        db.execute(
+            "INSERT INTO run_txs (run_id, tx_hash, start_timestamp, end_timestamp, block_number, gas_used) VALUES (?1, ?2, 100, 102, 1, '21000')",
+            params![run_id, TxHash::ZERO.encode_hex()],
+        )
+        .unwrap();
+        db.create_tables().unwrap();
+        // migrations run again on every startup; already-migrated rows are left as-is
+        db.create_tables().unwrap();
+
+        let res = db.get_run_txs(run_id).unwrap();
+        assert_eq!(res[0].start_timestamp, 100_000);
+        assert_eq!(res[0].end_timestamp, 102_000);
+        assert_eq!(res[0].block_timestamp, 102);
+    }
+
+    #[test]
+    fn migrates_named_txs_of_urls_that_normalize_to_a_saved_url() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let address = Some(Address::from_slice(&[4u8; 20]));
+        // saved before normalization was added
+        for (id, url) in [(100, "http://x:8545"), (101, "http://x:8545/")] {
+            db.execute(
+                "INSERT INTO rpc_urls (id, url) VALUES (?1, ?2)",
+                params![id, url],
+            )
+            .unwrap();
+        }
+        db.execute(
+            "INSERT INTO named_txs (name, tx_hash, contract_address, rpc_url_id) VALUES ('weth', ?1, ?2, 101)",
+            params![TxHash::ZERO.encode_hex(), address.unwrap().encode_hex()],
+        )
+        .unwrap();
+        db.create_tables().unwrap();
+
+        let rpc_url_id: i64 = db
+            .query_row(
+                "SELECT rpc_url_id FROM named_txs WHERE name = 'weth'",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rpc_url_id, 100);
+        for url in ["http://x:8545", "http://x:8545/"] {
+            let res = db.get_named_tx("weth", url, None).unwrap();
+            assert_eq!(res.unwrap().address, address);
+        }
+    }
+
+    #[test]
+    fn inserts_and_gets_run_metadata() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        assert!(db.get_run_metadata(run_id).unwrap().is_none());
+
+        let metadata = RunMetadata {
+            client_version: "reth/v1.1.0".to_owned(),
+            chain_id: 1337,
+            genesis_hash: B256::from_slice(&[7u8; 32]),
+            fork: "Cancun".to_owned(),
+        };
+        db.insert_run_metadata(run_id, &metadata).unwrap();
+        assert_eq!(db.get_run_metadata(run_id).unwrap(), Some(metadata));
+    }
+
+    #[test]
+    fn inserts_and_gets_run_provenance() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        assert!(db.get_run_provenance(run_id).unwrap().is_none());
+
+        let provenance = RunProvenance {
+            scenario: "[[spam]]\nto = \"0x0000000000000000000000000000000000000001\"".to_owned(),
+            args: r#"{"seed":"0x01","duration":10}"#.to_owned(),
+        };
+        db.insert_run_provenance(run_id, &provenance).unwrap();
+        assert_eq!(db.get_run_provenance(run_id).unwrap(), Some(provenance));
+    }
+
+    #[test]
+    fn inserts_and_gets_run_artifacts() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        assert!(db.get_run_artifacts(run_id).unwrap().is_none());
+
+        let artifacts = RunArtifacts::new(
+            "[[spam]]\nto = \"0x0000000000000000000000000000000000000001\"\n".repeat(50),
+        );
+        db.insert_run_artifacts(run_id, &artifacts).unwrap();
+        assert_eq!(db.get_run_artifacts(run_id).unwrap(), Some(artifacts));
+
+        // a snapshot that doesn't match its hash is rejected
+        db.execute(
+            "UPDATE run_artifacts SET scenario_hash = ?1 WHERE run_id = ?2",
+            params![B256::ZERO.encode_hex(), run_id],
+        )
+        .unwrap();
+        assert!(db.get_run_artifacts(run_id).is_err());
+    }
+
+    #[test]
+    fn inserts_and_gets_run_score() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        assert!(db.get_run_score(run_id).unwrap().is_none());
+
+        let score = RunScore {
+            score: 87.5,
+            grade: "B".to_owned(),
+            throughput: 0.9,
+            latency: 0.75,
+            reliability: 1.0,
+        };
+        db.insert_run_score(run_id, &score).unwrap();
+        assert_eq!(db.get_run_score(run_id).unwrap(), Some(score));
+    }
+
+    #[test]
+    fn appends_runs_to_baseline_series() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run = || {
+            db.insert_run(100000, 100, "test", "http://localhost:8545")
+                .unwrap()
+        };
+        let (run1, run2, run3) = (run(), run(), run());
+        db.insert_baseline_run("nightly", run2).unwrap();
+        db.insert_baseline_run("nightly", run1).unwrap();
+        db.insert_baseline_run("nightly", run1).unwrap();
+        db.insert_baseline_run("other", run3).unwrap();
+        assert_eq!(db.get_baseline_runs("nightly").unwrap(), vec![run1, run2]);
+        assert_eq!(db.get_baseline_runs("other").unwrap(), vec![run3]);
+        assert!(db.get_baseline_runs("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn inserts_and_gets_latency_snapshots() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let snapshot = |timestamp, method: &str| LatencySnapshot {
+            timestamp,
+            method: method.to_owned(),
+            p50_ms: 3,
+            p95_ms: 12,
+            count: 10,
+        };
+        db.insert_latency_snapshots(
+            run_id,
+            &[
+                snapshot(2000, "eth_sendRawTransaction"),
+                snapshot(1000, "eth_blockNumber"),
+            ],
+        )
+        .unwrap();
+
+        let res = db.get_latency_snapshots(run_id).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0], snapshot(1000, "eth_blockNumber"));
+        assert!(db.get_latency_snapshots(run_id + 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn inserts_and_gets_bundles() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let bundle = BundleSubmission {
+            bundle_hash: B256::from_slice(&[9u8; 32]),
+            target_block: 42,
+            num_submissions: 3,
+            tx_hashes: vec![
+                TxHash::from_slice(&[1u8; 32]),
+                TxHash::from_slice(&[2u8; 32]),
+            ],
+        };
+        db.insert_bundles(run_id, &[bundle.clone()]).unwrap();
+        assert_eq!(db.get_bundles(run_id).unwrap(), vec![bundle]);
+    }
+
+    #[test]
+    fn inserts_and_gets_run_accounts() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let accounts = vec![
+            ("admin".to_owned(), Address::from_slice(&[1u8; 20])),
+            ("pool1".to_owned(), Address::from_slice(&[2u8; 20])),
+        ];
+        db.insert_run_accounts(run_id, &accounts).unwrap();
+        assert_eq!(db.get_run_accounts(run_id).unwrap(), accounts);
+    }
+
+    #[test]
+    fn inserts_and_gets_funding_txs() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let funding_tx = |n: u8| FundingTx {
+            tx_hash: TxHash::from_slice(&[n; 32]),
+            sender: Address::from_slice(&[1u8; 20]),
+            recipient: Address::from_slice(&[n; 20]),
+            amount: U256::from(10).pow(U256::from(18)),
+            gas_used: 21000,
+            effective_gas_price: 1_000_000_000,
+        };
+        db.insert_funding_txs(Some(run_id), &[funding_tx(2), funding_tx(3)])
+            .unwrap();
+        // setup funding isn't linked to a run
+        db.insert_funding_txs(None, &[funding_tx(4)]).unwrap();
+
+        assert_eq!(
+            db.get_funding_txs(run_id).unwrap(),
+            vec![funding_tx(2), funding_tx(3)]
+        );
+    }
+
+    #[test]
+    fn links_setup_funding_to_the_run_using_the_accounts() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let funding_tx = |n: u8| FundingTx {
+            tx_hash: TxHash::from_slice(&[n; 32]),
+            sender: Address::from_slice(&[1u8; 20]),
+            recipient: Address::from_slice(&[n; 20]),
+            amount: U256::from(10).pow(U256::from(18)),
+            gas_used: 21000,
+            effective_gas_price: 1_000_000_000,
+        };
+        // funded by the setup command
+        db.insert_funding_txs(None, &[funding_tx(2), funding_tx(3)])
+            .unwrap();
+
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        db.insert_run_accounts(
+            run_id,
+            &[("pool1".to_owned(), Address::from_slice(&[2u8; 20]))],
+        )
+        .unwrap();
+        db.insert_funding_txs(Some(run_id), &[funding_tx(4)])
+            .unwrap();
+        assert_eq!(
+            db.get_funding_txs(run_id).unwrap(),
+            vec![funding_tx(2), funding_tx(4)]
+        );
+
+        // setup funding is only charged to one run
+        let next_run_id = db
+            .insert_run(100001, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let accounts = [2u8, 3]
+            .map(|n| ("pool1".to_owned(), Address::from_slice(&[n; 20])))
+            .to_vec();
+        db.insert_run_accounts(next_run_id, &accounts).unwrap();
+        assert_eq!(
+            db.get_funding_txs(next_run_id).unwrap(),
+            vec![funding_tx(3)]
+        );
+        assert_eq!(db.get_funding_txs(run_id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn inserts_and_gets_replaced_txs() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let replaced_tx = |n: u8| ReplacedTx {
+            tx_hash: TxHash::from_slice(&[n; 32]),
+            sender: Address::from_slice(&[1u8; 20]),
+            nonce: n as u64,
+            start_timestamp: 1_700_000_000_000,
+            block_number: 7,
+            kind: Some("swap".to_owned()),
+        };
+        db.insert_replaced_txs(run_id, &[replaced_tx(2), replaced_tx(3)])
+            .unwrap();
+        assert_eq!(
+            db.get_replaced_txs(run_id).unwrap(),
+            vec![replaced_tx(2), replaced_tx(3)]
+        );
+        assert!(db.get_replaced_txs(run_id + 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn inserts_and_gets_run_errors() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let errors = vec![
+            RunError {
+                kind: Some("swap".to_owned()),
+                error_kind: "nonce_conflict".to_owned(),
+                count: 3,
+            },
+            RunError {
+                kind: None,
+                error_kind: "timeout".to_owned(),
+                count: 1,
+            },
+        ];
+        db.insert_run_errors(run_id, &errors).unwrap();
+        assert_eq!(db.get_run_errors(run_id).unwrap(), errors);
+        assert!(db.get_run_errors(run_id + 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn inserts_and_gets_balances() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        let balance = |pool: &str, balance: u64| AccountBalance {
+            pool: pool.to_owned(),
+            address: Address::from_slice(&[1u8; 20]),
+            balance: U256::from(balance),
+        };
+        db.insert_balances(run_id, true, &[balance("admin", 100)])
+            .unwrap();
+        db.insert_balances(run_id, false, &[balance("admin", 90)])
+            .unwrap();
+
+        assert_eq!(
+            db.get_balances(run_id, true).unwrap(),
+            vec![balance("admin", 100)]
+        );
+        assert_eq!(
+            db.get_balances(run_id, false).unwrap(),
+            vec![balance("admin", 90)]
+        );
+    }
+
+    #[test]
+    fn inserts_gets_and_removes_aliases() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let weth = Address::from_slice(&[1u8; 20]);
+        let dai = Address::from_slice(&[2u8; 20]);
+        db.insert_alias("weth", Address::ZERO).unwrap();
+        db.insert_alias("weth", weth).unwrap();
+        db.insert_alias("dai", dai).unwrap();
+
+        assert_eq!(db.get_alias("weth").unwrap(), Some(weth));
+        assert_eq!(db.get_alias("usdc").unwrap(), None);
+        assert_eq!(
+            db.list_aliases().unwrap(),
+            vec![("dai".to_owned(), dai), ("weth".to_owned(), weth)]
+        );
+
+        assert!(db.remove_alias("dai").unwrap());
+        assert!(!db.remove_alias("dai").unwrap());
+        assert_eq!(db.list_aliases().unwrap().len(), 1);
+    }
+
+    fn writer_test_db() -> (Arc<SqliteDb>, u64) {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(100000, 100, "test", "http://localhost:8545")
+            .unwrap();
+        (Arc::new(db), run_id)
+    }
+
+    fn replaced_tx(n: u8) -> ReplacedTx {
+        ReplacedTx {
+            tx_hash: TxHash::repeat_byte(n),
+            sender: Address::repeat_byte(1),
+            nonce: n as u64,
+            start_timestamp: 1_700_000_000_000,
+            block_number: 7,
+            kind: None,
+        }
+    }
+
+    fn latency_snapshot(timestamp: u64) -> LatencySnapshot {
+        LatencySnapshot {
+            timestamp,
+            method: "eth_sendRawTransaction".to_owned(),
+            p50_ms: 3,
+            p95_ms: 12,
+            count: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn flushes_queued_writes() {
+        let (db, run_id) = writer_test_db();
+        let writer = DbWriter::new(db.clone(), 4, Duration::from_secs(60));
+        writer
+            .insert_run_txs(run_id, vec![RunTx::mock(1), RunTx::mock(2)])
+            .await
+            .unwrap();
+        writer
+            .insert_latency_snapshots(run_id, vec![latency_snapshot(1000)])
+            .await
+            .unwrap();
+        writer
+            .insert_replaced_txs(run_id, vec![replaced_tx(3)])
+            .await
+            .unwrap();
+
+        writer.flush().await.unwrap();
+        assert_eq!(db.get_run_txs(run_id).unwrap().len(), 2);
+        assert_eq!(db.get_latency_snapshots(run_id).unwrap().len(), 1);
+        assert_eq!(db.get_replaced_txs(run_id).unwrap(), vec![replaced_tx(3)]);
+    }
+
+    #[tokio::test]
+    async fn retries_writes_that_failed_to_flush() {
+        let (db, run_id) = writer_test_db();
+        // make the latency insert fail; the run_txs before it are still written
+        db.execute(
+            "ALTER TABLE latency_snapshots RENAME TO latency_snapshots_tmp",
+            params![],
+        )
+        .unwrap();
+        let writer = DbWriter::new(db.clone(), 4, Duration::from_secs(60));
+        writer
+            .insert_run_txs(run_id, vec![RunTx::mock(1), RunTx::mock(2)])
+            .await
+            .unwrap();
+        writer
+            .insert_latency_snapshots(run_id, vec![latency_snapshot(1000)])
+            .await
+            .unwrap();
+        writer
+            .insert_replaced_txs(run_id, vec![replaced_tx(3)])
+            .await
+            .unwrap();
+        assert!(writer.flush().await.is_err());
+        assert_eq!(db.get_run_txs(run_id).unwrap().len(), 2);
+        assert!(db.get_replaced_txs(run_id).unwrap().is_empty());
+
+        db.execute(
+            "ALTER TABLE latency_snapshots_tmp RENAME TO latency_snapshots",
+            params![],
+        )
+        .unwrap();
+        writer.flush().await.unwrap();
+        // the written run_txs aren't written again
+        assert_eq!(db.get_run_txs(run_id).unwrap().len(), 2);
+        assert_eq!(db.get_latency_snapshots(run_id).unwrap().len(), 1);
+        assert_eq!(db.get_replaced_txs(run_id).unwrap(), vec![replaced_tx(3)]);
     }
 }
@@ -0,0 +1,129 @@
+use alloy::primitives::keccak256;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Compiles `contract` from the Solidity file at `source` with `forge build`, and returns its
+/// hex-encoded creation bytecode.
+///
+/// Bytecode is cached in `cache_dir` by the hash of the source file, so unchanged sources are only
+/// compiled once. Only `source` itself is hashed; changes to files it imports aren't detected.
+pub fn forge_build(
+    source: &Path,
+    contract: &str,
+    cache_dir: &Path,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let source_code = std::fs::read(source)
+        .map_err(|e| format!("failed to read contract source {}: {}", source.display(), e))?;
+    let hash = keccak256([source_code.as_slice(), contract.as_bytes()].concat());
+    let cached = cache_dir.join(format!("{}.hex", hash));
+    if let Ok(bytecode) = std::fs::read_to_string(&cached) {
+        return Ok(bytecode);
+    }
+
+    let out_dir = cache_dir.join(format!("{}-out", hash));
+    println!("compiling {} from {}", contract, source.display());
+    let output = Command::new("forge")
+        .arg("build")
+        .arg(source)
+        .arg("--out")
+        .arg(&out_dir)
+        .arg("--cache-path")
+        .arg(out_dir.join("cache"))
+        .output()
+        .map_err(|e| format!("failed to run forge (is foundry installed?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "forge build failed for {}:\n{}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
+    let artifact = artifact_path(&out_dir, source, contract);
+    let bytecode = read_artifact_bytecode(&artifact)?;
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(&cached, &bytecode)?;
+    if let Some(abi) = read_artifact_abi(&artifact) {
+        std::fs::write(cache_dir.join(format!("{}.abi.json", hash)), abi)?;
+    }
+    std::fs::remove_dir_all(&out_dir)?;
+    Ok(bytecode)
+}
+
+/// Returns the JSON ABI of `contract` saved by [`forge_build`], if it's been compiled.
+pub fn cached_abi(source: &Path, contract: &str, cache_dir: &Path) -> Option<String> {
+    let source_code = std::fs::read(source).ok()?;
+    let hash = keccak256([source_code.as_slice(), contract.as_bytes()].concat());
+    std::fs::read_to_string(cache_dir.join(format!("{}.abi.json", hash))).ok()
+}
+
+/// Where compiled bytecode is cached by default: `~/.contender/artifacts`, or the system temp dir
+/// if `$HOME` isn't set.
+pub fn default_cache_dir() -> PathBuf {
+    std::env::var("HOME")
+        .map(|home| PathBuf::from(home).join(".contender"))
+        .unwrap_or_else(|_| std::env::temp_dir().join("contender"))
+        .join("artifacts")
+}
+
+/// Forge writes each contract's artifact to `<out>/<source file name>/<contract>.json`.
+fn artifact_path(out_dir: &Path, source: &Path, contract: &str) -> PathBuf {
+    let file_name = source.file_name().unwrap_or_default();
+    out_dir.join(file_name).join(format!("{}.json", contract))
+}
+
+fn read_artifact_bytecode(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let artifact: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(path)
+            .map_err(|e| format!("contract artifact {} not found: {}", path.display(), e))?,
+    )?;
+    let bytecode = artifact["bytecode"]["object"]
+        .as_str()
+        .filter(|bytecode| bytecode.len() > 2)
+        .ok_or_else(|| {
+            format!(
+                "no bytecode in {}; is the contract abstract?",
+                path.display()
+            )
+        })?;
+    Ok(bytecode.to_owned())
+}
+
+fn read_artifact_abi(path: &Path) -> Option<String> {
+    let artifact: serde_json::Value = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+    artifact.get("abi").map(|abi| abi.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cached_bytecode_without_compiling() {
+        let dir = std::env::temp_dir().join("contender-forge-build-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("Cached.sol");
+        std::fs::write(&source, "contract Cached {}").unwrap();
+        let hash = keccak256([b"contract Cached {}".as_slice(), b"Cached"].concat());
+        std::fs::write(dir.join(format!("{}.hex", hash)), "0x6080").unwrap();
+
+        assert_eq!(forge_build(&source, "Cached", &dir).unwrap(), "0x6080");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_bytecode_from_artifact() {
+        let dir = std::env::temp_dir().join("contender-forge-artifact-test");
+        let path = artifact_path(&dir, Path::new("contracts/Target.sol"), "Target");
+        assert_eq!(path, dir.join("Target.sol").join("Target.json"));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{"bytecode":{"object":"0x6080"},"abi":[]}"#).unwrap();
+        assert_eq!(read_artifact_bytecode(&path).unwrap(), "0x6080");
+        assert_eq!(read_artifact_abi(&path).unwrap(), "[]");
+
+        std::fs::write(&path, r#"{"bytecode":{"object":"0x"}}"#).unwrap();
+        assert!(read_artifact_bytecode(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
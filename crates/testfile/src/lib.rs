@@ -1,46 +1,166 @@
+mod forge;
 mod types;
 
+pub use crate::forge::{cached_abi, default_cache_dir, forge_build};
 pub use crate::types::TestConfig;
 use alloy::hex::ToHexExt;
 use alloy::primitives::Address;
 use contender_core::{
     error::ContenderError,
     generator::{
+        eip712::TypedDataDefinition,
+        multicall::MulticallConfig,
         templater::Templater,
-        types::{CreateDefinition, FunctionCallDefinition, SpamRequest},
+        types::{
+            CreateDefinition, FunctionCallDefinition, GasEstimateConfig, Phase, ReadRequest,
+            SenderAssignment, SpamRequest,
+        },
+        util::resolve_alias,
         PlanConfig,
     },
 };
 use std::collections::HashMap;
 use std::fs::read;
+use std::path::Path;
 
 impl TestConfig {
     pub fn from_file(file_path: &str) -> Result<TestConfig, Box<dyn std::error::Error>> {
         let file_contents = read(file_path)?;
         let file_contents_str = String::from_utf8_lossy(&file_contents).to_string();
-        let test_file: TestConfig = toml::from_str(&file_contents_str)?;
+        let mut test_file: TestConfig = toml::from_str(&file_contents_str)?;
+        let base_dir = Path::new(file_path).parent().unwrap_or(Path::new(""));
+        test_file.build_sources(base_dir, &default_cache_dir())?;
         Ok(test_file)
     }
 
+    /// Loads a scenario from a [`TestConfig::snapshot`], e.g. one saved with a run.
+    pub fn from_snapshot(snapshot: &str) -> Result<TestConfig, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(snapshot)?)
+    }
+
+    /// Compiles the bytecode of each `[[create]]` step which sets a `source`, resolving relative
+    /// sources against `base_dir`.
+    pub fn build_sources(
+        &mut self,
+        base_dir: &Path,
+        cache_dir: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for create in self.create.iter_mut().flatten() {
+            let Some(source) = &create.source else {
+                continue;
+            };
+            let contract = create.contract.as_deref().ok_or_else(|| {
+                format!(
+                    "create step '{}' sets a source but no contract name",
+                    create.name
+                )
+            })?;
+            let source = base_dir.join(source);
+            create.bytecode = forge_build(&source, contract, cache_dir)?;
+            if create.abi.is_none() {
+                create.abi = cached_abi(&source, contract, cache_dir);
+            }
+        }
+        Ok(())
+    }
+
+    /// Namespace of the named txs saved & looked up by the scenario at `file_path`: `namespace`
+    /// if it's set, or the file's name without its extension.
+    pub fn namespace_for(&self, file_path: &str) -> String {
+        self.namespace.to_owned().unwrap_or_else(|| {
+            Path::new(file_path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file_path.to_owned())
+        })
+    }
+
     pub fn encode_toml(&self) -> Result<String, Box<dyn std::error::Error>> {
         let encoded = toml::to_string(self)?;
         Ok(encoded)
     }
 
+    /// Encodes the scenario as it's run, with contract sources replaced by the bytecode they were
+    /// compiled to, so it can be loaded again without the files it was built from.
+    /// Keys are sorted, so the same scenario always encodes (and hashes) the same.
+    pub fn snapshot(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut config = self.to_owned();
+        for create in config.create.iter_mut().flatten() {
+            create.source = None;
+        }
+        Ok(toml::to_string(&toml::Value::try_from(config)?)?)
+    }
+
     pub fn save_toml(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let encoded = self.encode_toml()?;
         std::fs::write(file_path, encoded)?;
         Ok(())
     }
+
+    /// Replaces `env` values and the `to`, `args` & read addresses of steps which are `@alias`
+    /// references with addresses from the address book, which `lookup` reads by name. Only whole
+    /// values are resolved; see [`resolve_alias`].
+    pub fn resolve_aliases(
+        &mut self,
+        lookup: impl Fn(&str) -> contender_core::Result<Option<Address>>,
+    ) -> contender_core::Result<()> {
+        let resolve = |value: &mut String| {
+            if let Some(address) = resolve_alias(value, &lookup)? {
+                *value = address.to_string();
+            }
+            Ok::<_, ContenderError>(())
+        };
+        let resolve_call = |call: &mut FunctionCallDefinition| {
+            call.to.targets_mut().iter_mut().try_for_each(resolve)?;
+            call.args.iter_mut().flatten().try_for_each(resolve)
+        };
+
+        self.env
+            .iter_mut()
+            .flatten()
+            .try_for_each(|(_, value)| resolve(value))?;
+        self.setup.iter_mut().flatten().try_for_each(resolve_call)?;
+        for step in self.spam.iter_mut().flatten() {
+            match step {
+                SpamRequest::Tx(call) => resolve_call(call)?,
+                SpamRequest::Bundle(bundle) => bundle.txs.iter_mut().try_for_each(resolve_call)?,
+                SpamRequest::Transfer(transfer) => {
+                    if let Some(to) = &mut transfer.to {
+                        to.targets_mut().iter_mut().try_for_each(resolve)?;
+                    }
+                }
+            }
+        }
+        for step in self.read.iter_mut().flatten() {
+            match step {
+                ReadRequest::Call(call) | ReadRequest::EstimateGas(call) => {
+                    resolve(&mut call.to)?;
+                    call.args.iter_mut().flatten().try_for_each(resolve)?;
+                }
+                ReadRequest::Logs(logs) => logs.address.iter_mut().try_for_each(resolve)?,
+                ReadRequest::Balance(balance) => resolve(&mut balance.address)?,
+                ReadRequest::Block(_) => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PlanConfig<String> for TestConfig {
     fn get_spam_steps(&self) -> Result<Vec<SpamRequest>, ContenderError> {
-        Ok(self.spam.to_owned().unwrap_or_default())
+        let mut spam = self.spam.to_owned().unwrap_or_default();
+        if let Some(defaults) = &self.defaults {
+            spam.iter_mut().for_each(|req| defaults.apply_spam(req));
+        }
+        Ok(spam)
     }
 
     fn get_setup_steps(&self) -> Result<Vec<FunctionCallDefinition>, ContenderError> {
-        Ok(self.setup.to_owned().unwrap_or_default())
+        let mut setup = self.setup.to_owned().unwrap_or_default();
+        if let Some(defaults) = &self.defaults {
+            setup.iter_mut().for_each(|funcdef| defaults.apply(funcdef));
+        }
+        Ok(setup)
     }
 
     fn get_create_steps(&self) -> Result<Vec<CreateDefinition>, ContenderError> {
@@ -53,6 +173,34 @@ impl PlanConfig<String> for TestConfig {
             None,
         ))
     }
+
+    fn get_gas_estimate_config(&self) -> GasEstimateConfig {
+        self.gas.to_owned().unwrap_or_default()
+    }
+
+    fn get_sender_assignment(&self) -> SenderAssignment {
+        self.sender_assignment.unwrap_or_default()
+    }
+
+    fn get_concurrent_setup(&self) -> bool {
+        self.concurrent_setup.unwrap_or_default()
+    }
+
+    fn get_multicall_config(&self) -> Option<MulticallConfig> {
+        self.multicall.to_owned()
+    }
+
+    fn get_typed_data(&self) -> HashMap<String, TypedDataDefinition> {
+        self.eip712.to_owned().unwrap_or_default()
+    }
+
+    fn get_phases(&self) -> Vec<Phase> {
+        self.phases.to_owned().unwrap_or_default()
+    }
+
+    fn get_read_steps(&self) -> Vec<ReadRequest> {
+        self.read.to_owned().unwrap_or_default()
+    }
 }
 
 impl Templater<String> for TestConfig {
@@ -105,18 +253,25 @@ pub mod tests {
     use alloy::{
         hex::ToHexExt,
         node_bindings::{Anvil, AnvilInstance},
-        primitives::{Address, U256},
+        primitives::{Address, Signature, TxKind, U256},
+        providers::{Provider, ProviderBuilder},
         signers::local::PrivateKeySigner,
+        sol_types::SolCall,
     };
     use contender_core::{
+        agent_controller::{AgentStore, SignerStore},
         db::MockDb,
         generator::{
+            eip712::typed_data_hash,
+            multicall::{MulticallConfig, MULTICALL3_ADDRESS},
             named_txs::ExecutionRequest,
+            safe::{execTransactionCall, safe_tx, safe_tx_hash},
             types::{
                 BundleCallDefinition, CreateDefinition, FunctionCallDefinition, FuzzParam,
-                PlanType, SpamRequest,
+                GasEstimateConfig, GasLimit, PlanType, ReadRequest, SenderAssignment, SpamRequest,
+                StepDefaults,
             },
-            Generator, RandSeed,
+            Generator, PlanConfig, RandSeed,
         },
         test_scenario::TestScenario,
     };
@@ -142,7 +297,10 @@ pub mod tests {
 
     pub fn get_testconfig() -> TestConfig {
         let fncall = FunctionCallDefinition {
-            to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD".to_owned(),
+            to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD".into(),
+            to_selection: None,
+            relayer_pool: None,
+            safe: None,
             from: "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD"
                 .to_owned()
                 .into(),
@@ -158,10 +316,29 @@ pub mod tests {
             fuzz: None,
             value: None,
             kind: None,
+            gas_limit: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            rate: None,
+            weight: None,
+            expect_status: None,
+            max_gas_used: None,
+            route: None,
         };
 
         TestConfig {
+            chain_id: None,
             env: None,
+            gas: None,
+            defaults: None,
+            multicall: None,
+            phases: None,
+            slo: None,
+            namespace: None,
+            read: None,
+            eip712: None,
+            sender_assignment: None,
+            concurrent_setup: None,
             create: None,
             setup: None,
             spam: vec![SpamRequest::Tx(fncall)].into(),
@@ -170,7 +347,10 @@ pub mod tests {
 
     pub fn get_fuzzy_testconfig() -> TestConfig {
         let fn_call = |data: &str, from_addr: &str| FunctionCallDefinition {
-            to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+            to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+            to_selection: None,
+            relayer_pool: None,
+            safe: None,
             from: from_addr.to_owned().into(),
             from_pool: None,
             value: None,
@@ -190,9 +370,28 @@ pub mod tests {
                 max: None,
             }]
             .into(),
+            gas_limit: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            rate: None,
+            weight: None,
+            expect_status: None,
+            max_gas_used: None,
+            route: None,
         };
         TestConfig {
+            chain_id: None,
             env: None,
+            gas: None,
+            defaults: None,
+            multicall: None,
+            phases: None,
+            slo: None,
+            namespace: None,
+            read: None,
+            eip712: None,
+            sender_assignment: None,
+            concurrent_setup: None,
             create: None,
             setup: None,
             spam: vec![
@@ -214,6 +413,8 @@ pub mod tests {
                         fn_call("0xea75", "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
                         fn_call("0xf00d", "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"),
                     ],
+                    rate: None,
+                    weight: None,
                 }),
             ]
             .into(),
@@ -222,12 +423,26 @@ pub mod tests {
 
     pub fn get_setup_testconfig() -> TestConfig {
         TestConfig {
+            chain_id: None,
             env: None,
+            gas: None,
+            defaults: None,
+            multicall: None,
+            phases: None,
+            slo: None,
+            namespace: None,
+            read: None,
+            eip712: None,
+            sender_assignment: None,
+            concurrent_setup: None,
             create: None,
             spam: None,
             setup: vec![
                 FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
                         .to_owned()
                         .into(),
@@ -243,9 +458,20 @@ pub mod tests {
                     .into(),
                     kind: None,
                     fuzz: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 },
                 FunctionCallDefinition {
-                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".to_owned(),
+                    to: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D".into(),
+                    to_selection: None,
+                    relayer_pool: None,
+                    safe: None,
                     from: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
                         .to_owned()
                         .into(),
@@ -261,6 +487,14 @@ pub mod tests {
                     .into(),
                     kind: None,
                     fuzz: None,
+                    gas_limit: None,
+                    max_fee_per_gas: None,
+                    max_priority_fee_per_gas: None,
+                    rate: None,
+                    weight: None,
+                    expect_status: None,
+                    max_gas_used: None,
+                    route: None,
                 },
             ]
             .into(),
@@ -272,12 +506,26 @@ pub mod tests {
         env.insert("test1".to_owned(), "0xbeef".to_owned());
         env.insert("test2".to_owned(), "0x9001".to_owned());
         TestConfig {
+            chain_id: None,
             env: Some(env),
+            gas: None,
+            defaults: None,
+            multicall: None,
+            phases: None,
+            slo: None,
+            namespace: None,
+            read: None,
+            eip712: None,
+            sender_assignment: None,
+            concurrent_setup: None,
             create: Some(vec![CreateDefinition {
                 bytecode: COUNTER_BYTECODE.to_string(),
                 name: "test_counter".to_string(),
                 from: Some("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_owned()),
                 from_pool: None,
+                source: None,
+                contract: None,
+                abi: None,
             }]),
             spam: None,
             setup: None,
@@ -289,7 +537,18 @@ pub mod tests {
         let tc_setup = get_setup_testconfig();
         let tc_create = get_create_testconfig();
         TestConfig {
+            chain_id: None,
             env: tc_create.env, // TODO: add something here
+            gas: None,
+            defaults: None,
+            multicall: None,
+            phases: None,
+            slo: None,
+            namespace: None,
+            read: None,
+            eip712: None,
+            sender_assignment: None,
+            concurrent_setup: None,
             create: tc_create.create,
             spam: tc_fuzz.spam,
             setup: tc_setup.setup,
@@ -299,6 +558,18 @@ pub mod tests {
     #[test]
     fn parses_testconfig_toml() {
         let test_file = TestConfig::from_file("testConfig.toml").unwrap();
+        assert_eq!(
+            test_file.gas,
+            Some(GasEstimateConfig {
+                multiplier: 1.2,
+                fallback_gas_limit: None,
+                per_call_shape: true,
+            })
+        );
+        assert_eq!(
+            test_file.defaults.as_ref().unwrap().max_fee_per_gas,
+            Some(5_000_000_000)
+        );
         assert!(test_file.env.is_some());
         assert!(test_file.setup.is_some());
         assert!(test_file.spam.is_some());
@@ -325,6 +596,8 @@ pub mod tests {
                     Some(U256::from(100_000_000_000_000_000_u64))
                 );
                 assert_eq!(fncall.kind, Some("test".to_owned()));
+                assert_eq!(fncall.gas_limit, Some(GasLimit::Fixed(100_000)));
+                assert_eq!(setup[0].gas_limit, None);
             }
             _ => {
                 panic!("expected SpamRequest::Single");
@@ -369,6 +642,20 @@ pub mod tests {
         fs::remove_file("cargotest.toml").unwrap();
     }
 
+    #[test]
+    fn snapshot_drops_contract_sources() {
+        let mut cfg = get_composite_testconfig();
+        for create in cfg.create.iter_mut().flatten() {
+            create.source = Some("./Counter.sol".to_owned());
+            create.contract = Some("Counter".to_owned());
+        }
+        let snapshot: TestConfig = toml::from_str(&cfg.snapshot().unwrap()).unwrap();
+        let create = &snapshot.create.unwrap()[0];
+        assert!(create.source.is_none());
+        assert_eq!(create.bytecode, COUNTER_BYTECODE);
+        assert_eq!(cfg.snapshot().unwrap(), cfg.snapshot().unwrap());
+    }
+
     #[tokio::test]
     async fn gets_spam_txs() {
         let anvil = spawn_anvil();
@@ -408,6 +695,420 @@ pub mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn aggregates_spam_txs_into_multicalls() {
+        let anvil = spawn_anvil();
+        let mut test_file = get_testconfig();
+        test_file.multicall = Some(MulticallConfig {
+            calls_per_tx: 4,
+            address: MULTICALL3_ADDRESS.to_owned(),
+        });
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            RandSeed::new(),
+            &get_test_signers(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(10, |_| Ok(None)))
+            .await
+            .unwrap();
+        assert_eq!(spam_txs.len(), 10);
+        match &spam_txs[0] {
+            ExecutionRequest::Tx(req) => {
+                assert_eq!(
+                    req.tx.to,
+                    Some(TxKind::Call(MULTICALL3_ADDRESS.parse().unwrap()))
+                );
+            }
+            _ => panic!("expected ExecutionRequest::Tx"),
+        }
+    }
+
+    #[tokio::test]
+    async fn aggregates_each_senders_calls_separately() {
+        let anvil = spawn_anvil();
+        let mut test_file = get_fuzzy_testconfig();
+        // three steps with different senders; bundles can't be aggregated
+        test_file.spam.as_mut().unwrap().truncate(3);
+        test_file.multicall = Some(MulticallConfig {
+            calls_per_tx: 4,
+            address: MULTICALL3_ADDRESS.to_owned(),
+        });
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            RandSeed::new(),
+            &get_test_signers(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(6, |_| Ok(None)))
+            .await
+            .unwrap();
+        let mut txs_per_sender = HashMap::new();
+        for tx in &spam_txs {
+            match tx {
+                ExecutionRequest::Tx(req) => {
+                    *txs_per_sender.entry(req.tx.from.unwrap()).or_insert(0) += 1
+                }
+                _ => panic!("expected ExecutionRequest::Tx"),
+            }
+        }
+        assert_eq!(txs_per_sender.len(), 3);
+        assert!(txs_per_sender.values().all(|n| *n == 2));
+    }
+
+    #[tokio::test]
+    async fn signs_typed_data_args() {
+        let anvil = spawn_anvil();
+        let signers = get_test_signers();
+        let test_file: TestConfig = toml::from_str(&format!(
+            r#"
+[eip712.Permit]
+type = "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+domain = {{ name = "Token", version = "1", chain_id = 31337, verifying_contract = "{{token}}" }}
+values = {{ nonce = "0" }}
+
+[env]
+token = "7a250d5630B4cF539739dF2C5dAcb4c659F248DD"
+
+[[spam]]
+[spam.tx]
+to = "{{token}}"
+from = "{}"
+signature = "permit(address owner, address spender, uint256 value, uint256 deadline, bytes sig)"
+args = ["{{_sender}}", "0x1111111111111111111111111111111111111111", "100", "999", "{{_sig712:Permit}}"]
+"#,
+            signers[0].address()
+        ))
+        .unwrap();
+        let permit = test_file.get_typed_data()["Permit"].to_owned();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            RandSeed::new(),
+            &signers,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(1, |_| Ok(None)))
+            .await
+            .unwrap();
+        let ExecutionRequest::Tx(req) = &spam_txs[0] else {
+            panic!("expected ExecutionRequest::Tx");
+        };
+        // the signature is the last arg; 65 bytes, padded to 96
+        let input = req.tx.input.input().unwrap();
+        let sig_start = input.len() - 96;
+        let sig = Signature::try_from(&input[sig_start..sig_start + 65]).unwrap();
+        let hash = typed_data_hash(
+            &permit,
+            &[
+                signers[0].address().to_string(),
+                "0x1111111111111111111111111111111111111111".to_owned(),
+                "100".to_owned(),
+                "0".to_owned(),
+                "999".to_owned(),
+            ],
+            "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD".parse().ok(),
+        )
+        .unwrap();
+        assert_eq!(
+            sig.recover_address_from_prehash(&hash).unwrap(),
+            signers[0].address()
+        );
+    }
+
+    #[tokio::test]
+    async fn relayers_send_txs_for_pool_accounts() {
+        let anvil = spawn_anvil();
+        let test_file: TestConfig = toml::from_str(
+            r#"
+[[spam]]
+[spam.tx]
+to = "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD"
+from_pool = "users"
+relayer_pool = "relayers"
+signature = "execute(address user)"
+args = ["{_sender}"]
+"#,
+        )
+        .unwrap();
+        let seed = RandSeed::seed_from_bytes(&[0x01; 32]);
+        let mut agents = AgentStore::new();
+        agents.add_agent("users", SignerStore::new_random(4, &seed, "users"));
+        agents.add_agent("relayers", SignerStore::new_random(2, &seed, "relayers"));
+        let users = agents.get_agent("users").unwrap().signers.to_vec();
+        let relayers = agents.get_agent("relayers").unwrap().signers.to_vec();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            seed,
+            &get_test_signers(),
+            agents,
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(4, |_| Ok(None)))
+            .await
+            .unwrap();
+        for (i, req) in spam_txs.iter().enumerate() {
+            let ExecutionRequest::Tx(req) = req else {
+                panic!("expected ExecutionRequest::Tx");
+            };
+            assert_eq!(req.tx.from, Some(relayers[i % 2].address()));
+            let input = req.tx.input.input().unwrap();
+            assert_eq!(input[16..36], users[i].address()[..]);
+        }
+    }
+
+    #[tokio::test]
+    async fn splits_spam_txs_by_step_weight() {
+        let anvil = spawn_anvil();
+        let test_file: TestConfig = toml::from_str(
+            r#"
+[[spam]]
+[spam.transfer]
+to = "0x0000000000000000000000000000000000000001"
+from = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+kind = "transfer"
+weight = 9
+
+[[spam]]
+[spam.transfer]
+to = "0x0000000000000000000000000000000000000002"
+from = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+kind = "swap"
+"#,
+        )
+        .unwrap();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            RandSeed::seed_from_bytes(&[0x01; 32]),
+            &get_test_signers(),
+            AgentStore::new(),
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(20, |_| Ok(None)))
+            .await
+            .unwrap();
+        let kinds = spam_txs
+            .iter()
+            .map(|req| match req {
+                ExecutionRequest::Tx(req) => req.kind.to_owned().unwrap(),
+                _ => panic!("expected ExecutionRequest::Tx"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds.len(), 20);
+        assert_eq!(kinds.iter().filter(|k| *k == "transfer").count(), 18);
+        assert_eq!(kinds.iter().filter(|k| *k == "swap").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn wraps_builder_routed_txs_in_bundles() {
+        let anvil = spawn_anvil();
+        let test_file: TestConfig = toml::from_str(
+            r#"
+[[spam]]
+[spam.tx]
+to = "0x0000000000000000000000000000000000000001"
+from = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+signature = "set(uint256)"
+args = ["1"]
+gas_limit = 50000
+kind = "private"
+route = "builder"
+
+[[spam]]
+[spam.tx]
+to = "0x0000000000000000000000000000000000000001"
+from = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+signature = "set(uint256)"
+args = ["2"]
+gas_limit = 50000
+kind = "public"
+"#,
+        )
+        .unwrap();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            RandSeed::seed_from_bytes(&[0x01; 32]),
+            &get_test_signers(),
+            AgentStore::new(),
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(4, |_| Ok(None)))
+            .await
+            .unwrap();
+        assert_eq!(spam_txs.len(), 4);
+        for req in spam_txs {
+            match req {
+                ExecutionRequest::Bundle(reqs) => {
+                    assert_eq!(reqs.len(), 1);
+                    assert_eq!(reqs[0].kind.as_deref(), Some("private"));
+                }
+                ExecutionRequest::Tx(req) => assert_eq!(req.kind.as_deref(), Some("public")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn generates_transfers_to_pool_accounts() {
+        let anvil = spawn_anvil();
+        let test_file: TestConfig = toml::from_str(
+            r#"
+[[spam]]
+[spam.transfer]
+from_pool = "senders"
+to_pool = "recipients"
+data = "0xdeadbeef"
+fuzz = [{ value = true, min = "1", max = "1000" }]
+"#,
+        )
+        .unwrap();
+        let seed = RandSeed::seed_from_bytes(&[0x01; 32]);
+        let mut agents = AgentStore::new();
+        agents.add_agent("senders", SignerStore::new_random(2, &seed, "senders"));
+        agents.add_agent(
+            "recipients",
+            SignerStore::new_random(3, &seed, "recipients"),
+        );
+        let recipients = agents.get_agent("recipients").unwrap().signers.to_vec();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            seed,
+            &get_test_signers(),
+            agents,
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(4, |_| Ok(None)))
+            .await
+            .unwrap();
+        assert_eq!(spam_txs.len(), 4);
+        for (i, req) in spam_txs.iter().enumerate() {
+            let ExecutionRequest::Tx(req) = req else {
+                panic!("expected ExecutionRequest::Tx");
+            };
+            assert_eq!(req.tx.to, Some(TxKind::Call(recipients[i % 3].address())));
+            assert_eq!(req.tx.input.input().unwrap()[..], [0xde, 0xad, 0xbe, 0xef]);
+            let value = req.tx.value.unwrap();
+            assert!(value >= U256::from(1) && value <= U256::from(1000));
+        }
+    }
+
+    #[tokio::test]
+    async fn wraps_calls_in_safe_txs() {
+        let anvil = spawn_anvil();
+        let safe = Address::repeat_byte(0x5a);
+        // stub Safe whose `nonce()` returns 5
+        ProviderBuilder::new()
+            .on_http(anvil.endpoint_url())
+            .raw_request::<_, ()>("anvil_setCode".into(), (safe, "0x600560005260206000f3"))
+            .await
+            .unwrap();
+        let test_file: TestConfig = toml::from_str(&format!(
+            r#"
+[[spam]]
+[spam.tx]
+to = "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD"
+from = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+signature = "execute(uint256 x)"
+args = ["1"]
+safe = {{ address = "{}", owner_pool = "owners", threshold = 2 }}
+"#,
+            safe
+        ))
+        .unwrap();
+        let seed = RandSeed::seed_from_bytes(&[0x01; 32]);
+        let mut agents = AgentStore::new();
+        agents.add_agent("owners", SignerStore::new_random(3, &seed, "owners"));
+        let mut owners = agents.get_agent("owners").unwrap().signers[..2]
+            .iter()
+            .map(|owner| owner.address())
+            .collect::<Vec<_>>();
+        owners.sort();
+        let scenario = TestScenario::new(
+            test_file,
+            MockDb.into(),
+            anvil.endpoint_url(),
+            None,
+            seed,
+            &get_test_signers(),
+            agents,
+        )
+        .await
+        .unwrap();
+        let spam_txs = scenario
+            .load_txs(PlanType::Spam(2, |_| Ok(None)))
+            .await
+            .unwrap();
+        for (i, req) in spam_txs.iter().enumerate() {
+            let ExecutionRequest::Tx(req) = req else {
+                panic!("expected ExecutionRequest::Tx");
+            };
+            assert_eq!(req.tx.to, Some(TxKind::Call(safe)));
+            let call =
+                execTransactionCall::abi_decode(req.tx.input.input().unwrap(), true).unwrap();
+            assert_eq!(
+                call.to,
+                "0x7a250d5630B4cF539739dF2C5dAcb4c659F248DD"
+                    .parse::<Address>()
+                    .unwrap()
+            );
+            let tx = safe_tx(
+                call.to,
+                call.value,
+                call.data,
+                call.operation,
+                U256::from(5 + i),
+            );
+            let hash = safe_tx_hash(&tx, anvil.chain_id(), safe);
+            let signers = call
+                .signatures
+                .chunks(65)
+                .map(|sig| {
+                    Signature::try_from(sig)
+                        .unwrap()
+                        .recover_address_from_prehash(&hash)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(signers, owners);
+        }
+    }
+
     #[tokio::test]
     async fn fuzz_is_deterministic() {
         let anvil = spawn_anvil();
@@ -477,6 +1178,170 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn applies_step_defaults() {
+        let mut cfg = get_fuzzy_testconfig();
+        cfg.setup = get_setup_testconfig().setup;
+        cfg.defaults = Some(StepDefaults {
+            gas_limit: Some(GasLimit::Fixed(200_000)),
+            max_fee_per_gas: Some(5_000_000_000),
+            max_priority_fee_per_gas: None,
+            value: Some("1".to_owned()),
+        });
+        if let Some(SpamRequest::Tx(tx)) = cfg.spam.as_mut().and_then(|spam| spam.first_mut()) {
+            tx.gas_limit = Some(GasLimit::Auto);
+        }
+
+        let setup = cfg.get_setup_steps().unwrap();
+        // steps keep values they set themselves
+        assert_eq!(setup[0].value, Some("4096".to_owned()));
+        assert_eq!(setup[0].gas_limit, Some(GasLimit::Fixed(200_000)));
+        assert_eq!(setup[0].max_fee_per_gas, Some(5_000_000_000));
+
+        let spam = cfg.get_spam_steps().unwrap();
+        match (&spam[0], &spam[3]) {
+            (SpamRequest::Tx(tx), SpamRequest::Bundle(bundle)) => {
+                assert_eq!(tx.gas_limit, Some(GasLimit::Auto));
+                assert_eq!(tx.value, Some("1".to_owned()));
+                assert!(bundle
+                    .txs
+                    .iter()
+                    .all(|tx| tx.max_fee_per_gas == Some(5_000_000_000)
+                        && tx.max_priority_fee_per_gas.is_none()));
+            }
+            _ => panic!("expected a tx and a bundle"),
+        }
+    }
+
+    #[test]
+    fn parses_sender_assignment() {
+        let cfg: TestConfig = toml::from_str(r#"sender_assignment = "sticky""#).unwrap();
+        assert_eq!(cfg.get_sender_assignment(), SenderAssignment::Sticky);
+        let cfg: TestConfig = toml::from_str("").unwrap();
+        assert_eq!(cfg.get_sender_assignment(), SenderAssignment::RoundRobin);
+    }
+
+    #[test]
+    fn sends_setup_txs_one_at_a_time_by_default() {
+        let cfg: TestConfig = toml::from_str("").unwrap();
+        assert!(!cfg.get_concurrent_setup());
+        let cfg: TestConfig = toml::from_str("concurrent_setup = true").unwrap();
+        assert!(cfg.get_concurrent_setup());
+    }
+
+    #[test]
+    fn parses_phases() {
+        let cfg: TestConfig = toml::from_str(
+            r#"
+[[phases]]
+name = "warmup"
+duration = 30
+rate = 10
+steps = [0]
+
+[[phases]]
+name = "peak"
+duration = 60
+rate = 200
+"#,
+        )
+        .unwrap();
+        let phases = cfg.get_phases();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].steps, Some(vec![0]));
+        assert_eq!((phases[1].duration, phases[1].rate), (60, 200));
+        assert_eq!(phases[1].steps, None);
+    }
+
+    #[test]
+    fn parses_read_steps() {
+        let cfg: TestConfig = toml::from_str(
+            r#"
+[[read]]
+[read.call]
+to = "{testToken}"
+signature = "balanceOf(address) view returns (uint256)"
+args = ["0x0000000000000000000000000000000000000001"]
+
+[[read]]
+[read.logs]
+event = "Transfer(address,address,uint256)"
+max_range = 50
+
+[[read]]
+[read.balance]
+address = "@weth"
+
+[[read]]
+[read.block]
+lookback = 10
+
+[[read]]
+[read.estimate_gas]
+to = "{testToken}"
+signature = "transfer(address,uint256)"
+args = ["0x0000000000000000000000000000000000000001", "1"]
+"#,
+        )
+        .unwrap();
+        let reads = cfg.get_read_steps();
+        assert_eq!(reads.len(), 5);
+        assert!(matches!(&reads[0], ReadRequest::Call(call) if call.to == "{testToken}"));
+        assert!(
+            matches!(&reads[1], ReadRequest::Logs(logs) if logs.max_range == Some(50) && logs.lookback.is_none())
+        );
+        assert!(matches!(&reads[2], ReadRequest::Balance(b) if b.address == "@weth"));
+        assert!(
+            matches!(&reads[3], ReadRequest::Block(b) if b.lookback == Some(10) && b.full_txs.is_none())
+        );
+        assert!(matches!(&reads[4], ReadRequest::EstimateGas(call) if call.args.is_some()));
+    }
+
+    #[test]
+    fn resolves_whole_value_aliases() {
+        let weth = Address::repeat_byte(0x11);
+        let mut cfg: TestConfig = toml::from_str(
+            r#"
+env = { token = "@weth" }
+
+[[setup]]
+to = "@weth"
+from_pool = "admin"
+signature = "approve(address spender, uint256 amount)"
+args = ["@weth", "1"]
+
+[[spam]]
+[spam.tx]
+to = ["@weth", "{token}"]
+from_pool = "admin"
+signature = "transfer(address to, uint256 amount)"
+args = ["0x00@weth", "1"]
+
+[[read]]
+[read.balance]
+address = "@weth"
+"#,
+        )
+        .unwrap();
+        assert!(cfg.clone().resolve_aliases(|_| Ok(None)).is_err());
+        cfg.resolve_aliases(|name| Ok((name == "weth").then_some(weth)))
+            .unwrap();
+
+        assert_eq!(cfg.env.as_ref().unwrap()["token"], weth.to_string());
+        let setup = &cfg.setup.as_ref().unwrap()[0];
+        assert_eq!(setup.to.targets(), [weth.to_string()]);
+        assert_eq!(setup.args.as_ref().unwrap()[0], weth.to_string());
+        let SpamRequest::Tx(spam) = &cfg.spam.as_ref().unwrap()[0] else {
+            panic!("expected a tx step");
+        };
+        assert_eq!(spam.to.targets(), [weth.to_string(), "{token}".to_owned()]);
+        // aliases embedded in other values are left as-is
+        assert_eq!(spam.args.as_ref().unwrap()[0], "0x00@weth");
+        assert!(
+            matches!(&cfg.read.as_ref().unwrap()[0], ReadRequest::Balance(b) if b.address == weth.to_string())
+        );
+    }
+
     #[test]
     fn test_placeholders_count() {
         use crate::{types::TestConfig, Templater};
@@ -487,6 +1352,17 @@ pub mod tests {
         assert_eq!(count, 3);
     }
 
+    #[test]
+    fn namespaces_by_file_name_unless_set() {
+        let mut test_config = TestConfig::default();
+        assert_eq!(test_config.namespace_for("./scenarios/uniV2.toml"), "uniV2");
+        test_config.namespace = Some("my_app".to_owned());
+        assert_eq!(
+            test_config.namespace_for("./scenarios/uniV2.toml"),
+            "my_app"
+        );
+    }
+
     #[test]
     fn test_placeholders_find() {
         use crate::{types::TestConfig, Templater};
@@ -500,6 +1376,7 @@ pub mod tests {
                 &mut placeholder_map,
                 &MockDb,
                 "http://localhost:8545",
+                None,
             )
             .unwrap();
 
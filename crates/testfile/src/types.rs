@@ -1,4 +1,9 @@
-use contender_core::generator::types::{CreateDefinition, FunctionCallDefinition, SpamRequest};
+use contender_core::generator::eip712::TypedDataDefinition;
+use contender_core::generator::multicall::MulticallConfig;
+use contender_core::generator::types::{
+    CreateDefinition, FunctionCallDefinition, GasEstimateConfig, LatencySlo, Phase, ReadRequest,
+    SenderAssignment, SpamRequest, StepDefaults,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -6,9 +11,37 @@ use std::collections::HashMap;
 /// Defines TOML schema for scenario files.
 #[derive(Clone, Deserialize, Debug, Serialize, Default)]
 pub struct TestConfig {
+    /// Prefix for the names of contracts & setup txs saved by the scenario, so other scenarios
+    /// using the same names don't resolve them. Defaults to the scenario file's name.
+    pub namespace: Option<String>,
+
+    /// Chain ID the scenario is intended to run on.
+    /// Sending to an RPC that reports a different chain ID is refused unless explicitly confirmed.
+    pub chain_id: Option<u64>,
+
+    /// How spam txs with `from_pool` are assigned senders: "round_robin" (default), "random", or
+    /// "sticky" (one random sender per step).
+    pub sender_assignment: Option<SenderAssignment>,
+
+    /// Send setup txs from different senders to different contracts concurrently, instead of one
+    /// at a time. Off by default.
+    pub concurrent_setup: Option<bool>,
+
     /// Template variables
     pub env: Option<HashMap<String, String>>,
 
+    /// Settings for estimating gas limits of txs with `gas_limit = "auto"` (or none set).
+    pub gas: Option<GasEstimateConfig>,
+
+    /// Gas limit, fees & value applied to every setup & spam tx which doesn't set its own.
+    pub defaults: Option<StepDefaults>,
+
+    /// Aggregate spam calls into Multicall3 txs.
+    pub multicall: Option<MulticallConfig>,
+
+    /// EIP-712 typed data signed by tx senders, by the name used in `{_sig712:<name>}` args.
+    pub eip712: Option<HashMap<String, TypedDataDefinition>>,
+
     /// Contract deployments; array of hex-encoded bytecode strings.
     pub create: Option<Vec<CreateDefinition>>,
 
@@ -17,4 +50,14 @@ pub struct TestConfig {
 
     /// Function to call in spam txs.
     pub spam: Option<Vec<SpamRequest>>, // TODO: figure out how to implement BundleCallDefinition alongside FunctionCallDefinition
+
+    /// Traffic profile of the spam run, e.g. a warmup, peak & cooldown. Replaces the spam
+    /// command's duration & rate if set.
+    pub phases: Option<Vec<Phase>>,
+
+    /// Inclusion-latency targets per tx kind, checked once the run is done.
+    pub slo: Option<Vec<LatencySlo>>,
+
+    /// Read RPCs sent by `contender read`, to load-test an RPC provider's read capacity.
+    pub read: Option<Vec<ReadRequest>>,
 }
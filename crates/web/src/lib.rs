@@ -0,0 +1,335 @@
+mod pages;
+
+use std::{convert::Infallible, path::PathBuf, time::Duration};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Redirect, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use contender_core::db::{AsyncDb, DbOps, RunTxStats, SpamRun};
+use contender_report::ReportCommandArgs;
+use futures::Stream;
+use serde::Serialize;
+
+/// How often live metrics are re-read from the DB while a page is streaming them.
+pub const LIVE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the web UI needs to serve runs & reports.
+#[derive(Clone)]
+pub struct WebState<D> {
+    /// The DB, whose calls are run on tokio's blocking thread pool so they don't stall requests.
+    pub db: AsyncDb<D>,
+    /// Where reports are saved, usually `~/.contender/reports`, which is also where
+    /// `contender report` saves them.
+    pub reports_dir: PathBuf,
+}
+
+/// A run, as listed by the web UI.
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    #[serde(flatten)]
+    pub run: SpamRun,
+    /// Start time of the run, RFC 3339.
+    pub started_at: String,
+    pub has_report: bool,
+}
+
+/// Progress of a run, computed from the txs that have been recorded so far. Txs are recorded
+/// while the run is in progress, so this updates live while a spammer is running.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LiveMetrics {
+    pub run_id: u64,
+    /// Number of txs the run is expected to send.
+    pub tx_count: usize,
+    pub landed_txs: usize,
+    pub gas_used: u128,
+    /// Latest block a tx of the run landed in.
+    pub latest_block: Option<u64>,
+    /// Mean time from sending a tx to its inclusion, in milliseconds.
+    pub avg_inclusion_ms: Option<u64>,
+}
+
+impl LiveMetrics {
+    pub fn new(run: &SpamRun, stats: RunTxStats) -> Self {
+        Self {
+            run_id: run.id,
+            tx_count: run.tx_count,
+            landed_txs: stats.landed_txs,
+            gas_used: stats.gas_used,
+            latest_block: stats.latest_block,
+            avg_inclusion_ms: stats.avg_inclusion_ms,
+        }
+    }
+}
+
+/// Error returned by a handler, shown to the client as a 500 with the error message.
+pub struct WebError(String);
+
+impl<E: std::fmt::Display> From<E> for WebError {
+    fn from(err: E) -> Self {
+        Self(err.to_string())
+    }
+}
+
+impl IntoResponse for WebError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.0).into_response()
+    }
+}
+
+/// Routes of the web UI:
+/// - `GET /` lists runs, newest first
+/// - `GET /api/runs` lists runs as JSON
+/// - `GET /runs/:id` shows a run, with live metrics
+/// - `GET /runs/:id/live` streams the run's metrics as server-sent events
+/// - `GET /runs/:id/report` shows the run's report
+/// - `POST /runs/:id/report` (re)generates the run's report
+pub fn router<D>(state: WebState<D>) -> Router
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/", get(runs_page::<D>))
+        .route("/api/runs", get(runs_json::<D>))
+        .route("/runs/:id", get(run_page::<D>))
+        .route("/runs/:id/live", get(live_metrics::<D>))
+        .route(
+            "/runs/:id/report",
+            get(report_page::<D>).post(regenerate_report::<D>),
+        )
+        .with_state(state)
+}
+
+/// Path of the self-contained (`--archive`) report of a single run. Reports which link their
+/// charts & CSVs by file path can't be served, so the web UI only uses archived ones.
+pub fn report_path(reports_dir: &std::path::Path, run_id: u64) -> PathBuf {
+    reports_dir.join(format!("report-{}-{}-archive.html", run_id, run_id))
+}
+
+fn summarize<D: DbOps>(state: &WebState<D>, run: SpamRun) -> RunSummary {
+    RunSummary {
+        started_at: chrono::DateTime::from_timestamp_millis(run.timestamp as i64)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+        has_report: report_path(&state.reports_dir, run.id).exists(),
+        run,
+    }
+}
+
+async fn list_runs<D>(state: &WebState<D>) -> Result<Vec<RunSummary>, WebError>
+where
+    D: DbOps + Send + Sync + 'static,
+{
+    let runs = state.db.call(|db| db.get_runs()).await?;
+    Ok(runs.into_iter().map(|run| summarize(state, run)).collect())
+}
+
+async fn find_run<D>(state: &WebState<D>, run_id: u64) -> Result<SpamRun, Response>
+where
+    D: DbOps + Send + Sync + 'static,
+{
+    match state.db.call(move |db| db.get_run(run_id)).await {
+        Ok(Some(run)) => Ok(run),
+        Ok(None) => {
+            Err((StatusCode::NOT_FOUND, format!("run {} not found", run_id)).into_response())
+        }
+        Err(e) => Err(WebError::from(e).into_response()),
+    }
+}
+
+async fn runs_page<D>(State(state): State<WebState<D>>) -> Result<Html<String>, WebError>
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    Ok(Html(pages::runs(&list_runs(&state).await?)?))
+}
+
+async fn runs_json<D>(State(state): State<WebState<D>>) -> Result<Json<Vec<RunSummary>>, WebError>
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    Ok(Json(list_runs(&state).await?))
+}
+
+async fn run_page<D>(State(state): State<WebState<D>>, Path(run_id): Path<u64>) -> Response
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    let run = match find_run(&state, run_id).await {
+        Ok(run) => run,
+        Err(res) => return res,
+    };
+    match pages::run(&summarize(&state, run)) {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => WebError::from(e).into_response(),
+    }
+}
+
+async fn live_metrics<D>(
+    State(state): State<WebState<D>>,
+    Path(run_id): Path<u64>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response>
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    let run = find_run(&state, run_id).await?;
+    let stream = futures::stream::unfold(true, move |first| {
+        let db = state.db.clone();
+        let run = run.clone();
+        async move {
+            if !first {
+                tokio::time::sleep(LIVE_POLL_INTERVAL).await;
+            }
+            let event = db
+                .call(move |db| db.get_run_tx_stats(run_id))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|stats| {
+                    Event::default()
+                        .event("metrics")
+                        .json_data(LiveMetrics::new(&run, stats))
+                        .map_err(|e| e.to_string())
+                })
+                .unwrap_or_else(|e| Event::default().event("error").data(e));
+            Some((Ok(event), false))
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+async fn report_page<D>(State(state): State<WebState<D>>, Path(run_id): Path<u64>) -> Response
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    if let Err(res) = find_run(&state, run_id).await {
+        return res;
+    }
+    match std::fs::read_to_string(report_path(&state.reports_dir, run_id)) {
+        Ok(html) => Html(html).into_response(),
+        // no report yet; the run page offers to generate one
+        Err(_) => Redirect::to(&format!("/runs/{}", run_id)).into_response(),
+    }
+}
+
+/// Generates an archived report of the run in the reports dir, then shows the new report.
+async fn regenerate_report<D>(State(state): State<WebState<D>>, Path(run_id): Path<u64>) -> Response
+where
+    D: DbOps + Clone + Send + Sync + 'static,
+{
+    let run = match find_run(&state, run_id).await {
+        Ok(run) => run,
+        Err(res) => return res,
+    };
+    if run.rpc_url.is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("run {} has no recorded RPC URL to report with", run_id),
+        )
+            .into_response();
+    }
+    println!("generating report for run {}", run_id);
+    let args = ReportCommandArgs {
+        last_run_id: Some(run_id),
+        archive: true,
+        ..Default::default()
+    };
+    let out_dir = state.reports_dir.to_string_lossy().to_string();
+    let db = state.db.inner().as_ref().clone();
+    // the report's DB reads, charts & CSVs block, and its future isn't `Send`
+    let runtime = tokio::runtime::Handle::current();
+    let res = tokio::task::spawn_blocking(move || {
+        runtime
+            .block_on(contender_report::report(args, &db, &run.rpc_url, &out_dir))
+            .map_err(|e| e.to_string())
+    })
+    .await;
+    match res {
+        Ok(Ok(_)) => Redirect::to(&format!("/runs/{}/report", run_id)).into_response(),
+        Ok(Err(e)) => WebError(format!(
+            "failed to generate report for run {}: {}",
+            run_id, e
+        ))
+        .into_response(),
+        Err(e) => WebError::from(e).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contender_core::db::RunTx;
+    use contender_sqlite::SqliteDb;
+    use std::sync::Arc;
+
+    fn tx(n: u8, block_number: u64, start_timestamp: usize, end_timestamp: usize) -> RunTx {
+        RunTx {
+            block_number,
+            ..RunTx::mock(n).with_times(start_timestamp, end_timestamp)
+        }
+    }
+
+    #[tokio::test]
+    async fn computes_live_metrics_from_landed_txs() {
+        let db = SqliteDb::new_memory();
+        db.create_tables().unwrap();
+        let run_id = db
+            .insert_run(0, 10, "test", "http://localhost:8545")
+            .unwrap();
+        let db = AsyncDb::new(Arc::new(db));
+        let run = db
+            .call(move |db| db.get_run(run_id))
+            .await
+            .unwrap()
+            .unwrap();
+        let stats = db
+            .call(move |db| db.get_run_tx_stats(run_id))
+            .await
+            .unwrap();
+        let metrics = LiveMetrics::new(&run, stats);
+        assert_eq!(metrics.landed_txs, 0);
+        assert_eq!(metrics.avg_inclusion_ms, None);
+
+        db.call(move |db| {
+            db.insert_run_txs(
+                run_id,
+                vec![
+                    tx(1, 5, 1000, 2000),
+                    tx(2, 7, 1000, 4000),
+                    tx(3, 0, 1000, 0),
+                ],
+            )
+        })
+        .await
+        .unwrap();
+        let stats = db
+            .call(move |db| db.get_run_tx_stats(run_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            LiveMetrics::new(&run, stats),
+            LiveMetrics {
+                run_id,
+                tx_count: 10,
+                landed_txs: 2,
+                gas_used: 42000,
+                latest_block: Some(7),
+                avg_inclusion_ms: Some(2000),
+            }
+        );
+    }
+
+    #[test]
+    fn finds_archived_reports() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(
+            report_path(dir.path(), 12),
+            dir.path().join("report-12-12-archive.html")
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use contender_core::db::{AsyncDb, DbOps};
+use contender_sqlite::SqliteDb;
+use contender_web::{router, WebState};
+
+/// Serves the runs & reports in contender's DB over HTTP, so they can be shared with a team
+/// instead of opening local report files.
+#[derive(Parser, Debug)]
+#[command(name = "contender-web", version, author)]
+struct WebCli {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port to listen on.
+    #[arg(short, long, default_value = "8080")]
+    port: u16,
+}
+
+/// Returns the path to contender's data directory, as used by the CLI.
+fn data_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let home = std::env::var("HOME").map_err(|_| "Failed to get $HOME from environment")?;
+    Ok(PathBuf::from(home).join(".contender"))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = WebCli::parse();
+    let data_dir = data_dir()?;
+    let reports_dir = data_dir.join("reports");
+    std::fs::create_dir_all(&reports_dir)?;
+
+    let db_path = data_dir.join("contender.db");
+    println!("opening DB at {}", db_path.display());
+    let db = SqliteDb::from_file(db_path.to_str().ok_or("invalid DB path")?)?;
+    db.create_tables()?;
+
+    let app = router(WebState {
+        db: AsyncDb::new(Arc::new(db)),
+        reports_dir,
+    });
+    let listener = tokio::net::TcpListener::bind((args.host.as_str(), args.port)).await?;
+    println!(
+        "serving contender runs at http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
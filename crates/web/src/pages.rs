@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use handlebars::{Handlebars, RenderError};
+use serde::Serialize;
+
+use crate::RunSummary;
+
+fn render(template: &str, data: impl Serialize) -> Result<String, RenderError> {
+    let mut data_map = HashMap::new();
+    data_map.insert("data", data);
+    Handlebars::new().render_template(template, &data_map)
+}
+
+/// Page listing all runs.
+pub fn runs(runs: &[RunSummary]) -> Result<String, RenderError> {
+    render(include_str!("templates/runs.html"), runs)
+}
+
+/// Page showing a single run, with its live metrics.
+pub fn run(run: &RunSummary) -> Result<String, RenderError> {
+    render(include_str!("templates/run.html"), run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contender_core::db::SpamRun;
+
+    #[test]
+    fn escapes_run_fields() {
+        let run = RunSummary {
+            run: SpamRun {
+                id: 1,
+                timestamp: 0,
+                tx_count: 10,
+                scenario_name: "<script>alert(1)</script>".to_owned(),
+                rpc_url: "http://localhost:8545".to_owned(),
+            },
+            started_at: "1970-01-01T00:00:00+00:00".to_owned(),
+            has_report: false,
+        };
+        let html = runs(&[run]).unwrap();
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("href=\"/runs/1\""));
+    }
+}